@@ -1,10 +1,11 @@
 // Analyze engine module
 // Analyzes a single hash database and generates statistics
 
-use std::collections::{HashMap, HashSet};
+use std::collections::{BTreeMap, HashMap, HashSet};
 use std::path::{Path, PathBuf};
 use crate::database::{DatabaseHandler, DatabaseFormat};
 use crate::error::HashUtilityError;
+use crate::path_utils::format_size;
 
 /// A group of duplicate files (same hash)
 #[derive(Debug, Clone, serde::Serialize)]
@@ -208,6 +209,84 @@ impl AnalyzeReport {
     }
 }
 
+/// File size distribution (only for formats with a size column)
+#[derive(Debug, Clone, serde::Serialize)]
+pub struct SizeStats {
+    pub total: u64,
+    pub average: u64,
+    pub median: u64,
+    pub min: u64,
+    pub max: u64,
+}
+
+/// Files sharing one extension
+#[derive(Debug, Clone, serde::Serialize)]
+pub struct ExtensionCount {
+    /// Lowercased extension without the dot, or "(none)"
+    pub extension: String,
+    pub files: usize,
+    /// Combined size (only for formats with a size column)
+    pub bytes: Option<u64>,
+}
+
+/// Quick profile of what a database covers, from the `stats` command
+#[derive(Debug, Clone, serde::Serialize)]
+pub struct DatabaseStats {
+    pub database_path: PathBuf,
+    pub total_files: usize,
+    pub sizes: Option<SizeStats>,
+    /// Number of entries per algorithm
+    pub algorithms: BTreeMap<String, usize>,
+    pub duplicate_groups: usize,
+    /// Most common extensions first
+    pub extensions: Vec<ExtensionCount>,
+}
+
+impl DatabaseStats {
+    /// Format the statistics as plain text
+    pub fn to_plain_text(&self) -> String {
+        let mut output = String::new();
+
+        output.push_str("\n=== Database Statistics ===\n\n");
+        output.push_str(&format!("Database: {}\n", self.database_path.display()));
+        output.push_str(&format!("Files:    {}\n", self.total_files));
+        output.push_str(&format!("Duplicate groups: {}\n", self.duplicate_groups));
+
+        output.push_str("\nFile Sizes:\n");
+        match &self.sizes {
+            Some(sizes) => {
+                output.push_str(&format!("  Total:    {}\n", format_size(sizes.total)));
+                output.push_str(&format!("  Average:  {}\n", format_size(sizes.average)));
+                output.push_str(&format!("  Median:   {}\n", format_size(sizes.median)));
+                output.push_str(&format!("  Smallest: {}\n", format_size(sizes.min)));
+                output.push_str(&format!("  Largest:  {}\n", format_size(sizes.max)));
+            }
+            None => output.push_str("  Not recorded (use the hashdeep or tsv format for sizes)\n"),
+        }
+
+        output.push_str("\nAlgorithms:\n");
+        for (algorithm, count) in &self.algorithms {
+            output.push_str(&format!("  {:<16} {} files\n", algorithm, count));
+        }
+
+        output.push_str("\nExtensions:\n");
+        for ext in &self.extensions {
+            match ext.bytes {
+                Some(bytes) => output.push_str(&format!("  {:<16} {} files ({})\n", ext.extension, ext.files, format_size(bytes))),
+                None => output.push_str(&format!("  {:<16} {} files\n", ext.extension, ext.files)),
+            }
+        }
+
+        output.push('\n');
+        output
+    }
+
+    /// Format the statistics as JSON
+    pub fn to_json(&self) -> Result<String, serde_json::Error> {
+        serde_json::to_string_pretty(self)
+    }
+}
+
 /// Engine for analyzing hash databases
 pub struct AnalyzeEngine;
 
@@ -229,6 +308,9 @@ impl AnalyzeEngine {
         let format_str = match format {
             DatabaseFormat::Standard => "standard",
             DatabaseFormat::Hashdeep => "hashdeep",
+            DatabaseFormat::Tsv => "tsv",
+            DatabaseFormat::Coreutils => "coreutils",
+            DatabaseFormat::JsonDb => "json-db",
         };
 
         // Read database with size information
@@ -292,14 +374,54 @@ impl AnalyzeEngine {
         })
     }
 
+    /// Summarize a database: sizes, algorithms, duplicate groups and extensions
+    pub fn stats(&self, database_path: &Path) -> Result<DatabaseStats, HashUtilityError> {
+        let format = DatabaseHandler::detect_format(database_path)?;
+        let entries = Self::read_database_with_sizes(database_path, format)?;
+
+        let mut algorithms: BTreeMap<String, usize> = BTreeMap::new();
+        let mut extensions: HashMap<String, (usize, Option<u64>)> = HashMap::new();
+        let mut sizes: Vec<u64> = Vec::new();
+
+        for (path, entry) in &entries {
+            *algorithms.entry(entry.algorithm.clone()).or_default() += 1;
+
+            let extension = path
+                .extension()
+                .map(|ext| ext.to_string_lossy().to_lowercase())
+                .unwrap_or_else(|| "(none)".to_string());
+            let (files, bytes) = extensions.entry(extension).or_default();
+            *files += 1;
+            if let Some(size) = entry.file_size {
+                *bytes.get_or_insert(0) += size;
+                sizes.push(size);
+            }
+        }
+
+        let mut extensions: Vec<ExtensionCount> = extensions
+            .into_iter()
+            .map(|(extension, (files, bytes))| ExtensionCount { extension, files, bytes })
+            .collect();
+        extensions.sort_by(|a, b| b.files.cmp(&a.files).then_with(|| a.extension.cmp(&b.extension)));
+
+        Ok(DatabaseStats {
+            database_path: database_path.to_path_buf(),
+            total_files: entries.len(),
+            sizes: size_stats(sizes),
+            algorithms,
+            duplicate_groups: Self::find_duplicates(&entries).len(),
+            extensions,
+        })
+    }
+
     /// Read database and extract size information if available
     fn read_database_with_sizes(
         path: &Path,
         format: DatabaseFormat,
     ) -> Result<HashMap<PathBuf, EntryWithSize>, HashUtilityError> {
         match format {
-            DatabaseFormat::Standard => {
-                // Standard format doesn't have sizes
+            DatabaseFormat::Standard | DatabaseFormat::Coreutils => {
+                // Standard and coreutils formats don't have sizes
                 let db = DatabaseHandler::read_database(path)?;
                 Ok(db.into_iter().map(|(path, entry)| {
                     (path, EntryWithSize {
@@ -314,6 +436,22 @@ impl AnalyzeEngine {
                 // Parse hashdeep format with sizes
                 Self::read_hashdeep_with_sizes(path)
             }
+            DatabaseFormat::Tsv | DatabaseFormat::JsonDb => {
+                // TSV and JSON entries carry a size
+                let entries = if format == DatabaseFormat::Tsv {
+                    DatabaseHandler::read_tsv_entries(path)?
+                } else {
+                    DatabaseHandler::read_json_db_entries(path)?
+                };
+                Ok(entries.into_iter().map(|(path, entry, size)| {
+                    (path, EntryWithSize {
+                        hash: entry.hash,
+                        algorithm: entry.algorithm,
+                        fast_mode: entry.fast_mode,
+                        file_size: Some(size),
+                    })
+                }).collect())
+            }
         }
     }
 
@@ -335,9 +473,7 @@ impl AnalyzeEngine {
         let mut algorithms: Vec<String> = Vec::new();
 
         for line_result in reader.lines() {
-            let line = line_result.map_err(|e| {
-                HashUtilityError::from_io_error(e, "reading database", Some(path.to_path_buf()))
-            })?;
+            let line = line_result.map_err(|e| DatabaseHandler::read_error(e, path))?;
 
             let trimmed = line.trim();
 
@@ -347,17 +483,8 @@ impl AnalyzeEngine {
             }
 
             // Parse header to get algorithm names
-            if trimmed.starts_with("%%%%") && trimmed.contains(',') {
-                let header_parts: Vec<&str> = trimmed.split_whitespace().collect();
-                if header_parts.len() >= 2 {
-                    let fields: Vec<&str> = header_parts[1].split(',').collect();
-                    if fields.len() >= 3 {
-                        algorithms = fields[1..fields.len()-1]
-                            .iter()
-                            .map(|s| s.to_string())
-                            .collect();
-                    }
-                }
+            if let Some(header_algorithms) = DatabaseHandler::parse_hashdeep_header(trimmed) {
+                algorithms = header_algorithms;
                 continue;
             }
 
@@ -366,8 +493,8 @@ impl AnalyzeEngine {
                 continue;
             }
 
-            // Parse data line: size,hash1,hash2,...,filename
-            let parts: Vec<&str> = trimmed.split(',').collect();
+            // Parse data line: size,hash1,hash2,...,filename (commas or tabs)
+            let parts: Vec<&str> = trimmed.split(DatabaseHandler::hashdeep_separator(trimmed)).collect();
             if parts.len() < 3 {
                 continue;
             }
@@ -458,26 +585,31 @@ fn infer_algorithm_from_hash(hash: &str) -> String {
     }
 }
 
-/// Format byte size as human-readable string
-fn format_size(bytes: u64) -> String {
-    const KB: u64 = 1024;
-    const MB: u64 = KB * 1024;
-    const GB: u64 = MB * 1024;
-    const TB: u64 = GB * 1024;
-
-    if bytes >= TB {
-        format!("{:.2} TB", bytes as f64 / TB as f64)
-    } else if bytes >= GB {
-        format!("{:.2} GB", bytes as f64 / GB as f64)
-    } else if bytes >= MB {
-        format!("{:.2} MB", bytes as f64 / MB as f64)
-    } else if bytes >= KB {
-        format!("{:.2} KB", bytes as f64 / KB as f64)
-    } else {
-        format!("{} bytes", bytes)
+/// Total, average, median and range of the given file sizes (None if there are none)
+fn size_stats(mut sizes: Vec<u64>) -> Option<SizeStats> {
+    if sizes.is_empty() {
+        return None;
     }
+    sizes.sort_unstable();
+
+    let total: u64 = sizes.iter().sum();
+    let mid = sizes.len() / 2;
+    let median = if sizes.len().is_multiple_of(2) {
+        (sizes[mid - 1] + sizes[mid]) / 2
+    } else {
+        sizes[mid]
+    };
+
+    Some(SizeStats {
+        total,
+        average: total / sizes.len() as u64,
+        median,
+        min: sizes[0],
+        max: sizes[sizes.len() - 1],
+    })
 }
 
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -527,12 +659,58 @@ mod tests {
         fs::remove_file(db_path).unwrap();
     }
 
+    #[test]
+    fn test_stats_summarizes_database() {
+        let db_path = "test_stats_summary.tsv";
+        let content = "hash1\tsha256\t1000\tphotos/a.JPG\n\
+                       hash2\tsha256\t5000\tphotos/b.jpg\n\
+                       hash1\tsha256\t1000\tbackup/a.jpg\n\
+                       hash3\tblake3\t10\tnotes.txt\n\
+                       hash4\tblake3\t300\tMakefile\n";
+        fs::write(db_path, content).unwrap();
+
+        let stats = AnalyzeEngine::new().stats(Path::new(db_path)).unwrap();
+
+        assert_eq!(stats.total_files, 5);
+        assert_eq!(stats.duplicate_groups, 1);
+        assert_eq!(stats.algorithms.get("sha256"), Some(&3));
+        assert_eq!(stats.algorithms.get("blake3"), Some(&2));
+
+        let sizes = stats.sizes.as_ref().unwrap();
+        assert_eq!(sizes.total, 7310);
+        assert_eq!(sizes.average, 1462);
+        assert_eq!(sizes.median, 1000);
+        assert_eq!(sizes.min, 10);
+        assert_eq!(sizes.max, 5000);
+
+        // Extensions are case-insensitive, most common first
+        assert_eq!(stats.extensions[0].extension, "jpg");
+        assert_eq!(stats.extensions[0].files, 3);
+        assert_eq!(stats.extensions[0].bytes, Some(7000));
+        assert!(stats.extensions.iter().any(|e| e.extension == "(none)" && e.files == 1));
+
+        fs::remove_file(db_path).unwrap();
+    }
+
+    #[test]
+    fn test_stats_without_sizes() {
+        let db_path = "test_stats_no_sizes.txt";
+        fs::write(db_path, "hash1  sha256  normal  a.txt\nhash2  sha256  normal  b.txt\n").unwrap();
+
+        let stats = AnalyzeEngine::new().stats(Path::new(db_path)).unwrap();
+        assert_eq!(stats.total_files, 2);
+        assert!(stats.sizes.is_none());
+        assert_eq!(stats.extensions[0].bytes, None);
+
+        fs::remove_file(db_path).unwrap();
+    }
+
     #[test]
     fn test_format_size() {
         assert_eq!(format_size(500), "500 bytes");
-        assert_eq!(format_size(1024), "1.00 KB");
-        assert_eq!(format_size(1536), "1.50 KB");
-        assert_eq!(format_size(1048576), "1.00 MB");
-        assert_eq!(format_size(1073741824), "1.00 GB");
+        assert_eq!(format_size(1024), "1.00 KiB");
+        assert_eq!(format_size(1536), "1.50 KiB");
+        assert_eq!(format_size(1048576), "1.00 MiB");
+        assert_eq!(format_size(1073741824), "1.00 GiB");
     }
 }
@@ -0,0 +1,231 @@
+// Archive module
+// Hashes the members of tar and zip archives without extracting them
+
+use std::fs::File;
+use std::io::{BufReader, Read};
+use std::path::Path;
+use crate::error::HashUtilityError;
+use crate::hash::{HashComputer, HashResult};
+use xz2::read::XzDecoder;
+
+/// Hash every regular file in an archive (.tar, .tar.xz / .txz, or .zip)
+///
+/// Each result is reported with the member's path inside the archive.
+/// Directory entries, links and other special members are skipped.
+pub fn hash_archive(
+    archive: &Path,
+    computer: &HashComputer,
+    algorithms: &[String],
+) -> Result<Vec<HashResult>, HashUtilityError> {
+    let name = archive
+        .file_name()
+        .map(|n| n.to_string_lossy().to_lowercase())
+        .unwrap_or_default();
+
+    let file = File::open(archive)
+        .map_err(|e| HashUtilityError::from_io_error(e, "reading", Some(archive.to_path_buf())))?;
+
+    if name.ends_with(".zip") {
+        hash_zip_members(BufReader::new(file), computer, algorithms, archive)
+    } else if name.ends_with(".xz") || name.ends_with(".txz") {
+        hash_tar_members(XzDecoder::new(BufReader::new(file)), computer, algorithms, archive)
+    } else {
+        hash_tar_members(BufReader::new(file), computer, algorithms, archive)
+    }
+}
+
+/// Walk a tar stream, streaming each regular member through the hashers
+fn hash_tar_members<R: Read>(
+    reader: R,
+    computer: &HashComputer,
+    algorithms: &[String],
+    archive: &Path,
+) -> Result<Vec<HashResult>, HashUtilityError> {
+    // The tar crate reports bad headers and truncated members as I/O errors
+    let invalid = |e: std::io::Error| HashUtilityError::InvalidArguments {
+        message: format!("Not a valid tar archive: {} ({})", archive.display(), e),
+    };
+
+    let mut tar = tar::Archive::new(reader);
+    let mut results = Vec::new();
+
+    for entry in tar.entries().map_err(invalid)? {
+        let mut entry = entry.map_err(invalid)?;
+        if !entry.header().entry_type().is_file() {
+            continue;
+        }
+
+        // Long names from GNU and pax headers are already applied here
+        let path = entry.path().map_err(invalid)?.to_string_lossy().into_owned();
+        results.extend(computer.compute_multiple_hashes_reader(&mut entry, algorithms, Path::new(&path))?);
+    }
+
+    Ok(results)
+}
+
+/// Read a zip archive's central directory and hash each file member
+fn hash_zip_members<R: Read + std::io::Seek>(
+    reader: R,
+    computer: &HashComputer,
+    algorithms: &[String],
+    archive: &Path,
+) -> Result<Vec<HashResult>, HashUtilityError> {
+    let invalid = |e: zip::result::ZipError| HashUtilityError::InvalidArguments {
+        message: format!("Not a valid zip archive: {} ({})", archive.display(), e),
+    };
+
+    let mut zip = zip::ZipArchive::new(reader).map_err(invalid)?;
+    let mut results = Vec::new();
+
+    for index in 0..zip.len() {
+        let mut member = zip.by_index(index).map_err(invalid)?;
+        if !member.is_file() || member.is_symlink() {
+            continue;
+        }
+
+        // Reading a member to the end also checks its CRC
+        let path = member.name().to_string();
+        results.extend(computer.compute_multiple_hashes_reader(&mut member, algorithms, Path::new(&path))?);
+    }
+
+    Ok(results)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::fs;
+    use std::io::Write;
+
+    /// Append a regular file member to a tar under construction
+    fn append_file(builder: &mut tar::Builder<Vec<u8>>, path: &str, data: &[u8]) {
+        let mut header = tar::Header::new_gnu();
+        header.set_size(data.len() as u64);
+        header.set_mode(0o644);
+        builder.append_data(&mut header, path, data).unwrap();
+    }
+
+    fn build_test_tar() -> Vec<u8> {
+        let mut builder = tar::Builder::new(Vec::new());
+        append_file(&mut builder, "hello.txt", b"hello world");
+
+        let mut dir = tar::Header::new_gnu();
+        dir.set_entry_type(tar::EntryType::Directory);
+        dir.set_size(0);
+        dir.set_mode(0o755);
+        builder.append_data(&mut dir, "dir/", std::io::empty()).unwrap();
+
+        append_file(&mut builder, "dir/sub/empty.txt", b"");
+        // Longer than the 100-byte name field, so stored with a GNU long-name entry
+        append_file(&mut builder, &format!("docs/{}.txt", "x".repeat(120)), b"abc");
+        builder.into_inner().unwrap()
+    }
+
+    fn sha256_by_path(results: &[HashResult]) -> Vec<(String, String)> {
+        results
+            .iter()
+            .map(|r| (r.file_path.display().to_string(), r.hash.clone()))
+            .collect()
+    }
+
+    #[test]
+    fn test_hash_tar_members() {
+        let path = "test_archive_members.tar";
+        fs::write(path, build_test_tar()).unwrap();
+
+        let results = hash_archive(Path::new(path), &HashComputer::new(), &["sha256".to_string()]).unwrap();
+
+        // Directory entries are skipped; nested and long names are kept
+        assert_eq!(
+            sha256_by_path(&results),
+            vec![
+                ("hello.txt".to_string(), "b94d27b9934d3e08a52e52d7da7dabfac484efe37a5380ee9088f7ace2efcde9".to_string()),
+                ("dir/sub/empty.txt".to_string(), "e3b0c44298fc1c149afbf4c8996fb92427ae41e4649b934ca495991b7852b855".to_string()),
+                (format!("docs/{}.txt", "x".repeat(120)), "ba7816bf8f01cfea414140de5dae2223b00361a396177a9cb410ff61f20015ad".to_string()),
+            ]
+        );
+
+        // Cleanup
+        fs::remove_file(path).unwrap();
+    }
+
+    #[test]
+    fn test_hash_tar_xz_members() {
+        let path = "test_archive_members.tar.xz";
+        let mut encoder = xz2::write::XzEncoder::new(fs::File::create(path).unwrap(), 6);
+        encoder.write_all(&build_test_tar()).unwrap();
+        encoder.finish().unwrap();
+
+        let results = hash_archive(Path::new(path), &HashComputer::new(), &["sha256".to_string()]).unwrap();
+        assert_eq!(results.len(), 3);
+        assert_eq!(results[0].hash, "b94d27b9934d3e08a52e52d7da7dabfac484efe37a5380ee9088f7ace2efcde9");
+
+        // Cleanup
+        fs::remove_file(path).unwrap();
+    }
+
+    #[test]
+    fn test_hash_zip_members() {
+        let path = "test_archive_members.zip";
+        let mut writer = zip::ZipWriter::new(fs::File::create(path).unwrap());
+        let options = zip::write::SimpleFileOptions::default().compression_method(zip::CompressionMethod::Deflated);
+        writer.start_file("hello.txt", options).unwrap();
+        writer.write_all(b"hello world").unwrap();
+        writer.add_directory("dir/", options).unwrap();
+        writer.start_file("dir/abc.txt", options).unwrap();
+        writer.write_all(b"abc").unwrap();
+        writer.finish().unwrap();
+
+        let results = hash_archive(Path::new(path), &HashComputer::new(), &["sha256".to_string()]).unwrap();
+
+        // Deflated members are decompressed before hashing; directories are skipped
+        assert_eq!(
+            sha256_by_path(&results),
+            vec![
+                ("hello.txt".to_string(), "b94d27b9934d3e08a52e52d7da7dabfac484efe37a5380ee9088f7ace2efcde9".to_string()),
+                ("dir/abc.txt".to_string(), "ba7816bf8f01cfea414140de5dae2223b00361a396177a9cb410ff61f20015ad".to_string()),
+            ]
+        );
+
+        // Cleanup
+        fs::remove_file(path).unwrap();
+    }
+
+    #[test]
+    fn test_hash_archive_rejects_non_tar() {
+        let path = "test_archive_not_tar.tar";
+        fs::write(path, vec![b'x'; 1024]).unwrap();
+
+        let result = hash_archive(Path::new(path), &HashComputer::new(), &["sha256".to_string()]);
+        assert!(matches!(result, Err(HashUtilityError::InvalidArguments { .. })));
+
+        // Cleanup
+        fs::remove_file(path).unwrap();
+    }
+
+    #[test]
+    fn test_hash_archive_rejects_truncated_tar() {
+        let path = "test_archive_truncated.tar";
+        let tar = build_test_tar();
+        // Cut into the data of the first member
+        fs::write(path, &tar[..512 + 5]).unwrap();
+
+        let result = hash_archive(Path::new(path), &HashComputer::new(), &["sha256".to_string()]);
+        assert!(result.is_err());
+
+        // Cleanup
+        fs::remove_file(path).unwrap();
+    }
+
+    #[test]
+    fn test_hash_archive_rejects_non_zip() {
+        let path = "test_archive_not_zip.zip";
+        fs::write(path, b"not a zip file").unwrap();
+
+        let result = hash_archive(Path::new(path), &HashComputer::new(), &["sha256".to_string()]);
+        assert!(matches!(result, Err(HashUtilityError::InvalidArguments { .. })));
+
+        // Cleanup
+        fs::remove_file(path).unwrap();
+    }
+}
@@ -1,24 +1,119 @@
 // Benchmark module
 // Measures hash algorithm performance
 
-use crate::hash::HashRegistry;
+use crate::hash::{HashComputer, HashRegistry};
 use crate::error::HashUtilityError;
+use std::fs::{self, File};
+use std::io::IsTerminal;
 use std::time::{Duration, Instant};
 
+/// Buffer sizes tried by the buffer size sweep
+pub const SWEEP_BUFFER_SIZES: [usize; 5] = [16 * 1024, 64 * 1024, 256 * 1024, 1024 * 1024, 4 * 1024 * 1024];
+
 /// Result of a benchmark run for a single algorithm
 #[derive(Debug, Clone, serde::Serialize)]
 pub struct BenchmarkResult {
     pub algorithm: String,
+    /// Throughput of the timed run, or the mean over all of them with `--runs`
     pub throughput_mbps: f64,
+    /// Spread over the timed runs, when there was more than one
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub runs: Option<RunStats>,
+}
+
+/// Throughput spread over several timed runs of one algorithm (`--runs`)
+#[derive(Debug, Clone, Copy, PartialEq, serde::Serialize)]
+pub struct RunStats {
+    pub runs: usize,
+    pub min_mbps: f64,
+    pub max_mbps: f64,
+    pub mean_mbps: f64,
+}
+
+impl RunStats {
+    /// Summarize the throughputs of individual runs (None if there are none)
+    pub fn from_throughputs(throughputs: &[f64]) -> Option<Self> {
+        if throughputs.is_empty() {
+            return None;
+        }
+        Some(Self {
+            runs: throughputs.len(),
+            min_mbps: throughputs.iter().copied().fold(f64::INFINITY, f64::min),
+            max_mbps: throughputs.iter().copied().fold(f64::NEG_INFINITY, f64::max),
+            mean_mbps: throughputs.iter().sum::<f64>() / throughputs.len() as f64,
+        })
+    }
 }
 
+/// Result of hashing with a single buffer size during a sweep
+#[derive(Debug, Clone, serde::Serialize)]
+pub struct BufferSweepResult {
+    pub buffer_size: usize,
+    pub throughput_mbps: f64,
+}
+
+/// When to highlight the fastest and slowest algorithms (`--color`)
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum ColorMode {
+    /// Only when stdout is a terminal and NO_COLOR is unset
+    #[default]
+    Auto,
+    Always,
+    Never,
+}
+
+impl ColorMode {
+    /// Parse a color mode name ('auto', 'always' or 'never', case-insensitive)
+    pub fn parse(mode: &str) -> Result<Self, HashUtilityError> {
+        match mode.to_lowercase().as_str() {
+            "auto" => Ok(ColorMode::Auto),
+            "always" => Ok(ColorMode::Always),
+            "never" => Ok(ColorMode::Never),
+            _ => Err(HashUtilityError::InvalidArguments {
+                message: format!("Invalid color mode '{}'. Valid modes are: auto, always, never", mode),
+            }),
+        }
+    }
+    
+    /// Whether output should be colored
+    pub fn enabled(self) -> bool {
+        match self {
+            ColorMode::Auto => std::env::var_os("NO_COLOR").is_none() && std::io::stdout().is_terminal(),
+            ColorMode::Always => true,
+            ColorMode::Never => false,
+        }
+    }
+}
+
+/// ANSI colors for the fastest and slowest rows of the results table
+const FASTEST_COLOR: &str = "\x1b[32m";
+const SLOWEST_COLOR: &str = "\x1b[31m";
+const RESET_COLOR: &str = "\x1b[0m";
+
 /// Engine for benchmarking hash algorithms
-pub struct BenchmarkEngine;
+pub struct BenchmarkEngine {
+    warmup: usize,
+    runs: usize,
+}
 
 impl BenchmarkEngine {
-    /// Create a new BenchmarkEngine
+    /// Create a new BenchmarkEngine that times one run of each algorithm
     pub fn new() -> Self {
-        Self
+        Self { warmup: 0, runs: 1 }
+    }
+    
+    /// Hash the test data this many times, untimed, before measuring each algorithm (`--warmup`)
+    /// 
+    /// Lets CPU frequency and caches settle, so the first algorithm isn't measured cold.
+    pub fn with_warmup(mut self, warmup: usize) -> Self {
+        self.warmup = warmup;
+        self
+    }
+    
+    /// Time each algorithm this many times and report the mean, min and max (`--runs`)
+    pub fn with_runs(mut self, runs: usize) -> Self {
+        self.runs = runs;
+        self
     }
     
     /// Run benchmarks on all supported hash algorithms
@@ -29,6 +124,12 @@ impl BenchmarkEngine {
     /// # Returns
     /// Vector of BenchmarkResult containing throughput for each algorithm
     pub fn run_benchmarks(&self, data_size_mb: usize) -> Result<Vec<BenchmarkResult>, HashUtilityError> {
+        if self.runs == 0 {
+            return Err(HashUtilityError::InvalidArguments {
+                message: "Number of runs must be at least 1".to_string(),
+            });
+        }
+        
         // Generate test data
         let data_size_bytes = data_size_mb * 1024 * 1024;
         let test_data = generate_test_data(data_size_bytes);
@@ -52,13 +153,33 @@ impl BenchmarkEngine {
         Ok(results)
     }
     
-    /// Benchmark a single algorithm
+    /// Benchmark a single algorithm: the warmup runs, then the timed runs
     fn benchmark_algorithm(
         &self,
         algorithm: &str,
         test_data: &[u8],
         data_size_mb: usize,
     ) -> Result<BenchmarkResult, HashUtilityError> {
+        for _ in 0..self.warmup {
+            Self::time_hash(algorithm, test_data)?;
+        }
+        
+        // Calculate throughput in MB/s for each timed run
+        let mut throughputs = Vec::with_capacity(self.runs);
+        for _ in 0..self.runs {
+            throughputs.push(calculate_throughput(data_size_mb, Self::time_hash(algorithm, test_data)?));
+        }
+        let stats = RunStats::from_throughputs(&throughputs);
+        
+        Ok(BenchmarkResult {
+            algorithm: algorithm.to_string(),
+            throughput_mbps: stats.map_or(0.0, |stats| stats.mean_mbps),
+            runs: stats.filter(|stats| stats.runs > 1),
+        })
+    }
+    
+    /// Hash the test data once with a fresh hasher and return how long it took
+    fn time_hash(algorithm: &str, test_data: &[u8]) -> Result<Duration, HashUtilityError> {
         // Get hasher for this algorithm
         let mut hasher = HashRegistry::get_hasher(algorithm)
             .map_err(|e| HashUtilityError::BenchmarkFailed {
@@ -70,35 +191,119 @@ impl BenchmarkEngine {
         let start = Instant::now();
         hasher.update(test_data);
         let _ = hasher.finalize();
-        let duration = start.elapsed();
+        Ok(start.elapsed())
+    }
+    
+    /// Measure file hashing throughput of one algorithm at each of `SWEEP_BUFFER_SIZES`
+    /// 
+    /// The test data is written to a temporary file and streamed through
+    /// `HashComputer::with_buffer_size`, so each run pays the read syscall cost
+    /// of its buffer size.
+    /// 
+    /// # Arguments
+    /// * `algorithm` - Algorithm to hash with
+    /// * `data_size_mb` - Size of test data in megabytes
+    pub fn run_buffer_sweep(
+        &self,
+        algorithm: &str,
+        data_size_mb: usize,
+    ) -> Result<Vec<BufferSweepResult>, HashUtilityError> {
+        // Fail early on unknown algorithms, before writing any test data
+        HashRegistry::get_hasher(algorithm)?;
         
-        // Calculate throughput in MB/s
-        let throughput_mbps = calculate_throughput(data_size_mb, duration);
+        let data_path = std::env::temp_dir().join(format!("hash-buffer-sweep-{}.bin", std::process::id()));
+        fs::write(&data_path, generate_test_data(data_size_mb * 1024 * 1024))
+            .map_err(|e| HashUtilityError::from_io_error(e, "writing benchmark data", Some(data_path.clone())))?;
         
-        Ok(BenchmarkResult {
-            algorithm: algorithm.to_string(),
-            throughput_mbps,
-        })
+        let results = SWEEP_BUFFER_SIZES
+            .iter()
+            .map(|&buffer_size| {
+                let file = File::open(&data_path)
+                    .map_err(|e| HashUtilityError::from_io_error(e, "reading", Some(data_path.clone())))?;
+                let computer = HashComputer::with_buffer_size(buffer_size);
+                
+                let start = Instant::now();
+                computer.compute_hash_reader(file, algorithm)?;
+                let duration = start.elapsed();
+                
+                Ok(BufferSweepResult {
+                    buffer_size,
+                    throughput_mbps: calculate_throughput(data_size_mb, duration),
+                })
+            })
+            .collect();
+        
+        // Remove the test data whether or not the sweep succeeded
+        let _ = fs::remove_file(&data_path);
+        
+        results
+    }
+    
+    /// Display buffer sweep results in a table, marking the fastest buffer size
+    pub fn display_sweep_results(&self, algorithm: &str, results: &[BufferSweepResult]) {
+        let best = best_buffer_size(results);
+        
+        println!("\n{} throughput by buffer size", algorithm);
+        println!("\n{:<12} {:>15}", "Buffer", "Throughput (MB/s)");
+        println!("{}", "-".repeat(29));
+        
+        for result in results {
+            let marker = if Some(result.buffer_size) == best { "  <- best" } else { "" };
+            println!("{:<12} {:>15.2}{}", format_buffer_size(result.buffer_size), result.throughput_mbps, marker);
+        }
+        
+        if let Some(size) = best {
+            println!("\nFastest: --buffer-size {}", format_buffer_size(size));
+        }
+        println!();
     }
     
-    /// Display benchmark results in a formatted table
-    pub fn display_results(&self, results: &[BenchmarkResult]) {
+    /// Display benchmark results in a table ranked from fastest to slowest
+    /// 
+    /// Each row shows its throughput relative to BLAKE3. With `color`, the
+    /// fastest algorithm is shown in green and the slowest in red.
+    pub fn display_results(&self, results: &[BenchmarkResult], color: bool) {
         if results.is_empty() {
             println!("No benchmark results to display.");
             return;
         }
         
-        // Sort results by throughput (descending)
-        let mut sorted_results = results.to_vec();
-        sorted_results.sort_by(|a, b| b.throughput_mbps.partial_cmp(&a.throughput_mbps).unwrap());
+        let ranked = rank_results(results);
+        let blake3 = ranked
+            .iter()
+            .find(|result| HashRegistry::canonical_name(&result.algorithm) == "blake3")
+            .map(|result| result.throughput_mbps);
+        
+        // With --runs, the throughput is a mean and the spread gets its own columns
+        let with_spread = ranked.iter().any(|result| result.runs.is_some());
         
         // Print header
-        println!("\n{:<20} {:>15}", "Algorithm", "Throughput (MB/s)");
-        println!("{}", "-".repeat(37));
+        if with_spread {
+            println!("\n{:>4}  {:<20} {:>17} {:>10} {:>10} {:>10}", "Rank", "Algorithm", "Mean (MB/s)", "vs BLAKE3", "Min", "Max");
+            println!("{}", "-".repeat(76));
+        } else {
+            println!("\n{:>4}  {:<20} {:>17} {:>10}", "Rank", "Algorithm", "Throughput (MB/s)", "vs BLAKE3");
+            println!("{}", "-".repeat(54));
+        }
         
         // Print results
-        for result in sorted_results {
-            println!("{:<20} {:>15.2}", result.algorithm, result.throughput_mbps);
+        let last = ranked.len() - 1;
+        for (index, result) in ranked.iter().enumerate() {
+            let relative = match blake3 {
+                Some(baseline) if baseline > 0.0 => format!("{:.2}x", result.throughput_mbps / baseline),
+                _ => "-".to_string(),
+            };
+            let mut row = format!("{:>4}  {:<20} {:>17.2} {:>10}", index + 1, result.algorithm, result.throughput_mbps, relative);
+            if let Some(stats) = result.runs {
+                row.push_str(&format!(" {:>10.2} {:>10.2}", stats.min_mbps, stats.max_mbps));
+            }
+            
+            // A single result is both fastest and slowest, so leave it plain
+            match index {
+                0 if color && last > 0 => println!("{}{}{}", FASTEST_COLOR, row, RESET_COLOR),
+                i if color && i == last && last > 0 => println!("{}{}{}", SLOWEST_COLOR, row, RESET_COLOR),
+                _ => println!("{}", row),
+            }
         }
         
         println!();
@@ -132,6 +337,32 @@ fn generate_test_data(size_bytes: usize) -> Vec<u8> {
     data
 }
 
+/// Order benchmark results from fastest to slowest
+pub fn rank_results(results: &[BenchmarkResult]) -> Vec<BenchmarkResult> {
+    let mut ranked = results.to_vec();
+    ranked.sort_by(|a, b| b.throughput_mbps.total_cmp(&a.throughput_mbps));
+    ranked
+}
+
+/// Buffer size with the highest throughput in a sweep
+pub fn best_buffer_size(results: &[BufferSweepResult]) -> Option<usize> {
+    results
+        .iter()
+        .max_by(|a, b| a.throughput_mbps.total_cmp(&b.throughput_mbps))
+        .map(|r| r.buffer_size)
+}
+
+/// Format a buffer size the way `--buffer-size` accepts it (e.g. 64K, 4M)
+fn format_buffer_size(bytes: usize) -> String {
+    if bytes >= 1024 * 1024 && bytes.is_multiple_of(1024 * 1024) {
+        format!("{}M", bytes / (1024 * 1024))
+    } else if bytes >= 1024 && bytes.is_multiple_of(1024) {
+        format!("{}K", bytes / 1024)
+    } else {
+        bytes.to_string()
+    }
+}
+
 /// Calculate throughput in MB/s
 fn calculate_throughput(data_size_mb: usize, duration: Duration) -> f64 {
     let seconds = duration.as_secs_f64();
@@ -207,7 +438,88 @@ mod tests {
         for result in results {
             assert!(result.throughput_mbps > 0.0);
             assert!(!result.algorithm.is_empty());
+            assert!(result.runs.is_none());
+        }
+    }
+    
+    #[test]
+    fn test_run_benchmarks_with_runs_reports_spread() {
+        let engine = BenchmarkEngine::new().with_warmup(1).with_runs(3);
+        let results = engine.run_benchmarks(1).unwrap();
+        assert!(!results.is_empty());
+        
+        for result in results {
+            let stats = result.runs.expect("min/max/mean with more than one run");
+            assert_eq!(stats.runs, 3);
+            assert!(stats.min_mbps <= stats.mean_mbps && stats.mean_mbps <= stats.max_mbps);
+            assert_eq!(result.throughput_mbps, stats.mean_mbps);
         }
+        
+        assert!(BenchmarkEngine::new().with_runs(0).run_benchmarks(1).is_err());
+    }
+    
+    #[test]
+    fn test_run_stats_from_throughputs() {
+        let stats = RunStats::from_throughputs(&[100.0, 300.0, 200.0]).unwrap();
+        assert_eq!(stats, RunStats { runs: 3, min_mbps: 100.0, max_mbps: 300.0, mean_mbps: 200.0 });
+        assert!(RunStats::from_throughputs(&[]).is_none());
+    }
+    
+    #[test]
+    fn test_run_buffer_sweep() {
+        let engine = BenchmarkEngine::new();
+        let results = engine.run_buffer_sweep("sha256", 1).unwrap();
+        
+        // One result per swept size, in sweep order
+        let sizes: Vec<usize> = results.iter().map(|r| r.buffer_size).collect();
+        assert_eq!(sizes, SWEEP_BUFFER_SIZES.to_vec());
+        for result in &results {
+            assert!(result.throughput_mbps > 0.0);
+        }
+        
+        let best = best_buffer_size(&results).unwrap();
+        assert!(SWEEP_BUFFER_SIZES.contains(&best));
+    }
+    
+    #[test]
+    fn test_run_buffer_sweep_unknown_algorithm() {
+        let engine = BenchmarkEngine::new();
+        assert!(engine.run_buffer_sweep("nope", 1).is_err());
+    }
+    
+    #[test]
+    fn test_format_buffer_size() {
+        assert_eq!(format_buffer_size(16 * 1024), "16K");
+        assert_eq!(format_buffer_size(1024 * 1024), "1M");
+        assert_eq!(format_buffer_size(4 * 1024 * 1024), "4M");
+        assert_eq!(format_buffer_size(1000), "1000");
+    }
+    
+    #[test]
+    fn test_rank_results_fastest_first() {
+        let result = |algorithm: &str, throughput_mbps| BenchmarkResult {
+            algorithm: algorithm.to_string(),
+            throughput_mbps,
+            runs: None,
+        };
+        let results = vec![result("SHA-256", 500.0), result("BLAKE3", 4000.0), result("MD5", 700.0), result("SHA-512", 800.0)];
+        
+        let ranked = rank_results(&results);
+        let order: Vec<&str> = ranked.iter().map(|r| r.algorithm.as_str()).collect();
+        assert_eq!(order, ["BLAKE3", "SHA-512", "MD5", "SHA-256"]);
+        
+        // Same order as sorting the throughputs themselves
+        let mut throughputs: Vec<f64> = results.iter().map(|r| r.throughput_mbps).collect();
+        throughputs.sort_by(|a, b| b.total_cmp(a));
+        assert_eq!(ranked.iter().map(|r| r.throughput_mbps).collect::<Vec<_>>(), throughputs);
+    }
+    
+    #[test]
+    fn test_parse_color_mode() {
+        assert_eq!(ColorMode::parse("AUTO").unwrap(), ColorMode::Auto);
+        assert!(ColorMode::parse("always").unwrap().enabled());
+        assert!(!ColorMode::parse("never").unwrap().enabled());
+        assert!(ColorMode::parse("sometimes").is_err());
     }
     
     #[test]
@@ -215,6 +527,7 @@ mod tests {
         let result = BenchmarkResult {
             algorithm: "SHA-256".to_string(),
             throughput_mbps: 500.0,
+            runs: None,
         };
         
         assert_eq!(result.algorithm, "SHA-256");
@@ -0,0 +1,489 @@
+// Check module
+// Verifies files against a sha256sum-style checksum list (`hash --check FILE`)
+
+use std::fs;
+use std::path::{Path, PathBuf};
+use crate::database::DatabaseHandler;
+use crate::error::HashUtilityError;
+use crate::hash::{HashComputer, HashRegistry};
+
+/// Result of checking a single listed file
+#[derive(Debug, Clone, Copy, PartialEq, serde::Serialize)]
+#[serde(rename_all = "UPPERCASE")]
+pub enum CheckStatus {
+    Ok,
+    Failed,
+    /// The file could not be opened or read
+    Missing,
+}
+
+/// One checked line of the checksum list
+#[derive(Debug, Clone, serde::Serialize)]
+pub struct CheckEntry {
+    pub path: PathBuf,
+    pub status: CheckStatus,
+    pub expected: String,
+    /// Computed hash, absent when the file could not be read
+    pub actual: Option<String>,
+}
+
+/// Counts over all checked lines
+#[derive(Debug, Clone, Default, serde::Serialize)]
+pub struct CheckSummary {
+    pub total: usize,
+    pub ok: usize,
+    pub failed: usize,
+    pub missing: usize,
+    pub malformed_lines: usize,
+}
+
+/// Outcome of checking every line of a checksum list
+#[derive(Debug, Clone, serde::Serialize)]
+pub struct CheckReport {
+    /// Algorithm the list was checked with; comma-separated when inferred per line
+    pub algorithm: String,
+    pub entries: Vec<CheckEntry>,
+    pub summary: CheckSummary,
+    /// Missing files were skipped rather than treated as failures
+    #[serde(skip)]
+    pub ignore_missing: bool,
+    /// Malformed lines fail the check instead of only being warned about
+    #[serde(skip)]
+    pub strict: bool,
+}
+
+impl CheckReport {
+    /// Whether every listed file matched its expected hash
+    /// 
+    /// With `ignore_missing`, missing files only count against the result when
+    /// none of the listed files could be checked.
+    /// With `strict`, any improperly formatted line fails the check.
+    /// A list without a single checksum line never passes, as with coreutils.
+    pub fn passed(&self) -> bool {
+        if self.summary.total == 0 || self.summary.failed > 0 || (self.strict && self.summary.malformed_lines > 0) {
+            return false;
+        }
+        if self.ignore_missing {
+            self.summary.missing == 0 || self.summary.ok > 0
+        } else {
+            self.summary.missing == 0
+        }
+    }
+
+    /// Format the report as coreutils-style `<path>: OK` lines plus warnings
+    pub fn to_plain_text(&self) -> String {
+        let mut output = String::new();
+        for entry in &self.entries {
+            if self.ignore_missing && entry.status == CheckStatus::Missing {
+                continue;
+            }
+            let status = match entry.status {
+                CheckStatus::Ok => "OK",
+                CheckStatus::Failed => "FAILED",
+                CheckStatus::Missing => "FAILED open or read",
+            };
+            output.push_str(&format!("{}: {}\n", entry.path.display(), status));
+        }
+        if self.summary.total == 0 {
+            output.push_str("WARNING: no properly formatted checksum lines found\n");
+        } else if self.summary.malformed_lines > 0 {
+            output.push_str(&format!("WARNING: {} line(s) are improperly formatted\n", self.summary.malformed_lines));
+        }
+        if self.ignore_missing && self.summary.missing > 0 && self.summary.missing == self.summary.total {
+            output.push_str("WARNING: no file was verified\n");
+        } else if self.ignore_missing && self.summary.missing > 0 {
+            output.push_str(&format!("{} listed file(s) missing and ignored\n", self.summary.missing));
+        } else if self.summary.missing > 0 {
+            output.push_str(&format!("WARNING: {} listed file(s) could not be read\n", self.summary.missing));
+        }
+        if self.summary.failed > 0 {
+            output.push_str(&format!("WARNING: {} computed checksum(s) did NOT match\n", self.summary.failed));
+        }
+        output
+    }
+
+    /// Format the report as a JSON string
+    pub fn to_json(&self) -> Result<String, serde_json::Error> {
+        #[derive(serde::Serialize)]
+        struct JsonOutput<'a> {
+            #[serde(flatten)]
+            report: &'a CheckReport,
+            passed: bool,
+            timestamp: String,
+        }
+
+        serde_json::to_string_pretty(&JsonOutput {
+            report: self,
+            passed: self.passed(),
+            timestamp: chrono::Utc::now().to_rfc3339(),
+        })
+    }
+}
+
+/// Engine for checking files against a checksum list
+pub struct CheckEngine {
+    computer: HashComputer,
+    ignore_missing: bool,
+    strict: bool,
+    lenient: bool,
+}
+
+impl CheckEngine {
+    /// Create a new CheckEngine that hashes with the given computer
+    pub fn new(computer: HashComputer) -> Self {
+        Self {
+            computer,
+            ignore_missing: false,
+            strict: false,
+            lenient: false,
+        }
+    }
+
+    /// Skip listed files that do not exist instead of failing on them (like coreutils)
+    pub fn with_ignore_missing(mut self, ignore_missing: bool) -> Self {
+        self.ignore_missing = ignore_missing;
+        self
+    }
+
+    /// Fail on improperly formatted lines instead of only warning (like coreutils --strict)
+    pub fn with_strict(mut self, strict: bool) -> Self {
+        self.strict = strict;
+        self
+    }
+
+    /// Accept hand-edited lists: trailing `#` comments, extra whitespace and tabs between hash and path
+    pub fn with_lenient(mut self, lenient: bool) -> Self {
+        self.lenient = lenient;
+        self
+    }
+
+    /// Check every file listed in `checksum_file` against its recorded hash
+    ///
+    /// Relative paths are resolved against the current directory, as coreutils does.
+    /// Blank lines and `#` comments are skipped; malformed lines are warned about and counted.
+    /// Without an `algorithm`, each line's algorithm is inferred from its hash length
+    /// (md5, sha1, sha224, sha256, sha384 or sha512). A hash whose length doesn't fit the
+    /// algorithm is counted as malformed rather than reported as a mismatch.
+    pub fn check(&self, checksum_file: &Path, algorithm: Option<&str>) -> Result<CheckReport, HashUtilityError> {
+        let contents = fs::read_to_string(checksum_file)
+            .map_err(|e| HashUtilityError::from_io_error(e, "reading", Some(checksum_file.to_path_buf())))?;
+
+        let mut entries = Vec::new();
+        let mut summary = CheckSummary::default();
+        let mut used_algorithms: Vec<String> = algorithm.iter().map(|a| a.to_string()).collect();
+
+        for (line_num, line) in contents.lines().enumerate() {
+            let line = if self.lenient { DatabaseHandler::strip_lenient(line) } else { line.trim_end_matches('\r') };
+            if line.trim().is_empty() || line.starts_with('#') {
+                continue;
+            }
+
+            let parsed = if self.lenient {
+                DatabaseHandler::parse_coreutils_line_lenient(line)
+            } else {
+                DatabaseHandler::parse_coreutils_line(line)
+            };
+            let (expected, path) = match parsed {
+                Some(parsed) => parsed,
+                None => {
+                    eprintln!("Warning: {}: line {}: improperly formatted checksum line", checksum_file.display(), line_num + 1);
+                    summary.malformed_lines += 1;
+                    continue;
+                }
+            };
+
+            let candidates = match algorithm {
+                Some(algorithm) => vec![algorithm.to_string()],
+                None => candidate_algorithms(expected),
+            };
+            // A hash of the wrong length could never match, so don't report it as a changed file
+            if let Some(len) = algorithm.and_then(HashRegistry::expected_hex_len).filter(|&len| len != expected.len()) {
+                eprintln!(
+                    "Warning: {}: line {}: {}-character hash can't be {} ({} characters)",
+                    checksum_file.display(), line_num + 1, expected.len(), candidates[0], len
+                );
+                summary.malformed_lines += 1;
+                continue;
+            }
+            if candidates.is_empty() {
+                eprintln!(
+                    "Warning: {}: line {}: can't tell the algorithm of a {}-character hash, use -a",
+                    checksum_file.display(), line_num + 1, expected.len()
+                );
+                summary.malformed_lines += 1;
+                continue;
+            }
+
+            let path = PathBuf::from(path);
+            let mut checked = None;
+            for candidate in &candidates {
+                let outcome = match self.computer.compute_hash(&path, candidate) {
+                    Ok(result) if result.hash.eq_ignore_ascii_case(expected) => (CheckStatus::Ok, Some(result.hash)),
+                    Ok(result) => (CheckStatus::Failed, Some(result.hash)),
+                    Err(HashUtilityError::UnsupportedAlgorithm { algorithm }) => {
+                        return Err(HashUtilityError::UnsupportedAlgorithm { algorithm });
+                    }
+                    // With --ignore-missing only nonexistent files are skipped; unreadable ones still fail
+                    Err(_) if self.ignore_missing && path.exists() => (CheckStatus::Failed, None),
+                    Err(_) => (CheckStatus::Missing, None),
+                };
+                // Report the first candidate's result unless a later one matches
+                let mismatched = outcome.0 == CheckStatus::Failed && outcome.1.is_some();
+                if checked.is_none() || outcome.0 == CheckStatus::Ok {
+                    checked = Some((candidate, outcome));
+                }
+                if !mismatched {
+                    break;
+                }
+            }
+            let (line_algorithm, (status, actual)) = checked.expect("at least one candidate algorithm");
+            if !used_algorithms.contains(line_algorithm) {
+                used_algorithms.push(line_algorithm.clone());
+            }
+
+            summary.total += 1;
+            match status {
+                CheckStatus::Ok => summary.ok += 1,
+                CheckStatus::Failed => summary.failed += 1,
+                CheckStatus::Missing => summary.missing += 1,
+            }
+            entries.push(CheckEntry {
+                path,
+                status,
+                expected: expected.to_string(),
+                actual,
+            });
+        }
+
+        Ok(CheckReport {
+            algorithm: used_algorithms.join(","),
+            entries,
+            summary,
+            ignore_missing: self.ignore_missing,
+            strict: self.strict,
+        })
+    }
+}
+
+/// Algorithms a checksum line may have been written with when -a isn't given: the
+/// coreutils tool for its hash length, then this tool's own blake3 default
+fn candidate_algorithms(hash: &str) -> Vec<String> {
+    [DatabaseHandler::infer_algorithm_from_hash(hash), "blake3".to_string()]
+        .into_iter()
+        .filter(|algorithm| HashRegistry::expected_hex_len(algorithm) == Some(hash.len()))
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_check_json_reports_tampered_file() {
+        let test_dir = "test_check_json";
+        fs::create_dir_all(test_dir).unwrap();
+
+        let good = format!("{}/good.txt", test_dir);
+        let tampered = format!("{}/tampered.txt", test_dir);
+        fs::write(&good, b"hello world").unwrap();
+        fs::write(&tampered, b"hello world").unwrap();
+
+        let computer = HashComputer::new();
+        let hash = computer.compute_hash(Path::new(&good), "sha256").unwrap().hash;
+        let list = format!("{}/SHA256SUMS", test_dir);
+        fs::write(&list, format!("{hash}  {good}\n{hash}  {tampered}\n")).unwrap();
+
+        // Modify the file after the checksum was recorded
+        fs::write(&tampered, b"hello world!").unwrap();
+        let actual = computer.compute_hash(Path::new(&tampered), "sha256").unwrap().hash;
+
+        let report = CheckEngine::new(computer).check(Path::new(&list), Some("sha256")).unwrap();
+        assert!(!report.passed());
+        assert_eq!(report.summary.ok, 1);
+        assert_eq!(report.summary.failed, 1);
+
+        let json: serde_json::Value = serde_json::from_str(&report.to_json().unwrap()).unwrap();
+        let entries = json["entries"].as_array().unwrap();
+        assert_eq!(entries.len(), 2);
+        assert_eq!(entries[0]["status"], "OK");
+        assert_eq!(entries[1]["status"], "FAILED");
+        assert_eq!(entries[1]["path"], tampered.as_str());
+        assert_eq!(entries[1]["expected"], hash.as_str());
+        assert_eq!(entries[1]["actual"], actual.as_str());
+        assert_eq!(json["summary"]["failed"], 1);
+        assert_eq!(json["passed"], false);
+
+        fs::remove_dir_all(test_dir).unwrap();
+    }
+
+    #[test]
+    fn test_check_ignore_missing() {
+        let test_dir = "test_check_ignore_missing";
+        fs::create_dir_all(test_dir).unwrap();
+
+        let present = format!("{}/present.txt", test_dir);
+        let absent = format!("{}/absent.txt", test_dir);
+        fs::write(&present, b"hello world").unwrap();
+        let hash = HashComputer::new().compute_hash(Path::new(&present), "sha256").unwrap().hash;
+
+        let list = format!("{}/SHA256SUMS", test_dir);
+        fs::write(&list, format!("{hash}  {present}\n{hash}  {absent}\n")).unwrap();
+
+        // Without the flag the absent file fails the check
+        let report = CheckEngine::new(HashComputer::new()).check(Path::new(&list), Some("sha256")).unwrap();
+        assert!(!report.passed());
+        assert_eq!(report.summary.missing, 1);
+
+        let report = CheckEngine::new(HashComputer::new())
+            .with_ignore_missing(true)
+            .check(Path::new(&list), Some("sha256"))
+            .unwrap();
+        assert!(report.passed());
+        assert_eq!(report.summary.ok, 1);
+        assert_eq!(report.summary.missing, 1);
+        assert_eq!(report.entries[0].status, CheckStatus::Ok);
+        assert!(!report.to_plain_text().contains("absent.txt"));
+
+        // Still fails when nothing listed could be checked
+        fs::write(&list, format!("{hash}  {absent}\n")).unwrap();
+        let report = CheckEngine::new(HashComputer::new())
+            .with_ignore_missing(true)
+            .check(Path::new(&list), Some("sha256"))
+            .unwrap();
+        assert!(!report.passed());
+
+        fs::remove_dir_all(test_dir).unwrap();
+    }
+
+    #[test]
+    fn test_check_strict_fails_on_malformed_line() {
+        let test_dir = "test_check_strict";
+        fs::create_dir_all(test_dir).unwrap();
+
+        let file = format!("{}/file.txt", test_dir);
+        fs::write(&file, b"hello world").unwrap();
+        let hash = HashComputer::new().compute_hash(Path::new(&file), "sha256").unwrap().hash;
+
+        let list = format!("{}/SHA256SUMS", test_dir);
+        fs::write(&list, format!("{hash}  {file}\nthis line is not a checksum\n")).unwrap();
+
+        // Default: the malformed line is warned about and skipped
+        let report = CheckEngine::new(HashComputer::new()).check(Path::new(&list), Some("sha256")).unwrap();
+        assert!(report.passed());
+        assert_eq!(report.summary.ok, 1);
+        assert_eq!(report.summary.malformed_lines, 1);
+        assert!(report.to_plain_text().contains("improperly formatted"));
+
+        let report = CheckEngine::new(HashComputer::new())
+            .with_strict(true)
+            .check(Path::new(&list), Some("sha256"))
+            .unwrap();
+        assert!(!report.passed());
+        assert_eq!(report.summary.ok, 1);
+
+        fs::remove_dir_all(test_dir).unwrap();
+    }
+
+    #[test]
+    fn test_check_lenient_accepts_comments_and_spacing() {
+        let test_dir = "test_check_lenient";
+        fs::create_dir_all(test_dir).unwrap();
+
+        let file = format!("{}/file.txt", test_dir);
+        fs::write(&file, b"hello world").unwrap();
+        let hash = HashComputer::new().compute_hash(Path::new(&file), "sha256").unwrap().hash;
+
+        let list = format!("{}/SHA256SUMS", test_dir);
+        fs::write(&list, format!("  {hash}   {file}   # release build\n{hash}\t{file}\n    # indented comment\n")).unwrap();
+
+        // Default parsing treats every line as malformed
+        let report = CheckEngine::new(HashComputer::new()).check(Path::new(&list), Some("sha256")).unwrap();
+        assert_eq!(report.summary.ok, 0);
+        assert_eq!(report.summary.malformed_lines, 3);
+
+        let report = CheckEngine::new(HashComputer::new())
+            .with_lenient(true)
+            .with_strict(true)
+            .check(Path::new(&list), Some("sha256"))
+            .unwrap();
+        assert!(report.passed());
+        assert_eq!(report.summary.ok, 2);
+        assert_eq!(report.summary.malformed_lines, 0);
+
+        fs::remove_dir_all(test_dir).unwrap();
+    }
+
+    #[test]
+    fn test_check_fails_without_checksum_lines() {
+        let test_dir = "test_check_no_lines";
+        fs::create_dir_all(test_dir).unwrap();
+
+        let list = format!("{}/SHA256SUMS", test_dir);
+        fs::write(&list, "").unwrap();
+        let report = CheckEngine::new(HashComputer::new()).check(Path::new(&list), Some("sha256")).unwrap();
+        assert!(!report.passed());
+        assert!(report.to_plain_text().contains("no properly formatted checksum lines found"));
+
+        // Only malformed lines is no better than an empty list
+        fs::write(&list, "not a checksum\n").unwrap();
+        let report = CheckEngine::new(HashComputer::new()).check(Path::new(&list), Some("sha256")).unwrap();
+        assert_eq!(report.summary.total, 0);
+        assert!(!report.passed());
+
+        fs::remove_dir_all(test_dir).unwrap();
+    }
+
+    #[test]
+    fn test_check_infers_algorithm_from_hash_length() {
+        let test_dir = "test_check_infer";
+        fs::create_dir_all(test_dir).unwrap();
+
+        let file = format!("{}/file.txt", test_dir);
+        fs::write(&file, b"hello world").unwrap();
+        let computer = HashComputer::new();
+        let sha256 = computer.compute_hash(Path::new(&file), "sha256").unwrap().hash;
+        let md5 = computer.compute_hash(Path::new(&file), "md5").unwrap().hash;
+        let blake3 = computer.compute_hash(Path::new(&file), "blake3").unwrap().hash;
+
+        // Without -a, sha256sum and md5sum lines and this tool's own blake3 lines all check out
+        let list = format!("{}/SUMS", test_dir);
+        fs::write(&list, format!("{sha256}  {file}\n{md5}  {file}\n{blake3}  {file}\n")).unwrap();
+        let report = CheckEngine::new(HashComputer::new()).check(Path::new(&list), None).unwrap();
+        assert!(report.passed());
+        assert_eq!(report.summary.ok, 3);
+        assert_eq!(report.algorithm, "sha256,md5,blake3");
+
+        // A changed file is still reported against the coreutils algorithm for the length
+        fs::write(&file, b"hello world!").unwrap();
+        let report = CheckEngine::new(HashComputer::new()).check(Path::new(&list), None).unwrap();
+        assert_eq!(report.summary.failed, 3);
+        assert_eq!(report.entries[0].actual, Some(computer.compute_hash(Path::new(&file), "sha256").unwrap().hash));
+
+        fs::remove_dir_all(test_dir).unwrap();
+    }
+
+    #[test]
+    fn test_check_hash_length_mismatch_is_malformed() {
+        let test_dir = "test_check_length_mismatch";
+        fs::create_dir_all(test_dir).unwrap();
+
+        let file = format!("{}/file.txt", test_dir);
+        fs::write(&file, b"hello world").unwrap();
+        let sha512 = HashComputer::new().compute_hash(Path::new(&file), "sha512").unwrap().hash;
+
+        // A sha512 list checked with -a sha256 can't match, so no file is reported as FAILED
+        let list = format!("{}/SHA512SUMS", test_dir);
+        fs::write(&list, format!("{sha512}  {file}\n")).unwrap();
+        let report = CheckEngine::new(HashComputer::new()).check(Path::new(&list), Some("sha256")).unwrap();
+        assert_eq!(report.summary.failed, 0);
+        assert_eq!(report.summary.malformed_lines, 1);
+        assert!(!report.passed());
+
+        // Hash lengths no known algorithm produces are malformed when inferring too
+        fs::write(&list, format!("abcdef  {file}\n")).unwrap();
+        let report = CheckEngine::new(HashComputer::new()).check(Path::new(&list), None).unwrap();
+        assert_eq!(report.summary.malformed_lines, 1);
+
+        fs::remove_dir_all(test_dir).unwrap();
+    }
+}
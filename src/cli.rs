@@ -1,7 +1,8 @@
 // CLI interface module
 // Handles command-line argument parsing and validation
 
-use clap::{Parser, Subcommand};
+use clap::parser::ValueSource;
+use clap::{CommandFactory, FromArgMatches, Parser, Subcommand};
 use std::path::PathBuf;
 use crate::error::HashUtilityError;
 
@@ -17,26 +18,97 @@ use crate::error::HashUtilityError;
     hash file.txt                                           # uses blake3 by default\n  \
     hash file.txt -a sha256                                 # specify algorithm\n  \
     hash file.txt -f -a sha256                              # fast mode\n  \
+    hash file.txt -a sha256 --hash-only                     # print just the hash\n  \
+    hash bigfile.iso --buffer-size 4M                       # custom read buffer size\n  \
+    hash \"*.TXT\" --ignore-case                              # case-insensitive wildcard\n  \
+    hash https://example.com/file.iso -a sha256             # hash a download without saving it\n  \
+    hash --verify-url https://example.com/file.iso -a sha256  # check against file.iso.sha256\n  \
+    hash --archive backup.tar.xz -a sha256                  # hash archive members\n  \
+    hash dump.sql.gz --decompress -a sha256                 # hash the uncompressed contents\n  \
+    hash disk.img -a sha256 --checkpoint disk.ckpt          # resumable if interrupted\n  \
+    hash photos/ -a sha256 --combined                       # one hash for a whole directory\n  \
+    hash file.txt -a blake2b:256                            # same output as b2sum -l 256\n  \
+    hash file.txt -a blake2b --blake2-key 000102            # keyed BLAKE2b (hex key)\n  \
+    find . -name '*.iso' | hash --stdin-filenames -a sha256  # stream hashes as names arrive\n  \
+    hash --check SHA256SUMS                                 # algorithm inferred from hash length\n  \
+    hash --check SHA256SUMS -a sha256                       # verify files listed in a checksum file\n  \
+    hash --check SHA256SUMS -a sha256 --json                # per-file results as JSON\n  \
+    hash --check SHA256SUMS -a sha256 --ignore-missing      # skip files that don't exist\n  \
+    hash --check SHA256SUMS -a sha256 --strict              # fail on malformed lines\n  \
+    hash --check SHA256SUMS -a sha256 --lenient             # allow comments and extra spaces\n  \
     hash --text \"hello world\" -a sha256\n  \
     cat file.txt | hash -a sha256\n  \
     hash scan -d /path/to/dir -b hashes.txt                 # parallel by default\n  \
     hash scan -d /path/to/dir -b hashes.txt --hdd           # sequential for old HDDs\n  \
+    hash scan --root /ssd:parallel --root /hdd:sequential -b hashes.txt  # per-root mode for mixed storage\n  \
     hash scan -d /path/to/dir -b hashes.txt --format hashdeep  # hashdeep format\n  \
+    hash scan -d /path/to/dir -b hashes.tsv --format tsv    # tab-separated values\n  \
+    hash scan -d /path/to/dir -b SHA256SUMS -a sha256 --format coreutils  # checkable with sha256sum -c\n  \
+    hash scan -d /path/to/dir -b hashes.json --format json-db  # database as a single JSON document\n  \
     hash scan -d /path/to/dir -b hashes.txt --compress      # compressed output\n  \
+    hash scan -d /path/to/dir -b hashes.txt --direct-io     # bypass page cache (Linux)\n  \
+    hash scan -d /path/to/dir -b recent.txt --since 24h     # only files modified in last day\n  \
+    hash scan -d /path/to/dir -b hashes.txt --store-permissions  # record file modes\n  \
+    hash scan -d /path/to/dir -b hashes.txt --with-timestamp  # record modification times\n  \
     hash scan -d /path/to/dir -b hashes.txt --json          # JSON output\n  \
+    hash scan -d /path/to/dir -b hashes.txt --si            # sizes in MB/GB (powers of 1000)\n  \
+    hash scan -d . -b hashes.txt --skip-dir node_modules --skip-dir .git  # prune directories by name\n  \
+    hash scan -d . -b hashes.txt --exclude-from excludes.txt  # skip files matching listed globs\n  \
+    hash scan -d /path/to/dir -b hashes.txt --path-style dot  # write paths as ./dir/file.txt\n  \
+    hash scan -d /path/to/dir -b hashes.txt --with-header   # record how the database was created\n  \
+    hash scan -d /mnt/disk2 -b all.txt --append             # add to an existing database\n  \
+    hash scan -d /path/to/dir -b hashes.txt --verify-after  # re-read files to confirm the written hashes\n  \
+    hash scan -d /path/to/dir -b hashes.txt --warn-duplicates  # list files with identical content\n  \
+    hash scan -d /path/to/dir -b hashes.txt --count-only    # how many files and bytes a scan would hash\n  \
+    hash scan -d /abs/data -b hashes.txt --paths-relative-to-cwd  # store paths like data/file.txt\n  \
+    hash scan -d /path/to/dir -b hashes.txt --report-slow 10  # list the 10 slowest files\n  \
+    hash scan -d /path/to/dir -b hashes.txt --progress-by bytes # progress by bytes hashed\n  \
+    hash scan -d /path/to/dir -b hashes.txt --progress-template '{wide_bar} {pos}/{len}'  # custom bar\n  \
+    hash scan -d /media -b hashes.txt -a sha256 --algorithm-map mp4=xxh3  # per-extension algorithm\n  \
+    hash scan -d /path/to/dir -b layout.txt --structure-only  # names, sizes and mtimes only (no reads)\n  \
+    hash scan -d /path/to/dir -b hashes.txt --hash-symlink-targets  # also record where symlinks point\n  \
     hash verify -b hashes.txt -d /path/to/dir               # parallel by default\n  \
     hash verify -b hashes.txt -d /path/to/dir --hdd         # sequential for old HDDs\n  \
+    hash verify -b hashes.txt -d /path/to/dir --no-progress # no progress bar\n  \
+    hash verify -b hashes.txt -d /path/to/dir --verify-sample 5 --seed 42  # spot-check 5% of entries\n  \
+    hash verify -b hashes.tsv -d /path/to/dir --compare-sizes  # sizes only, no hashing\n  \
+    hash verify -b hashes.txt -d /etc --file /etc/passwd    # check one file\n  \
+    hash verify -b mac-hashes.txt -d /backup --normalize-unicode nfc  # match macOS (NFD) names\n  \
+    hash verify -b hashes.txt -d /path/to/dir -a sha256 --strict  # reject other algorithms\n  \
+    hash verify -b \"hashes.txt.*\" -d /path/to/dir --merge-databases  # shards as one database\n  \
+    hash verify -b links.txt -d /srv --skip-dir cache       # same link filters as the scan\n  \
+    hash prune -b hashes.txt -d /path/to/dir                # drop entries for deleted files\n  \
     hash compare db1.txt db2.txt                              # compare two databases\n  \
     hash compare db1.txt db2.txt -b report.txt --format json  # JSON output\n  \
+    hash compare db1.txt db2.txt --format unified             # diff-style -/+ lines\n  \
+    hash compare db1.txt db2.txt --skip-duplicates            # diff only, no duplicate scan\n  \
+    hash compare db1.txt db2.txt --canonicalize-paths --ref-dir /data  # match symlinks to their targets\n  \
+    hash compare mac.txt linux.txt --normalize-unicode nfc    # match NFD and NFC file names\n  \
+    hash compare-dir db.txt /path/to/dir                      # diff a database against the directory now\n  \
+    hash compare-multi mon.txt tue.txt wed.txt                # track changes across snapshots\n  \
     hash dedup -d /path/to/dir                              # find duplicates\n  \
     hash dedup -d /path/to/dir --fast --json                # fast mode with JSON output\n  \
+    hash dedup -d /path/to/dir --min-duplicate-size 1M      # ignore duplicates under 1 MiB\n  \
+    hash dedup -d /path/to/dir --count-only                 # count files without hashing\n  \
+    hash dedup -d /path/to/dir --sort count                 # most-copied files first\n  \
+    hash dedup -d /path/to/dir --json-ndjson -b dups.ndjson # stream one JSON line per group\n  \
+    hash dedup -d /path/to/dir --spill                      # group on disk to bound memory\n  \
     hash benchmark\n  \
-    hash list")]
+    hash benchmark --color never                            # ranked table without highlighting\n  \
+    hash benchmark --warmup 2 --runs 5                      # mean/min/max of 5 runs after 2 warmups\n  \
+    hash benchmark --sweep -a sha256                        # throughput vs buffer size\n  \
+    hash list\n  \
+    hash list --pq-only                                     # post-quantum algorithms only\n  \
+    hash list --show-aliases                                # include accepted alternative names\n  \
+    hash selftest                                           # verify all algorithms\n  \
+    hash equal a.iso b.iso                                  # exit 0 if identical, 1 if not\n  \
+    hash equal notes.txt --text \"hello\" -a sha256          # file against a string")]
 pub struct Cli {
     #[command(subcommand)]
     pub command: Option<Command>,
     
-    /// File or wildcard pattern to hash (e.g., *.txt, file?.bin, [abc]*.jpg)
+    /// File or wildcard pattern to hash (e.g., *.txt, file?.bin, [abc]*.jpg, img.{jpg,png}),
+    /// or an http(s):// URL whose download is streamed through the hasher
     /// If omitted, reads from stdin for piping
     #[arg(value_name = "FILE")]
     pub file: Option<String>,
@@ -45,10 +117,46 @@ pub struct Cli {
     #[arg(short = 't', long = "text", value_name = "TEXT", conflicts_with = "file")]
     pub text: Option<String>,
     
+    /// Download an http(s):// URL and check it against its sibling checksum file (<URL>.<ALGORITHM>)
+    #[arg(long = "verify-url", value_name = "URL", conflicts_with_all = ["file", "text"])]
+    pub verify_url: Option<String>,
+    
+    /// Hash each file inside a tar or zip archive (.tar, .tar.xz, .zip) without extracting it
+    #[arg(long = "archive", value_name = "FILE", conflicts_with_all = ["file", "text", "verify_url"])]
+    pub archive: Option<PathBuf>,
+    
+    /// Check files against a checksum list (`<hash>  <path>` lines, as written by sha256sum or this tool)
+    #[arg(short = 'c', long = "check", value_name = "FILE", conflicts_with_all = ["file", "text", "verify_url", "archive"])]
+    pub check: Option<PathBuf>,
+    
+    /// Act as a long-lived hashing worker: read file names from stdin, one per line, and print `<hash>  <path>` for each as soon as it is hashed
+    #[arg(long = "stdin-filenames", conflicts_with_all = ["file", "text", "verify_url", "archive", "check", "json"])]
+    pub stdin_filenames: bool,
+    
+    /// With --check, skip listed files that don't exist instead of reporting them as FAILED
+    #[arg(long = "ignore-missing", requires = "check", conflicts_with_all = ["file", "text", "verify_url", "archive"])]
+    pub ignore_missing: bool,
+    
+    /// With --check, fail on improperly formatted checksum lines instead of warning and skipping them
+    #[arg(long = "strict", requires = "check", conflicts_with_all = ["file", "text", "verify_url", "archive"])]
+    pub strict: bool,
+    
+    /// With --check, accept hand-edited lines: trailing '#' comments and any spaces or tabs between hash and path
+    #[arg(long = "lenient", requires = "check", conflicts_with_all = ["file", "text", "verify_url", "archive"])]
+    pub lenient: bool,
+    
+    /// Expected hash for --verify-url, instead of fetching the checksum file
+    #[arg(long = "expected", value_name = "HASH", requires = "verify_url", conflicts_with_all = ["file", "text"])]
+    pub expected: Option<String>,
+    
     /// Hash algorithm to use: md5, sha1, sha256, sha512, sha3-256, blake2b, blake3, xxh3, etc. (use 'hash list' to see all)
     #[arg(short = 'a', long = "algorithm", value_name = "ALGORITHM", default_value = "blake3")]
     pub algorithms: Vec<String>,
     
+    /// Whether -a was given on the command line rather than left at its default (set by `parse_args`)
+    #[arg(skip)]
+    pub algorithm_given: bool,
+    
     /// Write output to file instead of stdout
     #[arg(short = 'b', long = "output", value_name = "FILE")]
     pub output: Option<PathBuf>,
@@ -60,6 +168,70 @@ pub struct Cli {
     /// Output results as JSON instead of plain text
     #[arg(long = "json")]
     pub json: bool,
+    
+    /// Hash at most this many bytes of the input, with optional K/M/G suffix
+    /// (e.g., imaging part of a block device, or an endless source like /dev/zero)
+    #[arg(long = "count", value_name = "BYTES", value_parser = parse_size, conflicts_with_all = ["text", "fast", "archive"])]
+    pub count: Option<usize>,
+    
+    /// Skip this many bytes at the start of the input before hashing, with optional K/M/G suffix
+    #[arg(long = "skip", value_name = "BYTES", value_parser = parse_size, conflicts_with_all = ["text", "fast", "archive"])]
+    pub skip: Option<usize>,
+    
+    /// Don't end plain-text output with a newline (e.g. when the hash is pasted or compared as a string)
+    #[arg(long = "no-trailing-newline", conflicts_with = "json")]
+    pub no_trailing_newline: bool,
+    
+    /// Print only the hash, one per line, without the file path (e.g. for `$(hash file --hash-only)`)
+    #[arg(long = "hash-only", conflicts_with = "json")]
+    pub hash_only: bool,
+    
+    /// Key for keyed BLAKE2 hashing, as hex (up to 64 bytes for blake2b, 32 for blake2s)
+    #[arg(long = "blake2-key", value_name = "HEX", conflicts_with = "fast")]
+    pub blake2_key: Option<String>,
+    
+    /// Hash the decompressed contents of .gz, .xz and .zst files instead of their raw bytes
+    #[arg(long = "decompress", conflicts_with_all = ["text", "fast", "archive"])]
+    pub decompress: bool,
+    
+    /// Save hashing progress of a single large file here, and resume from it if it exists
+    /// (sha224, sha256, sha384 and sha512 only; removed once the hash is complete)
+    #[arg(long = "checkpoint", value_name = "FILE", requires = "file", conflicts_with_all = ["text", "fast", "archive", "stdin_filenames", "skip", "count", "decompress"])]
+    pub checkpoint: Option<PathBuf>,
+    
+    /// How often to update the checkpoint, in bytes hashed, with optional K/M/G suffix
+    #[arg(long = "checkpoint-interval", value_name = "BYTES", value_parser = parse_size, default_value = "1G", requires = "checkpoint")]
+    pub checkpoint_interval: usize,
+    
+    /// Hash a directory's files as one stream, in sorted path order with each path included, giving a single hash
+    #[arg(long = "combined", requires = "file", conflicts_with_all = ["text", "fast", "archive", "stdin_filenames", "skip", "count", "decompress", "checkpoint"])]
+    pub combined: bool,
+    
+    /// Read buffer size in bytes, with optional K/M suffix (e.g., 64K, 4M); chosen from file size if omitted
+    #[arg(long = "buffer-size", value_name = "SIZE", value_parser = parse_size)]
+    pub buffer_size: Option<usize>,
+    
+    /// Read files with O_DIRECT to avoid filling the page cache (Linux; falls back to normal reads)
+    #[cfg(unix)]
+    #[arg(long = "direct-io")]
+    pub direct_io: bool,
+    
+    /// Fold extended attributes into file hashes (Linux/macOS; hashes become non-portable)
+    #[cfg(any(target_os = "linux", target_os = "macos"))]
+    #[arg(long = "include-xattrs")]
+    pub include_xattrs: bool,
+    
+    /// Match wildcard patterns case-insensitively (default on Windows and macOS)
+    #[arg(long = "ignore-case", global = true)]
+    pub ignore_case: bool,
+    
+    /// How to report errors on stderr: 'text' (default) or 'json' ({"error": {"kind", "message", "path"}} on one line)
+    #[arg(long = "error-format", value_name = "FORMAT", default_value = "text", global = true)]
+    pub error_format: String,
+    
+    /// Report sizes in decimal units (kB, MB, GB: powers of 1000) instead of binary (KiB, MiB, GiB)
+    #[arg(long = "si", global = true)]
+    pub si: bool,
 }
 
 /// Available commands
@@ -70,14 +242,22 @@ pub enum Command {
     /// Recursively scans a directory and computes hashes for all files,
     /// storing the results in a plain text database file.
     Scan {
-        /// Directory or wildcard pattern to scan recursively (e.g., data/*/hashes)
-        #[arg(short = 'd', long = "directory", value_name = "DIR")]
-        directory: String,
+        /// Directory or wildcard pattern to scan recursively (e.g., data/*/hashes, data/**/logs)
+        #[arg(short = 'd', long = "directory", value_name = "DIR", required_unless_present = "root")]
+        directory: Option<String>,
+        
+        /// Directory to scan with its own mode, 'parallel' (SSD) or 'sequential' (HDD) (repeatable, e.g. --root /mnt/hdd:sequential)
+        #[arg(long = "root", value_name = "DIR:MODE")]
+        root: Vec<String>,
         
         /// Hash algorithm to use (use 'hash list' to see all available algorithms)
         #[arg(short = 'a', long = "algorithm", value_name = "ALGORITHM", default_value = "blake3")]
         algorithm: String,
         
+        /// Use a different algorithm for files with this extension (repeatable, e.g. --algorithm-map mp4=xxh3; standard and tsv formats)
+        #[arg(long = "algorithm-map", value_name = "EXT=ALGO")]
+        algorithm_map: Vec<String>,
+        
         /// Database file path to create (use .xz extension with --compress for automatic compression)
         #[arg(short = 'b', long = "database", value_name = "FILE")]
         database: PathBuf,
@@ -90,14 +270,109 @@ pub enum Command {
         #[arg(short = 'f', long = "fast")]
         fast: bool,
         
-        /// Output format: 'standard' (hash filepath) or 'hashdeep' (CSV format with size, hash, filename)
+        /// Output format: 'standard' (hash filepath), 'hashdeep' (CSV format with size, hash, filename), 'tsv' (tab-separated hash, algorithm, size, path),
+        /// 'coreutils' (hash  filepath, checkable with sha256sum -c from the scanned directory), or 'json-db' (one JSON array of entries)
         #[arg(long = "format", value_name = "FORMAT", default_value = "standard")]
         format: String,
         
+        /// With --format coreutils, mark entries as binary ('<hash> *<path>') instead of text ('<hash>  <path>')
+        #[arg(long = "binary")]
+        binary: bool,
+        
         /// Output results as JSON with metadata instead of plain text
         #[arg(long = "json")]
         json: bool,
         
+        /// Only hash files modified after this time: a duration ago (e.g., 24h, 7d) or an RFC3339 timestamp
+        #[arg(long = "since", value_name = "DURATION|TIMESTAMP")]
+        since: Option<String>,
+        
+        /// Record file permissions (Unix mode bits, or read-only flag on Windows) so verify can detect mode changes
+        #[arg(long = "store-permissions")]
+        store_permissions: bool,
+        
+        /// Record each file's modification time (RFC3339) for audit trails; verify reports timestamp drift
+        #[arg(long = "with-timestamp")]
+        with_timestamp: bool,
+        
+        /// Skip directories with this exact name anywhere in the tree (repeatable, e.g. --skip-dir node_modules)
+        #[arg(long = "skip-dir", value_name = "NAME")]
+        skip_dir: Vec<String>,
+        
+        /// Leave out files matching glob patterns listed in this file, one per line ('#' comments allowed, e.g. *.tmp)
+        #[arg(long = "exclude-from", value_name = "FILE")]
+        exclude_from: Option<PathBuf>,
+        
+        /// How relative paths are written: 'native' (default, platform separators), 'bare' (dir/file.txt), or 'dot' (./dir/file.txt)
+        #[arg(long = "path-style", value_name = "STYLE", default_value = "native")]
+        path_style: String,
+        
+        /// Store paths relative to the current directory instead of the scanned directory (files outside it keep absolute paths)
+        #[arg(long = "paths-relative-to-cwd", conflicts_with = "verify_after")]
+        paths_relative_to_cwd: bool,
+        
+        /// Hash each file's name, size and modification time instead of its contents (standard format only; verify recomputes the same way)
+        #[arg(long = "structure-only", conflicts_with = "fast")]
+        structure_only: bool,
+        
+        /// Record symlinks (normally skipped) by hashing the path each one points to, tagged 'symlink' so verify catches repointed links (standard format only)
+        #[arg(long = "hash-symlink-targets")]
+        hash_symlink_targets: bool,
+        
+        /// Stop hashing new files after this long (e.g., 30m, 2h) and keep the partial database
+        #[arg(long = "timeout", value_name = "DURATION")]
+        timeout: Option<String>,
+        
+        /// Report how much hashing time went to reading files versus updating the hasher (reads are never memory-mapped while profiling)
+        #[arg(long = "profile")]
+        profile: bool,
+        
+        /// List the N files that took longest to hash at the end of the scan (to spot huge files or slow mounts)
+        #[arg(long = "report-slow", value_name = "N")]
+        report_slow: Option<usize>,
+        
+        /// Advance the progress bar per file ('files') or by bytes hashed ('bytes', steadier with mixed file sizes)
+        #[arg(long = "progress-by", value_name = "UNIT", default_value = "files")]
+        progress_by: String,
+        
+        /// Draw the progress bar with this indicatif template instead, e.g. '{wide_bar} {pos}/{len} {msg}' (the default is used if it doesn't parse)
+        #[arg(long = "progress-template", value_name = "TEMPLATE")]
+        progress_template: Option<String>,
+        
+        /// Once the database is written, re-read every file and confirm its hash to catch unstable storage (single directory only)
+        #[arg(long = "verify-after")]
+        verify_after: bool,
+        
+        /// After the scan, list groups of files with identical content in the summary (no separate dedup run needed)
+        #[arg(long = "warn-duplicates", conflicts_with = "structure_only")]
+        warn_duplicates: bool,
+        
+        /// Only count the files that would be hashed and their total size; nothing is hashed or written
+        #[arg(long = "count-only", conflicts_with_all = ["verify_after", "warn_duplicates", "append", "compress"])]
+        count_only: bool,
+        
+        /// Start the database with '#' comment lines recording format version, creation time, algorithm, and command line (standard format only)
+        #[arg(long = "with-header")]
+        with_header: bool,
+        
+        /// Add entries to the end of the database instead of overwriting it, so several scans accumulate in one file
+        #[arg(long = "append", visible_alias = "output-append", conflicts_with = "compress")]
+        append: bool,
+        
+        /// Drop earlier entries for paths written more than once, keeping the most recent and warning if the hashes differ (on by default with --append)
+        #[arg(long = "dedupe-entries")]
+        dedupe_entries: bool,
+        
+        /// Read files with O_DIRECT to avoid filling the page cache (Linux; falls back to normal reads)
+        #[cfg(unix)]
+        #[arg(long = "direct-io")]
+        direct_io: bool,
+        
+        /// Fold extended attributes into file hashes (Linux/macOS; hashes become non-portable)
+        #[cfg(any(target_os = "linux", target_os = "macos"))]
+        #[arg(long = "include-xattrs")]
+        include_xattrs: bool,
+        
         /// Compress output database with LZMA compression (creates .xz file, saves ~70% space)
         #[arg(long = "compress")]
         compress: bool,
@@ -108,9 +383,9 @@ pub enum Command {
     /// Compares current file hashes against a stored database to detect
     /// modifications, deletions, and new files.
     Verify {
-        /// Hash database file or wildcard pattern (e.g., *.db, hashes?.txt)
+        /// Hash database file or wildcard pattern (e.g., *.db, hashes?.txt), or '-' to read it from stdin
         /// Supports standard, hashdeep, and compressed .xz formats
-        #[arg(short = 'b', long = "database", value_name = "FILE")]
+        #[arg(short = 'b', long = "database", value_name = "FILE", allow_hyphen_values = true)]
         database: String,
         
         /// Directory or wildcard pattern to verify (e.g., data/*, dir?)
@@ -124,6 +399,108 @@ pub enum Command {
         /// Output verification report as JSON instead of plain text
         #[arg(long = "json")]
         json: bool,
+        
+        /// Fold extended attributes into file hashes (Linux/macOS; hashes become non-portable)
+        #[cfg(any(target_os = "linux", target_os = "macos"))]
+        #[arg(long = "include-xattrs")]
+        include_xattrs: bool,
+        
+        /// Hide the progress bar (it is also hidden when stderr is not a terminal)
+        #[arg(long = "no-progress")]
+        no_progress: bool,
+        
+        /// Only check files present in both the database and the directory
+        /// Missing and new files are not reported; exits with status 1 if any content differs
+        #[arg(long = "intersection-only")]
+        intersection_only: bool,
+        
+        /// Rewrite database paths starting with OLD to start with NEW before looking them up (repeatable, e.g. --path-map /data=/mnt/restore)
+        #[arg(long = "path-map", value_name = "OLD=NEW")]
+        path_map: Vec<String>,
+        
+        /// Re-read files that mismatch and only report them as changed if the second read agrees (for flaky storage)
+        #[arg(long = "two-pass")]
+        two_pass: bool,
+        
+        /// Match paths that differ only in Unicode normalization by normalizing both to 'nfc' or 'nfd' (e.g. a database written on macOS)
+        #[arg(long = "normalize-unicode", value_name = "FORM")]
+        normalize_unicode: Option<String>,
+        
+        /// Quick check: verify only this percentage of database entries, picked at random, and estimate the rest
+        #[arg(long = "verify-sample", value_name = "PERCENT")]
+        verify_sample: Option<f64>,
+        
+        /// Seed for --verify-sample, to check the same subset again (default: random, shown in the report)
+        #[arg(long = "seed", value_name = "N", requires = "verify_sample")]
+        seed: Option<u64>,
+        
+        /// Quick check: compare only file sizes and existence, without hashing (needs a tsv or hashdeep database)
+        #[arg(long = "compare-sizes", conflicts_with_all = ["verify_sample", "two_pass"])]
+        compare_sizes: bool,
+        
+        /// Verify only this file against its database entry, for spot-checks of critical files
+        #[arg(long = "file", value_name = "PATH", conflicts_with_all = ["verify_sample", "compare_sizes"])]
+        file: Option<PathBuf>,
+        
+        /// Parse hand-edited databases leniently: trailing '#' comments and any spaces or tabs between columns (standard and coreutils formats)
+        #[arg(long = "lenient", conflicts_with = "compare_sizes")]
+        lenient: bool,
+        
+        /// Treat all databases matched by -b as one, e.g. shards from 'split', instead of verifying each on its own
+        #[arg(long = "merge-databases", conflicts_with = "compare_sizes")]
+        merge_databases: bool,
+        
+        /// Algorithm the database is expected to use; entries hashed with another algorithm are reported with a warning
+        #[arg(short = 'a', long = "algorithm", value_name = "ALG")]
+        algorithm: Option<String>,
+        
+        /// Fail before hashing if any entry was hashed with an algorithm other than --algorithm
+        #[arg(long = "strict", requires = "algorithm")]
+        strict: bool,
+        
+        /// Skip directories with this name when looking for unrecorded symlinks, as given to scan --hash-symlink-targets (repeatable)
+        #[arg(long = "skip-dir", value_name = "NAME")]
+        skip_dir: Vec<String>,
+        
+        /// Leave out symlinks matching the glob patterns in this file when looking for unrecorded ones, as given to scan
+        #[arg(long = "exclude-from", value_name = "FILE")]
+        exclude_from: Option<PathBuf>,
+    },
+    
+    /// Remove stale entries from a hash database
+    /// 
+    /// Drops entries whose files no longer exist in the directory and writes
+    /// the remaining entries to a new database (or back in place).
+    Prune {
+        /// Hash database file to prune (standard format, supports .xz compressed files)
+        #[arg(short = 'b', long = "database", value_name = "FILE")]
+        database: PathBuf,
+        
+        /// Directory the database paths are relative to
+        #[arg(short = 'd', long = "directory", value_name = "DIR")]
+        directory: PathBuf,
+        
+        /// Write the pruned database here instead of overwriting the input
+        #[arg(short = 'o', long = "output", value_name = "FILE")]
+        output: Option<PathBuf>,
+    },
+    
+    /// Split a hash database into shards
+    /// 
+    /// Partitions entries by a stable hash of their path, so each file always
+    /// lands in the same shard, for verifying parts of a tree on separate machines.
+    Split {
+        /// Hash database file to split (standard format, supports .xz compressed files)
+        #[arg(short = 'b', long = "database", value_name = "FILE")]
+        database: PathBuf,
+        
+        /// Number of shards to write
+        #[arg(short = 'n', long = "shards", value_name = "N")]
+        shards: usize,
+        
+        /// Write shards to PREFIX.1 .. PREFIX.N (default: the database path without .xz)
+        #[arg(short = 'o', long = "output", value_name = "PREFIX")]
+        output: Option<PathBuf>,
     },
     
     /// Benchmark hash algorithms
@@ -138,6 +515,26 @@ pub enum Command {
         /// Output benchmark results as JSON instead of formatted table
         #[arg(long = "json")]
         json: bool,
+        
+        /// Measure one algorithm at several buffer sizes (16K to 4M) to help pick --buffer-size
+        #[arg(long = "sweep", visible_alias = "chunk-report")]
+        sweep: bool,
+        
+        /// Algorithm to use with --sweep
+        #[arg(short = 'a', long = "algorithm", value_name = "ALGORITHM", default_value = "blake3", requires = "sweep")]
+        algorithm: String,
+        
+        /// Hash the test data this many times with each algorithm, untimed, before measuring it
+        #[arg(long = "warmup", value_name = "N", default_value = "0", conflicts_with = "sweep")]
+        warmup: usize,
+        
+        /// Time each algorithm this many times and report the mean, min and max
+        #[arg(long = "runs", value_name = "M", default_value = "1", conflicts_with = "sweep")]
+        runs: usize,
+        
+        /// Highlight the fastest and slowest algorithms: auto (when stdout is a terminal), always, or never
+        #[arg(long = "color", value_name = "WHEN", default_value = "auto")]
+        color: String,
     },
     
     /// List available hash algorithms
@@ -148,6 +545,18 @@ pub enum Command {
         /// Output algorithm list as JSON instead of formatted table
         #[arg(long = "json")]
         json: bool,
+        
+        /// Show only cryptographic algorithms
+        #[arg(long = "crypto-only")]
+        crypto_only: bool,
+        
+        /// Show only post-quantum resistant algorithms
+        #[arg(long = "pq-only")]
+        pq_only: bool,
+        
+        /// Show accepted alternative names for each algorithm
+        #[arg(long = "show-aliases")]
+        show_aliases: bool,
     },
     
     /// Compare two hash databases
@@ -168,7 +577,87 @@ pub enum Command {
         #[arg(short = 'b', long = "output", value_name = "FILE")]
         output: Option<PathBuf>,
 
-        /// Output format: 'plain-text' (default), 'json', or 'hashdeep'
+        /// Output format: 'plain-text' (default), 'json', 'hashdeep', or 'unified' (diff-style -/+ lines)
+        #[arg(long = "format", value_name = "FORMAT", default_value = "plain-text")]
+        format: String,
+
+        /// Skip looking for duplicate hashes within each database (faster on large databases)
+        #[arg(long = "skip-duplicates")]
+        skip_duplicates: bool,
+
+        /// Hide the progress bar (it is also hidden when stderr is not a terminal)
+        #[arg(long = "no-progress")]
+        no_progress: bool,
+
+        /// Fail instead of warning when the databases were hashed with different algorithms
+        #[arg(long = "strict")]
+        strict: bool,
+        
+        /// Resolve stored paths to their canonical form on disk before matching, so symlinked and real paths line up
+        #[arg(long = "canonicalize-paths")]
+        canonicalize_paths: bool,
+        
+        /// Directory that relative database paths are resolved against for --canonicalize-paths (default: current directory)
+        #[arg(long = "ref-dir", value_name = "DIR", requires = "canonicalize_paths")]
+        ref_dir: Option<PathBuf>,
+        
+        /// Match paths that differ only in Unicode normalization by normalizing both to 'nfc' or 'nfd' (macOS vs Linux/Windows names)
+        #[arg(long = "normalize-unicode", value_name = "FORM")]
+        normalize_unicode: Option<String>,
+    },
+    
+    /// Compare a hash database against a live directory
+    /// 
+    /// Hashes the directory in memory and reports changed, moved, removed and
+    /// added files as `compare` would, with the directory as the second database.
+    CompareDir {
+        /// Hash database file path (supports .xz compressed files)
+        #[arg(value_name = "DATABASE")]
+        database: PathBuf,
+
+        /// Directory to hash and compare against the database
+        #[arg(value_name = "DIRECTORY")]
+        directory: PathBuf,
+
+        /// Write comparison report to file instead of stdout
+        #[arg(short = 'b', long = "output", value_name = "FILE")]
+        output: Option<PathBuf>,
+
+        /// Output format: 'plain-text' (default), 'json', 'hashdeep', or 'unified' (diff-style -/+ lines)
+        #[arg(long = "format", value_name = "FORMAT", default_value = "plain-text")]
+        format: String,
+
+        /// Sequential mode for old HDDs (hashes files one by one instead of in parallel)
+        #[arg(long = "hdd")]
+        hdd: bool,
+
+        /// Skip directories with this exact name anywhere in the tree (repeatable, e.g. --skip-dir node_modules)
+        #[arg(long = "skip-dir", value_name = "NAME")]
+        skip_dir: Vec<String>,
+
+        /// Skip looking for duplicate hashes (faster on large trees)
+        #[arg(long = "skip-duplicates")]
+        skip_duplicates: bool,
+
+        /// Hide the progress bar (it is also hidden when stderr is not a terminal)
+        #[arg(long = "no-progress")]
+        no_progress: bool,
+    },
+    
+    /// Compare more than two hash databases
+    /// 
+    /// Shows the hash of every file in each snapshot, in the order given, and
+    /// flags files that changed, appeared, or disappeared at any step.
+    CompareMulti {
+        /// Database files in snapshot order (supports .xz compressed files)
+        #[arg(value_name = "DATABASE", num_args = 2.., required = true)]
+        databases: Vec<PathBuf>,
+
+        /// Write comparison report to file instead of stdout
+        #[arg(short = 'b', long = "output", value_name = "FILE")]
+        output: Option<PathBuf>,
+
+        /// Output format: 'plain-text' (default) or 'json'
         #[arg(long = "format", value_name = "FORMAT", default_value = "plain-text")]
         format: String,
     },
@@ -178,6 +667,31 @@ pub enum Command {
     /// Shows the current version of the Hash Utility.
     Version,
     
+    /// Run built-in self-test against known-answer vectors
+    /// 
+    /// Hashes a fixed test vector with every supported algorithm and compares
+    /// the result against the published digest. Exits non-zero if any algorithm fails.
+    Selftest,
+    
+    /// Check whether two inputs have identical content
+    /// 
+    /// Hashes both inputs with the same algorithm and exits with status 0 if they
+    /// are identical, 1 if they differ, or 2 if an input can't be read (like cmp).
+    /// An input is a file, '-' for stdin, or --text.
+    Equal {
+        /// Files to compare ('-' reads stdin); give one file with --text, or none with two --text
+        #[arg(value_name = "INPUT", num_args = 0..=2)]
+        inputs: Vec<String>,
+        
+        /// Text to compare as an input instead of a file (repeatable)
+        #[arg(short = 't', long = "text", value_name = "TEXT")]
+        text: Vec<String>,
+        
+        /// Hash algorithm used for both inputs
+        #[arg(short = 'a', long = "algorithm", value_name = "ALGORITHM", default_value = "blake3")]
+        algorithm: String,
+    },
+    
     /// Find duplicate files in a directory
     /// 
     /// Scans a directory recursively and identifies files with identical content
@@ -198,6 +712,46 @@ pub enum Command {
         /// Output results as JSON instead of plain text
         #[arg(long = "json")]
         json: bool,
+        
+        /// Stream newline-delimited JSON: one object per duplicate group as it is found, then a stats object
+        #[arg(long = "json-ndjson", conflicts_with_all = ["json", "sort", "reverse"])]
+        json_ndjson: bool,
+        
+        /// Skip directories with this exact name anywhere in the tree (repeatable, e.g. --skip-dir node_modules)
+        #[arg(long = "skip-dir", value_name = "NAME")]
+        skip_dir: Vec<String>,
+        
+        /// Keep the duplicate under this directory (repeatable, highest priority first; relative to DIR)
+        #[arg(long = "prefer-dir", value_name = "DIR")]
+        prefer_dir: Vec<PathBuf>,
+        
+        /// Cache file of hashes from earlier runs: unchanged files reuse their cached hash, and the cache is updated afterwards
+        #[arg(long = "continue-from", value_name = "CACHE")]
+        continue_from: Option<PathBuf>,
+        
+        /// Ignore duplicate groups whose files are smaller than this size, e.g. 4K or 1M
+        #[arg(long = "min-duplicate-size", value_name = "BYTES", value_parser = parse_size)]
+        min_duplicate_size: Option<usize>,
+        
+        /// Order duplicate groups by: wasted, size, count (largest first) or hash
+        #[arg(long = "sort", value_name = "KEY", default_value = "wasted")]
+        sort: String,
+        
+        /// Reverse the order of duplicate groups
+        #[arg(long = "reverse")]
+        reverse: bool,
+        
+        /// Draw the progress bar with this indicatif template instead, e.g. '{wide_bar} {pos}/{len} {msg}' (the default is used if it doesn't parse)
+        #[arg(long = "progress-template", value_name = "TEMPLATE")]
+        progress_template: Option<String>,
+        
+        /// Only count the files that would be hashed and their total size; nothing is hashed
+        #[arg(long = "count-only", conflicts_with_all = ["output", "json_ndjson", "continue_from"])]
+        count_only: bool,
+        
+        /// Group files in temporary files on disk instead of memory, for trees with millions of files (only files sharing a size are hashed)
+        #[arg(long = "spill", conflicts_with_all = ["continue_from", "count_only"])]
+        spill: bool,
     },
 
     /// Analyze a hash database and display statistics
@@ -218,6 +772,20 @@ pub enum Command {
         #[arg(short = 'b', long = "output", value_name = "FILE")]
         output: Option<PathBuf>,
     },
+
+    /// Summarize what a hash database covers
+    ///
+    /// Reports file count, size distribution (hashdeep and tsv formats),
+    /// algorithms used, duplicate groups, and a breakdown by file extension.
+    Stats {
+        /// Hash database file path (supports .xz compressed files)
+        #[arg(short = 'd', long = "database", value_name = "FILE")]
+        database: PathBuf,
+
+        /// Output results as JSON instead of plain text
+        #[arg(long = "json")]
+        json: bool,
+    },
 }
 
 /// Parse command-line arguments
@@ -228,7 +796,13 @@ pub enum Command {
 /// # Errors
 /// Returns an error if arguments are invalid or missing required values
 pub fn parse_args() -> Result<Cli, HashUtilityError> {
-    match Cli::try_parse() {
+    let parsed = Cli::command().try_get_matches().and_then(|matches| {
+        let mut cli = Cli::from_arg_matches(&matches)?;
+        // --check infers the algorithm from the list when -a is left at its default
+        cli.algorithm_given = matches.value_source("algorithms") == Some(ValueSource::CommandLine);
+        Ok(cli)
+    });
+    match parsed {
         Ok(cli) => Ok(cli),
         Err(e) => {
             // Check if this is a help or version request (which clap treats as "errors")
@@ -248,6 +822,66 @@ pub fn parse_args() -> Result<Cli, HashUtilityError> {
     }
 }
 
+impl Cli {
+    /// Whether `--direct-io` was given for hash mode or the scan command
+    /// 
+    /// Always false on non-Unix platforms, where the flag doesn't exist.
+    pub fn direct_io_requested(&self) -> bool {
+        #[cfg(unix)]
+        {
+            match &self.command {
+                Some(Command::Scan { direct_io, .. }) => *direct_io,
+                None => self.direct_io,
+                _ => false,
+            }
+        }
+        #[cfg(not(unix))]
+        {
+            false
+        }
+    }
+    
+    /// Whether `--include-xattrs` was given for hash mode, scan or verify
+    /// 
+    /// Always false on platforms without extended attribute support.
+    pub fn include_xattrs_requested(&self) -> bool {
+        #[cfg(any(target_os = "linux", target_os = "macos"))]
+        {
+            match &self.command {
+                Some(Command::Scan { include_xattrs, .. }) => *include_xattrs,
+                Some(Command::Verify { include_xattrs, .. }) => *include_xattrs,
+                None => self.include_xattrs,
+                _ => false,
+            }
+        }
+        #[cfg(not(any(target_os = "linux", target_os = "macos")))]
+        {
+            false
+        }
+    }
+}
+
+/// Parse a byte size such as "65536", "64K", or "4M" (binary units)
+pub fn parse_size(value: &str) -> Result<usize, String> {
+    let trimmed = value.trim();
+    let (digits, multiplier) = match trimmed.chars().last() {
+        Some('k') | Some('K') => (&trimmed[..trimmed.len() - 1], 1024),
+        Some('m') | Some('M') => (&trimmed[..trimmed.len() - 1], 1024 * 1024),
+        Some('g') | Some('G') => (&trimmed[..trimmed.len() - 1], 1024 * 1024 * 1024),
+        _ => (trimmed, 1),
+    };
+    
+    let number: usize = digits.parse()
+        .map_err(|_| format!("Invalid size '{}': expected a number with optional K, M or G suffix", value))?;
+    
+    if number == 0 {
+        return Err("Size must be greater than zero".to_string());
+    }
+    
+    number.checked_mul(multiplier)
+        .ok_or_else(|| format!("Size '{}' is too large", value))
+}
+
 // Re-export HashUtilityError as CliError for backward compatibility
 pub type CliError = HashUtilityError;
 
@@ -345,8 +979,8 @@ mod tests {
         let cli = Cli::try_parse_from(args).unwrap();
         
         match cli.command {
-            Some(Command::Scan { directory, algorithm, database, hdd, fast, format, json, compress }) => {
-                assert_eq!(directory, "/path/to/dir");
+            Some(Command::Scan { directory, algorithm, database, hdd, fast, format, json, compress, .. }) => {
+                assert_eq!(directory.as_deref(), Some("/path/to/dir"));
                 assert_eq!(algorithm, "sha256");
                 assert_eq!(database, PathBuf::from("hashes.txt"));
                 assert_eq!(hdd, false);
@@ -365,8 +999,8 @@ mod tests {
         let cli = Cli::try_parse_from(args).unwrap();
         
         match cli.command {
-            Some(Command::Scan { directory, algorithm, database, hdd, fast, format, json, compress }) => {
-                assert_eq!(directory, "/path/to/dir");
+            Some(Command::Scan { directory, algorithm, database, hdd, fast, format, json, compress, .. }) => {
+                assert_eq!(directory.as_deref(), Some("/path/to/dir"));
                 assert_eq!(algorithm, "sha256");
                 assert_eq!(database, PathBuf::from("hashes.txt"));
                 assert_eq!(hdd, true);
@@ -385,8 +1019,8 @@ mod tests {
         let cli = Cli::try_parse_from(args).unwrap();
         
         match cli.command {
-            Some(Command::Scan { directory, algorithm, database, hdd, fast, format, json, compress }) => {
-                assert_eq!(directory, "/path/to/dir");
+            Some(Command::Scan { directory, algorithm, database, hdd, fast, format, json, compress, .. }) => {
+                assert_eq!(directory.as_deref(), Some("/path/to/dir"));
                 assert_eq!(algorithm, "sha256");
                 assert_eq!(database, PathBuf::from("hashes.txt"));
                 assert_eq!(hdd, true);
@@ -405,7 +1039,7 @@ mod tests {
         let cli = Cli::try_parse_from(args).unwrap();
         
         match cli.command {
-            Some(Command::Verify { database, directory, hdd, json }) => {
+            Some(Command::Verify { database, directory, hdd, json, .. }) => {
                 assert_eq!(database, "hashes.txt");
                 assert_eq!(directory, "/path/to/dir");
                 assert_eq!(hdd, false); // parallel by default
@@ -415,13 +1049,43 @@ mod tests {
         }
     }
     
+    #[test]
+    fn test_parse_hash_byte_range() {
+        let cli = Cli::try_parse_from(vec!["hash", "/dev/sda", "--count", "4M"]).unwrap();
+        assert_eq!(cli.file, Some("/dev/sda".to_string()));
+        assert_eq!(cli.count, Some(4 * 1024 * 1024));
+        
+        let cli = Cli::try_parse_from(vec!["hash", "file.bin", "--skip", "10", "--count", "10"]).unwrap();
+        assert_eq!(cli.skip, Some(10));
+        assert_eq!(cli.count, Some(10));
+        
+        // A byte range makes no sense with sampled or text input
+        assert!(Cli::try_parse_from(vec!["hash", "file.bin", "--count", "10", "--fast"]).is_err());
+        assert!(Cli::try_parse_from(vec!["hash", "--text", "hi", "--count", "10"]).is_err());
+        assert!(Cli::try_parse_from(vec!["hash", "file.bin", "--skip", "1K", "--fast"]).is_err());
+    }
+    
+    #[test]
+    fn test_parse_verify_from_stdin() {
+        let args = vec!["hash", "verify", "-b", "-", "-d", "."];
+        let cli = Cli::try_parse_from(args).unwrap();
+        
+        match cli.command {
+            Some(Command::Verify { database, directory, .. }) => {
+                assert_eq!(database, "-");
+                assert_eq!(directory, ".");
+            }
+            _ => panic!("Expected Verify command"),
+        }
+    }
+    
     #[test]
     fn test_parse_verify_command_long_flags() {
         let args = vec!["hash", "verify", "--database", "hashes.txt", "--directory", "/path/to/dir"];
         let cli = Cli::try_parse_from(args).unwrap();
         
         match cli.command {
-            Some(Command::Verify { database, directory, hdd, json }) => {
+            Some(Command::Verify { database, directory, hdd, json, .. }) => {
                 assert_eq!(database, "hashes.txt");
                 assert_eq!(directory, "/path/to/dir");
                 assert_eq!(hdd, false); // parallel by default
@@ -437,7 +1101,7 @@ mod tests {
         let cli = Cli::try_parse_from(args).unwrap();
         
         match cli.command {
-            Some(Command::Verify { database, directory, hdd, json }) => {
+            Some(Command::Verify { database, directory, hdd, json, .. }) => {
                 assert_eq!(database, "hashes.txt");
                 assert_eq!(directory, "/path/to/dir");
                 assert_eq!(hdd, true); // sequential mode
@@ -453,7 +1117,7 @@ mod tests {
         let cli = Cli::try_parse_from(args).unwrap();
         
         match cli.command {
-            Some(Command::Benchmark { size_mb, json }) => {
+            Some(Command::Benchmark { size_mb, json, .. }) => {
                 assert_eq!(size_mb, 100); // default value
                 assert_eq!(json, false);
             }
@@ -467,7 +1131,7 @@ mod tests {
         let cli = Cli::try_parse_from(args).unwrap();
         
         match cli.command {
-            Some(Command::Benchmark { size_mb, json }) => {
+            Some(Command::Benchmark { size_mb, json, .. }) => {
                 assert_eq!(size_mb, 50);
                 assert_eq!(json, false);
             }
@@ -481,7 +1145,7 @@ mod tests {
         let cli = Cli::try_parse_from(args).unwrap();
         
         match cli.command {
-            Some(Command::Benchmark { size_mb, json }) => {
+            Some(Command::Benchmark { size_mb, json, .. }) => {
                 assert_eq!(size_mb, 200);
                 assert_eq!(json, false);
             }
@@ -495,8 +1159,26 @@ mod tests {
         let cli = Cli::try_parse_from(args).unwrap();
         
         match cli.command {
-            Some(Command::List { json }) => {
+            Some(Command::List { json, crypto_only, pq_only, show_aliases }) => {
                 assert_eq!(json, false);
+                assert!(!crypto_only);
+                assert!(!pq_only);
+                assert!(!show_aliases);
+            }
+            _ => panic!("Expected List command"),
+        }
+    }
+    
+    #[test]
+    fn test_parse_list_command_with_filters() {
+        let args = vec!["hash", "list", "--crypto-only", "--pq-only"];
+        let cli = Cli::try_parse_from(args).unwrap();
+        
+        match cli.command {
+            Some(Command::List { json, crypto_only, pq_only, .. }) => {
+                assert!(!json);
+                assert!(crypto_only);
+                assert!(pq_only);
             }
             _ => panic!("Expected List command"),
         }
@@ -610,8 +1292,8 @@ mod tests {
         let cli = Cli::try_parse_from(args).unwrap();
         
         match cli.command {
-            Some(Command::Scan { directory, algorithm, database, hdd, fast, format, json, compress }) => {
-                assert_eq!(directory, "/path/to/dir");
+            Some(Command::Scan { directory, algorithm, database, hdd, fast, format, json, compress, .. }) => {
+                assert_eq!(directory.as_deref(), Some("/path/to/dir"));
                 assert_eq!(algorithm, "sha256");
                 assert_eq!(database, PathBuf::from("hashes.txt"));
                 assert_eq!(hdd, false);
@@ -630,8 +1312,8 @@ mod tests {
         let cli = Cli::try_parse_from(args).unwrap();
         
         match cli.command {
-            Some(Command::Scan { directory, algorithm, database, hdd, fast, format, json, compress }) => {
-                assert_eq!(directory, "/path/to/dir");
+            Some(Command::Scan { directory, algorithm, database, hdd, fast, format, json, compress, .. }) => {
+                assert_eq!(directory.as_deref(), Some("/path/to/dir"));
                 assert_eq!(algorithm, "sha256");
                 assert_eq!(database, PathBuf::from("hashes.txt"));
                 assert_eq!(hdd, false);
@@ -650,8 +1332,8 @@ mod tests {
         let cli = Cli::try_parse_from(args).unwrap();
         
         match cli.command {
-            Some(Command::Scan { directory, algorithm, database, hdd, fast, format, json, compress }) => {
-                assert_eq!(directory, "/path/to/dir");
+            Some(Command::Scan { directory, algorithm, database, hdd, fast, format, json, compress, .. }) => {
+                assert_eq!(directory.as_deref(), Some("/path/to/dir"));
                 assert_eq!(algorithm, "sha256");
                 assert_eq!(database, PathBuf::from("hashes.txt"));
                 assert_eq!(hdd, true);
@@ -718,8 +1400,8 @@ mod tests {
         let cli = Cli::try_parse_from(args).unwrap();
         
         match cli.command {
-            Some(Command::Scan { directory, algorithm, database, hdd, fast, format, json, compress }) => {
-                assert_eq!(directory, "/path/to/dir");
+            Some(Command::Scan { directory, algorithm, database, hdd, fast, format, json, compress, .. }) => {
+                assert_eq!(directory.as_deref(), Some("/path/to/dir"));
                 assert_eq!(algorithm, "sha256");
                 assert_eq!(database, PathBuf::from("hashes.txt"));
                 assert_eq!(hdd, false);
@@ -738,8 +1420,8 @@ mod tests {
         let cli = Cli::try_parse_from(args).unwrap();
         
         match cli.command {
-            Some(Command::Scan { directory, algorithm, database, hdd, fast, format, json, compress }) => {
-                assert_eq!(directory, "/path/to/dir");
+            Some(Command::Scan { directory, algorithm, database, hdd, fast, format, json, compress, .. }) => {
+                assert_eq!(directory.as_deref(), Some("/path/to/dir"));
                 assert_eq!(algorithm, "sha256");
                 assert_eq!(database, PathBuf::from("hashes.txt"));
                 assert_eq!(hdd, true);
@@ -771,7 +1453,7 @@ mod tests {
         let cli = Cli::try_parse_from(args).unwrap();
         
         match cli.command {
-            Some(Command::Compare { database1, database2, output, format }) => {
+            Some(Command::Compare { database1, database2, output, format, .. }) => {
                 assert_eq!(database1, PathBuf::from("db1.txt"));
                 assert_eq!(database2, PathBuf::from("db2.txt"));
                 assert_eq!(output, None);
@@ -787,7 +1469,7 @@ mod tests {
         let cli = Cli::try_parse_from(args).unwrap();
         
         match cli.command {
-            Some(Command::Compare { database1, database2, output, format }) => {
+            Some(Command::Compare { database1, database2, output, format, .. }) => {
                 assert_eq!(database1, PathBuf::from("db1.txt"));
                 assert_eq!(database2, PathBuf::from("db2.txt"));
                 assert_eq!(output, Some(PathBuf::from("report.txt")));
@@ -803,7 +1485,7 @@ mod tests {
         let cli = Cli::try_parse_from(args).unwrap();
         
         match cli.command {
-            Some(Command::Compare { database1, database2, output, format }) => {
+            Some(Command::Compare { database1, database2, output, format, .. }) => {
                 assert_eq!(database1, PathBuf::from("db1.txt"));
                 assert_eq!(database2, PathBuf::from("db2.txt"));
                 assert_eq!(output, Some(PathBuf::from("report.txt")));
@@ -819,7 +1501,7 @@ mod tests {
         let cli = Cli::try_parse_from(args).unwrap();
         
         match cli.command {
-            Some(Command::Compare { database1, database2, output, format }) => {
+            Some(Command::Compare { database1, database2, output, format, .. }) => {
                 assert_eq!(database1, PathBuf::from("db1.txt"));
                 assert_eq!(database2, PathBuf::from("db2.txt"));
                 assert_eq!(output, None);
@@ -835,7 +1517,7 @@ mod tests {
         let cli = Cli::try_parse_from(args).unwrap();
 
         match cli.command {
-            Some(Command::Compare { database1, database2, output, format }) => {
+            Some(Command::Compare { database1, database2, output, format, .. }) => {
                 assert_eq!(database1, PathBuf::from("db1.txt"));
                 assert_eq!(database2, PathBuf::from("db2.txt"));
                 assert_eq!(output, None);
@@ -845,13 +1527,52 @@ mod tests {
         }
     }
     
+    #[test]
+    fn test_parse_compare_multi_command() {
+        let args = vec!["hash", "compare-multi", "db1.txt", "db2.txt", "db3.txt", "--format", "json"];
+        let cli = Cli::try_parse_from(args).unwrap();
+
+        match cli.command {
+            Some(Command::CompareMulti { databases, output, format }) => {
+                assert_eq!(databases, vec![
+                    PathBuf::from("db1.txt"),
+                    PathBuf::from("db2.txt"),
+                    PathBuf::from("db3.txt"),
+                ]);
+                assert_eq!(output, None);
+                assert_eq!(format, "json");
+            }
+            _ => panic!("Expected CompareMulti command"),
+        }
+
+        // A single database is rejected
+        let args = vec!["hash", "compare-multi", "db1.txt"];
+        assert!(Cli::try_parse_from(args).is_err());
+    }
+    
+    #[test]
+    fn test_parse_compare_dir_command() {
+        let args = vec!["hash", "compare-dir", "hashes.db", "data", "--hdd", "--format", "unified"];
+        let cli = Cli::try_parse_from(args).unwrap();
+
+        match cli.command {
+            Some(Command::CompareDir { database, directory, format, hdd, .. }) => {
+                assert_eq!(database, PathBuf::from("hashes.db"));
+                assert_eq!(directory, PathBuf::from("data"));
+                assert_eq!(format, "unified");
+                assert!(hdd);
+            }
+            _ => panic!("Expected CompareDir command"),
+        }
+    }
+    
     #[test]
     fn test_parse_compare_command_with_all_options() {
         let args = vec!["hash", "compare", "db1.txt", "db2.txt", "-b", "report.json", "--format", "json"];
         let cli = Cli::try_parse_from(args).unwrap();
         
         match cli.command {
-            Some(Command::Compare { database1, database2, output, format }) => {
+            Some(Command::Compare { database1, database2, output, format, .. }) => {
                 assert_eq!(database1, PathBuf::from("db1.txt"));
                 assert_eq!(database2, PathBuf::from("db2.txt"));
                 assert_eq!(output, Some(PathBuf::from("report.json")));
@@ -867,7 +1588,7 @@ mod tests {
         let cli = Cli::try_parse_from(args).unwrap();
         
         match cli.command {
-            Some(Command::Compare { database1, database2, output, format }) => {
+            Some(Command::Compare { database1, database2, output, format, .. }) => {
                 assert_eq!(database1, PathBuf::from("db1.txt.xz"));
                 assert_eq!(database2, PathBuf::from("db2.txt.xz"));
                 assert_eq!(output, None);
@@ -898,4 +1619,404 @@ mod tests {
             _ => panic!("Expected Version command"),
         }
     }
+    
+    #[test]
+    fn test_parse_equal_command() {
+        let cli = Cli::try_parse_from(vec!["hash", "equal", "a.txt", "-", "-a", "sha256"]).unwrap();
+        match cli.command {
+            Some(Command::Equal { inputs, text, algorithm }) => {
+                assert_eq!(inputs, ["a.txt", "-"]);
+                assert!(text.is_empty());
+                assert_eq!(algorithm, "sha256");
+            }
+            _ => panic!("Expected Equal command"),
+        }
+        
+        let cli = Cli::try_parse_from(vec!["hash", "equal", "a.txt", "--text", "hello"]).unwrap();
+        assert!(matches!(cli.command, Some(Command::Equal { ref text, .. }) if text == &["hello"]));
+        
+        assert!(Cli::try_parse_from(vec!["hash", "equal", "a", "b", "c"]).is_err());
+    }
+    
+    #[test]
+    fn test_parse_selftest_command() {
+        let args = vec!["hash", "selftest"];
+        let cli = Cli::try_parse_from(args).unwrap();
+        
+        match cli.command {
+            Some(Command::Selftest) => {
+                // Success - selftest command parsed correctly
+            }
+            _ => panic!("Expected Selftest command"),
+        }
+    }
+    
+    #[test]
+    fn test_parse_hash_command_with_buffer_size() {
+        let args = vec!["hash", "test.txt", "--buffer-size", "64K"];
+        let cli = Cli::try_parse_from(args).unwrap();
+        
+        assert_eq!(cli.buffer_size, Some(64 * 1024));
+        
+        let args = vec!["hash", "test.txt"];
+        let cli = Cli::try_parse_from(args).unwrap();
+        
+        assert_eq!(cli.buffer_size, None);
+    }
+    
+    #[test]
+    fn test_parse_size() {
+        assert_eq!(parse_size("4096"), Ok(4096));
+        assert_eq!(parse_size("64K"), Ok(64 * 1024));
+        assert_eq!(parse_size("64k"), Ok(64 * 1024));
+        assert_eq!(parse_size("4M"), Ok(4 * 1024 * 1024));
+        assert_eq!(parse_size("1G"), Ok(1024 * 1024 * 1024));
+        assert!(parse_size("0").is_err());
+        assert!(parse_size("abc").is_err());
+        assert!(parse_size("K").is_err());
+        assert!(parse_size("").is_err());
+    }
+    
+    #[cfg(unix)]
+    #[test]
+    fn test_parse_direct_io_flag() {
+        let args = vec!["hash", "test.txt", "--direct-io"];
+        let cli = Cli::try_parse_from(args).unwrap();
+        assert!(cli.direct_io);
+        
+        let args = vec!["hash", "scan", "-d", "/data", "-b", "db.txt", "--direct-io"];
+        let cli = Cli::try_parse_from(args).unwrap();
+        match cli.command {
+            Some(Command::Scan { direct_io, .. }) => assert!(direct_io),
+            _ => panic!("Expected Scan command"),
+        }
+    }
+    
+    #[test]
+    fn test_parse_scan_command_with_since() {
+        let args = vec!["hash", "scan", "-d", "/data", "-b", "db.txt", "--since", "24h"];
+        let cli = Cli::try_parse_from(args).unwrap();
+        
+        match cli.command {
+            Some(Command::Scan { since, .. }) => {
+                assert_eq!(since, Some("24h".to_string()));
+            }
+            _ => panic!("Expected Scan command"),
+        }
+        
+        let args = vec!["hash", "scan", "-d", "/data", "-b", "db.txt"];
+        let cli = Cli::try_parse_from(args).unwrap();
+        
+        match cli.command {
+            Some(Command::Scan { since, .. }) => assert_eq!(since, None),
+            _ => panic!("Expected Scan command"),
+        }
+    }
+    
+    #[test]
+    fn test_parse_prune_command() {
+        let args = vec!["hash", "prune", "-b", "db.txt", "-d", "/data", "-o", "pruned.txt"];
+        let cli = Cli::try_parse_from(args).unwrap();
+        
+        match cli.command {
+            Some(Command::Prune { database, directory, output }) => {
+                assert_eq!(database, PathBuf::from("db.txt"));
+                assert_eq!(directory, PathBuf::from("/data"));
+                assert_eq!(output, Some(PathBuf::from("pruned.txt")));
+            }
+            _ => panic!("Expected Prune command"),
+        }
+        
+        let args = vec!["hash", "prune", "-b", "db.txt", "-d", "/data"];
+        let cli = Cli::try_parse_from(args).unwrap();
+        
+        match cli.command {
+            Some(Command::Prune { output, .. }) => assert_eq!(output, None),
+            _ => panic!("Expected Prune command"),
+        }
+    }
+    
+    #[test]
+    fn test_parse_split_command() {
+        let args = vec!["hash", "split", "-b", "db.txt", "-n", "4"];
+        let cli = Cli::try_parse_from(args).unwrap();
+        
+        match cli.command {
+            Some(Command::Split { database, shards, output }) => {
+                assert_eq!(database, PathBuf::from("db.txt"));
+                assert_eq!(shards, 4);
+                assert_eq!(output, None);
+            }
+            _ => panic!("Expected Split command"),
+        }
+    }
+    
+    #[test]
+    fn test_parse_scan_command_with_store_permissions() {
+        let args = vec!["hash", "scan", "-d", "/data", "-b", "db.txt", "--store-permissions"];
+        let cli = Cli::try_parse_from(args).unwrap();
+        
+        match cli.command {
+            Some(Command::Scan { store_permissions, .. }) => assert!(store_permissions),
+            _ => panic!("Expected Scan command"),
+        }
+        
+        let args = vec!["hash", "scan", "-d", "/data", "-b", "db.txt"];
+        let cli = Cli::try_parse_from(args).unwrap();
+        
+        match cli.command {
+            Some(Command::Scan { store_permissions, .. }) => assert!(!store_permissions),
+            _ => panic!("Expected Scan command"),
+        }
+    }
+    
+    #[test]
+    fn test_parse_scan_command_with_timestamp() {
+        let args = vec!["hash", "scan", "-d", "/data", "-b", "db.txt", "--with-timestamp"];
+        let cli = Cli::try_parse_from(args).unwrap();
+        
+        match cli.command {
+            Some(Command::Scan { with_timestamp, .. }) => assert!(with_timestamp),
+            _ => panic!("Expected Scan command"),
+        }
+    }
+    
+    #[test]
+    fn test_parse_scan_command_with_roots() {
+        let args = vec!["hash", "scan", "--root", "/ssd:parallel", "--root", "/hdd:sequential", "-b", "hashes.txt"];
+        let cli = Cli::try_parse_from(args).unwrap();
+        
+        match cli.command {
+            Some(Command::Scan { directory, root, .. }) => {
+                assert_eq!(directory, None);
+                assert_eq!(root, vec!["/ssd:parallel".to_string(), "/hdd:sequential".to_string()]);
+            }
+            _ => panic!("Expected Scan command"),
+        }
+        
+        // Either -d or at least one --root is required
+        assert!(Cli::try_parse_from(vec!["hash", "scan", "-b", "hashes.txt"]).is_err());
+    }
+    
+    #[cfg(any(target_os = "linux", target_os = "macos"))]
+    #[test]
+    fn test_parse_include_xattrs_flag() {
+        let cli = Cli::try_parse_from(vec!["hash", "file.txt", "--include-xattrs"]).unwrap();
+        assert!(cli.include_xattrs_requested());
+        
+        let cli = Cli::try_parse_from(vec!["hash", "scan", "-d", "/data", "-b", "db.txt", "--include-xattrs"]).unwrap();
+        assert!(cli.include_xattrs_requested());
+        
+        let cli = Cli::try_parse_from(vec!["hash", "verify", "-b", "db.txt", "-d", "/data", "--include-xattrs"]).unwrap();
+        assert!(cli.include_xattrs_requested());
+        
+        let cli = Cli::try_parse_from(vec!["hash", "verify", "-b", "db.txt", "-d", "/data"]).unwrap();
+        assert!(!cli.include_xattrs_requested());
+    }
+    
+    #[test]
+    fn test_parse_si_flag() {
+        let cli = Cli::try_parse_from(vec!["hash", "scan", "-d", "/data", "-b", "db.txt", "--si"]).unwrap();
+        assert!(cli.si);
+        
+        let cli = Cli::try_parse_from(vec!["hash", "--si", "analyze", "-d", "db.txt"]).unwrap();
+        assert!(cli.si);
+        
+        let cli = Cli::try_parse_from(vec!["hash", "dedup", "-d", "/data"]).unwrap();
+        assert!(!cli.si);
+    }
+    
+    #[test]
+    fn test_parse_ignore_case_flag() {
+        let cli = Cli::try_parse_from(vec!["hash", "*.TXT", "--ignore-case"]).unwrap();
+        assert!(cli.ignore_case);
+        
+        let cli = Cli::try_parse_from(vec!["hash", "scan", "-d", "/data/*", "-b", "db.txt", "--ignore-case"]).unwrap();
+        assert!(cli.ignore_case);
+        
+        let cli = Cli::try_parse_from(vec!["hash", "verify", "-b", "db.txt", "-d", "/data"]).unwrap();
+        assert!(!cli.ignore_case);
+    }
+    
+    #[test]
+    fn test_parse_no_progress_flag() {
+        let cli = Cli::try_parse_from(vec!["hash", "verify", "-b", "db.txt", "-d", "/data", "--no-progress"]).unwrap();
+        assert!(matches!(cli.command, Some(Command::Verify { no_progress: true, .. })));
+        
+        let cli = Cli::try_parse_from(vec!["hash", "compare", "db1.txt", "db2.txt", "--no-progress"]).unwrap();
+        assert!(matches!(cli.command, Some(Command::Compare { no_progress: true, .. })));
+        
+        let cli = Cli::try_parse_from(vec!["hash", "compare", "db1.txt", "db2.txt"]).unwrap();
+        assert!(matches!(cli.command, Some(Command::Compare { no_progress: false, .. })));
+    }
+    
+    #[test]
+    fn test_parse_verify_sample() {
+        let cli = Cli::try_parse_from(vec!["hash", "verify", "-b", "db.txt", "-d", "/data", "--verify-sample", "2.5", "--seed", "42"]).unwrap();
+        assert!(matches!(cli.command, Some(Command::Verify { verify_sample: Some(p), seed: Some(42), .. }) if p == 2.5));
+        
+        // --seed only makes sense with a sample
+        assert!(Cli::try_parse_from(vec!["hash", "verify", "-b", "db.txt", "-d", "/data", "--seed", "42"]).is_err());
+    }
+    
+    #[test]
+    fn test_parse_verify_file() {
+        let cli = Cli::try_parse_from(vec!["hash", "verify", "-b", "db.txt", "-d", "/etc", "--file", "/etc/passwd"]).unwrap();
+        assert!(matches!(cli.command, Some(Command::Verify { file: Some(f), .. }) if f.as_path() == std::path::Path::new("/etc/passwd")));
+        
+        assert!(Cli::try_parse_from(vec!["hash", "verify", "-b", "db.txt", "-d", "/etc", "--file", "a", "--verify-sample", "5"]).is_err());
+    }
+    
+    #[test]
+    fn test_parse_verify_strict_algorithm() {
+        let cli = Cli::try_parse_from(vec!["hash", "verify", "-b", "db.txt", "-d", "/data", "-a", "sha256", "--strict"]).unwrap();
+        assert!(matches!(cli.command, Some(Command::Verify { algorithm: Some(ref a), strict: true, .. }) if a == "sha256"));
+        
+        assert!(Cli::try_parse_from(vec!["hash", "verify", "-b", "db.txt", "-d", "/data", "--strict"]).is_err());
+    }
+    
+    #[test]
+    fn test_parse_merge_databases() {
+        let cli = Cli::try_parse_from(vec!["hash", "verify", "-b", "db.txt.*", "-d", "/data", "--merge-databases"]).unwrap();
+        assert!(matches!(cli.command, Some(Command::Verify { merge_databases: true, .. })));
+        
+        assert!(Cli::try_parse_from(vec!["hash", "verify", "-b", "db.*", "-d", "/data", "--merge-databases", "--compare-sizes"]).is_err());
+    }
+    
+    #[test]
+    fn test_parse_compare_sizes() {
+        let cli = Cli::try_parse_from(vec!["hash", "verify", "-b", "db.tsv", "-d", "/data", "--compare-sizes"]).unwrap();
+        assert!(matches!(cli.command, Some(Command::Verify { compare_sizes: true, .. })));
+        
+        assert!(Cli::try_parse_from(vec!["hash", "verify", "-b", "db.tsv", "-d", "/data", "--compare-sizes", "--two-pass"]).is_err());
+    }
+    
+    #[test]
+    fn test_parse_benchmark_sweep() {
+        let cli = Cli::try_parse_from(vec!["hash", "benchmark", "--sweep", "-a", "sha256", "-s", "10"]).unwrap();
+        match cli.command {
+            Some(Command::Benchmark { size_mb, sweep, algorithm, .. }) => {
+                assert_eq!(size_mb, 10);
+                assert!(sweep);
+                assert_eq!(algorithm, "sha256");
+            }
+            _ => panic!("Expected Benchmark command"),
+        }
+        
+        // Warmup and repeated runs only apply to the full benchmark
+        let cli = Cli::try_parse_from(vec!["hash", "benchmark", "--warmup", "2", "--runs", "5"]).unwrap();
+        assert!(matches!(cli.command, Some(Command::Benchmark { warmup: 2, runs: 5, .. })));
+        assert!(Cli::try_parse_from(vec!["hash", "benchmark", "--sweep", "--runs", "3"]).is_err());
+        
+        // --chunk-report is accepted as an alias
+        let cli = Cli::try_parse_from(vec!["hash", "benchmark", "--chunk-report"]).unwrap();
+        assert!(matches!(cli.command, Some(Command::Benchmark { sweep: true, .. })));
+        
+        // -a only applies to the sweep
+        assert!(Cli::try_parse_from(vec!["hash", "benchmark", "-a", "sha256"]).is_err());
+    }
+    
+    #[test]
+    fn test_parse_compare_skip_duplicates() {
+        let cli = Cli::try_parse_from(vec!["hash", "compare", "db1.txt", "db2.txt", "--skip-duplicates"]).unwrap();
+        assert!(matches!(cli.command, Some(Command::Compare { skip_duplicates: true, .. })));
+        
+        let cli = Cli::try_parse_from(vec!["hash", "compare", "db1.txt", "db2.txt"]).unwrap();
+        assert!(matches!(cli.command, Some(Command::Compare { skip_duplicates: false, .. })));
+    }
+    
+    #[test]
+    fn test_parse_verify_url() {
+        let cli = Cli::try_parse_from(vec!["hash", "--verify-url", "http://host/f.iso", "-a", "sha256"]).unwrap();
+        assert_eq!(cli.verify_url.as_deref(), Some("http://host/f.iso"));
+        assert_eq!(cli.expected, None);
+        
+        let cli = Cli::try_parse_from(vec!["hash", "--verify-url", "http://host/f.iso", "--expected", "abc"]).unwrap();
+        assert_eq!(cli.expected.as_deref(), Some("abc"));
+        
+        // --expected only makes sense with --verify-url, which replaces FILE
+        assert!(Cli::try_parse_from(vec!["hash", "f.iso", "--expected", "abc"]).is_err());
+        assert!(Cli::try_parse_from(vec!["hash", "f.iso", "--verify-url", "http://host/f.iso"]).is_err());
+    }
+    
+    #[test]
+    fn test_parse_archive() {
+        let cli = Cli::try_parse_from(vec!["hash", "--archive", "backup.tar", "-a", "sha256"]).unwrap();
+        assert_eq!(cli.archive, Some(PathBuf::from("backup.tar")));
+        
+        assert!(Cli::try_parse_from(vec!["hash", "file.txt", "--archive", "backup.tar"]).is_err());
+    }
+    
+    #[test]
+    fn test_parse_decompress() {
+        let cli = Cli::try_parse_from(vec!["hash", "dump.sql.gz", "--decompress"]).unwrap();
+        assert!(cli.decompress);
+        
+        assert!(Cli::try_parse_from(vec!["hash", "dump.sql.gz", "--decompress", "--fast"]).is_err());
+    }
+    
+    #[test]
+    fn test_parse_combined() {
+        let cli = Cli::try_parse_from(vec!["hash", "photos", "-a", "sha256", "--combined"]).unwrap();
+        assert!(cli.combined);
+        
+        // Needs a directory to walk
+        assert!(Cli::try_parse_from(vec!["hash", "--text", "abc", "--combined"]).is_err());
+    }
+    
+    #[test]
+    fn test_parse_checkpoint() {
+        let cli = Cli::try_parse_from(vec!["hash", "disk.img", "-a", "sha256", "--checkpoint", "disk.ckpt", "--checkpoint-interval", "64M"]).unwrap();
+        assert_eq!(cli.checkpoint, Some(PathBuf::from("disk.ckpt")));
+        assert_eq!(cli.checkpoint_interval, 64 * 1024 * 1024);
+        
+        let cli = Cli::try_parse_from(vec!["hash", "disk.img", "--checkpoint", "disk.ckpt"]).unwrap();
+        assert_eq!(cli.checkpoint_interval, 1024 * 1024 * 1024);
+        
+        assert!(Cli::try_parse_from(vec!["hash", "disk.img", "--checkpoint", "disk.ckpt", "--fast"]).is_err());
+        assert!(Cli::try_parse_from(vec!["hash", "disk.img", "--checkpoint-interval", "1M"]).is_err());
+    }
+    
+    #[test]
+    fn test_parse_blake2_key() {
+        let cli = Cli::try_parse_from(vec!["hash", "file.txt", "-a", "blake2b:256", "--blake2-key", "00ff"]).unwrap();
+        assert_eq!(cli.algorithms, vec!["blake2b:256"]);
+        assert_eq!(cli.blake2_key, Some("00ff".to_string()));
+        
+        assert!(Cli::try_parse_from(vec!["hash", "file.txt", "--blake2-key", "00ff", "--fast"]).is_err());
+    }
+    
+    #[test]
+    fn test_parse_stdin_filenames() {
+        let cli = Cli::try_parse_from(vec!["hash", "--stdin-filenames", "-a", "sha256"]).unwrap();
+        assert!(cli.stdin_filenames);
+        assert!(cli.file.is_none());
+        
+        assert!(Cli::try_parse_from(vec!["hash", "file.txt", "--stdin-filenames"]).is_err());
+        assert!(Cli::try_parse_from(vec!["hash", "--stdin-filenames", "--json"]).is_err());
+    }
+    
+    #[test]
+    fn test_parse_check() {
+        let cli = Cli::try_parse_from(vec!["hash", "--check", "SHA256SUMS", "-a", "sha256", "--json"]).unwrap();
+        assert_eq!(cli.check, Some(PathBuf::from("SHA256SUMS")));
+        assert!(cli.json);
+        
+        let cli = Cli::try_parse_from(vec!["hash", "-c", "SHA256SUMS"]).unwrap();
+        assert_eq!(cli.check, Some(PathBuf::from("SHA256SUMS")));
+        
+        assert!(Cli::try_parse_from(vec!["hash", "file.txt", "--check", "SHA256SUMS"]).is_err());
+        
+        let cli = Cli::try_parse_from(vec!["hash", "-c", "SHA256SUMS", "--ignore-missing"]).unwrap();
+        assert!(cli.ignore_missing);
+        assert!(Cli::try_parse_from(vec!["hash", "file.txt", "--ignore-missing"]).is_err());
+        
+        let cli = Cli::try_parse_from(vec!["hash", "-c", "SHA256SUMS", "--strict"]).unwrap();
+        assert!(cli.strict);
+        assert!(Cli::try_parse_from(vec!["hash", "file.txt", "--strict"]).is_err());
+        
+        let cli = Cli::try_parse_from(vec!["hash", "-c", "SHA256SUMS", "--lenient"]).unwrap();
+        assert!(cli.lenient);
+        assert!(Cli::try_parse_from(vec!["hash", "file.txt", "--lenient"]).is_err());
+    }
 }
@@ -5,6 +5,10 @@ use std::collections::{HashMap, HashSet};
 use std::path::{Path, PathBuf};
 use crate::database::{DatabaseHandler, DatabaseEntry, DatabaseFormat};
 use crate::error::HashUtilityError;
+use crate::hash::{HashComputer, SYMLINK_ALGORITHM};
+use crate::path_utils::{format_size, UnicodeNormalization};
+use crate::scan::ScanEngine;
+use indicatif::{ProgressBar, ProgressStyle};
 
 /// Metadata about a database file
 #[derive(Debug, Clone, serde::Serialize)]
@@ -14,6 +18,8 @@ pub struct DatabaseInfo {
     pub size_bytes: u64,
     pub file_count: usize,
     pub modified: Option<String>,
+    /// Algorithm used by most entries (None for an empty database)
+    pub algorithm: Option<String>,
 }
 
 /// Result of comparing a single file between two databases
@@ -54,24 +60,18 @@ pub struct CompareReport {
     pub added_files: Vec<PathBuf>,
     pub duplicates_db1: Vec<DuplicateGroup>,
     pub duplicates_db2: Vec<DuplicateGroup>,
+    /// False when duplicate detection was skipped, leaving both duplicate lists empty
+    pub duplicates_computed: bool,
+    /// Set when the databases were hashed with different algorithms, so every file looks changed
+    pub algorithm_warning: Option<String>,
+    /// Hashes of removed files from DB1, used by the unified output
+    #[serde(skip)]
+    pub removed_hashes: HashMap<PathBuf, String>,
+    /// Hashes of added files from DB2, used by the unified output
+    #[serde(skip)]
+    pub added_hashes: HashMap<PathBuf, String>,
 }
 
-/// Format bytes as human-readable size
-fn format_size(bytes: u64) -> String {
-    const KB: u64 = 1024;
-    const MB: u64 = KB * 1024;
-    const GB: u64 = MB * 1024;
-
-    if bytes >= GB {
-        format!("{:.2} GB", bytes as f64 / GB as f64)
-    } else if bytes >= MB {
-        format!("{:.2} MB", bytes as f64 / MB as f64)
-    } else if bytes >= KB {
-        format!("{:.2} KB", bytes as f64 / KB as f64)
-    } else {
-        format!("{} bytes", bytes)
-    }
-}
 
 impl CompareReport {
     /// Display the comparison report in plain text format
@@ -87,8 +87,12 @@ impl CompareReport {
         println!("  Moved:      {} files", self.moved_files.len());
         println!("  Removed:    {} files", self.removed_files.len());
         println!("  Added:      {} files", self.added_files.len());
-        println!("  Duplicates in DB1: {} groups", self.duplicates_db1.len());
-        println!("  Duplicates in DB2: {} groups", self.duplicates_db2.len());
+        if self.duplicates_computed {
+            println!("  Duplicates in DB1: {} groups", self.duplicates_db1.len());
+            println!("  Duplicates in DB2: {} groups", self.duplicates_db2.len());
+        } else {
+            println!("  Duplicates: not computed");
+        }
 
         // Changed files section
         if !self.changed_files.is_empty() {
@@ -291,6 +295,55 @@ impl CompareReport {
         output
     }
 
+    /// Format the comparison report as a unified diff-style stream
+    ///
+    /// Each entry is written as `- <hash> <path>` for the DB1 side and
+    /// `+ <hash> <path>` for the DB2 side. Changed and moved files produce
+    /// one line of each; removed and added files produce only one. Entries
+    /// are ordered by path so the output is stable for patch-review tooling.
+    pub fn to_unified(&self) -> String {
+        let mut blocks: Vec<(&Path, String)> = Vec::new();
+
+        for changed in &self.changed_files {
+            blocks.push((&changed.path, format!(
+                "- {} {}\n+ {} {}\n",
+                changed.hash_db1, changed.path.display(),
+                changed.hash_db2, changed.path.display()
+            )));
+        }
+
+        for moved in &self.moved_files {
+            blocks.push((&moved.from_path, format!(
+                "- {} {}\n+ {} {}\n",
+                moved.hash, moved.from_path.display(),
+                moved.hash, moved.to_path.display()
+            )));
+        }
+
+        for path in &self.removed_files {
+            let hash = self.removed_hashes.get(path).map(String::as_str).unwrap_or("");
+            blocks.push((path, format!("- {} {}\n", hash, path.display())));
+        }
+
+        for path in &self.added_files {
+            let hash = self.added_hashes.get(path).map(String::as_str).unwrap_or("");
+            blocks.push((path, format!("+ {} {}\n", hash, path.display())));
+        }
+
+        blocks.sort_by(|a, b| a.0.cmp(b.0));
+
+        let mut output = format!(
+            "--- {}\n+++ {}\n",
+            self.db1_info.path.display(),
+            self.db2_info.path.display()
+        );
+        for (_, block) in blocks {
+            output.push_str(&block);
+        }
+
+        output
+    }
+
     /// Format the comparison report as JSON string
     pub fn to_json(&self) -> Result<String, serde_json::Error> {
         #[derive(serde::Serialize)]
@@ -298,6 +351,7 @@ impl CompareReport {
             metadata: Metadata,
             databases: Databases,
             summary: Summary,
+            algorithm_warning: Option<String>,
             unchanged_files: usize,
             changed_files: Vec<ChangedFileJson>,
             moved_files: Vec<MovedFileJson>,
@@ -323,6 +377,7 @@ impl CompareReport {
             size_bytes: u64,
             file_count: usize,
             modified: Option<String>,
+            algorithm: Option<String>,
         }
 
         #[derive(serde::Serialize)]
@@ -359,6 +414,7 @@ impl CompareReport {
                     size_bytes: self.db1_info.size_bytes,
                     file_count: self.db1_info.file_count,
                     modified: self.db1_info.modified.clone(),
+                    algorithm: self.db1_info.algorithm.clone(),
                 },
                 db2: DatabaseInfoJson {
                     path: self.db2_info.path.display().to_string(),
@@ -366,6 +422,7 @@ impl CompareReport {
                     size_bytes: self.db2_info.size_bytes,
                     file_count: self.db2_info.file_count,
                     modified: self.db2_info.modified.clone(),
+                    algorithm: self.db2_info.algorithm.clone(),
                 },
             },
             summary: Summary {
@@ -375,6 +432,7 @@ impl CompareReport {
                 removed_count: self.removed_files.len(),
                 added_count: self.added_files.len(),
             },
+            algorithm_warning: self.algorithm_warning.clone(),
             unchanged_files: self.unchanged_files,
             changed_files: self.changed_files.iter().map(|cf| ChangedFileJson {
                 path: cf.path.display().to_string(),
@@ -394,13 +452,197 @@ impl CompareReport {
     }
 }
 
+/// Hash history of a single file across several database snapshots
+#[derive(Debug, Clone, serde::Serialize)]
+pub struct FileHistory {
+    pub path: PathBuf,
+    /// Hash in each snapshot, in order (None if the file is absent)
+    pub hashes: Vec<Option<String>>,
+    /// 1-based snapshot numbers whose hash differs from the previous snapshot
+    pub change_points: Vec<usize>,
+}
+
+impl FileHistory {
+    /// Whether the file changed, appeared, or disappeared at any step
+    pub fn changed(&self) -> bool {
+        !self.change_points.is_empty()
+    }
+}
+
+/// Report comparing a file set across more than two databases
+#[derive(Debug, Clone, serde::Serialize)]
+pub struct MultiCompareReport {
+    pub databases: Vec<PathBuf>,
+    pub files: Vec<FileHistory>,
+    pub changed_count: usize,
+    pub unchanged_count: usize,
+}
+
+impl MultiCompareReport {
+    /// Format the report as a plain text matrix
+    ///
+    /// Each row is a file and each column a snapshot. Hashes are shortened to
+    /// 12 characters, absent files are shown as `-`, and rows for files that
+    /// changed at any step are marked with `*`.
+    pub fn to_plain_text(&self) -> String {
+        const HASH_WIDTH: usize = 12;
+        let mut output = String::new();
+
+        output.push_str("\n=== Multi-Database Comparison Report ===\n\n");
+
+        output.push_str("Databases:\n");
+        for (i, db) in self.databases.iter().enumerate() {
+            output.push_str(&format!("  DB{}: {}\n", i + 1, db.display()));
+        }
+
+        output.push_str("\nSummary:\n");
+        output.push_str(&format!("  Total files: {}\n", self.files.len()));
+        output.push_str(&format!("  Unchanged:   {} files\n", self.unchanged_count));
+        output.push_str(&format!("  Changed:     {} files\n", self.changed_count));
+
+        // Header row
+        output.push_str("\n  ");
+        for i in 0..self.databases.len() {
+            output.push_str(&format!("{:<width$}  ", format!("DB{}", i + 1), width = HASH_WIDTH));
+        }
+        output.push_str("File\n");
+
+        for file in &self.files {
+            output.push_str(if file.changed() { "* " } else { "  " });
+            for hash in &file.hashes {
+                let cell = match hash {
+                    Some(hash) => hash.chars().take(HASH_WIDTH).collect::<String>(),
+                    None => "-".to_string(),
+                };
+                output.push_str(&format!("{:<width$}  ", cell, width = HASH_WIDTH));
+            }
+            output.push_str(&format!("{}\n", file.path.display()));
+        }
+
+        // Where each changed file changed
+        let changed: Vec<&FileHistory> = self.files.iter().filter(|f| f.changed()).collect();
+        if !changed.is_empty() {
+            output.push_str("\nChange Points:\n");
+            for file in changed {
+                let steps: Vec<String> = file.change_points
+                    .iter()
+                    .map(|&n| format!("DB{} -> DB{}", n - 1, n))
+                    .collect();
+                output.push_str(&format!("  {}: {}\n", file.path.display(), steps.join(", ")));
+            }
+        }
+
+        output
+    }
+
+    /// Format the report as JSON string
+    pub fn to_json(&self) -> Result<String, serde_json::Error> {
+        #[derive(serde::Serialize)]
+        struct JsonOutput<'a> {
+            metadata: Metadata,
+            #[serde(flatten)]
+            report: &'a MultiCompareReport,
+        }
+
+        #[derive(serde::Serialize)]
+        struct Metadata {
+            timestamp: String,
+        }
+
+        let output = JsonOutput {
+            metadata: Metadata {
+                timestamp: chrono::Utc::now().to_rfc3339(),
+            },
+            report: self,
+        };
+
+        serde_json::to_string_pretty(&output)
+    }
+}
+
 /// Engine for comparing two hash databases
-pub struct CompareEngine;
+pub struct CompareEngine {
+    show_progress: bool,
+    detect_duplicates: bool,
+    strict: bool,
+    /// Resolve stored paths against this directory before matching (`--canonicalize-paths`)
+    canonicalize_base: Option<PathBuf>,
+    /// Normalize paths to this Unicode form before matching (`--normalize-unicode`)
+    unicode_normalization: Option<UnicodeNormalization>,
+}
 
 impl CompareEngine {
     /// Create a new CompareEngine
     pub fn new() -> Self {
-        CompareEngine
+        CompareEngine {
+            show_progress: true,
+            detect_duplicates: true,
+            strict: false,
+            canonicalize_base: None,
+            unicode_normalization: None,
+        }
+    }
+    
+    /// Look for duplicate hashes within each database (default)
+    /// 
+    /// Skipping this saves time on large databases when only the diff matters.
+    pub fn with_duplicates(mut self, detect_duplicates: bool) -> Self {
+        self.detect_duplicates = detect_duplicates;
+        self
+    }
+    
+    /// Show a progress bar while classifying entries (default)
+    /// 
+    /// The bar is drawn on stderr and stays hidden when stderr is not a TTY.
+    pub fn with_progress(mut self, show_progress: bool) -> Self {
+        self.show_progress = show_progress;
+        self
+    }
+    
+    /// Fail instead of warning when the databases use different algorithms
+    pub fn with_strict(mut self, strict: bool) -> Self {
+        self.strict = strict;
+        self
+    }
+    
+    /// Match entries by canonical path, resolving symlinks against files under `ref_dir`
+    /// 
+    /// Paths that exist on disk are replaced by their canonical form (relative to
+    /// `ref_dir` when inside it), so a symlinked path in one database matches its
+    /// target in the other. Paths that don't exist are compared as stored.
+    pub fn with_canonicalize_paths(mut self, ref_dir: Option<PathBuf>) -> Self {
+        self.canonicalize_base = ref_dir;
+        self
+    }
+    
+    /// Match entries whose paths differ only in Unicode normalization (NFC vs NFD)
+    pub fn with_unicode_normalization(mut self, form: Option<UnicodeNormalization>) -> Self {
+        self.unicode_normalization = form;
+        self
+    }
+    
+    /// Re-key a database by canonical path
+    fn canonicalize_keys(
+        database: HashMap<PathBuf, DatabaseEntry>,
+        base: &Path,
+    ) -> Result<HashMap<PathBuf, DatabaseEntry>, HashUtilityError> {
+        let canonical_base = base.canonicalize().map_err(|e| {
+            HashUtilityError::from_io_error(e, "resolving reference directory", Some(base.to_path_buf()))
+        })?;
+        
+        Ok(database
+            .into_iter()
+            .map(|(path, entry)| {
+                let canonical = match base.join(&path).canonicalize() {
+                    Ok(resolved) => match resolved.strip_prefix(&canonical_base) {
+                        Ok(relative) => relative.to_path_buf(),
+                        Err(_) => resolved,
+                    },
+                    Err(_) => path,
+                };
+                (canonical, entry)
+            })
+            .collect())
     }
     
     /// Compare two hash databases and generate a detailed report
@@ -423,13 +665,99 @@ impl CompareEngine {
         let db1_info = Self::get_database_info(database1)?;
         let db2_info = Self::get_database_info(database2)?;
 
+        // Byte-identical databases cannot differ, so skip the full diff
+        if db1_info.size_bytes == db2_info.size_bytes && Self::files_identical(database1, database2)? {
+            return self.identical_report(database1, db1_info, db2_info);
+        }
+
         // Load both databases
         let db1 = DatabaseHandler::read_database(database1)?;
         let db2 = DatabaseHandler::read_database(database2)?;
+        self.compare_entries(db1_info, db1, db2_info, db2)
+    }
+    
+    /// Compare a database against the files currently in a directory (`compare-dir`)
+    /// 
+    /// The directory is hashed in memory by `scanner` and stands in for the second
+    /// database, so renamed files are reported as moves. Each file the database lists is
+    /// hashed the way its entry was made (algorithm, fast or structure mode, symlink target);
+    /// files it doesn't list use the algorithm and mode most of its entries were made with.
+    pub fn compare_directory(
+        &self,
+        database: &Path,
+        directory: &Path,
+        scanner: ScanEngine,
+    ) -> Result<CompareReport, HashUtilityError> {
+        let db1_info = Self::get_database_info(database)?;
+        let db1 = DatabaseHandler::read_database(database)?;
+        
+        // Symlink entries hash a link's target path, so they don't count towards the scheme for new files
+        let (links, files): (Vec<&DatabaseEntry>, Vec<&DatabaseEntry>) =
+            db1.values().partition(|entry| entry.algorithm == SYMLINK_ALGORITHM);
+        let algorithm = Self::predominant_algorithm(files.iter().copied()).unwrap_or_else(|| "blake3".to_string());
+        let structure_only = files.iter().filter(|entry| entry.structure_only).count() * 2 > files.len();
+        let fast_mode = !structure_only && files.iter().filter(|entry| entry.fast_mode).count() * 2 > files.len();
+        let db2: HashMap<PathBuf, DatabaseEntry> = scanner
+            .with_fast_mode(fast_mode)
+            .with_structure_only(structure_only)
+            .with_hash_symlink_targets(!links.is_empty())
+            .scan_directory_to_entries(directory, &algorithm, &db1)?
+            .into_iter()
+            .collect();
+        
+        let db2_info = DatabaseInfo {
+            path: directory.to_path_buf(),
+            format: "directory".to_string(),
+            size_bytes: 0,
+            file_count: 0, // Will be updated after comparing
+            modified: None,
+            algorithm: None, // Will be updated after comparing
+        };
+        self.compare_entries(db1_info, db1, db2_info, db2)
+    }
+    
+    /// Diff two sets of entries, detecting moves and duplicates
+    fn compare_entries(
+        &self,
+        db1_info: DatabaseInfo,
+        mut db1: HashMap<PathBuf, DatabaseEntry>,
+        db2_info: DatabaseInfo,
+        mut db2: HashMap<PathBuf, DatabaseEntry>,
+    ) -> Result<CompareReport, HashUtilityError> {
+        // Symlinked and real paths to the same file should line up
+        if let Some(base) = &self.canonicalize_base {
+            db1 = Self::canonicalize_keys(db1, base)?;
+            db2 = Self::canonicalize_keys(db2, base)?;
+        }
+        
+        // A name written on macOS (NFD) should match the same name written elsewhere (NFC)
+        if let Some(form) = self.unicode_normalization {
+            db1 = db1.into_iter().map(|(path, entry)| (form.normalize_path(&path), entry)).collect();
+            db2 = db2.into_iter().map(|(path, entry)| (form.normalize_path(&path), entry)).collect();
+        }
+        
+        // Hashes from different algorithms never match, which would make every file look changed
+        let db1_algorithm = Self::predominant_algorithm(db1.values());
+        let db2_algorithm = Self::predominant_algorithm(db2.values());
+        let algorithm_warning = match (&db1_algorithm, &db2_algorithm) {
+            (Some(algorithm1), Some(algorithm2)) if !algorithm1.eq_ignore_ascii_case(algorithm2) => Some(format!(
+                "Databases use different algorithms ({} vs {}); matching files will appear changed",
+                algorithm1, algorithm2
+            )),
+            _ => None,
+        };
+        if let (Some(message), true) = (&algorithm_warning, self.strict) {
+            return Err(HashUtilityError::InvalidArguments {
+                message: message.clone(),
+            });
+        }
         
-        // Detect duplicates in each database
-        let duplicates_db1 = Self::find_duplicates(&db1);
-        let duplicates_db2 = Self::find_duplicates(&db2);
+        // Detect duplicates in each database, unless skipped
+        let (duplicates_db1, duplicates_db2) = if self.detect_duplicates {
+            (Self::find_duplicates(&db1), Self::find_duplicates(&db2))
+        } else {
+            (Vec::new(), Vec::new())
+        };
         
         // Get all unique file paths from both databases
         let all_paths: HashSet<PathBuf> = db1.keys()
@@ -443,7 +771,21 @@ impl CompareEngine {
         let mut removed_files = Vec::new();
         let mut added_files = Vec::new();
 
+        // Create progress bar driven by entries classified
+        let pb = if self.show_progress {
+            ProgressBar::new(all_paths.len() as u64)
+        } else {
+            ProgressBar::hidden()
+        };
+        pb.set_style(
+            ProgressStyle::default_bar()
+                .template("[{elapsed_precise}] [{bar:40.cyan/blue}] {pos}/{len} entries ({percent}%)")
+                .unwrap()
+                .progress_chars("=>-")
+        );
+
         for path in all_paths {
+            pb.inc(1);
             match (db1.get(&path), db2.get(&path)) {
                 (Some(entry1), Some(entry2)) => {
                     // File exists in both databases
@@ -474,6 +816,9 @@ impl CompareEngine {
             }
         }
 
+        // Clear progress bar
+        pb.finish_and_clear();
+
         // Detect moved files: files with same hash but different paths
         // Build hash-to-path map for removed files (from DB1)
         let mut removed_by_hash: HashMap<String, Vec<PathBuf>> = HashMap::new();
@@ -527,13 +872,25 @@ impl CompareEngine {
         removed_files.sort();
         added_files.sort();
 
+        // Keep hashes of unmatched files for formats that show them
+        let removed_hashes = removed_files
+            .iter()
+            .filter_map(|p| db1.get(p).map(|e| (p.clone(), e.hash.clone())))
+            .collect();
+        let added_hashes = added_files
+            .iter()
+            .filter_map(|p| db2.get(p).map(|e| (p.clone(), e.hash.clone())))
+            .collect();
+
         // Update file counts in database info
         let db1_info = DatabaseInfo {
             file_count: db1.len(),
+            algorithm: db1_algorithm,
             ..db1_info
         };
         let db2_info = DatabaseInfo {
             file_count: db2.len(),
+            algorithm: db2_algorithm,
             ..db2_info
         };
 
@@ -549,6 +906,122 @@ impl CompareEngine {
             added_files,
             duplicates_db1,
             duplicates_db2,
+            duplicates_computed: self.detect_duplicates,
+            algorithm_warning,
+            removed_hashes,
+            added_hashes,
+        })
+    }
+
+    /// Compare a file set across several database snapshots
+    ///
+    /// # Arguments
+    /// * `databases` - Database files in snapshot order (at least two)
+    ///
+    /// # Returns
+    /// A MultiCompareReport with the hash of every file in every snapshot
+    pub fn compare_multi(&self, databases: &[PathBuf]) -> Result<MultiCompareReport, HashUtilityError> {
+        if databases.len() < 2 {
+            return Err(HashUtilityError::InvalidArguments {
+                message: "compare-multi needs at least two databases".to_string(),
+            });
+        }
+
+        // Build the per-file hash sequence across all snapshots
+        let mut histories: HashMap<PathBuf, Vec<Option<String>>> = HashMap::new();
+        for (index, database) in databases.iter().enumerate() {
+            let entries = DatabaseHandler::read_database(database)?;
+            for (path, entry) in entries {
+                let hashes = histories
+                    .entry(path)
+                    .or_insert_with(|| vec![None; databases.len()]);
+                hashes[index] = Some(entry.hash);
+            }
+        }
+
+        let mut files: Vec<FileHistory> = histories
+            .into_iter()
+            .map(|(path, hashes)| {
+                let change_points = (1..hashes.len())
+                    .filter(|&i| hashes[i] != hashes[i - 1])
+                    .map(|i| i + 1)
+                    .collect();
+                FileHistory { path, hashes, change_points }
+            })
+            .collect();
+
+        // Sort results for consistent output
+        files.sort_by(|a, b| a.path.cmp(&b.path));
+
+        let changed_count = files.iter().filter(|f| f.changed()).count();
+
+        Ok(MultiCompareReport {
+            databases: databases.to_vec(),
+            unchanged_count: files.len() - changed_count,
+            changed_count,
+            files,
+        })
+    }
+
+    /// Check whether two database files have identical contents
+    ///
+    /// Callers should compare sizes first; this hashes both files with BLAKE3.
+    fn files_identical(database1: &Path, database2: &Path) -> Result<bool, HashUtilityError> {
+        // Comparing a database with itself needs no reading at all
+        if let (Ok(canonical1), Ok(canonical2)) = (database1.canonicalize(), database2.canonicalize()) {
+            if canonical1 == canonical2 {
+                return Ok(true);
+            }
+        }
+
+        let computer = HashComputer::new();
+        let hash1 = computer.compute_hash(database1, "blake3")?;
+        let hash2 = computer.compute_hash(database2, "blake3")?;
+        Ok(hash1.hash == hash2.hash)
+    }
+
+    /// Build the report for two byte-identical databases
+    ///
+    /// Only the first database is parsed; every entry is unchanged and both
+    /// databases share the same duplicate groups.
+    fn identical_report(
+        &self,
+        database: &Path,
+        db1_info: DatabaseInfo,
+        db2_info: DatabaseInfo,
+    ) -> Result<CompareReport, HashUtilityError> {
+        let db = DatabaseHandler::read_database(database)?;
+        let duplicates = if self.detect_duplicates {
+            Self::find_duplicates(&db)
+        } else {
+            Vec::new()
+        };
+        let algorithm = Self::predominant_algorithm(db.values());
+
+        Ok(CompareReport {
+            db1_info: DatabaseInfo {
+                file_count: db.len(),
+                algorithm: algorithm.clone(),
+                ..db1_info
+            },
+            db2_info: DatabaseInfo {
+                file_count: db.len(),
+                algorithm,
+                ..db2_info
+            },
+            db1_total_files: db.len(),
+            db2_total_files: db.len(),
+            unchanged_files: db.len(),
+            changed_files: Vec::new(),
+            moved_files: Vec::new(),
+            removed_files: Vec::new(),
+            added_files: Vec::new(),
+            duplicates_db1: duplicates.clone(),
+            duplicates_db2: duplicates,
+            duplicates_computed: self.detect_duplicates,
+            algorithm_warning: None,
+            removed_hashes: HashMap::new(),
+            added_hashes: HashMap::new(),
         })
     }
 
@@ -566,6 +1039,9 @@ impl CompareEngine {
         let format_str = match format {
             DatabaseFormat::Standard => "standard",
             DatabaseFormat::Hashdeep => "hashdeep",
+            DatabaseFormat::Tsv => "tsv",
+            DatabaseFormat::Coreutils => "coreutils",
+            DatabaseFormat::JsonDb => "json-db",
         };
 
         // Get modification time
@@ -580,9 +1056,25 @@ impl CompareEngine {
             size_bytes: metadata.len(),
             file_count: 0, // Will be updated after reading
             modified,
+            algorithm: None, // Will be updated after reading
         })
     }
     
+    /// The algorithm used by most of a database's entries
+    /// 
+    /// Ties go to the alphabetically first name so the result is deterministic.
+    fn predominant_algorithm<'a>(entries: impl IntoIterator<Item = &'a DatabaseEntry>) -> Option<String> {
+        let mut counts: HashMap<String, usize> = HashMap::new();
+        for entry in entries {
+            *counts.entry(entry.algorithm.to_lowercase()).or_default() += 1;
+        }
+        
+        counts
+            .into_iter()
+            .max_by(|(name1, count1), (name2, count2)| count1.cmp(count2).then_with(|| name2.cmp(name1)))
+            .map(|(name, _)| name)
+    }
+    
     /// Find duplicate hashes within a database
     /// 
     /// # Arguments
@@ -590,7 +1082,7 @@ impl CompareEngine {
     /// 
     /// # Returns
     /// A vector of DuplicateGroup, each containing files with the same hash
-    fn find_duplicates(database: &HashMap<PathBuf, DatabaseEntry>) -> Vec<DuplicateGroup> {
+    pub fn find_duplicates(database: &HashMap<PathBuf, DatabaseEntry>) -> Vec<DuplicateGroup> {
         // Build a map from hash to list of paths
         let mut hash_to_paths: HashMap<String, Vec<PathBuf>> = HashMap::new();
         
@@ -629,6 +1121,295 @@ mod tests {
     use std::fs;
     use crate::database::DatabaseHandler;
 
+    #[test]
+    fn test_compare_directory_reports_rename_as_move() {
+        let test_dir = "test_compare_directory";
+        let db_path = "test_compare_directory.txt";
+        fs::create_dir_all(test_dir).unwrap();
+        fs::write(format!("{}/old_name.txt", test_dir), b"renamed content").unwrap();
+        fs::write(format!("{}/kept.txt", test_dir), b"kept").unwrap();
+        fs::write(format!("{}/edited.txt", test_dir), b"before").unwrap();
+        
+        ScanEngine::new().scan_directory(Path::new(test_dir), "sha256", Path::new(db_path)).unwrap();
+        
+        // Rename one file and edit another after the database was written
+        fs::rename(format!("{}/old_name.txt", test_dir), format!("{}/new_name.txt", test_dir)).unwrap();
+        fs::write(format!("{}/edited.txt", test_dir), b"after").unwrap();
+        
+        for parallel in [false, true] {
+            let engine = CompareEngine::new().with_progress(false);
+            let report = engine.compare_directory(Path::new(db_path), Path::new(test_dir), ScanEngine::with_parallel(parallel)).unwrap();
+            
+            assert_eq!(report.unchanged_files, 1);
+            assert_eq!(report.changed_files.len(), 1);
+            assert_eq!(report.changed_files[0].path, PathBuf::from("edited.txt"));
+            assert_eq!(report.moved_files.len(), 1);
+            assert_eq!(report.moved_files[0].from_path, PathBuf::from("old_name.txt"));
+            assert_eq!(report.moved_files[0].to_path, PathBuf::from("new_name.txt"));
+            assert!(report.removed_files.is_empty());
+            assert!(report.added_files.is_empty());
+            assert_eq!(report.db2_info.format, "directory");
+        }
+        
+        // Cleanup
+        fs::remove_file(db_path).unwrap();
+        fs::remove_dir_all(test_dir).unwrap();
+    }
+
+    #[test]
+    fn test_compare_directory_structure_only_database() {
+        let test_dir = "test_compare_directory_structure";
+        let db_path = "test_compare_directory_structure.txt";
+        fs::create_dir_all(test_dir).unwrap();
+        fs::write(format!("{}/a.txt", test_dir), b"alpha").unwrap();
+        fs::write(format!("{}/b.txt", test_dir), b"beta").unwrap();
+        
+        ScanEngine::new().with_structure_only(true).scan_directory(Path::new(test_dir), "sha256", Path::new(db_path)).unwrap();
+        fs::write(format!("{}/c.txt", test_dir), b"gamma").unwrap();
+        
+        // Listed files are hashed from their structure again, so the untouched ones match
+        let report = CompareEngine::new()
+            .with_progress(false)
+            .compare_directory(Path::new(db_path), Path::new(test_dir), ScanEngine::new())
+            .unwrap();
+        assert_eq!(report.unchanged_files, 2);
+        assert!(report.changed_files.is_empty());
+        assert_eq!(report.added_files.len(), 1);
+        assert_eq!(report.added_files[0], PathBuf::from("c.txt"));
+        
+        // Cleanup
+        fs::remove_file(db_path).unwrap();
+        fs::remove_dir_all(test_dir).unwrap();
+    }
+    
+    #[test]
+    fn test_compare_directory_mixed_algorithm_database() {
+        let test_dir = "test_compare_directory_mixed";
+        let db_path = "test_compare_directory_mixed.txt";
+        fs::create_dir_all(test_dir).unwrap();
+        
+        // Every entry names its own algorithm, none of them in a majority
+        let computer = HashComputer::new();
+        let mut db = String::new();
+        for (name, algorithm) in [("a.txt", "md5"), ("b.txt", "sha256"), ("c.txt", "sha512")] {
+            let path = format!("{}/{}", test_dir, name);
+            fs::write(&path, name).unwrap();
+            let hash = computer.compute_hash(Path::new(&path), algorithm).unwrap().hash;
+            db.push_str(&format!("{}  {}  normal  {}\n", hash, algorithm, name));
+        }
+        
+        // A symlink entry hashes the link's target path, not its contents
+        #[cfg(unix)]
+        {
+            let link = format!("{}/link.txt", test_dir);
+            std::os::unix::fs::symlink("a.txt", &link).unwrap();
+            let hash = computer.compute_symlink_hash(Path::new(&link)).unwrap().hash;
+            db.push_str(&format!("{}  {}  normal  link.txt\n", hash, SYMLINK_ALGORITHM));
+        }
+        fs::write(db_path, db).unwrap();
+        fs::write(format!("{}/b.txt", test_dir), b"edited").unwrap();
+        
+        for parallel in [false, true] {
+            let report = CompareEngine::new()
+                .with_progress(false)
+                .compare_directory(Path::new(db_path), Path::new(test_dir), ScanEngine::with_parallel(parallel))
+                .unwrap();
+            assert_eq!(report.unchanged_files, if cfg!(unix) { 3 } else { 2 });
+            assert_eq!(report.changed_files.len(), 1);
+            assert_eq!(report.changed_files[0].path, PathBuf::from("b.txt"));
+            assert!(report.removed_files.is_empty());
+            assert!(report.added_files.is_empty());
+        }
+        
+        // Cleanup
+        fs::remove_file(db_path).unwrap();
+        fs::remove_dir_all(test_dir).unwrap();
+    }
+    
+    #[test]
+    fn test_compare_with_progress_hook() {
+        let db1_path = "test_compare_progress_db1.txt";
+        let db2_path = "test_compare_progress_db2.txt";
+        
+        fs::write(db1_path, "hash1  sha256  normal  file1.txt\n\
+                             hash2  sha256  normal  file2.txt\n\
+                             hash3  sha256  normal  file3.txt\n").unwrap();
+        fs::write(db2_path, "hash1  sha256  normal  file1.txt\n\
+                             changed  sha256  normal  file2.txt\n\
+                             hash4  sha256  normal  file4.txt\n").unwrap();
+        
+        // Results must not depend on whether the progress bar is shown
+        for show_progress in [true, false] {
+            let engine = CompareEngine::new().with_progress(show_progress);
+            let report = engine.compare(Path::new(db1_path), Path::new(db2_path)).unwrap();
+            
+            assert_eq!(report.unchanged_files, 1);
+            assert_eq!(report.changed_files.len(), 1);
+            assert_eq!(report.changed_files[0].path, PathBuf::from("file2.txt"));
+            assert_eq!(report.removed_files, vec![PathBuf::from("file3.txt")]);
+            assert_eq!(report.added_files, vec![PathBuf::from("file4.txt")]);
+        }
+        
+        // Cleanup
+        fs::remove_file(db1_path).unwrap();
+        fs::remove_file(db2_path).unwrap();
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn test_compare_canonicalize_paths() {
+        let test_dir = "test_compare_canonicalize";
+        let db1_path = "test_compare_canonicalize_db1.txt";
+        let db2_path = "test_compare_canonicalize_db2.txt";
+        fs::create_dir_all(format!("{}/real", test_dir)).unwrap();
+        fs::write(format!("{}/real/data.txt", test_dir), b"hello").unwrap();
+        std::os::unix::fs::symlink("real", format!("{}/link", test_dir)).unwrap();
+        
+        // DB1 was made through the symlink, DB2 through the real directory
+        fs::write(db1_path, "hash1  sha256  normal  link/data.txt\n\
+                             hash2  sha256  normal  gone.txt\n").unwrap();
+        fs::write(db2_path, "hash1  sha256  normal  real/data.txt\n\
+                             hash2  sha256  normal  gone.txt\n").unwrap();
+        
+        // Compared as stored, the same file looks moved
+        let report = CompareEngine::new().compare(Path::new(db1_path), Path::new(db2_path)).unwrap();
+        assert_eq!(report.unchanged_files, 1);
+        assert_eq!(report.moved_files.len(), 1);
+        assert_eq!(report.moved_files[0].from_path, PathBuf::from("link/data.txt"));
+        assert_eq!(report.moved_files[0].to_path, PathBuf::from("real/data.txt"));
+        
+        // Canonicalized, it matches; paths missing on disk are compared as stored
+        let engine = CompareEngine::new().with_canonicalize_paths(Some(PathBuf::from(test_dir)));
+        let report = engine.compare(Path::new(db1_path), Path::new(db2_path)).unwrap();
+        assert_eq!(report.unchanged_files, 2);
+        assert!(report.moved_files.is_empty());
+        assert!(report.removed_files.is_empty());
+        assert!(report.added_files.is_empty());
+        assert!(report.changed_files.is_empty());
+        
+        // Cleanup
+        fs::remove_file(db1_path).unwrap();
+        fs::remove_file(db2_path).unwrap();
+        fs::remove_dir_all(test_dir).unwrap();
+    }
+
+    #[test]
+    fn test_compare_normalize_unicode() {
+        let db1_path = "test_compare_unicode_db1.txt";
+        let db2_path = "test_compare_unicode_db2.txt";
+        
+        // The same names, written decomposed on macOS and composed on Linux
+        fs::write(db1_path, "hash1  sha256  normal  cafe\u{301}/menu.txt\nhash2  sha256  normal  re\u{301}sume\u{301}.txt\n").unwrap();
+        fs::write(db2_path, "hash1  sha256  normal  caf\u{e9}/menu.txt\nhash3  sha256  normal  r\u{e9}sum\u{e9}.txt\n").unwrap();
+        
+        // Compared byte for byte, nothing lines up by path
+        let report = CompareEngine::new().compare(Path::new(db1_path), Path::new(db2_path)).unwrap();
+        assert_eq!(report.moved_files.len(), 1);
+        assert_eq!(report.removed_files.len(), 1);
+        assert_eq!(report.added_files.len(), 1);
+        
+        for form in [UnicodeNormalization::Nfc, UnicodeNormalization::Nfd] {
+            let engine = CompareEngine::new().with_unicode_normalization(Some(form));
+            let report = engine.compare(Path::new(db1_path), Path::new(db2_path)).unwrap();
+            assert_eq!(report.unchanged_files, 1);
+            assert_eq!(report.changed_files.len(), 1);
+            assert!(report.moved_files.is_empty());
+            assert!(report.removed_files.is_empty());
+            assert!(report.added_files.is_empty());
+        }
+        
+        // Cleanup
+        fs::remove_file(db1_path).unwrap();
+        fs::remove_file(db2_path).unwrap();
+    }
+
+    #[test]
+    fn test_compare_skip_duplicates() {
+        let db1_path = "test_compare_skip_dups_db1.txt";
+        let db2_path = "test_compare_skip_dups_db2.txt";
+        
+        // Both databases contain duplicates
+        fs::write(db1_path, "hash1  sha256  normal  file1.txt\n\
+                             hash1  sha256  normal  file2.txt\n").unwrap();
+        fs::write(db2_path, "hash1  sha256  normal  file1.txt\n\
+                             hash1  sha256  normal  file2.txt\n\
+                             hash1  sha256  normal  file3.txt\n").unwrap();
+        
+        let engine = CompareEngine::new().with_duplicates(false);
+        let report = engine.compare(Path::new(db1_path), Path::new(db2_path)).unwrap();
+        
+        assert!(!report.duplicates_computed);
+        assert!(report.duplicates_db1.is_empty());
+        assert!(report.duplicates_db2.is_empty());
+        // The diff itself is unaffected
+        assert_eq!(report.unchanged_files, 2);
+        assert_eq!(report.added_files, vec![PathBuf::from("file3.txt")]);
+        
+        // The byte-identical fast path honors the setting too
+        let report = engine.compare(Path::new(db1_path), Path::new(db1_path)).unwrap();
+        assert!(!report.duplicates_computed);
+        assert!(report.duplicates_db1.is_empty());
+        
+        // Cleanup
+        fs::remove_file(db1_path).unwrap();
+        fs::remove_file(db2_path).unwrap();
+    }
+
+    #[test]
+    fn test_compare_warns_on_algorithm_mismatch() {
+        let db1_path = "test_compare_algo_md5.txt";
+        let db2_path = "test_compare_algo_sha256.txt";
+        
+        fs::write(db1_path, "d41d8cd98f00b204e9800998ecf8427e  md5  normal  file1.txt\n\
+                             0cc175b9c0f1b6a831c399e269772661  md5  normal  file2.txt\n").unwrap();
+        fs::write(db2_path, "e3b0c44298fc1c149afbf4c8996fb92427ae41e4649b934ca495991b7852b855  sha256  normal  file1.txt\n\
+                             ca978112ca1bbdcafac231b39a23dc4da786eff8147c4e72b9807785afee48bb  sha256  normal  file2.txt\n").unwrap();
+        
+        let report = CompareEngine::new().compare(Path::new(db1_path), Path::new(db2_path)).unwrap();
+        assert_eq!(report.db1_info.algorithm.as_deref(), Some("md5"));
+        assert_eq!(report.db2_info.algorithm.as_deref(), Some("sha256"));
+        let warning = report.algorithm_warning.expect("mismatch should be flagged");
+        assert!(warning.contains("md5 vs sha256"));
+        
+        // --strict turns the warning into an error
+        let result = CompareEngine::new().with_strict(true).compare(Path::new(db1_path), Path::new(db2_path));
+        assert!(matches!(result, Err(HashUtilityError::InvalidArguments { .. })));
+        
+        // Matching algorithms produce no warning
+        let report = CompareEngine::new().compare(Path::new(db2_path), Path::new(db2_path)).unwrap();
+        assert!(report.algorithm_warning.is_none());
+        
+        // Cleanup
+        fs::remove_file(db1_path).unwrap();
+        fs::remove_file(db2_path).unwrap();
+    }
+
+    #[test]
+    fn test_compare_database_with_itself() {
+        let db_path = "test_compare_self.txt";
+        fs::write(db_path, "hash1  sha256  normal  file1.txt\n\
+                            hash2  sha256  normal  file2.txt\n\
+                            hash1  sha256  normal  copy_of_file1.txt\n").unwrap();
+        
+        let engine = CompareEngine::new();
+        let report = engine.compare(Path::new(db_path), Path::new(db_path)).unwrap();
+        
+        // Fast path still reports full counts and zero differences
+        assert_eq!(report.db1_total_files, 3);
+        assert_eq!(report.db2_total_files, 3);
+        assert_eq!(report.db1_info.file_count, 3);
+        assert_eq!(report.unchanged_files, 3);
+        assert!(report.changed_files.is_empty());
+        assert!(report.moved_files.is_empty());
+        assert!(report.removed_files.is_empty());
+        assert!(report.added_files.is_empty());
+        assert_eq!(report.duplicates_db1.len(), 1);
+        assert_eq!(report.duplicates_db2.len(), 1);
+        
+        // Cleanup
+        fs::remove_file(db_path).unwrap();
+    }
+
     #[test]
     fn test_compare_identical_databases() {
         // Create two identical databases
@@ -855,6 +1636,11 @@ mod tests {
                 hash: "hash1".to_string(),
                 algorithm: "sha256".to_string(),
                 fast_mode: false,
+                permissions: None,
+                modified: None,
+                size: None,
+                structure_only: false,
+                fast_v2: false,
             },
         );
         db.insert(
@@ -863,6 +1649,11 @@ mod tests {
                 hash: "hash2".to_string(),
                 algorithm: "sha256".to_string(),
                 fast_mode: false,
+                permissions: None,
+                modified: None,
+                size: None,
+                structure_only: false,
+                fast_v2: false,
             },
         );
         
@@ -879,6 +1670,11 @@ mod tests {
                 hash: "hash_dup".to_string(),
                 algorithm: "sha256".to_string(),
                 fast_mode: false,
+                permissions: None,
+                modified: None,
+                size: None,
+                structure_only: false,
+                fast_v2: false,
             },
         );
         db.insert(
@@ -887,6 +1683,11 @@ mod tests {
                 hash: "hash_dup".to_string(),
                 algorithm: "sha256".to_string(),
                 fast_mode: false,
+                permissions: None,
+                modified: None,
+                size: None,
+                structure_only: false,
+                fast_v2: false,
             },
         );
         db.insert(
@@ -895,6 +1696,11 @@ mod tests {
                 hash: "hash_unique".to_string(),
                 algorithm: "sha256".to_string(),
                 fast_mode: false,
+                permissions: None,
+                modified: None,
+                size: None,
+                structure_only: false,
+                fast_v2: false,
             },
         );
         
@@ -1073,4 +1879,108 @@ mod tests {
         fs::remove_file(db1_path).unwrap();
         fs::remove_file(db2_path).unwrap();
     }
+
+    #[test]
+    fn test_to_unified_format() {
+        let db1_path = "test_unified_db1.txt";
+        let db2_path = "test_unified_db2.txt";
+
+        let content1 = "hash1  sha256  normal  same.txt\n\
+                        hash2  sha256  normal  changed.txt\n\
+                        hash3  sha256  normal  removed.txt\n\
+                        hash4  sha256  normal  old_name.txt\n";
+
+        let content2 = "hash1  sha256  normal  same.txt\n\
+                        hash2_new  sha256  normal  changed.txt\n\
+                        hash5  sha256  normal  added.txt\n\
+                        hash4  sha256  normal  new_name.txt\n";
+
+        fs::write(db1_path, content1).unwrap();
+        fs::write(db2_path, content2).unwrap();
+
+        let engine = CompareEngine::new();
+        let report = engine.compare(Path::new(db1_path), Path::new(db2_path)).unwrap();
+        let unified = report.to_unified();
+
+        let lines: Vec<&str> = unified.lines().collect();
+        assert_eq!(lines[0], format!("--- {}", db1_path));
+        assert_eq!(lines[1], format!("+++ {}", db2_path));
+
+        // A changed file yields exactly one '-' and one '+' line with the right hashes
+        let changed: Vec<&str> = lines.iter()
+            .filter(|l| l.ends_with(" changed.txt"))
+            .cloned()
+            .collect();
+        assert_eq!(changed, vec!["- hash2 changed.txt", "+ hash2_new changed.txt"]);
+
+        // Unchanged files are omitted
+        assert!(!unified.contains("same.txt"));
+
+        // Removed, added and moved files carry their hashes
+        assert!(lines.contains(&"- hash3 removed.txt"));
+        assert!(lines.contains(&"+ hash5 added.txt"));
+        assert!(lines.contains(&"- hash4 old_name.txt"));
+        assert!(lines.contains(&"+ hash4 new_name.txt"));
+        assert_eq!(lines.len(), 2 + 6);
+
+        fs::remove_file(db1_path).unwrap();
+        fs::remove_file(db2_path).unwrap();
+    }
+
+    #[test]
+    fn test_compare_multi_identifies_change_point() {
+        let db_paths: Vec<PathBuf> = (1..=3)
+            .map(|i| PathBuf::from(format!("test_compare_multi_db{}.txt", i)))
+            .collect();
+
+        fs::write(&db_paths[0], "hash1  sha256  normal  stable.txt\n\
+                                 hash2  sha256  normal  late_change.txt\n").unwrap();
+        fs::write(&db_paths[1], "hash1  sha256  normal  stable.txt\n\
+                                 hash2  sha256  normal  late_change.txt\n\
+                                 hash3  sha256  normal  appears.txt\n").unwrap();
+        fs::write(&db_paths[2], "hash1  sha256  normal  stable.txt\n\
+                                 hash2_new  sha256  normal  late_change.txt\n\
+                                 hash3  sha256  normal  appears.txt\n").unwrap();
+
+        let engine = CompareEngine::new();
+        let report = engine.compare_multi(&db_paths).unwrap();
+
+        assert_eq!(report.files.len(), 3);
+        assert_eq!(report.changed_count, 2);
+        assert_eq!(report.unchanged_count, 1);
+
+        let find = |name: &str| report.files.iter().find(|f| f.path == Path::new(name)).unwrap();
+
+        let stable = find("stable.txt");
+        assert!(!stable.changed());
+        assert_eq!(stable.hashes, vec![Some("hash1".to_string()); 3]);
+
+        // Changed between snapshot 2 and 3
+        let late_change = find("late_change.txt");
+        assert_eq!(late_change.change_points, vec![3]);
+        assert_eq!(late_change.hashes[2], Some("hash2_new".to_string()));
+
+        // Appeared in snapshot 2
+        let appears = find("appears.txt");
+        assert_eq!(appears.hashes[0], None);
+        assert_eq!(appears.change_points, vec![2]);
+
+        let text = report.to_plain_text();
+        assert!(text.contains("late_change.txt: DB2 -> DB3"));
+
+        let json: serde_json::Value = serde_json::from_str(&report.to_json().unwrap()).unwrap();
+        assert_eq!(json["files"].as_array().unwrap().len(), 3);
+        assert_eq!(json["changed_count"], 2);
+
+        for path in &db_paths {
+            fs::remove_file(path).unwrap();
+        }
+    }
+
+    #[test]
+    fn test_compare_multi_requires_two_databases() {
+        let engine = CompareEngine::new();
+        let result = engine.compare_multi(&[PathBuf::from("only_one.txt")]);
+        assert!(matches!(result, Err(HashUtilityError::InvalidArguments { .. })));
+    }
 }
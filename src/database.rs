@@ -3,29 +3,136 @@
 
 use std::collections::HashMap;
 use std::fs::File;
-use std::io::{self, BufRead, BufReader, Write};
+use std::io::{self, BufRead, BufReader, Cursor, Write};
 use std::path::{Path, PathBuf};
 use xz2::read::XzDecoder;
 use xz2::write::XzEncoder;
 
+use crate::hash::{HashComputer, HashError, HashRegistry, HashResult, SYMLINK_ALGORITHM};
 use crate::path_utils;
 use crate::error::HashUtilityError;
 
+/// Marker for the optional permissions column in standard format databases
+const PERMISSIONS_PREFIX: &str = "perm:";
+
+/// Marker for the optional modification time column in standard format databases
+const MODIFIED_PREFIX: &str = "mtime:";
+
+/// Marker for the optional file size column in standard format databases
+const SIZE_PREFIX: &str = "size:";
+
+/// Mode column value for entries hashed from name, size and mtime instead of content
+const STRUCTURE_MODE: &str = "structure";
+
+/// Mode column value for original fast mode entries (sampled regions only)
+const FAST_MODE: &str = "fast";
+
+/// Mode column value for fast mode entries whose hash also covers the file size
+const FAST_V2_MODE: &str = "fast2";
+
+/// First line of the optional standard format header
+const STANDARD_HEADER: &str = "# quichash standard v1";
+
 /// Database entry with metadata
 #[derive(Debug, Clone)]
 pub struct DatabaseEntry {
     pub hash: String,
     pub algorithm: String,
     pub fast_mode: bool,
+    /// Recorded file permissions (octal mode on Unix, "ro"/"rw" elsewhere), if stored
+    pub permissions: Option<String>,
+    /// Recorded modification time (RFC3339), if stored
+    pub modified: Option<String>,
+    /// Recorded file size in bytes, if stored in a standard format `size:` column
+    pub size: Option<u64>,
+    /// Hash covers the file's name, size and mtime rather than its content
+    pub structure_only: bool,
+    /// Fast mode hash that also folds in the file size (`fast2`); implies `fast_mode`
+    pub fast_v2: bool,
+}
+
+/// Optional metadata columns of a standard format entry, written before the path
+#[derive(Debug, Clone, Copy, Default)]
+pub struct EntryMetadata<'a> {
+    /// File permissions (`perm:` column)
+    pub permissions: Option<&'a str>,
+    /// Modification time (`mtime:` column)
+    pub modified: Option<&'a str>,
+    /// File size in bytes (`size:` column)
+    pub size: Option<u64>,
+}
+
+impl DatabaseEntry {
+    /// Metadata columns to write back for this entry
+    pub fn metadata(&self) -> EntryMetadata<'_> {
+        EntryMetadata {
+            permissions: self.permissions.as_deref(),
+            modified: self.modified.as_deref(),
+            size: self.size,
+        }
+    }
+    
+    /// Hash the file at `path` the way this entry was hashed: as a symlink target,
+    /// from its structure, in either fast mode scheme, or in full with the entry's algorithm
+    pub fn rehash(&self, computer: &HashComputer, path: &Path) -> Result<HashResult, HashError> {
+        if self.algorithm == SYMLINK_ALGORITHM {
+            computer.compute_symlink_hash(path)
+        } else if self.structure_only {
+            computer.compute_structure_hash(path, &self.algorithm)
+        } else if self.fast_v2 {
+            computer.compute_hash_fast_v2(path, &self.algorithm)
+        } else if self.fast_mode {
+            computer.compute_hash_fast(path, &self.algorithm)
+        } else {
+            computer.compute_hash(path, &self.algorithm)
+        }
+    }
+    
+    /// Value of the standard format mode column for this entry
+    pub fn mode(&self) -> &'static str {
+        if self.fast_mode && !self.fast_v2 {
+            // Keep legacy entries in their original scheme
+            FAST_MODE
+        } else {
+            DatabaseHandler::mode_label(self.fast_mode, self.structure_only)
+        }
+    }
+}
+
+/// A path listed more than once with different hashes, found while deduplicating
+#[derive(Debug, Clone, PartialEq, serde::Serialize)]
+pub struct DuplicateConflict {
+    pub path: PathBuf,
+    /// Hash of the earlier entry, which was dropped
+    pub dropped_hash: String,
+    /// Hash of the most recent entry, which was kept
+    pub kept_hash: String,
 }
 
 /// Database format type
 #[derive(Debug, Clone, Copy, PartialEq)]
 pub enum DatabaseFormat {
-    /// Standard format: hash  algorithm  fast_mode  filepath
+    /// Standard format: hash  algorithm  mode  [perm:mode]  [mtime:time]  filepath
     Standard,
     /// Hashdeep format: size,hash1,hash2,...,filename
     Hashdeep,
+    /// Tab-separated format: hash<TAB>algorithm<TAB>size<TAB>filepath
+    Tsv,
+    /// Coreutils format, as written by sha256sum: `hash  filepath` (text) or `hash *filepath` (binary)
+    Coreutils,
+    /// JSON format: a single array of entry objects, see [`JsonDbEntry`]
+    JsonDb,
+}
+
+/// One entry of a json-db database
+#[derive(Debug, Clone, PartialEq, serde::Serialize, serde::Deserialize)]
+pub struct JsonDbEntry {
+    pub path: String,
+    pub hash: String,
+    pub algorithm: String,
+    /// Same values as the standard format mode column (`normal`, `fast2`, `structure`)
+    pub mode: String,
+    pub size: u64,
 }
 
 /// Handler for reading and writing hash database files
@@ -93,52 +200,191 @@ impl DatabaseHandler {
         }
     }
     
+    /// Error for a failed read partway through a database
+    /// 
+    /// Decompression failures in an .xz database (e.g. a compression run that was
+    /// interrupted) are reported as a truncated or corrupt database rather than a raw xz error.
+    pub fn read_error(err: io::Error, path: &Path) -> HashUtilityError {
+        if Self::is_compressed(path) {
+            HashUtilityError::CorruptDatabase {
+                path: path.to_path_buf(),
+                reason: err.to_string(),
+            }
+        } else {
+            HashUtilityError::from_io_error(err, "reading database", Some(path.to_path_buf()))
+        }
+    }
+    
     /// Detect the format of a database file by reading its first few lines
     pub fn detect_format(path: &Path) -> Result<DatabaseFormat, HashUtilityError> {
         let reader = Self::open_database_reader(path)?;
+        let lines = reader
+            .lines()
+            .take(10)
+            .collect::<io::Result<Vec<_>>>()
+            .map_err(|e| Self::read_error(e, path))?;
         
-        for line_result in reader.lines().take(10) {
-            let line = line_result.map_err(|e| {
-                HashUtilityError::from_io_error(e, "reading database", Some(path.to_path_buf()))
-            })?;
-            
+        Ok(Self::detect_format_from_lines(lines.iter().map(String::as_str)))
+    }
+    
+    /// Detect the database format from its first few lines
+    fn detect_format_from_lines<'a>(lines: impl Iterator<Item = &'a str>) -> DatabaseFormat {
+        for line in lines.take(10) {
             let trimmed = line.trim();
             
-            // Skip empty lines
-            if trimmed.is_empty() {
+            // Skip empty lines and standard format header comments
+            if trimmed.is_empty() || trimmed.starts_with('#') {
                 continue;
             }
             
+            // A JSON array can't start any line of the text formats
+            if trimmed.starts_with('[') {
+                return DatabaseFormat::JsonDb;
+            }
+            
             // Check for hashdeep header (starts with %)
             if trimmed.starts_with('%') {
-                return Ok(DatabaseFormat::Hashdeep);
+                return DatabaseFormat::Hashdeep;
+            }
+            
+            // Check for tab-separated format before CSV, since paths may contain commas.
+            // Tab-separated hashdeep lines (size<TAB>hash...<TAB>filename) don't fit the TSV layout.
+            if trimmed.contains('\t') {
+                if Self::parse_tsv_line(trimmed).is_some() {
+                    return DatabaseFormat::Tsv;
+                }
+                return DatabaseFormat::Hashdeep;
+            }
+            
+            // Checksum lines without algorithm and mode columns, checked first since paths may contain commas
+            if Self::parse_entry_line(trimmed).is_none() && Self::parse_coreutils_line(trimmed).is_some() {
+                return DatabaseFormat::Coreutils;
             }
             
             // Check for hashdeep CSV format (contains commas)
             if trimmed.contains(',') {
-                return Ok(DatabaseFormat::Hashdeep);
+                return DatabaseFormat::Hashdeep;
             }
             
             // Check for standard format (contains two spaces)
             if trimmed.contains("  ") {
-                return Ok(DatabaseFormat::Standard);
+                return DatabaseFormat::Standard;
             }
         }
         
         // Default to standard format if we can't determine
-        Ok(DatabaseFormat::Standard)
+        DatabaseFormat::Standard
     }
-    /// Write a single hash entry to the output writer
-    /// Format: `<hash>  <algorithm>  <fast_mode>  <filepath>` (two spaces between fields)
-    pub fn write_entry(
+    /// Mode column value for new entries: `normal`, `fast2`, or `structure` (structure-only entries)
+    /// 
+    /// Older databases may also contain `fast` entries, which verify with the original fast scheme.
+    pub fn mode_label(fast_mode: bool, structure_only: bool) -> &'static str {
+        if structure_only {
+            STRUCTURE_MODE
+        } else if fast_mode {
+            FAST_V2_MODE
+        } else {
+            "normal"
+        }
+    }
+    
+    /// Write a single hash entry, optionally recording permissions, modification time and size
+    /// Format: `<hash>  <algorithm>  <mode>  [perm:<mode>]  [mtime:<time>]  [size:<bytes>]  <filepath>`
+    pub fn write_entry_with_metadata(
+        writer: &mut impl Write,
+        hash: &str,
+        algorithm: &str,
+        mode: &str,
+        metadata: EntryMetadata,
+        path: &Path,
+    ) -> io::Result<()> {
+        write!(writer, "{}  {}  {}  ", hash, algorithm, mode)?;
+        if let Some(perm) = metadata.permissions {
+            write!(writer, "{}{}  ", PERMISSIONS_PREFIX, perm)?;
+        }
+        if let Some(time) = metadata.modified {
+            write!(writer, "{}{}  ", MODIFIED_PREFIX, time)?;
+        }
+        if let Some(size) = metadata.size {
+            write!(writer, "{}{}  ", SIZE_PREFIX, size)?;
+        }
+        writeln!(writer, "{}", path.display())
+    }
+    
+    /// Format a file's modification time for storage in the database
+    /// 
+    /// Uses RFC3339 in UTC with whole seconds (e.g. "2024-01-15T10:00:00Z").
+    /// Returns None if the platform does not report modification times.
+    pub fn format_modified(metadata: &std::fs::Metadata) -> Option<String> {
+        let modified: chrono::DateTime<chrono::Utc> = metadata.modified().ok()?.into();
+        Some(modified.to_rfc3339_opts(chrono::SecondsFormat::Secs, true))
+    }
+    
+    /// Format a file's permissions for storage in the database
+    /// 
+    /// On Unix this is the octal mode (e.g. "0644"); elsewhere only the
+    /// read-only flag is available, stored as "ro" or "rw".
+    pub fn format_permissions(metadata: &std::fs::Metadata) -> String {
+        #[cfg(unix)]
+        {
+            use std::os::unix::fs::PermissionsExt;
+            format!("{:04o}", metadata.permissions().mode() & 0o7777)
+        }
+        #[cfg(not(unix))]
+        {
+            if metadata.permissions().readonly() { "ro".to_string() } else { "rw".to_string() }
+        }
+    }
+    
+    /// Write the opening of a json-db array
+    pub fn write_json_db_start(writer: &mut impl Write) -> io::Result<()> {
+        writeln!(writer, "[")
+    }
+    
+    /// Write one json-db entry, on its own line; every entry but the first is preceded by a comma
+    pub fn write_json_db_entry(
         writer: &mut impl Write,
+        first: bool,
         hash: &str,
         algorithm: &str,
-        fast_mode: bool,
+        mode: &str,
+        size: u64,
         path: &Path,
     ) -> io::Result<()> {
-        let fast_str = if fast_mode { "fast" } else { "normal" };
-        writeln!(writer, "{}  {}  {}  {}", hash, algorithm, fast_str, path.display())
+        let entry = JsonDbEntry {
+            path: path.display().to_string(),
+            hash: hash.to_string(),
+            algorithm: algorithm.to_string(),
+            mode: mode.to_string(),
+            size,
+        };
+        if !first {
+            writeln!(writer, ",")?;
+        }
+        write!(writer, "  ")?;
+        serde_json::to_writer(&mut *writer, &entry).map_err(io::Error::from)
+    }
+    
+    /// Close a json-db array after `entries` entries
+    pub fn write_json_db_end(writer: &mut impl Write, entries: usize) -> io::Result<()> {
+        if entries > 0 {
+            writeln!(writer)?;
+        }
+        writeln!(writer, "]")
+    }
+    
+    /// Write the optional standard format header
+    /// Comment lines recording when and how the database was created
+    pub fn write_standard_header(
+        writer: &mut impl Write,
+        algorithm: &str,
+        command_line: &str,
+    ) -> io::Result<()> {
+        writeln!(writer, "{}", STANDARD_HEADER)?;
+        writeln!(writer, "# created: {}", chrono::Utc::now().to_rfc3339_opts(chrono::SecondsFormat::Secs, true))?;
+        writeln!(writer, "# algorithm: {}", algorithm)?;
+        writeln!(writer, "# command: {}", command_line)?;
+        Ok(())
     }
     
     /// Write hashdeep format header
@@ -170,42 +416,126 @@ impl DatabaseHandler {
         writeln!(writer, ",{}", path.display())
     }
     
+    /// Write a single entry in tab-separated format
+    /// Format: `<hash>\t<algorithm>\t<size>\t<filepath>`
+    pub fn write_tsv_entry(
+        writer: &mut impl Write,
+        hash: &str,
+        algorithm: &str,
+        size: u64,
+        path: &Path,
+    ) -> io::Result<()> {
+        writeln!(writer, "{}\t{}\t{}\t{}", hash, algorithm, size, path.display())
+    }
+    
+    /// Write a single entry in coreutils format
+    /// Format: `<hash>  <filepath>`, or `<hash> *<filepath>` when marked binary
+    pub fn write_coreutils_entry(
+        writer: &mut impl Write,
+        hash: &str,
+        binary: bool,
+        path: &Path,
+    ) -> io::Result<()> {
+        let marker = if binary { '*' } else { ' ' };
+        writeln!(writer, "{} {}{}", hash, marker, path.display())
+    }
+    
     /// Read a hash database file and parse it into a HashMap
     /// Maps file paths to their database entries (hash, algorithm, fast_mode)
     /// Malformed lines are skipped with a warning to stderr
-    /// Auto-detects format (standard or hashdeep)
+    /// Auto-detects format (standard, hashdeep, or tsv)
     pub fn read_database(path: &Path) -> Result<HashMap<PathBuf, DatabaseEntry>, HashUtilityError> {
+        Self::read_database_with(path, false)
+    }
+    
+    /// Read a hash database file, optionally parsing standard and coreutils lines leniently
+    /// 
+    /// With `lenient` (`--lenient`), trailing `#` comments are dropped, surrounding whitespace
+    /// is trimmed, and any run of spaces or tabs separates the columns before the path.
+    pub fn read_database_with(path: &Path, lenient: bool) -> Result<HashMap<PathBuf, DatabaseEntry>, HashUtilityError> {
         let format = Self::detect_format(path)?;
+        Self::read_format_from(format, Self::open_database_reader(path)?, path, lenient)
+    }
+    
+    /// Read several databases and union their entries into one (`verify --merge-databases`)
+    /// 
+    /// A path listed in more than one database keeps the entry read last, with a
+    /// warning to stderr if the hashes disagree.
+    pub fn read_merged(paths: &[PathBuf], lenient: bool) -> Result<HashMap<PathBuf, DatabaseEntry>, HashUtilityError> {
+        let mut merged: HashMap<PathBuf, DatabaseEntry> = HashMap::new();
+        for path in paths {
+            for (file, entry) in Self::read_database_with(path, lenient)? {
+                if merged.get(&file).is_some_and(|previous| previous.hash != entry.hash) {
+                    eprintln!(
+                        "Warning: {} has different hashes in the merged databases; using the one from {}",
+                        file.display(),
+                        path.display()
+                    );
+                }
+                merged.insert(file, entry);
+            }
+        }
+        Ok(merged)
+    }
+    
+    /// Read a database from any reader (e.g. stdin), auto-detecting its format
+    /// 
+    /// `source` names the input in warnings and errors. The content is buffered
+    /// in memory so the format can be detected before parsing. With `lenient`,
+    /// it is parsed as in `read_database_with`.
+    pub fn read_database_from_with<R: BufRead>(mut reader: R, source: &Path, lenient: bool) -> Result<HashMap<PathBuf, DatabaseEntry>, HashUtilityError> {
+        let mut content = String::new();
+        reader.read_to_string(&mut content).map_err(|e| {
+            Self::read_error(e, source)
+        })?;
         
-        match format {
-            DatabaseFormat::Standard => Self::read_standard_database(path),
-            DatabaseFormat::Hashdeep => Self::read_hashdeep_database(path),
+        let format = Self::detect_format_from_lines(content.lines());
+        Self::read_format_from(format, Cursor::new(content), source, lenient)
+    }
+    
+    /// Parse database content in a known format
+    /// 
+    /// Algorithm names are canonicalized (`SHA-256` -> `sha256`), so databases
+    /// written with different spellings of the same algorithm compare equal.
+    fn read_format_from<R: BufRead>(format: DatabaseFormat, reader: R, source: &Path, lenient: bool) -> Result<HashMap<PathBuf, DatabaseEntry>, HashUtilityError> {
+        let mut database = match format {
+            DatabaseFormat::Standard => Self::read_standard_from(reader, source, lenient)?,
+            DatabaseFormat::Hashdeep => Self::read_hashdeep_from(reader, source)?,
+            DatabaseFormat::Tsv => Self::read_tsv_entries_from(reader, source)?
+                .into_iter()
+                .map(|(file_path, entry, _)| (file_path, entry))
+                .collect(),
+            DatabaseFormat::Coreutils => Self::read_coreutils_from(reader, source, lenient)?,
+            DatabaseFormat::JsonDb => Self::read_json_db_entries_from(reader, source)?
+                .into_iter()
+                .map(|(file_path, entry, _)| (file_path, entry))
+                .collect(),
+        };
+        for entry in database.values_mut() {
+            entry.algorithm = HashRegistry::canonical_name(&entry.algorithm);
         }
+        Ok(database)
     }
     
-    /// Read a standard format database file
-    fn read_standard_database(path: &Path) -> Result<HashMap<PathBuf, DatabaseEntry>, HashUtilityError> {
-        let reader = Self::open_database_reader(path)?;
+    /// Read standard format database content
+    /// Lines starting with `#` (the optional header) are skipped
+    fn read_standard_from<R: BufRead>(reader: R, path: &Path, lenient: bool) -> Result<HashMap<PathBuf, DatabaseEntry>, HashUtilityError> {
         let mut database = HashMap::new();
         
         for (line_num, line_result) in reader.lines().enumerate() {
-            let line = line_result.map_err(|e| {
-                HashUtilityError::from_io_error(e, "reading database", Some(path.to_path_buf()))
-            })?;
+            let line = line_result.map_err(|e| Self::read_error(e, path))?;
+            let line = if lenient { Self::strip_lenient(&line) } else { line.as_str() };
             
-            // Skip empty lines
-            if line.trim().is_empty() {
+            // Skip empty lines and header comments
+            if line.trim().is_empty() || line.starts_with('#') {
                 continue;
             }
             
-            // Parse line: split on two spaces
-            match Self::parse_line(&line) {
-                Some((hash, algorithm, fast_mode, file_path)) => {
-                    database.insert(file_path, DatabaseEntry {
-                        hash,
-                        algorithm,
-                        fast_mode,
-                    });
+            // Parse line: split on two spaces (or any whitespace run when lenient)
+            let parsed = if lenient { Self::parse_entry_line_lenient(line) } else { Self::parse_entry_line(line) };
+            match parsed {
+                Some((file_path, entry)) => {
+                    database.insert(file_path, entry);
                 }
                 None => {
                     // Warn about malformed line but continue processing (Requirement 2.4)
@@ -222,12 +552,59 @@ impl DatabaseHandler {
         Ok(database)
     }
     
-    /// Parse a single line from the database file
-    /// Expected format: `<hash>  <algorithm>  <fast_mode>  <filepath>` (two spaces between fields)
-    /// Returns None if the line is malformed
-    /// Handles both forward and backward slashes in paths
-    /// Note: Filenames may contain two spaces, so we only split on the first 3 delimiters
-    fn parse_line(line: &str) -> Option<(String, String, bool, PathBuf)> {
+    /// Drop a trailing `#` comment and the surrounding whitespace from a line (`--lenient`)
+    /// 
+    /// A `#` only starts a comment at the beginning of a whitespace-separated word,
+    /// so names like `file#1.txt` survive, but a path containing ` #` is cut short.
+    pub fn strip_lenient(line: &str) -> &str {
+        let line = line.trim();
+        let end = line
+            .char_indices()
+            .find(|&(i, c)| c == '#' && (i == 0 || line[..i].ends_with(char::is_whitespace)))
+            .map_or(line.len(), |(i, _)| i);
+        line[..end].trim_end()
+    }
+    
+    /// Split off the first column, however many spaces or tabs follow it
+    fn split_lenient_field(line: &str) -> Option<(&str, &str)> {
+        let (field, rest) = line.split_once(char::is_whitespace)?;
+        Some((field, rest.trim_start()))
+    }
+    
+    /// Parse a checksum line with any run of spaces or tabs between hash and path (`--lenient`)
+    /// 
+    /// The line should already have been through `strip_lenient`.
+    pub fn parse_coreutils_line_lenient(line: &str) -> Option<(&str, &str)> {
+        let (hash, path) = Self::split_lenient_field(line)?;
+        let path = path.strip_prefix('*').unwrap_or(path);
+        if hash.is_empty() || path.is_empty() || !hash.chars().all(|c| c.is_ascii_hexdigit()) {
+            return None;
+        }
+        Some((hash, path))
+    }
+    
+    /// Parse a standard format line with any run of spaces or tabs between the leading columns (`--lenient`)
+    fn parse_entry_line_lenient(line: &str) -> Option<(PathBuf, DatabaseEntry)> {
+        let (hash, rest) = Self::split_lenient_field(line)?;
+        let (algorithm, rest) = Self::split_lenient_field(rest)?;
+        let (mode, path) = Self::split_lenient_field(rest)?;
+        Self::parse_entry_line(&format!("{}  {}  {}  {}", hash, algorithm, mode, path))
+    }
+    
+    /// Parse a mode column value into (fast_mode, fast_v2, structure_only)
+    fn parse_mode(mode: &str) -> Option<(bool, bool, bool)> {
+        match mode {
+            FAST_MODE => Some((true, false, false)),
+            FAST_V2_MODE => Some((true, true, false)),
+            "normal" => Some((false, false, false)),
+            STRUCTURE_MODE => Some((false, false, true)),
+            _ => None,
+        }
+    }
+    
+    /// Parse a single line into a database entry, including the optional
+    /// `perm:<mode>`, `mtime:<time>` and `size:<bytes>` columns before the path
+    fn parse_entry_line(line: &str) -> Option<(PathBuf, DatabaseEntry)> {
         // Split on two spaces, but only for the first 3 fields
         // The rest is the filename (which may contain two spaces)
         let parts: Vec<&str> = line.splitn(4, "  ").collect();
@@ -236,39 +613,278 @@ impl DatabaseHandler {
             let hash = parts[0].trim();
             let algorithm = parts[1].trim();
             let fast_mode_str = parts[2].trim();
-            let path_str = parts[3].trim();
+            let mut path_str = parts[3].trim();
             
-            // Parse fast_mode
-            let fast_mode = match fast_mode_str {
-                "fast" => true,
-                "normal" => false,
-                _ => return None, // Invalid fast_mode value
-            };
+            // Parse the mode column
+            let (fast_mode, fast_v2, structure_only) = Self::parse_mode(fast_mode_str)?;
+            
+            // Peel off the optional metadata columns, which always precede the path in this order
+            let mut permissions = None;
+            let mut modified = None;
+            for (prefix, value) in [(PERMISSIONS_PREFIX, &mut permissions), (MODIFIED_PREFIX, &mut modified)] {
+                if let Some(rest) = path_str.strip_prefix(prefix) {
+                    if let Some((column, file)) = rest.split_once("  ") {
+                        *value = Some(column.to_string());
+                        path_str = file.trim();
+                    }
+                }
+            }
+            let mut size = None;
+            if let Some((column, file)) = path_str.strip_prefix(SIZE_PREFIX).and_then(|rest| rest.split_once("  ")) {
+                if let Ok(bytes) = column.parse() {
+                    size = Some(bytes);
+                    path_str = file.trim();
+                }
+            }
             
             // Validate that all fields are not empty
             if !hash.is_empty() && !algorithm.is_empty() && !path_str.is_empty() {
                 // Use path_utils to parse the path with proper separator handling
                 let path = path_utils::parse_database_path(path_str);
-                return Some((hash.to_string(), algorithm.to_string(), fast_mode, path));
+                return Some((path, DatabaseEntry {
+                    hash: hash.to_string(),
+                    algorithm: algorithm.to_string(),
+                    fast_mode,
+                    permissions,
+                    modified,
+                    size,
+                    structure_only,
+                    fast_v2,
+                }));
             }
         }
         
         None
     }
     
-    /// Read a hashdeep format database file
+    /// Read the recorded file size of every entry (`verify --compare-sizes`)
+    /// 
+    /// Only the tsv and hashdeep formats have a size column; other formats are an error.
+    pub fn read_sizes(path: &Path) -> Result<HashMap<PathBuf, u64>, HashUtilityError> {
+        match Self::detect_format(path)? {
+            DatabaseFormat::Tsv => Ok(Self::read_tsv_entries(path)?
+                .into_iter()
+                .map(|(file_path, _, size)| (file_path, size))
+                .collect()),
+            DatabaseFormat::Hashdeep => Self::read_hashdeep_sizes_from(Self::open_database_reader(path)?, path),
+            DatabaseFormat::JsonDb => Ok(Self::read_json_db_entries(path)?
+                .into_iter()
+                .map(|(file_path, _, size)| (file_path, size))
+                .collect()),
+            DatabaseFormat::Standard | DatabaseFormat::Coreutils => Err(HashUtilityError::InvalidArguments {
+                message: format!(
+                    "Database '{}' has no size column; write it with 'scan --format tsv' or '--format hashdeep'",
+                    path.display()
+                ),
+            }),
+        }
+    }
+    
+    /// Read the size and filename of each hashdeep line
+    fn read_hashdeep_sizes_from<R: BufRead>(reader: R, path: &Path) -> Result<HashMap<PathBuf, u64>, HashUtilityError> {
+        let mut sizes = HashMap::new();
+        
+        for (line_num, line_result) in reader.lines().enumerate() {
+            let line = line_result.map_err(|e| Self::read_error(e, path))?;
+            let trimmed = line.trim();
+            
+            // Skip empty lines, comments and headers
+            if trimmed.is_empty() || trimmed.starts_with('#') || trimmed.starts_with('%') {
+                continue;
+            }
+            
+            let parts: Vec<&str> = trimmed.split(Self::hashdeep_separator(trimmed)).collect();
+            let size = parts[0].trim().parse::<u64>().ok();
+            let filename = parts[parts.len() - 1].trim();
+            match size {
+                Some(size) if parts.len() >= 3 && !filename.is_empty() => {
+                    sizes.insert(path_utils::parse_database_path(filename), size);
+                }
+                _ => {
+                    eprintln!(
+                        "Warning: Skipping malformed line {} in hashdeep database {}: {}",
+                        line_num + 1,
+                        path.display(),
+                        trimmed
+                    );
+                }
+            }
+        }
+        
+        Ok(sizes)
+    }
+    
+    /// Read a json-db database file, keeping the file size of each entry
+    pub fn read_json_db_entries(path: &Path) -> Result<Vec<(PathBuf, DatabaseEntry, u64)>, HashUtilityError> {
+        Self::read_json_db_entries_from(Self::open_database_reader(path)?, path)
+    }
+    
+    /// Read json-db content, keeping the file size of each entry
+    /// 
+    /// The whole document must parse; entries with an unknown mode are skipped with a warning.
+    fn read_json_db_entries_from<R: BufRead>(reader: R, path: &Path) -> Result<Vec<(PathBuf, DatabaseEntry, u64)>, HashUtilityError> {
+        let entries: Vec<JsonDbEntry> = serde_json::from_reader(reader).map_err(|e| HashUtilityError::CorruptDatabase {
+            path: path.to_path_buf(),
+            reason: e.to_string(),
+        })?;
+        
+        let mut database = Vec::with_capacity(entries.len());
+        for entry in entries {
+            let Some((fast_mode, fast_v2, structure_only)) = Self::parse_mode(&entry.mode) else {
+                eprintln!(
+                    "Warning: Skipping entry with unknown mode '{}' in JSON database {}: {}",
+                    entry.mode,
+                    path.display(),
+                    entry.path
+                );
+                continue;
+            };
+            database.push((path_utils::parse_database_path(&entry.path), DatabaseEntry {
+                hash: entry.hash,
+                algorithm: HashRegistry::canonical_name(&entry.algorithm),
+                fast_mode,
+                permissions: None,
+                modified: None,
+                size: None,
+                structure_only,
+                fast_v2,
+            }, entry.size));
+        }
+        
+        Ok(database)
+    }
+    
+    /// Read a tab-separated database file, keeping the file size of each entry
+    /// Format: `<hash>\t<algorithm>\t<size>\t<filepath>`
+    /// Malformed lines are skipped with a warning to stderr
+    pub fn read_tsv_entries(path: &Path) -> Result<Vec<(PathBuf, DatabaseEntry, u64)>, HashUtilityError> {
+        Self::read_tsv_entries_from(Self::open_database_reader(path)?, path)
+    }
+    
+    /// Read tab-separated database content, keeping the file size of each entry
+    fn read_tsv_entries_from<R: BufRead>(reader: R, path: &Path) -> Result<Vec<(PathBuf, DatabaseEntry, u64)>, HashUtilityError> {
+        let mut entries = Vec::new();
+        
+        for (line_num, line_result) in reader.lines().enumerate() {
+            let line = line_result.map_err(|e| Self::read_error(e, path))?;
+            
+            // Skip empty lines
+            if line.trim().is_empty() {
+                continue;
+            }
+            
+            match Self::parse_tsv_line(&line) {
+                Some((hash, algorithm, size, file_path)) => {
+                    entries.push((file_path, DatabaseEntry {
+                        hash,
+                        algorithm: HashRegistry::canonical_name(&algorithm),
+                        fast_mode: false,
+                        permissions: None,
+                        modified: None,
+                        size: None,
+                        structure_only: false,
+                        fast_v2: false,
+                    }, size));
+                }
+                None => {
+                    eprintln!(
+                        "Warning: Skipping malformed line {} in TSV database {}: {}",
+                        line_num + 1,
+                        path.display(),
+                        line
+                    );
+                }
+            }
+        }
+        
+        Ok(entries)
+    }
+    
+    /// Parse a single tab-separated line
+    /// Only the first 3 tabs are delimiters, so the path may itself contain tabs
+    fn parse_tsv_line(line: &str) -> Option<(String, String, u64, PathBuf)> {
+        let parts: Vec<&str> = line.trim_end_matches(['\r', '\n']).splitn(4, '\t').collect();
+        
+        if parts.len() != 4 {
+            return None;
+        }
+        
+        let hash = parts[0].trim();
+        let algorithm = parts[1].trim();
+        let size: u64 = parts[2].trim().parse().ok()?;
+        let path_str = parts[3];
+        
+        if hash.is_empty() || algorithm.is_empty() || path_str.is_empty() {
+            return None;
+        }
+        
+        Some((hash.to_string(), algorithm.to_string(), size, path_utils::parse_database_path(path_str)))
+    }
+    
+    /// Split a coreutils checksum line into its hash and path
+    /// 
+    /// Accepts `sha256sum` style lines: `<hash>  <path>` (text) or `<hash> *<path>` (binary).
+    pub fn parse_coreutils_line(line: &str) -> Option<(&str, &str)> {
+        let (hash, rest) = line.split_once(' ')?;
+        let path = rest.strip_prefix(' ').or_else(|| rest.strip_prefix('*'))?;
+        if hash.is_empty() || path.is_empty() || !hash.chars().all(|c| c.is_ascii_hexdigit()) {
+            return None;
+        }
+        Some((hash, path))
+    }
+    
+    /// Read coreutils format database content
+    /// The lines don't name their algorithm, so it is inferred from the hash length
+    /// Lines starting with `#` are skipped
+    fn read_coreutils_from<R: BufRead>(reader: R, path: &Path, lenient: bool) -> Result<HashMap<PathBuf, DatabaseEntry>, HashUtilityError> {
+        let mut database = HashMap::new();
+        
+        for (line_num, line_result) in reader.lines().enumerate() {
+            let line = line_result.map_err(|e| Self::read_error(e, path))?;
+            let line = if lenient { Self::strip_lenient(&line) } else { line.trim_end_matches('\r') };
+            
+            if line.trim().is_empty() || line.starts_with('#') {
+                continue;
+            }
+            
+            let parsed = if lenient { Self::parse_coreutils_line_lenient(line) } else { Self::parse_coreutils_line(line) };
+            match parsed {
+                Some((hash, file_path)) => {
+                    database.insert(path_utils::parse_database_path(file_path), DatabaseEntry {
+                        hash: hash.to_lowercase(),
+                        algorithm: Self::infer_algorithm_from_hash(hash),
+                        fast_mode: false,
+                        permissions: None,
+                        modified: None,
+                        size: None,
+                        structure_only: false,
+                        fast_v2: false,
+                    });
+                }
+                None => {
+                    eprintln!(
+                        "Warning: Skipping malformed line {} in database {}: {}",
+                        line_num + 1,
+                        path.display(),
+                        line
+                    );
+                }
+            }
+        }
+        
+        Ok(database)
+    }
+    
+    /// Read hashdeep format database content
     /// Format: size,hash1,hash2,...,filename
     /// Header lines start with %
     /// Note: For files with multiple hashes, only the first hash is stored
-    fn read_hashdeep_database(path: &Path) -> Result<HashMap<PathBuf, DatabaseEntry>, HashUtilityError> {
-        let reader = Self::open_database_reader(path)?;
+    fn read_hashdeep_from<R: BufRead>(reader: R, path: &Path) -> Result<HashMap<PathBuf, DatabaseEntry>, HashUtilityError> {
         let mut database = HashMap::new();
         let mut hash_algorithms = Vec::new();
         
         for (line_num, line_result) in reader.lines().enumerate() {
-            let line = line_result.map_err(|e| {
-                HashUtilityError::from_io_error(e, "reading database", Some(path.to_path_buf()))
-            })?;
+            let line = line_result.map_err(|e| Self::read_error(e, path))?;
             
             let trimmed = line.trim();
             
@@ -287,20 +903,8 @@ impl DatabaseHandler {
                 // Extract algorithm information from header
                 // Format: %%%% HASHDEEP-1.0
                 // %%%% size,md5,sha256,filename
-                if trimmed.starts_with("%%%%") && trimmed.contains(',') {
-                    // Parse the algorithm list from header
-                    let header_parts: Vec<&str> = trimmed.split_whitespace().collect();
-                    if header_parts.len() >= 2 {
-                        let fields = header_parts[1];
-                        let field_list: Vec<&str> = fields.split(',').collect();
-                        // First field is size, last is filename, middle are hash algorithms
-                        if field_list.len() >= 3 {
-                            hash_algorithms = field_list[1..field_list.len()-1]
-                                .iter()
-                                .map(|s| s.to_string())
-                                .collect();
-                        }
-                    }
+                if let Some(algorithms) = Self::parse_hashdeep_header(trimmed) {
+                    hash_algorithms = algorithms;
                 }
                 continue;
             }
@@ -328,11 +932,111 @@ impl DatabaseHandler {
         Ok(database)
     }
     
+    /// Remove earlier entries for paths that are listed again later, keeping the most recent
+    /// 
+    /// Works line by line, so kept entries, headers and comments are returned unchanged.
+    /// Returns the deduplicated content, the number of entries removed, and the
+    /// paths whose dropped and kept hashes differ.
+    pub fn dedupe_entries(content: &str, format: DatabaseFormat) -> (String, usize, Vec<DuplicateConflict>) {
+        let mut hash_algorithms = Vec::new();
+        let entries: Vec<Option<(PathBuf, String)>> = content
+            .lines()
+            .map(|line| Self::entry_path_and_hash(line, format, &mut hash_algorithms))
+            .collect();
+        
+        // Index of the last entry for each path
+        let mut latest: HashMap<&Path, usize> = HashMap::new();
+        for (index, entry) in entries.iter().enumerate() {
+            if let Some((path, _)) = entry {
+                latest.insert(path, index);
+            }
+        }
+        
+        let mut kept_lines = Vec::new();
+        let mut removed = 0;
+        let mut conflicts = Vec::new();
+        for (index, line) in content.lines().enumerate() {
+            if let Some((path, hash)) = &entries[index] {
+                let latest_index = latest[path.as_path()];
+                if latest_index != index {
+                    removed += 1;
+                    let kept_hash = &entries[latest_index].as_ref().unwrap().1;
+                    if !hash.eq_ignore_ascii_case(kept_hash) {
+                        conflicts.push(DuplicateConflict {
+                            path: path.clone(),
+                            dropped_hash: hash.clone(),
+                            kept_hash: kept_hash.clone(),
+                        });
+                    }
+                    continue;
+                }
+            }
+            kept_lines.push(line);
+        }
+        
+        let mut deduped = kept_lines.join("\n");
+        if content.ends_with('\n') {
+            deduped.push('\n');
+        }
+        (deduped, removed, conflicts)
+    }
+    
+    /// Path and hash of a database line, or None for headers, comments and malformed lines
+    /// 
+    /// `hash_algorithms` carries the column header between hashdeep lines.
+    fn entry_path_and_hash(line: &str, format: DatabaseFormat, hash_algorithms: &mut Vec<String>) -> Option<(PathBuf, String)> {
+        let line = line.trim_end_matches('\r');
+        if line.trim().is_empty() || line.starts_with('#') {
+            return None;
+        }
+        
+        match format {
+            DatabaseFormat::Standard => Self::parse_entry_line(line).map(|(path, entry)| (path, entry.hash)),
+            DatabaseFormat::Hashdeep => {
+                let trimmed = line.trim();
+                if trimmed.starts_with('%') {
+                    if let Some(algorithms) = Self::parse_hashdeep_header(trimmed) {
+                        *hash_algorithms = algorithms;
+                    }
+                    return None;
+                }
+                Self::parse_hashdeep_line(trimmed, hash_algorithms)?
+                    .into_iter()
+                    .next()
+                    .map(|(path, entry)| (path, entry.hash))
+            }
+            DatabaseFormat::Tsv => Self::parse_tsv_line(line).map(|(hash, _, _, path)| (path, hash)),
+            DatabaseFormat::Coreutils => Self::parse_coreutils_line(line)
+                .map(|(hash, path)| (path_utils::parse_database_path(path), hash.to_lowercase())),
+            // Dropping a line could leave a dangling comma, so JSON databases are never deduplicated
+            DatabaseFormat::JsonDb => None,
+        }
+    }
+    
+    /// Field separator of a hashdeep line: tab for tab-separated variants, comma otherwise
+    pub fn hashdeep_separator(line: &str) -> char {
+        if line.contains('\t') { '\t' } else { ',' }
+    }
+    
+    /// Parse the algorithm list from a hashdeep column header
+    /// Format: `%%%% size,md5,sha256,filename` (fields may also be tab-separated)
+    /// Returns None for other header lines
+    pub fn parse_hashdeep_header(line: &str) -> Option<Vec<String>> {
+        let fields = line.strip_prefix("%%%%")?.trim();
+        let field_list: Vec<&str> = fields.split(Self::hashdeep_separator(fields)).map(str::trim).collect();
+        // First field is size, last is filename, middle are hash algorithms
+        if field_list.len() >= 3 && field_list[0] == "size" {
+            Some(field_list[1..field_list.len()-1].iter().map(|s| s.to_string()).collect())
+        } else {
+            None
+        }
+    }
+    
     /// Parse a single hashdeep format line
-    /// Format: size,hash1,hash2,...,filename
+    /// Format: size,hash1,hash2,...,filename (or the same fields separated by tabs)
     /// Returns multiple entries (one per hash algorithm)
     fn parse_hashdeep_line(line: &str, algorithms: &[String]) -> Option<Vec<(PathBuf, DatabaseEntry)>> {
-        let parts: Vec<&str> = line.split(',').collect();
+        let parts: Vec<&str> = line.split(Self::hashdeep_separator(line)).collect();
         
         // Need at least: size, one hash, filename
         if parts.len() < 3 {
@@ -372,6 +1076,11 @@ impl DatabaseHandler {
                             hash: hash.to_string(),
                             algorithm: algorithms[i].clone(),
                             fast_mode: false,
+                            permissions: None,
+                            modified: None,
+                            size: None,
+                            structure_only: false,
+                            fast_v2: false,
                         }
                     ));
                 }
@@ -387,6 +1096,11 @@ impl DatabaseHandler {
                             hash: hash.to_string(),
                             algorithm,
                             fast_mode: false,
+                            permissions: None,
+                            modified: None,
+                            size: None,
+                            structure_only: false,
+                            fast_v2: false,
                         }
                     ));
                 }
@@ -401,7 +1115,7 @@ impl DatabaseHandler {
     }
     
     /// Infer hash algorithm from hash string length
-    fn infer_algorithm_from_hash(hash: &str) -> String {
+    pub fn infer_algorithm_from_hash(hash: &str) -> String {
         match hash.len() {
             32 => "md5".to_string(),
             40 => "sha1".to_string(),
@@ -424,10 +1138,9 @@ mod tests {
         let mut buffer = Vec::new();
         let hash = "d41d8cd98f00b204e9800998ecf8427e";
         let algorithm = "md5";
-        let fast_mode = false;
         let path = Path::new("./test/file.txt");
         
-        DatabaseHandler::write_entry(&mut buffer, hash, algorithm, fast_mode, path).unwrap();
+        DatabaseHandler::write_entry_with_metadata(&mut buffer, hash, algorithm, "normal", EntryMetadata::default(), path).unwrap();
         
         let output = String::from_utf8(buffer).unwrap();
         assert_eq!(output, "d41d8cd98f00b204e9800998ecf8427e  md5  normal  ./test/file.txt\n");
@@ -437,56 +1150,72 @@ mod tests {
     fn test_write_multiple_entries() {
         let mut buffer = Vec::new();
         
-        DatabaseHandler::write_entry(
+        DatabaseHandler::write_entry_with_metadata(
             &mut buffer,
             "abc123",
             "sha256",
-            false,
+            "normal",
+            EntryMetadata::default(),
             Path::new("file1.txt")
         ).unwrap();
         
-        DatabaseHandler::write_entry(
+        DatabaseHandler::write_entry_with_metadata(
             &mut buffer,
             "def456",
             "sha256",
-            true,
+            "fast2",
+            EntryMetadata::default(),
             Path::new("file2.txt")
         ).unwrap();
         
         let output = String::from_utf8(buffer).unwrap();
-        assert_eq!(output, "abc123  sha256  normal  file1.txt\ndef456  sha256  fast  file2.txt\n");
+        assert_eq!(output, "abc123  sha256  normal  file1.txt\ndef456  sha256  fast2  file2.txt\n");
+    }
+    
+    #[test]
+    fn test_parse_fast_mode_versions() {
+        // Legacy `fast` entries keep their scheme; `fast2` entries also fold in the size
+        let (_, legacy) = DatabaseHandler::parse_entry_line("abc123  sha256  fast  file.bin").unwrap();
+        assert!(legacy.fast_mode);
+        assert!(!legacy.fast_v2);
+        assert_eq!(legacy.mode(), "fast");
+        
+        let (_, v2) = DatabaseHandler::parse_entry_line("abc123  sha256  fast2  file.bin").unwrap();
+        assert!(v2.fast_mode);
+        assert!(v2.fast_v2);
+        assert_eq!(v2.mode(), "fast2");
     }
     
     #[test]
     fn test_parse_line_valid() {
         let line = "d41d8cd98f00b204e9800998ecf8427e  md5  normal  ./test/file.txt";
-        let result = DatabaseHandler::parse_line(line);
+        let result = DatabaseHandler::parse_entry_line(line);
         
         assert!(result.is_some());
-        let (hash, algorithm, fast_mode, path) = result.unwrap();
-        assert_eq!(hash, "d41d8cd98f00b204e9800998ecf8427e");
-        assert_eq!(algorithm, "md5");
-        assert_eq!(fast_mode, false);
+        let (path, entry) = result.unwrap();
+        assert_eq!(entry.hash, "d41d8cd98f00b204e9800998ecf8427e");
+        assert_eq!(entry.algorithm, "md5");
+        assert_eq!(entry.fast_mode, false);
         assert_eq!(path, PathBuf::from("./test/file.txt"));
     }
     
     #[test]
     fn test_parse_line_with_spaces_in_path() {
         let line = "abc123  sha256  fast  ./path with spaces/file.txt";
-        let result = DatabaseHandler::parse_line(line);
+        let result = DatabaseHandler::parse_entry_line(line);
         
         assert!(result.is_some());
-        let (hash, algorithm, fast_mode, path) = result.unwrap();
-        assert_eq!(hash, "abc123");
-        assert_eq!(algorithm, "sha256");
-        assert_eq!(fast_mode, true);
+        let (path, entry) = result.unwrap();
+        assert_eq!(entry.hash, "abc123");
+        assert_eq!(entry.algorithm, "sha256");
+        assert_eq!(entry.fast_mode, true);
         assert_eq!(path, PathBuf::from("./path with spaces/file.txt"));
     }
     
     #[test]
     fn test_parse_line_malformed_missing_fields() {
         let line = "abc123  sha256  file.txt";  // Missing fast_mode field
-        let result = DatabaseHandler::parse_line(line);
+        let result = DatabaseHandler::parse_entry_line(line);
         
         // Should fail because we expect 4 fields
         assert!(result.is_none());
@@ -495,7 +1224,7 @@ mod tests {
     #[test]
     fn test_parse_line_malformed_no_space() {
         let line = "abc123sha256normalfile.txt";
-        let result = DatabaseHandler::parse_line(line);
+        let result = DatabaseHandler::parse_entry_line(line);
         
         assert!(result.is_none());
     }
@@ -503,7 +1232,7 @@ mod tests {
     #[test]
     fn test_parse_line_empty_hash() {
         let line = "  sha256  normal  file.txt";
-        let result = DatabaseHandler::parse_line(line);
+        let result = DatabaseHandler::parse_entry_line(line);
         
         assert!(result.is_none());
     }
@@ -511,7 +1240,7 @@ mod tests {
     #[test]
     fn test_parse_line_empty_path() {
         let line = "abc123  sha256  normal  ";
-        let result = DatabaseHandler::parse_line(line);
+        let result = DatabaseHandler::parse_entry_line(line);
         
         assert!(result.is_none());
     }
@@ -519,7 +1248,7 @@ mod tests {
     #[test]
     fn test_parse_line_invalid_fast_mode() {
         let line = "abc123  sha256  invalid  file.txt";
-        let result = DatabaseHandler::parse_line(line);
+        let result = DatabaseHandler::parse_entry_line(line);
         
         // Should fail because fast_mode must be "fast" or "normal"
         assert!(result.is_none());
@@ -559,6 +1288,23 @@ mod tests {
         fs::remove_file(temp_file).unwrap();
     }
     
+    #[test]
+    fn test_read_database_normalizes_algorithm_names() {
+        let temp_file = "test_db_algorithm_names_temp.txt";
+        let content = "abc123  SHA-256  normal  file1.txt\n\
+                       def456  Blake2b-512  normal  file2.txt\n\
+                       789abc  custom-hash  normal  file3.txt\n";
+        fs::write(temp_file, content).unwrap();
+        
+        let database = DatabaseHandler::read_database(Path::new(temp_file)).unwrap();
+        fs::remove_file(temp_file).unwrap();
+        
+        assert_eq!(database[Path::new("file1.txt")].algorithm, "sha256");
+        assert_eq!(database[Path::new("file2.txt")].algorithm, "blake2b");
+        // Unknown algorithms keep their spelling for error messages
+        assert_eq!(database[Path::new("file3.txt")].algorithm, "custom-hash");
+    }
+    
     #[test]
     fn test_read_database_with_empty_lines() {
         let temp_file = "test_db_empty_lines_temp.txt";
@@ -606,8 +1352,8 @@ mod tests {
     fn test_round_trip() {
         // Write entries to a buffer
         let mut buffer = Vec::new();
-        DatabaseHandler::write_entry(&mut buffer, "hash1", "sha256", false, Path::new("file1.txt")).unwrap();
-        DatabaseHandler::write_entry(&mut buffer, "hash2", "sha256", true, Path::new("file2.txt")).unwrap();
+        DatabaseHandler::write_entry_with_metadata(&mut buffer, "hash1", "sha256", "normal", EntryMetadata::default(), Path::new("file1.txt")).unwrap();
+        DatabaseHandler::write_entry_with_metadata(&mut buffer, "hash2", "sha256", "fast2", EntryMetadata::default(), Path::new("file2.txt")).unwrap();
         
         // Write buffer to file
         let temp_file = "test_round_trip_temp.txt";
@@ -636,13 +1382,13 @@ mod tests {
     #[test]
     fn test_parse_line_with_forward_slashes() {
         let line = "abc123  sha256  normal  path/to/file.txt";
-        let result = DatabaseHandler::parse_line(line);
+        let result = DatabaseHandler::parse_entry_line(line);
         
         assert!(result.is_some());
-        let (hash, algorithm, fast_mode, path) = result.unwrap();
-        assert_eq!(hash, "abc123");
-        assert_eq!(algorithm, "sha256");
-        assert_eq!(fast_mode, false);
+        let (path, entry) = result.unwrap();
+        assert_eq!(entry.hash, "abc123");
+        assert_eq!(entry.algorithm, "sha256");
+        assert_eq!(entry.fast_mode, false);
         // Path should be parsed correctly regardless of platform
         assert!(path.to_str().unwrap().contains("file.txt"));
     }
@@ -650,13 +1396,13 @@ mod tests {
     #[test]
     fn test_parse_line_with_backward_slashes() {
         let line = "abc123  sha256  fast  path\\to\\file.txt";
-        let result = DatabaseHandler::parse_line(line);
+        let result = DatabaseHandler::parse_entry_line(line);
         
         assert!(result.is_some());
-        let (hash, algorithm, fast_mode, path) = result.unwrap();
-        assert_eq!(hash, "abc123");
-        assert_eq!(algorithm, "sha256");
-        assert_eq!(fast_mode, true);
+        let (path, entry) = result.unwrap();
+        assert_eq!(entry.hash, "abc123");
+        assert_eq!(entry.algorithm, "sha256");
+        assert_eq!(entry.fast_mode, true);
         // Path should be parsed correctly regardless of platform
         assert!(path.to_str().unwrap().contains("file.txt"));
     }
@@ -664,13 +1410,13 @@ mod tests {
     #[test]
     fn test_parse_line_with_mixed_slashes() {
         let line = "abc123  sha256  normal  path/to\\mixed/file.txt";
-        let result = DatabaseHandler::parse_line(line);
+        let result = DatabaseHandler::parse_entry_line(line);
         
         assert!(result.is_some());
-        let (hash, algorithm, fast_mode, path) = result.unwrap();
-        assert_eq!(hash, "abc123");
-        assert_eq!(algorithm, "sha256");
-        assert_eq!(fast_mode, false);
+        let (path, entry) = result.unwrap();
+        assert_eq!(entry.hash, "abc123");
+        assert_eq!(entry.algorithm, "sha256");
+        assert_eq!(entry.fast_mode, false);
         // Path should be parsed correctly with normalized separators
         assert!(path.to_str().unwrap().contains("file.txt"));
     }
@@ -697,13 +1443,13 @@ mod tests {
     fn test_parse_line_with_double_spaces_in_filename() {
         // Test case for filenames that contain two consecutive spaces
         let line = "abc123  sha256  normal  path/to/file  with  spaces.txt";
-        let result = DatabaseHandler::parse_line(line);
+        let result = DatabaseHandler::parse_entry_line(line);
         
         assert!(result.is_some());
-        let (hash, algorithm, fast_mode, path) = result.unwrap();
-        assert_eq!(hash, "abc123");
-        assert_eq!(algorithm, "sha256");
-        assert_eq!(fast_mode, false);
+        let (path, entry) = result.unwrap();
+        assert_eq!(entry.hash, "abc123");
+        assert_eq!(entry.algorithm, "sha256");
+        assert_eq!(entry.fast_mode, false);
         // The filename should preserve the double spaces
         assert!(path.to_str().unwrap().contains("file  with  spaces.txt"));
     }
@@ -735,4 +1481,338 @@ mod tests {
         // Cleanup
         fs::remove_file(temp_file).unwrap();
     }
+    
+    #[test]
+    fn test_write_and_parse_entry_with_permissions() {
+        let mut output = Vec::new();
+        DatabaseHandler::write_entry_with_metadata(
+            &mut output,
+            "abc123",
+            "sha256",
+            "normal",
+            EntryMetadata { permissions: Some("0644"), modified: None, size: None },
+            Path::new("dir/my  file.txt"),
+        ).unwrap();
+        
+        let line = String::from_utf8(output).unwrap();
+        assert_eq!(line, "abc123  sha256  normal  perm:0644  dir/my  file.txt\n");
+        
+        let (path, entry) = DatabaseHandler::parse_entry_line(line.trim_end()).unwrap();
+        assert_eq!(entry.hash, "abc123");
+        assert_eq!(entry.algorithm, "sha256");
+        assert!(!entry.fast_mode);
+        assert_eq!(entry.permissions, Some("0644".to_string()));
+        assert_eq!(entry.modified, None);
+        assert_eq!(path, path_utils::parse_database_path("dir/my  file.txt"));
+        
+        // Lines without the column still parse with no permissions
+        let (path, entry) = DatabaseHandler::parse_entry_line("abc123  sha256  normal  file.txt").unwrap();
+        assert_eq!(entry.permissions, None);
+        assert_eq!(path, PathBuf::from("file.txt"));
+    }
+    
+    #[test]
+    fn test_write_and_parse_entry_with_timestamp() {
+        let mut output = Vec::new();
+        DatabaseHandler::write_entry_with_metadata(
+            &mut output,
+            "abc123",
+            "sha256",
+            "fast",
+            EntryMetadata { permissions: Some("0755"), modified: Some("2024-01-15T10:00:00Z"), size: None },
+            Path::new("run.sh"),
+        ).unwrap();
+        
+        let line = String::from_utf8(output).unwrap();
+        assert_eq!(line, "abc123  sha256  fast  perm:0755  mtime:2024-01-15T10:00:00Z  run.sh\n");
+        
+        let (path, entry) = DatabaseHandler::parse_entry_line(line.trim_end()).unwrap();
+        assert_eq!(path, PathBuf::from("run.sh"));
+        assert_eq!(entry.permissions, Some("0755".to_string()));
+        assert_eq!(entry.modified, Some("2024-01-15T10:00:00Z".to_string()));
+        
+        // Timestamp without permissions
+        let (path, entry) = DatabaseHandler::parse_entry_line(
+            "abc123  sha256  normal  mtime:2024-01-15T10:00:00Z  notes.txt"
+        ).unwrap();
+        assert_eq!(path, PathBuf::from("notes.txt"));
+        assert_eq!(entry.permissions, None);
+        assert_eq!(entry.modified, Some("2024-01-15T10:00:00Z".to_string()));
+    }
+    
+    #[test]
+    fn test_write_and_parse_entry_with_size() {
+        let mut output = Vec::new();
+        let metadata = EntryMetadata { permissions: None, modified: Some("2024-01-15T10:00:00.250000000Z"), size: Some(4096) };
+        DatabaseHandler::write_entry_with_metadata(&mut output, "abc123", "blake3", "normal", metadata, Path::new("data.bin")).unwrap();
+        
+        let line = String::from_utf8(output).unwrap();
+        assert_eq!(line, "abc123  blake3  normal  mtime:2024-01-15T10:00:00.250000000Z  size:4096  data.bin\n");
+        
+        let (path, entry) = DatabaseHandler::parse_entry_line(line.trim_end()).unwrap();
+        assert_eq!(path, PathBuf::from("data.bin"));
+        assert_eq!(entry.modified, Some("2024-01-15T10:00:00.250000000Z".to_string()));
+        assert_eq!(entry.size, Some(4096));
+        
+        // A name that only looks like a size column stays part of the path
+        let (path, entry) = DatabaseHandler::parse_entry_line("abc123  blake3  normal  size:big  file.txt").unwrap();
+        assert_eq!(entry.size, None);
+        assert_eq!(path, PathBuf::from("size:big  file.txt"));
+    }
+    
+    #[test]
+    fn test_read_database_with_permissions() {
+        let test_file = "test_read_db_permissions.txt";
+        fs::write(
+            test_file,
+            "hash1  sha256  normal  perm:0755  script.sh\nhash2  sha256  fast  plain.txt\n",
+        ).unwrap();
+        
+        let db = DatabaseHandler::read_database(Path::new(test_file)).unwrap();
+        fs::remove_file(test_file).unwrap();
+        
+        assert_eq!(db.len(), 2);
+        assert_eq!(db[&PathBuf::from("script.sh")].permissions, Some("0755".to_string()));
+        assert_eq!(db[&PathBuf::from("plain.txt")].permissions, None);
+        assert!(db[&PathBuf::from("plain.txt")].fast_mode);
+    }
+    
+    #[test]
+    fn test_tsv_round_trip() {
+        let test_file = "test_tsv_round_trip.tsv";
+        {
+            let mut file = File::create(test_file).unwrap();
+            DatabaseHandler::write_tsv_entry(&mut file, "abc123", "sha256", 42, Path::new("my  file.txt")).unwrap();
+            DatabaseHandler::write_tsv_entry(&mut file, "def456", "blake3", 0, Path::new("dir/a,b.txt")).unwrap();
+        }
+        
+        let contents = fs::read_to_string(test_file).unwrap();
+        assert!(contents.starts_with("abc123\tsha256\t42\tmy  file.txt\n"));
+        
+        let entries = DatabaseHandler::read_tsv_entries(Path::new(test_file)).unwrap();
+        assert_eq!(entries.len(), 2);
+        assert_eq!(entries[0].0, PathBuf::from("my  file.txt"));
+        assert_eq!(entries[0].1.hash, "abc123");
+        assert_eq!(entries[0].1.algorithm, "sha256");
+        assert_eq!(entries[0].2, 42);
+        
+        let db = DatabaseHandler::read_database(Path::new(test_file)).unwrap();
+        fs::remove_file(test_file).unwrap();
+        
+        assert_eq!(db.len(), 2);
+        assert_eq!(db[&PathBuf::from("my  file.txt")].hash, "abc123");
+        assert_eq!(db[&path_utils::parse_database_path("dir/a,b.txt")].algorithm, "blake3");
+    }
+    
+    #[test]
+    fn test_detect_format_tsv() {
+        // Paths with double spaces and commas must not be mistaken for other formats
+        let test_file = "test_detect_format_tsv.tsv";
+        fs::write(test_file, "abc123\tsha256\t42\tmy  file,v2.txt\n").unwrap();
+        let format = DatabaseHandler::detect_format(Path::new(test_file)).unwrap();
+        fs::remove_file(test_file).unwrap();
+        assert_eq!(format, DatabaseFormat::Tsv);
+        
+        let test_file = "test_detect_format_standard.txt";
+        fs::write(test_file, "abc123  sha256  normal  file.txt\n").unwrap();
+        let format = DatabaseHandler::detect_format(Path::new(test_file)).unwrap();
+        fs::remove_file(test_file).unwrap();
+        assert_eq!(format, DatabaseFormat::Standard);
+    }
+    
+    #[test]
+    fn test_read_sizes_from_hashdeep() {
+        let test_file = "test_read_sizes_hashdeep.txt";
+        fs::write(test_file, "%%%% HASHDEEP-1.0\n%%%% size,md5,filename\n## comment\n11,abc,dir/a.txt\n0,def,empty.txt\n").unwrap();
+        let sizes = DatabaseHandler::read_sizes(Path::new(test_file)).unwrap();
+        fs::remove_file(test_file).unwrap();
+        
+        assert_eq!(sizes.len(), 2);
+        assert_eq!(sizes[Path::new("dir/a.txt")], 11);
+        assert_eq!(sizes[Path::new("empty.txt")], 0);
+    }
+    
+    #[test]
+    fn test_parse_tsv_line_malformed() {
+        assert!(DatabaseHandler::parse_tsv_line("abc123\tsha256\tfile.txt").is_none());
+        assert!(DatabaseHandler::parse_tsv_line("abc123\tsha256\tbig\tfile.txt").is_none());
+        assert!(DatabaseHandler::parse_tsv_line("\tsha256\t1\tfile.txt").is_none());
+    }
+    
+    #[test]
+    fn test_read_database_with_standard_header() {
+        let mut plain = Vec::new();
+        DatabaseHandler::write_entry_with_metadata(&mut plain, "hash1", "sha256", "normal", EntryMetadata::default(), Path::new("file1.txt")).unwrap();
+        DatabaseHandler::write_entry_with_metadata(&mut plain, "hash2", "sha256", "fast2", EntryMetadata::default(), Path::new("dir/file, with comma.txt")).unwrap();
+        
+        // The command line may contain commas and tabs that must not confuse format detection
+        let mut headered = Vec::new();
+        DatabaseHandler::write_standard_header(&mut headered, "sha256", "hash scan -d a,b\t -b out.txt --with-header").unwrap();
+        headered.extend_from_slice(&plain);
+        assert!(String::from_utf8_lossy(&headered).starts_with("# quichash standard v1\n"));
+        
+        let plain_file = "test_standard_header_plain.txt";
+        let headered_file = "test_standard_header_headered.txt";
+        fs::write(plain_file, &plain).unwrap();
+        fs::write(headered_file, &headered).unwrap();
+        
+        assert_eq!(DatabaseHandler::detect_format(Path::new(headered_file)).unwrap(), DatabaseFormat::Standard);
+        let expected = DatabaseHandler::read_database(Path::new(plain_file)).unwrap();
+        let actual = DatabaseHandler::read_database(Path::new(headered_file)).unwrap();
+        
+        fs::remove_file(plain_file).unwrap();
+        fs::remove_file(headered_file).unwrap();
+        
+        assert_eq!(actual.len(), 2);
+        for (path, entry) in &expected {
+            let read_back = actual.get(path).unwrap();
+            assert_eq!(read_back.hash, entry.hash);
+            assert_eq!(read_back.algorithm, entry.algorithm);
+            assert_eq!(read_back.fast_mode, entry.fast_mode);
+        }
+    }
+    
+    #[test]
+    fn test_read_tab_separated_hashdeep_database() {
+        let md5 = "5eb63bbbe01eeed093cb22bb8f5acdc3";
+        let sha256 = "b94d27b9934d3e08a52e52d7da7dabfac484efe37a5380ee9088f7ace2efcde9";
+        
+        // With a header the column names come from it
+        let test_file = "test_hashdeep_tabs_header.txt";
+        fs::write(test_file, format!(
+            "%%%% HASHDEEP-1.0\n%%%% size\tmd5\tsha256\tfilename\n## comment\n11\t{md5}\t{sha256}\tdir/my file, v2.txt\n"
+        )).unwrap();
+        let format = DatabaseHandler::detect_format(Path::new(test_file)).unwrap();
+        let database = DatabaseHandler::read_database(Path::new(test_file)).unwrap();
+        fs::remove_file(test_file).unwrap();
+        
+        assert_eq!(format, DatabaseFormat::Hashdeep);
+        assert_eq!(database.len(), 1);
+        let entry = database.get(&PathBuf::from("dir/my file, v2.txt")).unwrap();
+        assert_eq!(entry.hash, md5);
+        assert_eq!(entry.algorithm, "md5");
+        
+        // Headerless data lines are told apart from the TSV layout and the algorithm is inferred
+        let test_file = "test_hashdeep_tabs_headerless.txt";
+        fs::write(test_file, format!("11\t{sha256}\thello.txt\n")).unwrap();
+        let format = DatabaseHandler::detect_format(Path::new(test_file)).unwrap();
+        let database = DatabaseHandler::read_database(Path::new(test_file)).unwrap();
+        fs::remove_file(test_file).unwrap();
+        
+        assert_eq!(format, DatabaseFormat::Hashdeep);
+        let entry = database.get(&PathBuf::from("hello.txt")).unwrap();
+        assert_eq!(entry.hash, sha256);
+        assert_eq!(entry.algorithm, "sha256");
+    }
+    
+    #[test]
+    fn test_read_truncated_xz_database() {
+        let plain = "test_truncated_xz.txt";
+        let mut content = String::new();
+        for i in 0..2000 {
+            content.push_str(&format!("{:064x}  sha256  normal  dir{}/file{}.txt\n", i * 7919, i % 13, i));
+        }
+        fs::write(plain, content).unwrap();
+        let compressed = DatabaseHandler::compress_database(Path::new(plain)).unwrap();
+        
+        // Cut the compressed stream in half, as an interrupted compression would
+        let bytes = fs::read(&compressed).unwrap();
+        fs::write(&compressed, &bytes[..bytes.len() / 2]).unwrap();
+        
+        match DatabaseHandler::read_database(&compressed) {
+            Err(err @ HashUtilityError::CorruptDatabase { .. }) => {
+                assert_eq!(err.path(), Some(compressed.as_path()));
+                assert!(err.to_string().contains("appears truncated or corrupt"));
+            }
+            other => panic!("Expected CorruptDatabase, got {:?}", other.map(|db| db.len())),
+        }
+        
+        fs::remove_file(plain).unwrap();
+        fs::remove_file(&compressed).unwrap();
+    }
+
+    
+    #[test]
+    fn test_parse_coreutils_line() {
+        assert_eq!(DatabaseHandler::parse_coreutils_line("abc123  file.txt"), Some(("abc123", "file.txt")));
+        assert_eq!(DatabaseHandler::parse_coreutils_line("abc123 *file.bin"), Some(("abc123", "file.bin")));
+        assert_eq!(DatabaseHandler::parse_coreutils_line("abc123  name with spaces.txt"), Some(("abc123", "name with spaces.txt")));
+        assert_eq!(DatabaseHandler::parse_coreutils_line("abc123 file.txt"), None);
+        assert_eq!(DatabaseHandler::parse_coreutils_line("not-a-hash  file.txt"), None);
+        assert_eq!(DatabaseHandler::parse_coreutils_line("abc123"), None);
+    }
+    
+    #[test]
+    fn test_lenient_parsing() {
+        // Comments start at a word beginning with '#', not inside names
+        assert_eq!(DatabaseHandler::strip_lenient("  abc123  file.txt   # from the vendor  "), "abc123  file.txt");
+        assert_eq!(DatabaseHandler::strip_lenient("abc123  file#1.txt"), "abc123  file#1.txt");
+        assert_eq!(DatabaseHandler::strip_lenient("   # whole-line comment"), "");
+        
+        let parse = |line| DatabaseHandler::parse_coreutils_line_lenient(DatabaseHandler::strip_lenient(line));
+        assert_eq!(parse("abc123 file.txt # note"), Some(("abc123", "file.txt")));
+        assert_eq!(parse("abc123\t*file.bin"), Some(("abc123", "file.bin")));
+        assert_eq!(parse("not-a-hash  file.txt"), None);
+        
+        // Standard lines: strict parsing rejects the extra spacing, lenient parsing accepts it
+        let content = "  aaa   sha256  normal\tfile.txt  # checked by hand\n# header comment\n";
+        let strict = DatabaseHandler::read_standard_from(content.as_bytes(), Path::new("<test>"), false).unwrap();
+        assert!(strict.is_empty());
+        let lenient = DatabaseHandler::read_standard_from(content.as_bytes(), Path::new("<test>"), true).unwrap();
+        assert_eq!(lenient.len(), 1);
+        assert_eq!(lenient[&PathBuf::from("file.txt")].hash, "aaa");
+    }
+    
+    #[test]
+    fn test_dedupe_entries_keeps_most_recent() {
+        let content = "# quichash standard v1\n\
+                       aaa  sha256  normal  a.txt\n\
+                       bbb  sha256  normal  b.txt\n\
+                       aaa  sha256  normal  a.txt\n\
+                       ccc  sha256  normal  b.txt\n";
+        let (deduped, removed, conflicts) = DatabaseHandler::dedupe_entries(content, DatabaseFormat::Standard);
+        
+        assert_eq!(deduped, "# quichash standard v1\naaa  sha256  normal  a.txt\nccc  sha256  normal  b.txt\n");
+        assert_eq!(removed, 2);
+        // Only the path whose hash changed is a conflict
+        assert_eq!(conflicts, vec![DuplicateConflict {
+            path: PathBuf::from("b.txt"),
+            dropped_hash: "bbb".to_string(),
+            kept_hash: "ccc".to_string(),
+        }]);
+        
+        // Hashdeep headers are kept, and entries are told apart by their filename column
+        let content = "%%%% HASHDEEP-1.0\n%%%% size,sha256,filename\n5,aaa,a.txt\n%%%% HASHDEEP-1.0\n%%%% size,sha256,filename\n5,aaa,a.txt\n";
+        let (deduped, removed, conflicts) = DatabaseHandler::dedupe_entries(content, DatabaseFormat::Hashdeep);
+        assert_eq!(deduped, "%%%% HASHDEEP-1.0\n%%%% size,sha256,filename\n%%%% HASHDEEP-1.0\n%%%% size,sha256,filename\n5,aaa,a.txt\n");
+        assert_eq!(removed, 1);
+        assert!(conflicts.is_empty());
+    }
+    
+    #[test]
+    fn test_coreutils_round_trip() {
+        let sha256 = "b94d27b9934d3e08a52e52d7da7dabfac484efe37a5380ee9088f7ace2efcde9";
+        let md5 = "5eb63bbbe01eeed093cb22bb8f5acdc3";
+        
+        let mut buffer = Vec::new();
+        DatabaseHandler::write_coreutils_entry(&mut buffer, sha256, false, Path::new("dir/a, b.txt")).unwrap();
+        DatabaseHandler::write_coreutils_entry(&mut buffer, md5, true, Path::new("image.bin")).unwrap();
+        let content = String::from_utf8(buffer).unwrap();
+        assert_eq!(content, format!("{}  dir/a, b.txt\n{} *image.bin\n", sha256, md5));
+        
+        // Detected ahead of hashdeep even though the path contains a comma
+        assert_eq!(DatabaseHandler::detect_format_from_lines(content.lines()), DatabaseFormat::Coreutils);
+        
+        let database = DatabaseHandler::read_database_from_with(content.as_bytes(), Path::new("<test>"), false).unwrap();
+        assert_eq!(database.len(), 2);
+        let entry = &database[&path_utils::parse_database_path("dir/a, b.txt")];
+        assert_eq!(entry.hash, sha256);
+        assert_eq!(entry.algorithm, "sha256");
+        assert_eq!(database[&PathBuf::from("image.bin")].algorithm, "md5");
+        
+        // Standard lines also fit the coreutils layout but keep their own format
+        let standard = format!("{}  sha256  normal  file.txt\n", sha256);
+        assert_eq!(DatabaseHandler::detect_format_from_lines(standard.lines()), DatabaseFormat::Standard);
+    }
+
 }
@@ -0,0 +1,120 @@
+// Decompress module
+// Opens gzip, xz and zstd files as a stream of their decompressed contents
+
+use std::fs::File;
+use std::io::{BufReader, Read};
+use std::path::Path;
+use crate::error::HashUtilityError;
+use crate::path_utils::extended_length_path;
+use flate2::read::MultiGzDecoder;
+use xz2::read::XzDecoder;
+
+/// Compression formats recognized by file extension
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Compression {
+    Gzip,
+    Xz,
+    Zstd,
+}
+
+impl Compression {
+    /// Detect the compression of a file from its extension (.gz, .xz, .zst)
+    pub fn from_path(path: &Path) -> Option<Self> {
+        let ext = path.extension()?.to_string_lossy().to_lowercase();
+        match ext.as_str() {
+            "gz" => Some(Compression::Gzip),
+            "xz" => Some(Compression::Xz),
+            "zst" => Some(Compression::Zstd),
+            _ => None,
+        }
+    }
+}
+
+/// Open a compressed file for reading its decompressed contents
+///
+/// Returns `None` if the extension isn't a recognized compression format,
+/// in which case the caller should hash the raw bytes.
+pub fn open_decompressed(path: &Path) -> Result<Option<Box<dyn Read>>, HashUtilityError> {
+    let Some(compression) = Compression::from_path(path) else {
+        return Ok(None);
+    };
+
+    let file = File::open(extended_length_path(path))
+        .map_err(|e| HashUtilityError::from_io_error(e, "reading", Some(path.to_path_buf())))?;
+
+    let reader: Box<dyn Read> = match compression {
+        // Concatenated gzip members decompress as one stream, like `gzip -dc`
+        Compression::Gzip => Box::new(MultiGzDecoder::new(BufReader::new(file))),
+        Compression::Xz => Box::new(XzDecoder::new_multi_decoder(BufReader::new(file))),
+        Compression::Zstd => Box::new(
+            zstd::stream::read::Decoder::new(file)
+                .map_err(|e| HashUtilityError::from_io_error(e, "reading", Some(path.to_path_buf())))?,
+        ),
+    };
+    Ok(Some(reader))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::hash::HashComputer;
+    use std::fs;
+    use std::io::Write;
+
+    const CONTENT: &[u8] = b"The quick brown fox jumps over the lazy dog\n";
+
+    /// Hash a file's decompressed contents the way `hash --decompress` does
+    fn decompressed_sha256(path: &Path) -> String {
+        let reader = open_decompressed(path).unwrap().expect("recognized extension");
+        let results = HashComputer::new()
+            .compute_multiple_hashes_reader(reader, &["sha256".to_string()], path)
+            .unwrap();
+        results[0].hash.clone()
+    }
+
+    #[test]
+    fn test_compression_from_path() {
+        assert_eq!(Compression::from_path(Path::new("log.txt.gz")), Some(Compression::Gzip));
+        assert_eq!(Compression::from_path(Path::new("dump.SQL.XZ")), Some(Compression::Xz));
+        assert_eq!(Compression::from_path(Path::new("data.zst")), Some(Compression::Zstd));
+        assert_eq!(Compression::from_path(Path::new("plain.txt")), None);
+        assert_eq!(Compression::from_path(Path::new("noext")), None);
+    }
+
+    #[test]
+    fn test_decompressed_hash_matches_original() {
+        let test_dir = "test_decompress_hash";
+        fs::create_dir_all(test_dir).unwrap();
+
+        let original = Path::new(test_dir).join("original.txt");
+        fs::write(&original, CONTENT).unwrap();
+        let expected = HashComputer::new().compute_hash(&original, "sha256").unwrap().hash;
+
+        let gz_path = Path::new(test_dir).join("original.txt.gz");
+        let mut encoder = flate2::write::GzEncoder::new(Vec::new(), flate2::Compression::default());
+        encoder.write_all(CONTENT).unwrap();
+        fs::write(&gz_path, encoder.finish().unwrap()).unwrap();
+
+        let xz_path = Path::new(test_dir).join("original.txt.xz");
+        let mut encoder = xz2::write::XzEncoder::new(Vec::new(), 6);
+        encoder.write_all(CONTENT).unwrap();
+        fs::write(&xz_path, encoder.finish().unwrap()).unwrap();
+
+        let zst_path = Path::new(test_dir).join("original.txt.zst");
+        fs::write(&zst_path, zstd::encode_all(CONTENT, 0).unwrap()).unwrap();
+
+        assert_eq!(decompressed_sha256(&gz_path), expected);
+        assert_eq!(decompressed_sha256(&xz_path), expected);
+        assert_eq!(decompressed_sha256(&zst_path), expected);
+
+        // Without decompression the raw bytes hash differently
+        let raw = HashComputer::new().compute_hash(&gz_path, "sha256").unwrap().hash;
+        assert_ne!(raw, expected);
+
+        // Uncompressed files aren't opened
+        assert!(open_decompressed(&original).unwrap().is_none());
+
+        // Cleanup
+        fs::remove_dir_all(test_dir).unwrap();
+    }
+}
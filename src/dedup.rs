@@ -1,17 +1,22 @@
 // Dedup engine module
 // Finds duplicate files within a directory by comparing hash values
 
-use std::collections::HashMap;
-use std::fs;
+use std::cmp::Reverse;
+use std::collections::{BinaryHeap, HashMap};
+use std::fs::{self, File};
+use std::io::{self, BufRead, BufReader, BufWriter, Write};
 use std::path::{Path, PathBuf};
 use std::time::{Duration, Instant};
 use crate::hash::HashComputer;
+use crate::database::{DatabaseEntry, DatabaseHandler, EntryMetadata};
 use crate::error::HashUtilityError;
 use crate::ignore_handler::IgnoreHandler;
+use crate::path_utils;
 use rayon::prelude::*;
 use indicatif::{ProgressBar, ProgressStyle};
 use crossbeam_channel::bounded;
 use jwalk::WalkDir;
+use std::sync::atomic::{AtomicUsize, Ordering};
 use std::sync::{Arc, Mutex};
 use std::thread;
 
@@ -20,6 +25,8 @@ use std::thread;
 pub struct DedupStats {
     pub files_scanned: usize,
     pub files_failed: usize,
+    /// Files whose hash was reused from the `--continue-from` cache instead of recomputed
+    pub files_cached: usize,
     pub total_bytes: u64,
     pub duplicate_groups: usize,
     pub duplicate_files: usize,
@@ -41,6 +48,50 @@ where
 pub struct DedupReport {
     pub stats: DedupStats,
     pub duplicate_groups: Vec<DuplicateGroupWithSize>,
+    /// Order of `duplicate_groups`, used for the plain-text heading
+    #[serde(skip)]
+    pub sort: DedupSort,
+}
+
+/// Key used to order duplicate groups in the report (`--sort`)
+/// 
+/// Numeric keys sort largest first and hashes sort ascending; `--reverse` flips either.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum DedupSort {
+    /// Space reclaimable by removing the extra copies
+    #[default]
+    Wasted,
+    /// Size of each file in the group
+    Size,
+    /// Number of copies
+    Count,
+    /// Hash value
+    Hash,
+}
+
+impl DedupSort {
+    /// Parse a sort key name ('wasted', 'size', 'count' or 'hash', case-insensitive)
+    pub fn parse(key: &str) -> Result<Self, HashUtilityError> {
+        match key.to_lowercase().as_str() {
+            "wasted" => Ok(DedupSort::Wasted),
+            "size" => Ok(DedupSort::Size),
+            "count" => Ok(DedupSort::Count),
+            "hash" => Ok(DedupSort::Hash),
+            _ => Err(HashUtilityError::InvalidArguments {
+                message: format!("Invalid sort key '{}'. Valid keys are: wasted, size, count, hash", key),
+            }),
+        }
+    }
+    
+    /// Human-readable description for report headings
+    pub fn description(self) -> &'static str {
+        match self {
+            DedupSort::Wasted => "wasted space",
+            DedupSort::Size => "file size",
+            DedupSort::Count => "file count",
+            DedupSort::Hash => "hash",
+        }
+    }
 }
 
 /// Duplicate group with file size information
@@ -51,6 +102,7 @@ pub struct DuplicateGroupWithSize {
     pub count: usize,
     pub file_size: u64,
     pub wasted_space: u64, // (count - 1) * file_size
+    pub keeper: PathBuf, // copy to keep; always paths[0]
 }
 
 impl DedupReport {
@@ -62,27 +114,29 @@ impl DedupReport {
         println!("Summary:");
         println!("  Files scanned:     {}", self.stats.files_scanned);
         println!("  Files failed:      {}", self.stats.files_failed);
-        println!("  Total bytes:       {} ({:.2} MB)", 
-            self.stats.total_bytes, 
-            self.stats.total_bytes as f64 / 1_048_576.0
+        if self.stats.files_cached > 0 {
+            println!("  Cached hashes:     {}", self.stats.files_cached);
+        }
+        println!("  Total bytes:       {} ({})",
+            self.stats.total_bytes,
+            path_utils::format_size(self.stats.total_bytes)
         );
         println!("  Duplicate groups:  {}", self.stats.duplicate_groups);
         println!("  Duplicate files:   {}", self.stats.duplicate_files);
-        println!("  Wasted space:      {} ({:.2} MB)", 
-            self.stats.wasted_space, 
-            self.stats.wasted_space as f64 / 1_048_576.0
+        println!("  Wasted space:      {} ({})",
+            self.stats.wasted_space,
+            path_utils::format_size(self.stats.wasted_space)
         );
         println!("  Duration:          {:.2}s", self.stats.duration.as_secs_f64());
         
         // Calculate and display throughput
         if self.stats.duration.as_secs_f64() > 0.0 {
-            let throughput_mbps = (self.stats.total_bytes as f64 / 1_048_576.0) / self.stats.duration.as_secs_f64();
-            println!("  Throughput:        {:.2} MB/s", throughput_mbps);
+            println!("  Throughput:        {}", path_utils::format_throughput(self.stats.total_bytes, self.stats.duration));
         }
         
-        // Duplicate groups section (sorted by wasted space, largest first)
+        // Duplicate groups section, in the order chosen by --sort
         if !self.duplicate_groups.is_empty() {
-            println!("\nDuplicate Groups (sorted by wasted space):");
+            println!("\nDuplicate Groups (sorted by {}):", self.sort.description());
             for group in &self.duplicate_groups {
                 println!("\n  Hash: {} ({} files, {} bytes each, {} bytes wasted)", 
                     group.hash, 
@@ -91,7 +145,11 @@ impl DedupReport {
                     group.wasted_space
                 );
                 for path in &group.paths {
-                    println!("    {}", path.display());
+                    if *path == group.keeper {
+                        println!("    {} (keep)", path.display());
+                    } else {
+                        println!("    {}", path.display());
+                    }
                 }
             }
         } else {
@@ -121,6 +179,7 @@ impl DedupReport {
             count: usize,
             file_size: u64,
             wasted_space: u64,
+            keeper: String,
             paths: Vec<String>,
         }
         
@@ -134,6 +193,7 @@ impl DedupReport {
                 count: dg.count,
                 file_size: dg.file_size,
                 wasted_space: dg.wasted_space,
+                keeper: dg.keeper.display().to_string(),
                 paths: dg.paths.iter().map(|p| p.display().to_string()).collect(),
             }).collect(),
         };
@@ -142,13 +202,99 @@ impl DedupReport {
     }
 }
 
+/// Write one duplicate group as a line of newline-delimited JSON (`--json-ndjson`)
+pub fn write_ndjson_group<W: Write>(writer: &mut W, group: &DuplicateGroupWithSize) -> io::Result<()> {
+    #[derive(serde::Serialize)]
+    struct GroupLine<'a> {
+        #[serde(rename = "type")]
+        kind: &'static str,
+        hash: &'a str,
+        count: usize,
+        file_size: u64,
+        wasted_space: u64,
+        keeper: String,
+        paths: Vec<String>,
+    }
+    
+    let line = GroupLine {
+        kind: "group",
+        hash: &group.hash,
+        count: group.count,
+        file_size: group.file_size,
+        wasted_space: group.wasted_space,
+        keeper: group.keeper.display().to_string(),
+        paths: group.paths.iter().map(|p| p.display().to_string()).collect(),
+    };
+    serde_json::to_writer(&mut *writer, &line)?;
+    writeln!(writer)
+}
+
+/// Write the closing statistics line of newline-delimited JSON output
+pub fn write_ndjson_stats<W: Write>(writer: &mut W, stats: &DedupStats) -> io::Result<()> {
+    #[derive(serde::Serialize)]
+    struct StatsLine<'a> {
+        #[serde(rename = "type")]
+        kind: &'static str,
+        #[serde(flatten)]
+        stats: &'a DedupStats,
+    }
+    
+    serde_json::to_writer(&mut *writer, &StatsLine { kind: "stats", stats })?;
+    writeln!(writer)
+}
+
+/// Progress bar template once the number of files is known
+const PROGRESS_TEMPLATE: &str = "[{elapsed_precise}] [{bar:40.cyan/blue}] {pos}/{len} files ({percent}%) | Processed: {msg}";
+
+/// Lines sorted in memory at a time before a run is written out (`--spill`)
+const SPILL_RUN_LINES: usize = 100_000;
+
+/// Distinguishes spill directories of engines running in the same process
+static SPILL_DIRS_CREATED: AtomicUsize = AtomicUsize::new(0);
+
+/// Temporary directory holding the `--spill` files, removed when dropped
+struct SpillDir(PathBuf);
+
+impl SpillDir {
+    fn create() -> Result<Self, HashUtilityError> {
+        let id = SPILL_DIRS_CREATED.fetch_add(1, Ordering::Relaxed);
+        let dir = std::env::temp_dir().join(format!("hash-dedup-spill-{}-{}", std::process::id(), id));
+        fs::create_dir_all(&dir).map_err(|e| {
+            HashUtilityError::from_io_error(e, "creating dedup spill directory", Some(dir.clone()))
+        })?;
+        Ok(Self(dir))
+    }
+    
+    fn file(&self, name: &str) -> PathBuf {
+        self.0.join(name)
+    }
+}
+
+impl Drop for SpillDir {
+    fn drop(&mut self) {
+        let _ = fs::remove_dir_all(&self.0);
+    }
+}
+
 /// Engine for finding duplicate files in a directory
 pub struct DedupEngine {
     computer: HashComputer,
     fast_mode: bool,
     parallel: bool,
+    skip_dirs: Vec<String>,
+    prefer_dirs: Vec<PathBuf>,
+    cache: Option<PathBuf>,
+    min_duplicate_size: u64,
+    sort: DedupSort,
+    reverse: bool,
+    progress_template: Option<String>,
+    spill: bool,
+    spill_run_lines: usize,
 }
 
+/// Files hashed during a scan, grouped by hash: (path, size, nanosecond mtime for the cache)
+type HashGroups = HashMap<String, Vec<(PathBuf, u64, Option<String>)>>;
+
 impl DedupEngine {
     /// Create a new DedupEngine with default settings
     /// Always uses BLAKE3 algorithm (fast and secure)
@@ -157,6 +303,15 @@ impl DedupEngine {
             computer: HashComputer::new(),
             fast_mode: false,
             parallel: true, // Default to parallel for better performance
+            skip_dirs: Vec::new(),
+            prefer_dirs: Vec::new(),
+            cache: None,
+            min_duplicate_size: 0,
+            sort: DedupSort::Wasted,
+            reverse: false,
+            progress_template: None,
+            spill: false,
+            spill_run_lines: SPILL_RUN_LINES,
         }
     }
     
@@ -172,6 +327,64 @@ impl DedupEngine {
         self
     }
     
+    /// Never descend into directories with any of these exact names (e.g. node_modules)
+    pub fn with_skip_dirs(mut self, skip_dirs: Vec<String>) -> Self {
+        self.skip_dirs = skip_dirs;
+        self
+    }
+    
+    /// Prefer keeping copies under these directories, highest priority first
+    /// 
+    /// Relative directories are resolved against the scanned root. Files outside
+    /// every preferred directory (and ties within one) fall back to path order.
+    pub fn with_prefer_dirs(mut self, prefer_dirs: Vec<PathBuf>) -> Self {
+        self.prefer_dirs = prefer_dirs;
+        self
+    }
+    
+    /// Reuse and refresh a cache of already-hashed files (`--continue-from`)
+    /// 
+    /// The cache is a standard format database with nanosecond modification times
+    /// and sizes. A file whose mtime and size both still match its cached entry is
+    /// not hashed again, so repeated or interrupted runs over a slowly changing
+    /// tree finish quickly.
+    pub fn with_cache(mut self, cache: Option<PathBuf>) -> Self {
+        self.cache = cache;
+        self
+    }
+    
+    /// Ignore duplicate groups whose files are smaller than this many bytes
+    /// 
+    /// Filtered groups are left out of both the report and the wasted-space totals.
+    pub fn with_min_duplicate_size(mut self, min_duplicate_size: u64) -> Self {
+        self.min_duplicate_size = min_duplicate_size;
+        self
+    }
+    
+    /// Order duplicate groups by this key, optionally reversed
+    pub fn with_sort(mut self, sort: DedupSort, reverse: bool) -> Self {
+        self.sort = sort;
+        self.reverse = reverse;
+        self
+    }
+    
+    /// Draw the progress bar with a custom indicatif template (checked with `scan::checked_progress_template`)
+    pub fn with_progress_template(mut self, progress_template: Option<String>) -> Self {
+        self.progress_template = progress_template;
+        self
+    }
+    
+    /// Group files on disk instead of in memory (`--spill`)
+    /// 
+    /// Files are first sorted by size in a temporary file and only files sharing
+    /// a size with another file are hashed. Their hashes are sorted externally
+    /// too, so memory stays bounded by the largest duplicate group rather than
+    /// the size of the tree. Not compatible with a `--continue-from` cache.
+    pub fn with_spill(mut self, spill: bool) -> Self {
+        self.spill = spill;
+        self
+    }
+    
     /// Scan a directory recursively and find duplicate files
     /// 
     /// # Arguments
@@ -183,6 +396,36 @@ impl DedupEngine {
         &self,
         root: &Path,
     ) -> Result<DedupReport, HashUtilityError> {
+        let mut duplicate_groups = Vec::new();
+        let stats = self.find_duplicates_streaming(root, |group| {
+            duplicate_groups.push(group);
+            Ok(())
+        })?;
+        
+        // Start from hash order so ties under any sort key come out the same every run
+        duplicate_groups.sort_by(|a, b| a.hash.cmp(&b.hash));
+        Self::sort_groups(&mut duplicate_groups, self.sort, self.reverse);
+        
+        Ok(DedupReport {
+            stats,
+            duplicate_groups,
+            sort: self.sort,
+        })
+    }
+    
+    /// Scan a directory recursively and pass each duplicate group to `on_group` as it is built
+    /// 
+    /// Groups are neither collected nor sorted, so memory doesn't grow with the number
+    /// of duplicates (`--json-ndjson`). Grouping needs every file hashed first, so the
+    /// first group arrives once hashing finishes. Returns the final statistics.
+    pub fn find_duplicates_streaming<F>(
+        &self,
+        root: &Path,
+        mut on_group: F,
+    ) -> Result<DedupStats, HashUtilityError>
+    where
+        F: FnMut(DuplicateGroupWithSize) -> Result<(), HashUtilityError>,
+    {
         let start_time = Instant::now();
         
         // Canonicalize root directory for consistent path handling
@@ -197,44 +440,323 @@ impl DedupEngine {
             println!("Fast mode enabled: sampling first, middle, and last 100MB of large files");
         }
         
+        // Resolve --prefer-dir entries to match the canonical scanned paths
+        let prefer_dirs: Vec<PathBuf> = self.prefer_dirs.iter().map(|dir| {
+            let dir = if dir.is_absolute() { dir.clone() } else { canonical_root.join(dir) };
+            dir.canonicalize().unwrap_or(dir)
+        }).collect();
+        
+        if self.spill {
+            return self.find_duplicates_spilled(&canonical_root, &prefer_dirs, start_time, on_group);
+        }
+        
+        // Load hashes from a previous run, if any
+        let cache = match &self.cache {
+            Some(cache_path) => self.load_cache(cache_path)?,
+            None => HashMap::new(),
+        };
+        
         // Scan directory and compute hashes
-        let (hash_map, files_scanned, files_failed, total_bytes) = if self.parallel {
-            self.scan_parallel(&canonical_root, start_time)?
+        let (hash_map, files_scanned, files_failed, files_cached, total_bytes) = if self.parallel {
+            self.scan_parallel(&canonical_root, start_time, &cache)?
         } else {
-            self.scan_sequential(&canonical_root, start_time)?
+            self.scan_sequential(&canonical_root, start_time, &cache)?
         };
         
-        let duration = start_time.elapsed();
+        if let Some(cache_path) = &self.cache {
+            self.write_cache(cache_path, &hash_map)?;
+        }
         
-        // Find duplicates by grouping files with the same hash
-        let duplicate_groups = self.find_duplicate_groups(&hash_map);
+        let duration = start_time.elapsed();
         
-        // Calculate statistics
-        let duplicate_files: usize = duplicate_groups.iter().map(|g| g.count).sum();
-        let wasted_space: u64 = duplicate_groups.iter().map(|g| g.wasted_space).sum();
+        // Files sharing a hash form a duplicate group (all files with the same hash have the same size)
+        let mut duplicate_groups = 0;
+        let mut duplicate_files = 0;
+        let mut wasted_space = 0;
+        for (hash, paths) in &hash_map {
+            if paths.len() < 2 || paths[0].1 < self.min_duplicate_size {
+                continue;
+            }
+            let group = Self::build_group(hash, paths, &prefer_dirs);
+            duplicate_groups += 1;
+            duplicate_files += group.count;
+            wasted_space += group.wasted_space;
+            on_group(group)?;
+        }
         
-        let stats = DedupStats {
+        Ok(DedupStats {
             files_scanned,
             files_failed,
+            files_cached,
             total_bytes,
-            duplicate_groups: duplicate_groups.len(),
+            duplicate_groups,
             duplicate_files,
             wasted_space,
             duration,
+        })
+    }
+    
+    /// Find duplicates with every intermediate list kept in temporary files (`--spill`)
+    /// 
+    /// Discovered files are written out as `size<TAB>path` and sorted externally.
+    /// Each run of equal sizes is hashed and written out as `hash<TAB>size<TAB>path`,
+    /// which is sorted the same way so each run of equal hashes is one group.
+    /// Files with a unique size count as scanned but are never hashed.
+    fn find_duplicates_spilled<F>(
+        &self,
+        canonical_root: &Path,
+        prefer_dirs: &[PathBuf],
+        start_time: Instant,
+        mut on_group: F,
+    ) -> Result<DedupStats, HashUtilityError>
+    where
+        F: FnMut(DuplicateGroupWithSize) -> Result<(), HashUtilityError>,
+    {
+        let spill = SpillDir::create()?;
+        let spill_error = |path: &Path| {
+            let path = path.to_path_buf();
+            move |e: io::Error| HashUtilityError::from_io_error(e, "spilling dedup data", Some(path))
         };
         
-        Ok(DedupReport {
-            stats,
+        println!("Spill mode: grouping files on disk in {}", spill.0.display());
+        
+        let mut files_scanned = 0;
+        let mut files_failed = 0;
+        let mut total_bytes = 0u64;
+        
+        // Stream discovered files into the size list
+        let sizes_path = spill.file("sizes");
+        let (sender, receiver) = bounded::<PathBuf>(10000);
+        let walker_root = canonical_root.to_path_buf();
+        let skip_dirs = self.skip_dirs.clone();
+        let files_discovered = Arc::new(Mutex::new(0usize));
+        let files_discovered_walker = Arc::clone(&files_discovered);
+        let walker_handle = thread::spawn(move || {
+            Self::walk_directory_streaming(&walker_root, sender, skip_dirs, files_discovered_walker)
+        });
+        
+        let mut sizes = BufWriter::new(File::create(&sizes_path).map_err(spill_error(&sizes_path))?);
+        for file_path in receiver {
+            let metadata = match fs::metadata(&file_path) {
+                Ok(m) => m,
+                Err(_) => {
+                    files_failed += 1;
+                    continue;
+                }
+            };
+            match file_path.to_str() {
+                Some(path) if !path.contains('\n') => {
+                    writeln!(sizes, "{:020}\t{}", metadata.len(), path).map_err(spill_error(&sizes_path))?;
+                }
+                _ => {
+                    eprintln!("Warning: Cannot spill path {}: not valid UTF-8 on one line", file_path.display());
+                    files_failed += 1;
+                }
+            }
+        }
+        sizes.flush().map_err(spill_error(&sizes_path))?;
+        drop(sizes);
+        
+        match walker_handle.join() {
+            Ok(Err(e)) => eprintln!("Warning: Walker thread encountered error: {}", e),
+            Err(e) => eprintln!("Warning: Walker thread panicked: {:?}", e),
+            Ok(Ok(())) => {}
+        }
+        
+        let pb = ProgressBar::new(*files_discovered.lock().unwrap() as u64);
+        pb.set_style(
+            ProgressStyle::default_bar()
+                .template(self.progress_template.as_deref().unwrap_or(PROGRESS_TEMPLATE))
+                .unwrap()
+                .progress_chars("=>-")
+        );
+        pb.inc(files_failed as u64);
+        
+        // Hash only the files that share their size with another file
+        let sorted_sizes = self.sort_spill_file(&spill, "sizes")?;
+        let hashes_path = spill.file("hashes");
+        let mut hashes = BufWriter::new(File::create(&hashes_path).map_err(spill_error(&hashes_path))?);
+        Self::for_each_spill_run(&sorted_sizes, |size, paths| {
+            let file_size: u64 = size.parse().unwrap_or(0);
+            files_scanned += paths.len();
+            total_bytes += file_size * paths.len() as u64;
+            pb.set_message(format!("{} OK, {} failed", files_scanned, files_failed));
+            
+            if paths.len() < 2 || file_size < self.min_duplicate_size {
+                pb.inc(paths.len() as u64);
+                return Ok(());
+            }
+            
+            let hash_one = |path: &String| {
+                let result = if self.fast_mode {
+                    self.computer.compute_hash_fast_v2(Path::new(path), "blake3")
+                } else {
+                    self.computer.compute_hash(Path::new(path), "blake3")
+                };
+                pb.inc(1);
+                result.map(|result| result.hash)
+            };
+            let results: Vec<_> = if self.parallel {
+                paths.par_iter().map(hash_one).collect()
+            } else {
+                paths.iter().map(hash_one).collect()
+            };
+            
+            for (path, result) in paths.iter().zip(results) {
+                match result {
+                    Ok(hash) => {
+                        writeln!(hashes, "{}\t{}\t{}", hash, file_size, path).map_err(spill_error(&hashes_path))?;
+                    }
+                    Err(e) => {
+                        eprintln!("Warning: Failed to hash {}: {}", path, e);
+                        files_scanned -= 1;
+                        files_failed += 1;
+                        total_bytes -= file_size;
+                    }
+                }
+            }
+            Ok(())
+        })?;
+        hashes.flush().map_err(spill_error(&hashes_path))?;
+        drop(hashes);
+        pb.finish_and_clear();
+        
+        // Each run of equal hashes is a duplicate group
+        let sorted_hashes = self.sort_spill_file(&spill, "hashes")?;
+        let mut duplicate_groups = 0;
+        let mut duplicate_files = 0;
+        let mut wasted_space = 0;
+        Self::for_each_spill_run(&sorted_hashes, |hash, files| {
+            if files.len() < 2 {
+                return Ok(());
+            }
+            let paths: Vec<(PathBuf, u64, Option<String>)> = files
+                .iter()
+                .filter_map(|file| file.split_once('\t'))
+                .map(|(size, path)| (PathBuf::from(path), size.parse().unwrap_or(0), None))
+                .collect();
+            let group = Self::build_group(hash, &paths, prefer_dirs);
+            duplicate_groups += 1;
+            duplicate_files += group.count;
+            wasted_space += group.wasted_space;
+            on_group(group)
+        })?;
+        
+        Ok(DedupStats {
+            files_scanned,
+            files_failed,
+            files_cached: 0,
+            total_bytes,
             duplicate_groups,
+            duplicate_files,
+            wasted_space,
+            duration: start_time.elapsed(),
         })
     }
     
+    /// Sort a spill file line by line without holding it in memory, returning the sorted file
+    /// 
+    /// Runs of `spill_run_lines` lines are sorted and written out, then merged.
+    fn sort_spill_file(&self, spill: &SpillDir, name: &str) -> Result<PathBuf, HashUtilityError> {
+        let input_path = spill.file(name);
+        let sorted_path = spill.file(&format!("{}.sorted", name));
+        let spill_error = |e: io::Error| {
+            HashUtilityError::from_io_error(e, "sorting dedup spill file", Some(input_path.clone()))
+        };
+        
+        // Split the input into sorted runs
+        let mut runs = Vec::new();
+        let mut chunk = Vec::new();
+        let mut lines = BufReader::new(File::open(&input_path).map_err(spill_error)?).lines();
+        loop {
+            let line = lines.next().transpose().map_err(spill_error)?;
+            let done = line.is_none();
+            chunk.extend(line);
+            
+            if chunk.len() >= self.spill_run_lines || (done && !chunk.is_empty()) {
+                chunk.sort_unstable();
+                let run_path = spill.file(&format!("{}.run{}", name, runs.len()));
+                let mut run = BufWriter::new(File::create(&run_path).map_err(spill_error)?);
+                for line in chunk.drain(..) {
+                    writeln!(run, "{}", line).map_err(spill_error)?;
+                }
+                run.flush().map_err(spill_error)?;
+                runs.push(run_path);
+            }
+            if done {
+                break;
+            }
+        }
+        fs::remove_file(&input_path).map_err(spill_error)?;
+        
+        // Merge the runs, always taking the smallest next line
+        let mut readers = runs
+            .iter()
+            .map(|run| File::open(run).map(|file| BufReader::new(file).lines()))
+            .collect::<io::Result<Vec<_>>>()
+            .map_err(spill_error)?;
+        let mut heap = BinaryHeap::new();
+        for (index, reader) in readers.iter_mut().enumerate() {
+            if let Some(line) = reader.next().transpose().map_err(spill_error)? {
+                heap.push(Reverse((line, index)));
+            }
+        }
+        
+        let mut sorted = BufWriter::new(File::create(&sorted_path).map_err(spill_error)?);
+        while let Some(Reverse((line, index))) = heap.pop() {
+            writeln!(sorted, "{}", line).map_err(spill_error)?;
+            if let Some(next) = readers[index].next().transpose().map_err(spill_error)? {
+                heap.push(Reverse((next, index)));
+            }
+        }
+        sorted.flush().map_err(spill_error)?;
+        
+        for run in runs {
+            fs::remove_file(run).map_err(spill_error)?;
+        }
+        Ok(sorted_path)
+    }
+    
+    /// Call `on_run` with each run of consecutive lines sharing the key before their first tab
+    /// 
+    /// Only one run is held in memory at a time; `on_run` receives the key and the rest of each line.
+    fn for_each_spill_run<F>(path: &Path, mut on_run: F) -> Result<(), HashUtilityError>
+    where
+        F: FnMut(&str, &[String]) -> Result<(), HashUtilityError>,
+    {
+        let read_error = |e: io::Error| {
+            HashUtilityError::from_io_error(e, "reading dedup spill file", Some(path.to_path_buf()))
+        };
+        
+        let mut key = String::new();
+        let mut run: Vec<String> = Vec::new();
+        for line in BufReader::new(File::open(path).map_err(read_error)?).lines() {
+            let line = line.map_err(read_error)?;
+            let Some((line_key, rest)) = line.split_once('\t') else {
+                continue;
+            };
+            if line_key != key {
+                if !run.is_empty() {
+                    on_run(&key, &run)?;
+                    run.clear();
+                }
+                key = line_key.to_string();
+            }
+            run.push(rest.to_string());
+        }
+        if !run.is_empty() {
+            on_run(&key, &run)?;
+        }
+        Ok(())
+    }
+    
     /// Sequential scan implementation
     fn scan_sequential(
         &self,
         canonical_root: &Path,
         start_time: Instant,
-    ) -> Result<(HashMap<String, Vec<(PathBuf, u64)>>, usize, usize, u64), HashUtilityError> {
+        cache: &HashMap<PathBuf, DatabaseEntry>,
+    ) -> Result<(HashGroups, usize, usize, usize, u64), HashUtilityError> {
         // Collect all files
         let files = self.collect_files(canonical_root)?;
         
@@ -243,16 +765,17 @@ impl DedupEngine {
         // Track statistics
         let mut files_scanned = 0;
         let mut files_failed = 0;
+        let mut files_cached = 0;
         let mut total_bytes = 0u64;
         
-        // Map from hash to list of (path, size) tuples
-        let mut hash_map: HashMap<String, Vec<(PathBuf, u64)>> = HashMap::new();
+        // Map from hash to list of (path, size, mtime) tuples
+        let mut hash_map: HashGroups = HashMap::new();
         
         // Create progress bar
         let pb = ProgressBar::new(files.len() as u64);
         pb.set_style(
             ProgressStyle::default_bar()
-                .template("[{elapsed_precise}] [{bar:40.cyan/blue}] {pos}/{len} files ({percent}%) | Processed: {msg}")
+                .template(self.progress_template.as_deref().unwrap_or(PROGRESS_TEMPLATE))
                 .unwrap()
                 .progress_chars("=>-")
         );
@@ -273,21 +796,25 @@ impl DedupEngine {
             };
             
             let file_size = metadata.len();
+            let modified = self.cache.as_ref().and_then(|_| Self::cache_modified(&metadata));
             
-            // Compute hash for the file (always use BLAKE3)
-            let hash_result = if self.fast_mode {
-                self.computer.compute_hash_fast(file_path, "blake3")
+            // Reuse the cached hash of an unchanged file, otherwise hash it (always use BLAKE3)
+            let hash_result = if let Some(hash) = Self::cached_hash(cache, file_path, modified.as_deref(), file_size) {
+                files_cached += 1;
+                Ok(hash)
+            } else if self.fast_mode {
+                self.computer.compute_hash_fast_v2(file_path, "blake3").map(|result| result.hash)
             } else {
-                self.computer.compute_hash(file_path, "blake3")
+                self.computer.compute_hash(file_path, "blake3").map(|result| result.hash)
             };
             
             match hash_result {
-                Ok(result) => {
+                Ok(hash) => {
                     // Add to hash map
                     hash_map
-                        .entry(result.hash)
+                        .entry(hash)
                         .or_insert_with(Vec::new)
-                        .push((file_path.clone(), file_size));
+                        .push((file_path.clone(), file_size, modified));
                     
                     files_scanned += 1;
                     total_bytes += file_size;
@@ -303,7 +830,7 @@ impl DedupEngine {
         
         pb.finish_and_clear();
         
-        Ok((hash_map, files_scanned, files_failed, total_bytes))
+        Ok((hash_map, files_scanned, files_failed, files_cached, total_bytes))
     }
     
     /// Parallel scan implementation using producer-consumer pattern
@@ -311,10 +838,12 @@ impl DedupEngine {
         &self,
         canonical_root: &Path,
         start_time: Instant,
-    ) -> Result<(HashMap<String, Vec<(PathBuf, u64)>>, usize, usize, u64), HashUtilityError> {
+        cache: &HashMap<PathBuf, DatabaseEntry>,
+    ) -> Result<(HashGroups, usize, usize, usize, u64), HashUtilityError> {
         // Thread-safe counters
         let files_scanned = Arc::new(Mutex::new(0usize));
         let files_failed = Arc::new(Mutex::new(0usize));
+        let files_cached = Arc::new(Mutex::new(0usize));
         let total_bytes = Arc::new(Mutex::new(0u64));
         
         // Create progress bar
@@ -335,23 +864,26 @@ impl DedupEngine {
         
         // Capture fast_mode for use in closure
         let fast_mode = self.fast_mode;
+        let use_cache = self.cache.is_some();
         
         // Clone for walker thread
         let walker_root = canonical_root.to_path_buf();
+        let skip_dirs = self.skip_dirs.clone();
+        let progress_template = self.progress_template.clone();
         let total_files_discovered_walker = Arc::clone(&total_files_discovered);
         let discovery_complete_walker = Arc::clone(&discovery_complete);
         let pb_walker = pb.clone();
         
         // Spawn walker thread
         let walker_handle = thread::spawn(move || {
-            let result = Self::walk_directory_streaming(&walker_root, sender, Arc::clone(&total_files_discovered_walker));
+            let result = Self::walk_directory_streaming(&walker_root, sender, skip_dirs, Arc::clone(&total_files_discovered_walker));
             
             // Mark discovery as complete
             let total = *total_files_discovered_walker.lock().unwrap();
             pb_walker.set_length(total as u64);
             pb_walker.set_style(
                 ProgressStyle::default_bar()
-                    .template("[{elapsed_precise}] [{bar:40.cyan/blue}] {pos}/{len} files ({percent}%) | Processed: {msg}")
+                    .template(progress_template.as_deref().unwrap_or(PROGRESS_TEMPLATE))
                     .unwrap()
                     .progress_chars("=>-")
             );
@@ -363,6 +895,7 @@ impl DedupEngine {
         // Clone Arc references for parallel closure
         let files_scanned_clone = Arc::clone(&files_scanned);
         let files_failed_clone = Arc::clone(&files_failed);
+        let files_cached_clone = Arc::clone(&files_cached);
         let total_bytes_clone = Arc::clone(&total_bytes);
         let pb_clone = pb.clone();
         
@@ -391,23 +924,27 @@ impl DedupEngine {
                 drop(scanned);
                 drop(failed);
                 
-                // Compute hash (always use BLAKE3)
+                // Reuse the cached hash of an unchanged file, otherwise hash it (always use BLAKE3)
+                let modified = if use_cache { Self::cache_modified(&metadata) } else { None };
                 let computer = HashComputer::new();
-                let hash_result = if fast_mode {
-                    computer.compute_hash_fast(&file_path, "blake3")
+                let hash_result = if let Some(hash) = Self::cached_hash(cache, &file_path, modified.as_deref(), file_size) {
+                    *files_cached_clone.lock().unwrap() += 1;
+                    Ok(hash)
+                } else if fast_mode {
+                    computer.compute_hash_fast_v2(&file_path, "blake3").map(|result| result.hash)
                 } else {
-                    computer.compute_hash(&file_path, "blake3")
+                    computer.compute_hash(&file_path, "blake3").map(|result| result.hash)
                 };
                 
                 let result = match hash_result {
-                    Ok(result) => {
+                    Ok(hash) => {
                         // Update counters
                         let mut scanned = files_scanned_clone.lock().unwrap();
                         *scanned += 1;
                         let mut bytes = total_bytes_clone.lock().unwrap();
                         *bytes += file_size;
                         
-                        Some((result.hash, file_path.clone(), file_size))
+                        Some((hash, file_path.clone(), file_size, modified))
                     }
                     Err(e) => {
                         eprintln!("Warning: Failed to hash {}: {}", file_path.display(), e);
@@ -437,26 +974,93 @@ impl DedupEngine {
         pb.finish_and_clear();
         
         // Build hash map from results
-        let mut hash_map: HashMap<String, Vec<(PathBuf, u64)>> = HashMap::new();
-        for (hash, path, size) in results {
+        let mut hash_map: HashGroups = HashMap::new();
+        for (hash, path, size, modified) in results {
             hash_map
                 .entry(hash)
                 .or_insert_with(Vec::new)
-                .push((path, size));
+                .push((path, size, modified));
         }
         
         // Extract final statistics
         let final_scanned = *files_scanned.lock().unwrap();
         let final_failed = *files_failed.lock().unwrap();
+        let final_cached = *files_cached.lock().unwrap();
         let final_bytes = *total_bytes.lock().unwrap();
         
-        Ok((hash_map, final_scanned, final_failed, final_bytes))
+        Ok((hash_map, final_scanned, final_failed, final_cached, final_bytes))
+    }
+    
+    /// Read the hash cache, keeping only entries hashed the way this run hashes
+    /// 
+    /// A missing cache is not an error: the first run starts empty and creates it.
+    fn load_cache(&self, cache_path: &Path) -> Result<HashMap<PathBuf, DatabaseEntry>, HashUtilityError> {
+        if !cache_path.exists() {
+            return Ok(HashMap::new());
+        }
+        
+        let mut cache = DatabaseHandler::read_database(cache_path)?;
+        cache.retain(|_, entry| {
+            entry.algorithm == "blake3"
+                && entry.modified.is_some()
+                && entry.size.is_some()
+                && !entry.structure_only
+                && entry.fast_mode == self.fast_mode
+                && entry.fast_v2 == self.fast_mode
+        });
+        Ok(cache)
+    }
+    
+    /// Modification time recorded in the cache, with nanoseconds so that a file
+    /// rewritten within the same second isn't mistaken for an unchanged one
+    fn cache_modified(metadata: &fs::Metadata) -> Option<String> {
+        let modified: chrono::DateTime<chrono::Utc> = metadata.modified().ok()?.into();
+        Some(modified.to_rfc3339_opts(chrono::SecondsFormat::Nanos, true))
+    }
+    
+    /// Cached hash for a file whose modification time and size haven't changed since it was cached
+    fn cached_hash(
+        cache: &HashMap<PathBuf, DatabaseEntry>,
+        path: &Path,
+        modified: Option<&str>,
+        size: u64,
+    ) -> Option<String> {
+        let entry = cache.get(path)?;
+        (modified.is_some() && entry.modified.as_deref() == modified && entry.size == Some(size))
+            .then(|| entry.hash.clone())
+    }
+    
+    /// Rewrite the cache with every file hashed (or reused) in this run
+    /// 
+    /// Files that have disappeared since the last run drop out of the cache.
+    fn write_cache(&self, cache_path: &Path, hash_map: &HashGroups) -> Result<(), HashUtilityError> {
+        let write_error = |e: std::io::Error| HashUtilityError::from_io_error(e, "writing dedup cache", Some(cache_path.to_path_buf()));
+        
+        let mut entries: Vec<(&PathBuf, &str, &str, u64)> = hash_map
+            .iter()
+            .flat_map(|(hash, files)| {
+                files.iter().filter_map(move |(path, size, modified)| {
+                    modified.as_deref().map(|modified| (path, hash.as_str(), modified, *size))
+                })
+            })
+            .collect();
+        entries.sort();
+        
+        let mode = DatabaseHandler::mode_label(self.fast_mode, false);
+        let mut writer = BufWriter::new(File::create(cache_path).map_err(write_error)?);
+        for (path, hash, modified, size) in entries {
+            let metadata = EntryMetadata { permissions: None, modified: Some(modified), size: Some(size) };
+            DatabaseHandler::write_entry_with_metadata(&mut writer, hash, "blake3", mode, metadata, path)
+                .map_err(write_error)?;
+        }
+        writer.flush().map_err(write_error)
     }
     
     /// Walk directory and send file paths to channel
     fn walk_directory_streaming(
         root: &Path,
         sender: crossbeam_channel::Sender<PathBuf>,
+        skip_dirs: Vec<String>,
         total_files_discovered: Arc<Mutex<usize>>,
     ) -> Result<(), HashUtilityError> {
         // Load .hashignore patterns
@@ -473,6 +1077,13 @@ impl DedupEngine {
             .parallelism(jwalk::Parallelism::RayonNewPool(0))
             .skip_hidden(false)
             .follow_links(false)
+            .process_read_dir(move |_, _, _, children| {
+                // Prune --skip-dir directories before jwalk reads them
+                children.retain(|child| match child {
+                    Ok(entry) => !(entry.file_type().is_dir() && path_utils::is_skipped_dir(&entry.path(), &skip_dirs)),
+                    Err(_) => true,
+                });
+            })
         {
             match entry_result {
                 Ok(entry) => {
@@ -510,6 +1121,17 @@ impl DedupEngine {
         Ok(())
     }
     
+    /// Count the files a duplicate search of `root` would hash, and their total size, without reading them
+    pub fn count_files(&self, root: &Path) -> Result<(usize, u64), HashUtilityError> {
+        let files = self.collect_files(root)?;
+        let total_bytes = files
+            .iter()
+            .filter_map(|path| fs::metadata(path).ok())
+            .map(|metadata| metadata.len())
+            .sum();
+        Ok((files.len(), total_bytes))
+    }
+    
     /// Recursively collect all regular files in a directory tree
     fn collect_files(&self, root: &Path) -> Result<Vec<PathBuf>, HashUtilityError> {
         let mut files = Vec::new();
@@ -585,7 +1207,7 @@ impl DedupEngine {
             
             if metadata.is_file() {
                 files.push(path);
-            } else if is_dir {
+            } else if is_dir && !path_utils::is_skipped_dir(&path, &self.skip_dirs) {
                 if let Err(e) = self.collect_files_recursive(root, &path, files, ignore_handler) {
                     eprintln!("Warning: Error processing directory {}: {}", path.display(), e);
                 }
@@ -595,37 +1217,53 @@ impl DedupEngine {
         Ok(())
     }
     
-    /// Find duplicate groups from hash map
-    fn find_duplicate_groups(
-        &self,
-        hash_map: &HashMap<String, Vec<(PathBuf, u64)>>,
-    ) -> Vec<DuplicateGroupWithSize> {
-        // Filter to only groups with more than one file (duplicates)
-        let mut duplicates: Vec<DuplicateGroupWithSize> = hash_map
-            .iter()
-            .filter(|(_, paths)| paths.len() > 1)
-            .map(|(hash, paths)| {
-                let count = paths.len();
-                let file_size = paths[0].1; // All files with same hash have same size
-                let wasted_space = (count as u64 - 1) * file_size;
-                
-                let mut sorted_paths: Vec<PathBuf> = paths.iter().map(|(p, _)| p.clone()).collect();
-                sorted_paths.sort();
-                
-                DuplicateGroupWithSize {
-                    hash: hash.clone(),
-                    paths: sorted_paths,
-                    count,
-                    file_size,
-                    wasted_space,
-                }
-            })
-            .collect();
+    /// Build a duplicate group from the files sharing one hash
+    /// 
+    /// Paths in the group are ordered keeper first: by the index of the first
+    /// preferred directory containing them, then lexically.
+    fn build_group(
+        hash: &str,
+        paths: &[(PathBuf, u64, Option<String>)],
+        prefer_dirs: &[PathBuf],
+    ) -> DuplicateGroupWithSize {
+        let count = paths.len();
+        let file_size = paths[0].1;
+        let wasted_space = (count as u64 - 1) * file_size;
         
-        // Sort by wasted space (largest first)
-        duplicates.sort_by(|a, b| b.wasted_space.cmp(&a.wasted_space));
+        let mut sorted_paths: Vec<PathBuf> = paths.iter().map(|(p, _, _)| p.clone()).collect();
+        sorted_paths.sort();
+        sorted_paths.sort_by_key(|p| Self::preference_rank(p, prefer_dirs));
+        let keeper = sorted_paths[0].clone();
         
-        duplicates
+        DuplicateGroupWithSize {
+            hash: hash.to_string(),
+            paths: sorted_paths,
+            count,
+            file_size,
+            wasted_space,
+            keeper,
+        }
+    }
+    
+    /// Sort duplicate groups by the chosen key (numeric keys largest first)
+    fn sort_groups(groups: &mut [DuplicateGroupWithSize], sort: DedupSort, reverse: bool) {
+        match sort {
+            DedupSort::Wasted => groups.sort_by_key(|g| Reverse(g.wasted_space)),
+            DedupSort::Size => groups.sort_by_key(|g| Reverse(g.file_size)),
+            DedupSort::Count => groups.sort_by_key(|g| Reverse(g.count)),
+            DedupSort::Hash => groups.sort_by(|a, b| a.hash.cmp(&b.hash)),
+        }
+        if reverse {
+            groups.reverse();
+        }
+    }
+    
+    /// Priority of a path under --prefer-dir (lower is better, unmatched last)
+    fn preference_rank(path: &Path, prefer_dirs: &[PathBuf]) -> usize {
+        prefer_dirs
+            .iter()
+            .position(|dir| path.starts_with(dir))
+            .unwrap_or(prefer_dirs.len())
     }
 }
 
@@ -634,3 +1272,284 @@ impl Default for DedupEngine {
         Self::new()
     }
 }
+
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_prefer_dir_chooses_keeper() {
+        let test_dir = "test_dedup_prefer_dir";
+        fs::create_dir_all(format!("{}/primary", test_dir)).unwrap();
+        fs::create_dir_all(format!("{}/backup", test_dir)).unwrap();
+        
+        // "backup" sorts before "primary", so path order alone would keep the backup
+        fs::write(format!("{}/primary/photo.jpg", test_dir), b"same content").unwrap();
+        fs::write(format!("{}/backup/photo.jpg", test_dir), b"same content").unwrap();
+        
+        let report = DedupEngine::new()
+            .with_parallel(false)
+            .find_duplicates(Path::new(test_dir))
+            .unwrap();
+        assert_eq!(report.duplicate_groups.len(), 1);
+        assert!(report.duplicate_groups[0].keeper.ends_with("backup/photo.jpg"));
+        
+        let report = DedupEngine::new()
+            .with_parallel(false)
+            .with_prefer_dirs(vec![PathBuf::from("primary")])
+            .find_duplicates(Path::new(test_dir))
+            .unwrap();
+        let group = &report.duplicate_groups[0];
+        assert!(group.keeper.ends_with("primary/photo.jpg"));
+        assert_eq!(group.paths[0], group.keeper);
+        assert_eq!(group.count, 2);
+        
+        fs::remove_dir_all(test_dir).unwrap();
+    }
+    
+    #[test]
+    fn test_spill_matches_in_memory_groups() {
+        let test_dir = "test_dedup_spill";
+        fs::create_dir_all(format!("{}/sub", test_dir)).unwrap();
+        for i in 0..3 {
+            fs::write(format!("{}/a{}.txt", test_dir, i), b"group a").unwrap();
+            fs::write(format!("{}/sub/b{}.txt", test_dir, i), b"group bb").unwrap();
+        }
+        // Same size as the "group a" files but different content, and a unique size
+        fs::write(format!("{}/same_size.txt", test_dir), b"group z").unwrap();
+        fs::write(format!("{}/unique.txt", test_dir), b"nothing like the others").unwrap();
+        
+        let expected = DedupEngine::new()
+            .with_parallel(false)
+            .with_sort(DedupSort::Hash, false)
+            .find_duplicates(Path::new(test_dir))
+            .unwrap();
+        assert_eq!(expected.duplicate_groups.len(), 2);
+        
+        for parallel in [false, true] {
+            // Tiny runs so the external sort has to merge several of them
+            let mut engine = DedupEngine::new()
+                .with_parallel(parallel)
+                .with_sort(DedupSort::Hash, false)
+                .with_spill(true);
+            engine.spill_run_lines = 2;
+            let report = engine.find_duplicates(Path::new(test_dir)).unwrap();
+            
+            let groups = |report: &DedupReport| -> Vec<(String, Vec<PathBuf>, PathBuf)> {
+                report.duplicate_groups.iter().map(|g| (g.hash.clone(), g.paths.clone(), g.keeper.clone())).collect()
+            };
+            assert_eq!(groups(&report), groups(&expected));
+            assert_eq!(report.stats.files_scanned, expected.stats.files_scanned);
+            assert_eq!(report.stats.total_bytes, expected.stats.total_bytes);
+            assert_eq!(report.stats.wasted_space, expected.stats.wasted_space);
+        }
+        
+        fs::remove_dir_all(test_dir).unwrap();
+    }
+    
+    #[test]
+    fn test_continue_from_reuses_cached_hashes() {
+        let test_dir = "test_dedup_continue_from";
+        let cache = PathBuf::from("test_dedup_continue_from.cache");
+        fs::create_dir_all(test_dir).unwrap();
+        fs::write(format!("{}/a.txt", test_dir), b"duplicate").unwrap();
+        fs::write(format!("{}/b.txt", test_dir), b"duplicate").unwrap();
+        fs::write(format!("{}/c.txt", test_dir), b"unique").unwrap();
+        
+        for parallel in [false, true] {
+            fs::remove_file(&cache).ok();
+            let engine = DedupEngine::new()
+                .with_parallel(parallel)
+                .with_cache(Some(cache.clone()));
+            
+            // First run hashes everything and writes the cache
+            let first = engine.find_duplicates(Path::new(test_dir)).unwrap();
+            assert_eq!(first.stats.files_scanned, 3);
+            assert_eq!(first.stats.files_cached, 0);
+            assert_eq!(DatabaseHandler::read_database(&cache).unwrap().len(), 3);
+            
+            // Second run over the unchanged tree computes no hashes and finds the same duplicates
+            let second = engine.find_duplicates(Path::new(test_dir)).unwrap();
+            assert_eq!(second.stats.files_scanned, 3);
+            assert_eq!(second.stats.files_cached, 3);
+            assert_eq!(second.duplicate_groups.len(), 1);
+            assert_eq!(second.duplicate_groups[0].hash, first.duplicate_groups[0].hash);
+            assert_eq!(second.duplicate_groups[0].count, 2);
+        }
+        
+        fs::remove_file(&cache).unwrap();
+        fs::remove_dir_all(test_dir).unwrap();
+    }
+    
+    #[test]
+    fn test_continue_from_rehashes_rewrite_within_same_second() {
+        let test_dir = "test_dedup_continue_from_same_second";
+        let cache = PathBuf::from("test_dedup_continue_from_same_second.cache");
+        fs::create_dir_all(test_dir).unwrap();
+        fs::remove_file(&cache).ok();
+        let file = PathBuf::from(format!("{}/a.txt", test_dir));
+        fs::write(format!("{}/b.txt", test_dir), b"after").unwrap();
+        
+        // Pin the mtimes so every rewrite lands in the same whole second
+        let second = std::time::UNIX_EPOCH + Duration::from_secs(1_700_000_000);
+        let rewrite = |contents: &[u8], nanos: u32| {
+            fs::write(&file, contents).unwrap();
+            let modified = second + Duration::from_nanos(nanos as u64);
+            fs::File::options().write(true).open(&file).unwrap().set_modified(modified).unwrap();
+        };
+        let engine = DedupEngine::new()
+            .with_parallel(false)
+            .with_cache(Some(cache.clone()));
+        
+        rewrite(b"befor", 100_000_000);
+        let first = engine.find_duplicates(Path::new(test_dir)).unwrap();
+        assert!(first.duplicate_groups.is_empty());
+        
+        // Same size, same second, new contents: the cached hash must not be reused
+        rewrite(b"after", 200_000_000);
+        let second_run = engine.find_duplicates(Path::new(test_dir)).unwrap();
+        assert_eq!(second_run.stats.files_cached, 1);
+        assert_eq!(second_run.duplicate_groups.len(), 1);
+        
+        // Same mtime as cached but a different size is rehashed too
+        rewrite(b"after!", 200_000_000);
+        let third = engine.find_duplicates(Path::new(test_dir)).unwrap();
+        assert_eq!(third.stats.files_cached, 1);
+        assert!(third.duplicate_groups.is_empty());
+        
+        fs::remove_file(&cache).unwrap();
+        fs::remove_dir_all(test_dir).unwrap();
+    }
+    
+    #[test]
+    fn test_min_duplicate_size_filters_small_groups() {
+        let test_dir = "test_dedup_min_duplicate_size";
+        fs::create_dir_all(test_dir).unwrap();
+        fs::write(format!("{}/small_a.conf", test_dir), b"x=1").unwrap();
+        fs::write(format!("{}/small_b.conf", test_dir), b"x=1").unwrap();
+        fs::write(format!("{}/large_a.bin", test_dir), vec![7u8; 4096]).unwrap();
+        fs::write(format!("{}/large_b.bin", test_dir), vec![7u8; 4096]).unwrap();
+        fs::write(format!("{}/large_c.bin", test_dir), vec![7u8; 4096]).unwrap();
+        
+        let all = DedupEngine::new()
+            .with_parallel(false)
+            .find_duplicates(Path::new(test_dir))
+            .unwrap();
+        assert_eq!(all.duplicate_groups.len(), 2);
+        assert_eq!(all.stats.wasted_space, 2 * 4096 + 3);
+        
+        let filtered = DedupEngine::new()
+            .with_parallel(false)
+            .with_min_duplicate_size(1024)
+            .find_duplicates(Path::new(test_dir))
+            .unwrap();
+        assert_eq!(filtered.duplicate_groups.len(), 1);
+        assert_eq!(filtered.duplicate_groups[0].file_size, 4096);
+        assert_eq!(filtered.stats.duplicate_groups, 1);
+        assert_eq!(filtered.stats.duplicate_files, 3);
+        assert_eq!(filtered.stats.wasted_space, 2 * 4096);
+        
+        // Files are still scanned, only the report is filtered
+        assert_eq!(filtered.stats.files_scanned, 5);
+        
+        fs::remove_dir_all(test_dir).unwrap();
+    }
+    
+    #[test]
+    fn test_sort_keys_order_groups() {
+        let test_dir = "test_dedup_sort_keys";
+        fs::create_dir_all(test_dir).unwrap();
+        // big: 2 x 1000 bytes (1000 wasted), many: 5 x 100 bytes (400 wasted), mid: 3 x 600 bytes (1200 wasted)
+        for i in 0..2 {
+            fs::write(format!("{}/big_{}.bin", test_dir, i), vec![1u8; 1000]).unwrap();
+        }
+        for i in 0..5 {
+            fs::write(format!("{}/many_{}.bin", test_dir, i), vec![2u8; 100]).unwrap();
+        }
+        for i in 0..3 {
+            fs::write(format!("{}/mid_{}.bin", test_dir, i), vec![3u8; 600]).unwrap();
+        }
+        
+        let sizes = |sort: DedupSort, reverse: bool| -> Vec<u64> {
+            let report = DedupEngine::new()
+                .with_parallel(false)
+                .with_sort(sort, reverse)
+                .find_duplicates(Path::new(test_dir))
+                .unwrap();
+            assert_eq!(report.sort, sort);
+            report.duplicate_groups.iter().map(|g| g.file_size).collect()
+        };
+        
+        assert_eq!(sizes(DedupSort::Wasted, false), vec![600, 1000, 100]);
+        assert_eq!(sizes(DedupSort::Size, false), vec![1000, 600, 100]);
+        assert_eq!(sizes(DedupSort::Count, false), vec![100, 600, 1000]);
+        assert_eq!(sizes(DedupSort::Size, true), vec![100, 600, 1000]);
+        
+        // Hash order is ascending, and reversing gives descending
+        let report = DedupEngine::new()
+            .with_parallel(false)
+            .with_sort(DedupSort::Hash, false)
+            .find_duplicates(Path::new(test_dir))
+            .unwrap();
+        let hashes: Vec<&String> = report.duplicate_groups.iter().map(|g| &g.hash).collect();
+        let mut expected = hashes.clone();
+        expected.sort();
+        assert_eq!(hashes, expected);
+        let reversed = sizes(DedupSort::Hash, true);
+        let forward: Vec<u64> = report.duplicate_groups.iter().rev().map(|g| g.file_size).collect();
+        assert_eq!(reversed, forward);
+        
+        assert_eq!(DedupSort::parse("COUNT").unwrap(), DedupSort::Count);
+        assert!(DedupSort::parse("name").is_err());
+        
+        fs::remove_dir_all(test_dir).unwrap();
+    }
+    
+    #[test]
+    fn test_ndjson_stream_one_line_per_group() {
+        let test_dir = "test_dedup_ndjson";
+        fs::create_dir_all(test_dir).unwrap();
+        fs::write(format!("{}/a1.txt", test_dir), b"first").unwrap();
+        fs::write(format!("{}/a2.txt", test_dir), b"first").unwrap();
+        fs::write(format!("{}/b1.txt", test_dir), b"second").unwrap();
+        fs::write(format!("{}/b2.txt", test_dir), b"second").unwrap();
+        fs::write(format!("{}/b3.txt", test_dir), b"second").unwrap();
+        fs::write(format!("{}/unique.txt", test_dir), b"unique").unwrap();
+        
+        let mut stream = Vec::new();
+        let stats = DedupEngine::new()
+            .with_parallel(false)
+            .find_duplicates_streaming(Path::new(test_dir), |group| {
+                write_ndjson_group(&mut stream, &group)
+                    .map_err(|e| HashUtilityError::from_io_error(e, "writing output", None))
+            })
+            .unwrap();
+        write_ndjson_stats(&mut stream, &stats).unwrap();
+        
+        let lines: Vec<serde_json::Value> = String::from_utf8(stream)
+            .unwrap()
+            .lines()
+            .map(|line| serde_json::from_str(line).unwrap())
+            .collect();
+        assert_eq!(lines.len(), 3);
+        
+        let mut counts: Vec<u64> = lines[..2]
+            .iter()
+            .map(|line| {
+                assert_eq!(line["type"], "group");
+                line["count"].as_u64().unwrap()
+            })
+            .collect();
+        counts.sort();
+        assert_eq!(counts, vec![2, 3]);
+        
+        let last = &lines[2];
+        assert_eq!(last["type"], "stats");
+        assert_eq!(last["files_scanned"], 6);
+        assert_eq!(last["duplicate_groups"], 2);
+        assert_eq!(last["duplicate_files"], 5);
+        
+        fs::remove_dir_all(test_dir).unwrap();
+    }
+}
@@ -3,7 +3,7 @@
 
 use std::fmt;
 use std::io;
-use std::path::PathBuf;
+use std::path::{Path, PathBuf};
 
 /// Main error type for the hash utility
 /// Provides context-rich error messages with file paths and operations
@@ -24,6 +24,7 @@ pub enum HashUtilityError {
     DatabaseParseError { path: PathBuf, line: usize, reason: String },
     DatabaseWriteError { path: PathBuf, reason: String },
     EmptyDatabase { path: PathBuf },
+    CorruptDatabase { path: PathBuf, reason: String },
     
     /// Verification errors
     VerificationFailed { reason: String },
@@ -38,7 +39,16 @@ pub enum HashUtilityError {
 
 impl fmt::Display for HashUtilityError {
     fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
-        match self {
+        write!(f, "Error [{}]: {}", self.code(), Description(self))
+    }
+}
+
+/// The message and suggestion of an error, without the `Error [CODE]: ` prefix
+struct Description<'a>(&'a HashUtilityError);
+
+impl fmt::Display for Description<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self.0 {
             // File system errors
             HashUtilityError::FileNotFound { path } => {
                 write!(f, "File not found: {}\n", path.display())?;
@@ -88,6 +98,10 @@ impl fmt::Display for HashUtilityError {
                 write!(f, "Database file is empty: {}\n", path.display())?;
                 write!(f, "Suggestion: Ensure the database contains at least one hash entry")
             }
+            HashUtilityError::CorruptDatabase { path, reason } => {
+                write!(f, "Database appears truncated or corrupt: {} ({})\n", path.display(), reason)?;
+                write!(f, "Suggestion: Restore the database from a backup or re-run the scan to recreate it")
+            }
             
             // Verification errors
             HashUtilityError::VerificationFailed { reason } => {
@@ -123,6 +137,84 @@ impl std::error::Error for HashUtilityError {
     }
 }
 
+// Machine-readable views of errors for --error-format json
+impl HashUtilityError {
+    /// Stable snake_case name of the error variant, for scripts to match on
+    pub fn kind(&self) -> &'static str {
+        match self {
+            HashUtilityError::FileNotFound { .. } => "file_not_found",
+            HashUtilityError::DirectoryNotFound { .. } => "directory_not_found",
+            HashUtilityError::PermissionDenied { .. } => "permission_denied",
+            HashUtilityError::IoError { .. } => "io_error",
+            HashUtilityError::UnsupportedAlgorithm { .. } => "unsupported_algorithm",
+            HashUtilityError::HashComputationFailed { .. } => "hash_computation_failed",
+            HashUtilityError::DatabaseNotFound { .. } => "database_not_found",
+            HashUtilityError::DatabaseParseError { .. } => "database_parse_error",
+            HashUtilityError::DatabaseWriteError { .. } => "database_write_error",
+            HashUtilityError::EmptyDatabase { .. } => "empty_database",
+            HashUtilityError::CorruptDatabase { .. } => "corrupt_database",
+            HashUtilityError::VerificationFailed { .. } => "verification_failed",
+            HashUtilityError::InvalidArguments { .. } => "invalid_arguments",
+            HashUtilityError::MissingRequiredArgument { .. } => "missing_required_argument",
+            HashUtilityError::BenchmarkFailed { .. } => "benchmark_failed",
+        }
+    }
+    
+    /// Stable machine code for the error, shown in brackets before every message
+    pub fn code(&self) -> &'static str {
+        match self {
+            HashUtilityError::FileNotFound { .. } => "E_NOT_FOUND",
+            HashUtilityError::DirectoryNotFound { .. } => "E_DIR_NOT_FOUND",
+            HashUtilityError::PermissionDenied { .. } => "E_PERMISSION_DENIED",
+            HashUtilityError::IoError { .. } => "E_IO",
+            HashUtilityError::UnsupportedAlgorithm { .. } => "E_UNSUPPORTED_ALGORITHM",
+            HashUtilityError::HashComputationFailed { .. } => "E_HASH_FAILED",
+            HashUtilityError::DatabaseNotFound { .. } => "E_DB_NOT_FOUND",
+            HashUtilityError::DatabaseParseError { .. } => "E_DB_PARSE",
+            HashUtilityError::DatabaseWriteError { .. } => "E_DB_WRITE",
+            HashUtilityError::EmptyDatabase { .. } => "E_DB_EMPTY",
+            HashUtilityError::CorruptDatabase { .. } => "E_DB_CORRUPT",
+            HashUtilityError::VerificationFailed { .. } => "E_VERIFY_FAILED",
+            HashUtilityError::InvalidArguments { .. } => "E_INVALID_ARGS",
+            HashUtilityError::MissingRequiredArgument { .. } => "E_MISSING_ARG",
+            HashUtilityError::BenchmarkFailed { .. } => "E_BENCHMARK_FAILED",
+        }
+    }
+    
+    /// The file or directory the error is about, if any
+    pub fn path(&self) -> Option<&Path> {
+        match self {
+            HashUtilityError::FileNotFound { path }
+            | HashUtilityError::DirectoryNotFound { path }
+            | HashUtilityError::PermissionDenied { path, .. }
+            | HashUtilityError::HashComputationFailed { path, .. }
+            | HashUtilityError::DatabaseNotFound { path }
+            | HashUtilityError::DatabaseParseError { path, .. }
+            | HashUtilityError::DatabaseWriteError { path, .. }
+            | HashUtilityError::EmptyDatabase { path }
+            | HashUtilityError::CorruptDatabase { path, .. } => Some(path),
+            HashUtilityError::IoError { path, .. } => path.as_deref(),
+            _ => None,
+        }
+    }
+    
+    /// Render as `{"error": {"kind": ..., "message": ..., "path": ...}}` on a single line
+    /// 
+    /// The message is the first line of the text form, without the code prefix or the suggestion.
+    pub fn to_json(&self) -> String {
+        let text = Description(self).to_string();
+        let message = text.lines().next().unwrap_or_default();
+        serde_json::json!({
+            "error": {
+                "kind": self.kind(),
+                "message": message,
+                "path": self.path(),
+            }
+        })
+        .to_string()
+    }
+}
+
 // Conversion from io::Error with context
 impl HashUtilityError {
     /// Create an IoError with context about the operation and optional path
@@ -354,4 +446,77 @@ mod tests {
         
         assert!(error.source().is_none());
     }
+
+    #[test]
+    fn test_file_not_found_json_error() {
+        let io_err = std::fs::File::open("no_such_file_for_json_error.txt").unwrap_err();
+        let error = HashUtilityError::from_io_error(
+            io_err,
+            "reading",
+            Some(PathBuf::from("no_such_file_for_json_error.txt")),
+        );
+        assert_eq!(error.kind(), "file_not_found");
+        
+        let json = error.to_json();
+        assert!(!json.contains('\n'));
+        let value: serde_json::Value = serde_json::from_str(&json).unwrap();
+        let obj = value["error"].as_object().unwrap();
+        assert_eq!(obj.len(), 3);
+        assert_eq!(obj["kind"], "file_not_found");
+        assert_eq!(obj["message"], "File not found: no_such_file_for_json_error.txt");
+        assert_eq!(obj["path"], "no_such_file_for_json_error.txt");
+    }
+
+    #[test]
+    fn test_json_error_without_path() {
+        let error = HashUtilityError::InvalidArguments {
+            message: "bad value".to_string(),
+        };
+        let value: serde_json::Value = serde_json::from_str(&error.to_json()).unwrap();
+        assert_eq!(value["error"]["kind"], "invalid_arguments");
+        assert_eq!(value["error"]["message"], "Invalid arguments: bad value");
+        assert!(value["error"]["path"].is_null());
+    }
+
+
+    #[test]
+    fn test_error_codes() {
+        let path = || PathBuf::from("x");
+        let cases = [
+            (HashUtilityError::FileNotFound { path: path() }, "E_NOT_FOUND"),
+            (HashUtilityError::DirectoryNotFound { path: path() }, "E_DIR_NOT_FOUND"),
+            (HashUtilityError::PermissionDenied { path: path(), operation: "reading".to_string() }, "E_PERMISSION_DENIED"),
+            (HashUtilityError::IoError { path: None, operation: "reading".to_string(), source: io::Error::new(io::ErrorKind::Other, "x") }, "E_IO"),
+            (HashUtilityError::UnsupportedAlgorithm { algorithm: "x".to_string() }, "E_UNSUPPORTED_ALGORITHM"),
+            (HashUtilityError::HashComputationFailed { path: path(), algorithm: "x".to_string(), reason: "x".to_string() }, "E_HASH_FAILED"),
+            (HashUtilityError::DatabaseNotFound { path: path() }, "E_DB_NOT_FOUND"),
+            (HashUtilityError::DatabaseParseError { path: path(), line: 1, reason: "x".to_string() }, "E_DB_PARSE"),
+            (HashUtilityError::DatabaseWriteError { path: path(), reason: "x".to_string() }, "E_DB_WRITE"),
+            (HashUtilityError::EmptyDatabase { path: path() }, "E_DB_EMPTY"),
+            (HashUtilityError::CorruptDatabase { path: path(), reason: "x".to_string() }, "E_DB_CORRUPT"),
+            (HashUtilityError::VerificationFailed { reason: "x".to_string() }, "E_VERIFY_FAILED"),
+            (HashUtilityError::InvalidArguments { message: "x".to_string() }, "E_INVALID_ARGS"),
+            (HashUtilityError::MissingRequiredArgument { argument: "x".to_string() }, "E_MISSING_ARG"),
+            (HashUtilityError::BenchmarkFailed { algorithm: "x".to_string(), reason: "x".to_string() }, "E_BENCHMARK_FAILED"),
+        ];
+        
+        for (error, code) in &cases {
+            assert_eq!(error.code(), *code);
+            assert!(error.to_string().starts_with(&format!("Error [{}]: ", code)));
+        }
+    }
+
+    #[test]
+    fn test_display_prefixes_code() {
+        let error = HashUtilityError::FileNotFound {
+            path: PathBuf::from("missing.txt"),
+        };
+        let message = format!("{}", error);
+        assert!(message.starts_with("Error [E_NOT_FOUND]: File not found: missing.txt\n"));
+        
+        // The JSON message carries the plain text only
+        let value: serde_json::Value = serde_json::from_str(&error.to_json()).unwrap();
+        assert_eq!(value["error"]["message"], "File not found: missing.txt");
+    }
+
 }
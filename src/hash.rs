@@ -5,8 +5,12 @@ use std::fs::File;
 use std::io::{Read, Seek};
 use std::path::{Path, PathBuf};
 use crate::error::HashUtilityError;
+use crate::path_utils::extended_length_path;
 use memmap2::Mmap;
 use std::io::IsTerminal;
+use std::sync::Arc;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::time::{Duration, Instant};
 
 /// Trait for hash algorithm implementations
 pub trait Hasher: Send {
@@ -18,6 +22,16 @@ pub trait Hasher: Send {
     
     /// Get the output size in bytes
     fn output_size(&self) -> usize;
+    
+    /// Snapshot the intermediate state so hashing can resume later, or None if the algorithm can't
+    fn serialize_state(&self) -> Option<Vec<u8>> {
+        None
+    }
+    
+    /// Continue from a snapshot taken by `serialize_state`, or None if it doesn't fit this hasher
+    fn restore_state(&mut self, _state: &[u8]) -> Option<()> {
+        None
+    }
 }
 
 /// Information about a hash algorithm
@@ -27,6 +41,50 @@ pub struct AlgorithmInfo {
     pub output_bits: usize,
     pub post_quantum: bool,
     pub cryptographic: bool,
+    /// Lowercase names accepted by `HashRegistry::get_hasher` for this algorithm
+    pub aliases: Vec<String>,
+}
+
+/// Known-answer test vector for a single algorithm
+pub struct TestVector {
+    /// Canonical algorithm name as reported by `list_algorithms`
+    pub algorithm: &'static str,
+    pub input: &'static [u8],
+    /// Expected digest as lowercase hex, exactly as the tool prints it
+    pub expected: &'static str,
+}
+
+/// Known-answer vectors used by the selftest command
+/// 
+/// Cryptographic algorithms use the standard "abc" vectors from their specifications.
+/// XXH3/XXH128 use the empty-input reference digests; note that the wrappers emit
+/// these in little-endian byte order, so the hex here is byte-reversed compared to
+/// the xxhsum reference output.
+pub const TEST_VECTORS: &[TestVector] = &[
+    TestVector { algorithm: "MD5", input: b"abc", expected: "900150983cd24fb0d6963f7d28e17f72" },
+    TestVector { algorithm: "SHA1", input: b"abc", expected: "a9993e364706816aba3e25717850c26c9cd0d89d" },
+    TestVector { algorithm: "SHA-224", input: b"abc", expected: "23097d223405d8228642a477bda255b32aadbce4bda0b3f7e36c9da7" },
+    TestVector { algorithm: "SHA-256", input: b"abc", expected: "ba7816bf8f01cfea414140de5dae2223b00361a396177a9cb410ff61f20015ad" },
+    TestVector { algorithm: "SHA-384", input: b"abc", expected: "cb00753f45a35e8bb5a03d699ac65007272c32ab0eded1631a8b605a43ff5bed8086072ba1e7cc2358baeca134c825a7" },
+    TestVector { algorithm: "SHA-512", input: b"abc", expected: "ddaf35a193617abacc417349ae20413112e6fa4e89a97ea20a9eeee64b55d39a2192992a274fc1a836ba3c23a3feebbd454d4423643ce80e2a9ac94fa54ca49f" },
+    TestVector { algorithm: "SHA3-224", input: b"abc", expected: "e642824c3f8cf24ad09234ee7d3c766fc9a3a5168d0c94ad73b46fdf" },
+    TestVector { algorithm: "SHA3-256", input: b"abc", expected: "3a985da74fe225b2045c172d6bd390bd855f086e3e9d525b46bfe24511431532" },
+    TestVector { algorithm: "SHA3-384", input: b"abc", expected: "ec01498288516fc926459f58e2c6ad8df9b473cb0fc08c2596da7cf0e49be4b298d88cea927ac7f539f1edf228376d25" },
+    TestVector { algorithm: "SHA3-512", input: b"abc", expected: "b751850b1a57168a5693cd924b6b096e08f621827444f70d884f5d0240d2712e10e116e9192af3c91a7ec57647e3934057340b4cf408d5a56592f8274eec53f0" },
+    TestVector { algorithm: "BLAKE2b-512", input: b"abc", expected: "ba80a53f981c4d0d6a2797b69f12f6e94c212f14685ac4b74b12bb6fdbffa2d17d87c5392aab792dc252d5de4533cc9518d38aa8dbf1925ab92386edd4009923" },
+    TestVector { algorithm: "BLAKE2s-256", input: b"abc", expected: "508c5e8c327c14e2e1a72ba34eeb452f37458b209ed63a294d999b4c86675982" },
+    TestVector { algorithm: "BLAKE3", input: b"abc", expected: "6437b3ac38465133ffb63b75273a8db548c558465d79db03fd359c6cd5bd9d85" },
+    TestVector { algorithm: "XXH3", input: b"", expected: "c294d3380580062d" },
+    TestVector { algorithm: "XXH128", input: b"", expected: "7f498d4624c30160d8984701d306aa99" },
+];
+
+/// Outcome of running one algorithm against its known-answer vector
+#[derive(Debug, Clone, serde::Serialize)]
+pub struct SelfTestResult {
+    pub algorithm: String,
+    pub passed: bool,
+    pub expected: String,
+    pub actual: String,
 }
 
 // Re-export HashUtilityError as HashError for backward compatibility
@@ -35,9 +93,12 @@ pub type HashError = HashUtilityError;
 // Wrapper types for hash algorithms
 use md5::{Md5, Digest as Md5Digest};
 use sha1::{Sha1, Digest as Sha1Digest};
-use sha2::{Sha224, Sha256, Sha384, Sha512, Digest as Sha2Digest};
+use sha2::{Sha224, Sha256, Sha384, Sha512, Digest as Sha2Digest, compress256, compress512};
+use sha2::digest::block_buffer::EagerBuffer;
+use sha2::digest::consts::{U64, U128};
 use sha3::{Sha3_224, Sha3_256, Sha3_384, Sha3_512, Digest as Sha3Digest};
-use blake2::{Blake2b512, Blake2s256, Digest as Blake2Digest};
+use blake2::{Blake2b512, Blake2bVarCore, Blake2s256, Blake2sVarCore, Digest as Blake2Digest};
+use blake2::digest::core_api::{Buffer, UpdateCore, VariableOutputCore};
 use blake3::Hasher as Blake3Hasher;
 
 // MD5 wrapper
@@ -142,6 +203,156 @@ impl Hasher for Sha512Wrapper {
     }
 }
 
+// SHA-224/SHA-256 on the raw compression function so its state can be saved (`--checkpoint` only)
+pub struct ResumableSha256 {
+    state: [u32; 8],
+    /// Whole blocks compressed so far
+    blocks: u64,
+    buffer: EagerBuffer<U64>,
+    output_size: usize,
+}
+
+impl ResumableSha256 {
+    pub fn sha224() -> Self {
+        Self::with_iv(SHA224_IV, 28)
+    }
+    
+    pub fn sha256() -> Self {
+        Self::with_iv(SHA256_IV, 32)
+    }
+    
+    fn with_iv(state: [u32; 8], output_size: usize) -> Self {
+        Self { state, blocks: 0, buffer: Default::default(), output_size }
+    }
+}
+
+impl Hasher for ResumableSha256 {
+    fn update(&mut self, data: &[u8]) {
+        let Self { state, blocks, buffer, .. } = self;
+        buffer.digest_blocks(data, |chunk| {
+            *blocks += chunk.len() as u64;
+            compress256(state, chunk);
+        });
+    }
+    
+    fn finalize(mut self: Box<Self>) -> Vec<u8> {
+        let bit_len = 8 * (self.blocks * 64 + self.buffer.get_pos() as u64);
+        let Self { state, buffer, .. } = &mut *self;
+        buffer.len64_padding_be(bit_len, |block| compress256(state, std::slice::from_ref(block)));
+        let mut out: Vec<u8> = self.state.iter().flat_map(|word| word.to_be_bytes()).collect();
+        out.truncate(self.output_size);
+        out
+    }
+    
+    fn output_size(&self) -> usize {
+        self.output_size
+    }
+    
+    fn serialize_state(&self) -> Option<Vec<u8>> {
+        let mut out = vec![self.output_size as u8];
+        out.extend(self.state.iter().flat_map(|word| word.to_be_bytes()));
+        out.extend(self.blocks.to_be_bytes());
+        out.extend(self.buffer.get_data());
+        Some(out)
+    }
+    
+    fn restore_state(&mut self, saved: &[u8]) -> Option<()> {
+        // output size, 8 state words, block count, then the unprocessed tail of a block
+        if saved.len() < 41 || saved[0] as usize != self.output_size {
+            return None;
+        }
+        for (word, bytes) in self.state.iter_mut().zip(saved[1..33].chunks_exact(4)) {
+            *word = u32::from_be_bytes(bytes.try_into().ok()?);
+        }
+        self.blocks = u64::from_be_bytes(saved[33..41].try_into().ok()?);
+        self.buffer = EagerBuffer::try_new(&saved[41..]).ok()?;
+        Some(())
+    }
+}
+
+// SHA-384/SHA-512 on the raw compression function so its state can be saved (`--checkpoint` only)
+pub struct ResumableSha512 {
+    state: [u64; 8],
+    /// Whole blocks compressed so far
+    blocks: u128,
+    buffer: EagerBuffer<U128>,
+    output_size: usize,
+}
+
+impl ResumableSha512 {
+    pub fn sha384() -> Self {
+        Self::with_iv(SHA384_IV, 48)
+    }
+    
+    pub fn sha512() -> Self {
+        Self::with_iv(SHA512_IV, 64)
+    }
+    
+    fn with_iv(state: [u64; 8], output_size: usize) -> Self {
+        Self { state, blocks: 0, buffer: Default::default(), output_size }
+    }
+}
+
+impl Hasher for ResumableSha512 {
+    fn update(&mut self, data: &[u8]) {
+        let Self { state, blocks, buffer, .. } = self;
+        buffer.digest_blocks(data, |chunk| {
+            *blocks += chunk.len() as u128;
+            compress512(state, chunk);
+        });
+    }
+    
+    fn finalize(mut self: Box<Self>) -> Vec<u8> {
+        let bit_len = 8 * (self.blocks * 128 + self.buffer.get_pos() as u128);
+        let Self { state, buffer, .. } = &mut *self;
+        buffer.len128_padding_be(bit_len, |block| compress512(state, std::slice::from_ref(block)));
+        let mut out: Vec<u8> = self.state.iter().flat_map(|word| word.to_be_bytes()).collect();
+        out.truncate(self.output_size);
+        out
+    }
+    
+    fn output_size(&self) -> usize {
+        self.output_size
+    }
+    
+    fn serialize_state(&self) -> Option<Vec<u8>> {
+        let mut out = vec![self.output_size as u8];
+        out.extend(self.state.iter().flat_map(|word| word.to_be_bytes()));
+        out.extend(self.blocks.to_be_bytes());
+        out.extend(self.buffer.get_data());
+        Some(out)
+    }
+    
+    fn restore_state(&mut self, saved: &[u8]) -> Option<()> {
+        // output size, 8 state words, block count, then the unprocessed tail of a block
+        if saved.len() < 81 || saved[0] as usize != self.output_size {
+            return None;
+        }
+        for (word, bytes) in self.state.iter_mut().zip(saved[1..65].chunks_exact(8)) {
+            *word = u64::from_be_bytes(bytes.try_into().ok()?);
+        }
+        self.blocks = u128::from_be_bytes(saved[65..81].try_into().ok()?);
+        self.buffer = EagerBuffer::try_new(&saved[81..]).ok()?;
+        Some(())
+    }
+}
+
+// Initial hash values from FIPS 180-4
+const SHA224_IV: [u32; 8] = [
+    0xc1059ed8, 0x367cd507, 0x3070dd17, 0xf70e5939, 0xffc00b31, 0x68581511, 0x64f98fa7, 0xbefa4fa4,
+];
+const SHA256_IV: [u32; 8] = [
+    0x6a09e667, 0xbb67ae85, 0x3c6ef372, 0xa54ff53a, 0x510e527f, 0x9b05688c, 0x1f83d9ab, 0x5be0cd19,
+];
+const SHA384_IV: [u64; 8] = [
+    0xcbbb9d5dc1059ed8, 0x629a292a367cd507, 0x9159015a3070dd17, 0x152fecd8f70e5939,
+    0x67332667ffc00b31, 0x8eb44a8768581511, 0xdb0c2e0d64f98fa7, 0x47b5481dbefa4fa4,
+];
+const SHA512_IV: [u64; 8] = [
+    0x6a09e667f3bcc908, 0xbb67ae8584caa73b, 0x3c6ef372fe94f82b, 0xa54ff53a5f1d36f1,
+    0x510e527fade682d1, 0x9b05688c2b3e6c1f, 0x1f83d9abfb41bd6b, 0x5be0cd19137e2179,
+];
+
 // SHA3-224 wrapper
 pub struct Sha3_224Wrapper(Sha3_224);
 
@@ -244,6 +455,75 @@ impl Hasher for Blake2s256Wrapper {
     }
 }
 
+// Keyed and variable-length BLAKE2 wrapper (`blake2b:256`, `--blake2-key`)
+// 
+// Drives the BLAKE2 cores directly, because the crate's digest and MAC types fix
+// the output length at compile time. Output matches `b2sum -l <bits>`.
+pub struct Blake2VarWrapper {
+    state: Blake2VarState,
+    output_size: usize,
+}
+
+enum Blake2VarState {
+    B(Blake2bVarCore, Buffer<Blake2bVarCore>),
+    S(Blake2sVarCore, Buffer<Blake2sVarCore>),
+}
+
+impl Blake2VarWrapper {
+    /// BLAKE2b with an output of `output_size` bytes (1-64) and an optional key (up to 64 bytes)
+    pub fn blake2b(output_size: usize, key: &[u8]) -> Self {
+        let core = Blake2bVarCore::new_with_params(&[], &[], key.len(), output_size);
+        Self::keyed(Blake2VarState::B(core, Buffer::<Blake2bVarCore>::default()), output_size, key, 128)
+    }
+    
+    /// BLAKE2s with an output of `output_size` bytes (1-32) and an optional key (up to 32 bytes)
+    pub fn blake2s(output_size: usize, key: &[u8]) -> Self {
+        let core = Blake2sVarCore::new_with_params(&[], &[], key.len(), output_size);
+        Self::keyed(Blake2VarState::S(core, Buffer::<Blake2sVarCore>::default()), output_size, key, 64)
+    }
+    
+    /// A key is hashed as a zero-padded first block; the core's parameters already record its length
+    fn keyed(state: Blake2VarState, output_size: usize, key: &[u8], block_size: usize) -> Self {
+        let mut wrapper = Self { state, output_size };
+        if !key.is_empty() {
+            let mut padded_key = vec![0u8; block_size];
+            padded_key[..key.len()].copy_from_slice(key);
+            wrapper.update(&padded_key);
+        }
+        wrapper
+    }
+}
+
+impl Hasher for Blake2VarWrapper {
+    fn update(&mut self, data: &[u8]) {
+        // The buffers are lazy, so the last block is held back until finalization
+        match &mut self.state {
+            Blake2VarState::B(core, buffer) => buffer.digest_blocks(data, |blocks| core.update_blocks(blocks)),
+            Blake2VarState::S(core, buffer) => buffer.digest_blocks(data, |blocks| core.update_blocks(blocks)),
+        }
+    }
+    
+    fn finalize(self: Box<Self>) -> Vec<u8> {
+        let Self { state, output_size } = *self;
+        match state {
+            Blake2VarState::B(mut core, mut buffer) => {
+                let mut full_output = Default::default();
+                core.finalize_variable_core(&mut buffer, &mut full_output);
+                full_output[..output_size].to_vec()
+            }
+            Blake2VarState::S(mut core, mut buffer) => {
+                let mut full_output = Default::default();
+                core.finalize_variable_core(&mut buffer, &mut full_output);
+                full_output[..output_size].to_vec()
+            }
+        }
+    }
+    
+    fn output_size(&self) -> usize {
+        self.output_size
+    }
+}
+
 // BLAKE3 wrapper
 // 
 // When the rayon feature is enabled for blake3, this wrapper automatically uses
@@ -320,6 +600,11 @@ impl HashRegistry {
     pub fn get_hasher(algorithm: &str) -> Result<Box<dyn Hasher>, HashError> {
         let alg_lower = algorithm.to_lowercase();
         
+        // Variable-length BLAKE2, e.g. blake2b:256
+        if alg_lower.contains(':') {
+            return Self::get_blake2_hasher(algorithm, &[]);
+        }
+        
         match alg_lower.as_str() {
             "md5" => Ok(Box::new(Md5Wrapper(Md5Digest::new()))),
             "sha1" => Ok(Box::new(Sha1Wrapper(Sha1Digest::new()))),
@@ -342,6 +627,105 @@ impl HashRegistry {
         }
     }
     
+    /// Get a hasher whose state can be saved and restored (`--checkpoint`)
+    /// 
+    /// Only the SHA-2 family is supported. These hashers do their own padding over
+    /// the raw compression function, so normal hashing uses `get_hasher` instead.
+    pub fn get_resumable_hasher(algorithm: &str) -> Result<Box<dyn Hasher>, HashError> {
+        match algorithm.to_lowercase().as_str() {
+            "sha224" | "sha-224" => Ok(Box::new(ResumableSha256::sha224())),
+            "sha256" | "sha-256" => Ok(Box::new(ResumableSha256::sha256())),
+            "sha384" | "sha-384" => Ok(Box::new(ResumableSha512::sha384())),
+            "sha512" | "sha-512" => Ok(Box::new(ResumableSha512::sha512())),
+            _ => Err(HashUtilityError::InvalidArguments {
+                message: format!("Algorithm '{}' can't save its state; --checkpoint supports sha224, sha256, sha384 and sha512", algorithm),
+            }),
+        }
+    }
+    
+    /// Get a BLAKE2 hasher with an optional key, for `blake2b`/`blake2s` with an optional `:bits` output length
+    /// 
+    /// Without a length suffix the full output is used (512 bits for BLAKE2b, 256 for BLAKE2s).
+    pub fn get_blake2_hasher(algorithm: &str, key: &[u8]) -> Result<Box<dyn Hasher>, HashError> {
+        let alg_lower = algorithm.to_lowercase();
+        let (name, bits) = match alg_lower.split_once(':') {
+            Some((name, bits)) => (name, Some(bits)),
+            None => (alg_lower.as_str(), None),
+        };
+        
+        let max_bytes = match name {
+            "blake2b" | "blake2b-512" => 64,
+            "blake2s" | "blake2s-256" => 32,
+            _ => {
+                return Err(HashUtilityError::UnsupportedAlgorithm {
+                    algorithm: algorithm.to_string(),
+                });
+            }
+        };
+        
+        let output_size = match bits {
+            None => max_bytes,
+            Some(bits) => match bits.parse::<usize>() {
+                Ok(bits) if bits > 0 && bits % 8 == 0 && bits / 8 <= max_bytes => bits / 8,
+                _ => {
+                    return Err(HashUtilityError::InvalidArguments {
+                        message: format!(
+                            "Invalid output length in '{}': expected a multiple of 8 bits up to {}",
+                            algorithm,
+                            max_bytes * 8
+                        ),
+                    });
+                }
+            },
+        };
+        
+        if key.len() > max_bytes {
+            return Err(HashUtilityError::InvalidArguments {
+                message: format!("BLAKE2 key for '{}' is {} bytes; at most {} are allowed", algorithm, key.len(), max_bytes),
+            });
+        }
+        
+        if max_bytes == 64 {
+            Ok(Box::new(Blake2VarWrapper::blake2b(output_size, key)))
+        } else {
+            Ok(Box::new(Blake2VarWrapper::blake2s(output_size, key)))
+        }
+    }
+    
+    /// Canonical spelling of an algorithm name, e.g. `SHA-256` -> `sha256`, `BLAKE2b-512:256` -> `blake2b:256`
+    /// 
+    /// Unknown names are returned unchanged, so errors still show what was written.
+    pub fn canonical_name(algorithm: &str) -> String {
+        let alg_lower = algorithm.to_lowercase();
+        let (name, bits) = match alg_lower.split_once(':') {
+            Some((name, bits)) => (name, Some(bits)),
+            None => (alg_lower.as_str(), None),
+        };
+        
+        let canonical = match name {
+            "md5" | "sha1" | "sha224" | "sha256" | "sha384" | "sha512"
+            | "sha3-224" | "sha3-256" | "sha3-384" | "sha3-512"
+            | "blake2b" | "blake2s" | "blake3" | "xxh3" | "xxh128" => name,
+            "sha-224" => "sha224",
+            "sha-256" => "sha256",
+            "sha-384" => "sha384",
+            "sha-512" => "sha512",
+            "blake2b-512" => "blake2b",
+            "blake2s-256" => "blake2s",
+            _ => return algorithm.to_string(),
+        };
+        
+        match bits {
+            Some(bits) => format!("{}:{}", canonical, bits),
+            None => canonical.to_string(),
+        }
+    }
+    
+    /// Length of a hex-encoded hash from this algorithm, or None if the algorithm is unknown
+    pub fn expected_hex_len(algorithm: &str) -> Option<usize> {
+        Self::get_hasher(algorithm).ok().map(|hasher| hasher.output_size() * 2)
+    }
+    
     /// List all available hash algorithms
     pub fn list_algorithms() -> Vec<AlgorithmInfo> {
         vec![
@@ -350,94 +734,121 @@ impl HashRegistry {
                 output_bits: 128,
                 post_quantum: false,
                 cryptographic: true,
+                aliases: vec!["md5".to_string()],
             },
             AlgorithmInfo {
                 name: "SHA1".to_string(),
                 output_bits: 160,
                 post_quantum: false,
                 cryptographic: true,
+                aliases: vec!["sha1".to_string()],
             },
             AlgorithmInfo {
                 name: "SHA-224".to_string(),
                 output_bits: 224,
                 post_quantum: false,
                 cryptographic: true,
+                aliases: vec!["sha224".to_string(), "sha-224".to_string()],
             },
             AlgorithmInfo {
                 name: "SHA-256".to_string(),
                 output_bits: 256,
                 post_quantum: false,
                 cryptographic: true,
+                aliases: vec!["sha256".to_string(), "sha-256".to_string()],
             },
             AlgorithmInfo {
                 name: "SHA-384".to_string(),
                 output_bits: 384,
                 post_quantum: false,
                 cryptographic: true,
+                aliases: vec!["sha384".to_string(), "sha-384".to_string()],
             },
             AlgorithmInfo {
                 name: "SHA-512".to_string(),
                 output_bits: 512,
                 post_quantum: false,
                 cryptographic: true,
+                aliases: vec!["sha512".to_string(), "sha-512".to_string()],
             },
             AlgorithmInfo {
                 name: "SHA3-224".to_string(),
                 output_bits: 224,
                 post_quantum: true,
                 cryptographic: true,
+                aliases: vec!["sha3-224".to_string()],
             },
             AlgorithmInfo {
                 name: "SHA3-256".to_string(),
                 output_bits: 256,
                 post_quantum: true,
                 cryptographic: true,
+                aliases: vec!["sha3-256".to_string()],
             },
             AlgorithmInfo {
                 name: "SHA3-384".to_string(),
                 output_bits: 384,
                 post_quantum: true,
                 cryptographic: true,
+                aliases: vec!["sha3-384".to_string()],
             },
             AlgorithmInfo {
                 name: "SHA3-512".to_string(),
                 output_bits: 512,
                 post_quantum: true,
                 cryptographic: true,
+                aliases: vec!["sha3-512".to_string()],
             },
             AlgorithmInfo {
                 name: "BLAKE2b-512".to_string(),
                 output_bits: 512,
                 post_quantum: false,
                 cryptographic: true,
+                aliases: vec!["blake2b".to_string(), "blake2b-512".to_string()],
             },
             AlgorithmInfo {
                 name: "BLAKE2s-256".to_string(),
                 output_bits: 256,
                 post_quantum: false,
                 cryptographic: true,
+                aliases: vec!["blake2s".to_string(), "blake2s-256".to_string()],
             },
             AlgorithmInfo {
                 name: "BLAKE3".to_string(),
                 output_bits: 256,
                 post_quantum: false,
                 cryptographic: true,
+                aliases: vec!["blake3".to_string()],
             },
             AlgorithmInfo {
                 name: "XXH3".to_string(),
                 output_bits: 64,
                 post_quantum: false,
                 cryptographic: false,
+                aliases: vec!["xxh3".to_string()],
             },
             AlgorithmInfo {
                 name: "XXH128".to_string(),
                 output_bits: 128,
                 post_quantum: false,
                 cryptographic: false,
+                aliases: vec!["xxh128".to_string()],
             },
         ]
     }
     
+    /// List available hash algorithms, optionally restricted by their properties
+    /// 
+    /// Filters compose: with both flags set, only algorithms that are
+    /// cryptographic AND post-quantum resistant are returned.
+    pub fn filter_algorithms(crypto_only: bool, pq_only: bool) -> Vec<AlgorithmInfo> {
+        Self::list_algorithms()
+            .into_iter()
+            .filter(|algo| !crypto_only || algo.cryptographic)
+            .filter(|algo| !pq_only || algo.post_quantum)
+            .collect()
+    }
+    
     /// Check if an algorithm is post-quantum resistant
     pub fn is_post_quantum(algorithm: &str) -> bool {
         let alg_lower = algorithm.to_lowercase();
@@ -447,6 +858,40 @@ impl HashRegistry {
         alg_lower == "shake128" || 
         alg_lower == "shake256"
     }
+    
+    /// Run every registered algorithm against its known-answer test vector
+    /// 
+    /// An algorithm without an entry in `TEST_VECTORS` is reported as a failure
+    /// so that newly added algorithms can't silently skip the self-test.
+    pub fn run_self_test() -> Vec<SelfTestResult> {
+        Self::list_algorithms()
+            .into_iter()
+            .map(|algo| {
+                let vector = TEST_VECTORS.iter().find(|v| v.algorithm == algo.name);
+                
+                let (expected, actual) = match vector {
+                    Some(vector) => {
+                        let actual = match Self::get_hasher(&algo.name) {
+                            Ok(mut hasher) => {
+                                hasher.update(vector.input);
+                                bytes_to_hex(&hasher.finalize())
+                            }
+                            Err(_) => "unsupported".to_string(),
+                        };
+                        (vector.expected.to_string(), actual)
+                    }
+                    None => ("no test vector".to_string(), String::new()),
+                };
+                
+                SelfTestResult {
+                    algorithm: algo.name,
+                    passed: expected == actual,
+                    expected,
+                    actual,
+                }
+            })
+            .collect()
+    }
 }
 
 /// Result of a hash computation
@@ -457,11 +902,102 @@ pub struct HashResult {
     pub file_path: PathBuf,
 }
 
+/// Saved progress of a checkpointed hash (`--checkpoint`)
+#[derive(Debug, serde::Serialize, serde::Deserialize)]
+struct HashCheckpoint {
+    algorithm: String,
+    /// Size of the file when hashing started, to detect a different or changed file
+    file_size: u64,
+    /// Modification time of the file when hashing started (RFC 3339), if the filesystem has one
+    file_modified: Option<String>,
+    /// Bytes already fed to the hasher
+    offset: u64,
+    /// Hex-encoded hasher state from `Hasher::serialize_state`
+    state: String,
+}
+
+impl HashCheckpoint {
+    fn read(path: &Path) -> Result<Self, HashError> {
+        let content = std::fs::read_to_string(path)
+            .map_err(|e| HashUtilityError::from_io_error(e, "reading checkpoint", Some(path.to_path_buf())))?;
+        serde_json::from_str(&content).map_err(|e| HashUtilityError::InvalidArguments {
+            message: format!("Checkpoint '{}' is damaged: {}", path.display(), e),
+        })
+    }
+    
+    /// Write through a temporary file so an interruption never leaves a half-written checkpoint
+    fn write(&self, path: &Path) -> Result<(), HashError> {
+        let mut temp = path.as_os_str().to_owned();
+        temp.push(".tmp");
+        let temp = PathBuf::from(temp);
+        let content = serde_json::to_string(self).map_err(|e| HashUtilityError::InvalidArguments {
+            message: format!("Failed to serialize checkpoint: {}", e),
+        })?;
+        std::fs::write(&temp, content)
+            .and_then(|_| std::fs::rename(&temp, path))
+            .map_err(|e| HashUtilityError::from_io_error(e, "writing checkpoint", Some(path.to_path_buf())))
+    }
+}
+
+/// Time spent reading files versus updating hashers, summed over all threads (`--profile`)
+#[derive(Debug, Default)]
+pub struct HashProfile {
+    read_nanos: AtomicU64,
+    hash_nanos: AtomicU64,
+    bytes: AtomicU64,
+}
+
+impl HashProfile {
+    pub fn new() -> Self {
+        Self::default()
+    }
+    
+    /// Total time spent in `read` calls
+    pub fn read_time(&self) -> Duration {
+        Duration::from_nanos(self.read_nanos.load(Ordering::Relaxed))
+    }
+    
+    /// Total time spent in `hasher.update` calls
+    pub fn hash_time(&self) -> Duration {
+        Duration::from_nanos(self.hash_nanos.load(Ordering::Relaxed))
+    }
+    
+    /// Total bytes read
+    pub fn bytes(&self) -> u64 {
+        self.bytes.load(Ordering::Relaxed)
+    }
+    
+    fn add_read(&self, elapsed: Duration, bytes: usize) {
+        self.read_nanos.fetch_add(elapsed.as_nanos() as u64, Ordering::Relaxed);
+        self.bytes.fetch_add(bytes as u64, Ordering::Relaxed);
+    }
+    
+    fn add_hash(&self, elapsed: Duration) {
+        self.hash_nanos.fetch_add(elapsed.as_nanos() as u64, Ordering::Relaxed);
+    }
+}
+
 /// Hash computer with streaming I/O
 pub struct HashComputer {
+    /// Buffer size used when the input size is unknown (stdin) or when adaptive sizing is off
     buffer_size: usize,
+    /// Choose the read buffer size from each file's size instead of using `buffer_size`
+    adaptive: bool,
+    /// Read files with O_DIRECT to bypass the page cache (falls back to normal reads)
+    direct_io: bool,
+    /// Advise the kernel that files will be read sequentially (used in HDD mode)
+    sequential_hint: bool,
+    /// Fold extended attribute names and values into file hashes
+    include_xattrs: bool,
+    /// Accumulate read and hashing time here (disables memory mapping and direct I/O)
+    profile: Option<Arc<HashProfile>>,
+    /// Key for keyed BLAKE2 hashing (`--blake2-key`)
+    blake2_key: Option<Vec<u8>>,
 }
 
+/// Algorithm tag of entries that record where a symlink points (`scan --hash-symlink-targets`)
+pub const SYMLINK_ALGORITHM: &str = "symlink";
+
 // Constants for fast mode sampling
 const FAST_MODE_SAMPLE_SIZE: u64 = 100 * 1024 * 1024; // 100MB
 const FAST_MODE_THRESHOLD: u64 = 3 * FAST_MODE_SAMPLE_SIZE; // 300MB
@@ -473,17 +1009,144 @@ const MMAP_THRESHOLD: u64 = 2 * 1024 * 1024 * 1024; // 2GB
 const PROGRESS_BAR_THRESHOLD: u64 = 1024 * 1024 * 1024; // 1GB
 const PROGRESS_UPDATE_INTERVAL_MS: u64 = 100; // 10 times per second
 
+// Constants for concurrent multi-algorithm hashing
+const CONCURRENT_HASH_THRESHOLD: u64 = 64 * 1024 * 1024; // 64MB
+const CONCURRENT_HASH_CHANNEL_DEPTH: usize = 4; // chunks buffered per worker
+
+// Alignment of buffers, offsets and read sizes required by O_DIRECT
+const DIRECT_IO_ALIGNMENT: usize = 4096;
+
+// Constants for read buffer sizing
+const DEFAULT_BUFFER_SIZE: usize = 1024 * 1024; // 1MB
+
+/// Choose a read buffer size appropriate for a file of the given size
+/// 
+/// Small files get small buffers so we don't allocate far more than we read,
+/// while multi-GB files get larger buffers to cut down on read syscalls.
+pub fn adaptive_buffer_size(file_size: u64) -> usize {
+    const KB: u64 = 1024;
+    const MB: u64 = 1024 * KB;
+    const GB: u64 = 1024 * MB;
+    
+    if file_size < 64 * KB {
+        16 * 1024 // 16KB
+    } else if file_size < MB {
+        64 * 1024 // 64KB
+    } else if file_size < 64 * MB {
+        256 * 1024 // 256KB
+    } else if file_size < GB {
+        DEFAULT_BUFFER_SIZE // 1MB
+    } else {
+        4 * 1024 * 1024 // 4MB
+    }
+}
+
 impl HashComputer {
-    /// Create a new HashComputer with default buffer size (1MB)
+    /// Create a new HashComputer with adaptive buffer sizing (1MB for stdin)
     pub fn new() -> Self {
         Self {
-            buffer_size: 1024 * 1024,
+            buffer_size: DEFAULT_BUFFER_SIZE,
+            adaptive: true,
+            direct_io: false,
+            sequential_hint: false,
+            include_xattrs: false,
+            profile: None,
+            blake2_key: None,
         }
     }
     
-    /// Create a new HashComputer with custom buffer size
+    /// Create a new HashComputer with a fixed custom buffer size
     pub fn with_buffer_size(buffer_size: usize) -> Self {
-        Self { buffer_size }
+        Self {
+            buffer_size,
+            adaptive: false,
+            direct_io: false,
+            sequential_hint: false,
+            include_xattrs: false,
+            profile: None,
+            blake2_key: None,
+        }
+    }
+    
+    /// Enable or disable direct (page-cache bypassing) I/O for file reads
+    pub fn with_direct_io(mut self, direct_io: bool) -> Self {
+        self.direct_io = direct_io;
+        self
+    }
+    
+    /// Enable or disable the sequential-access hint issued after opening each file
+    pub fn with_sequential_hint(mut self, sequential_hint: bool) -> Self {
+        self.sequential_hint = sequential_hint;
+        self
+    }
+    
+    /// Include extended attributes in file hashes
+    /// 
+    /// Hashes of files that carry extended attributes are then no longer plain
+    /// content hashes and won't match tools (or platforms) that ignore them.
+    pub fn with_xattrs(mut self, include_xattrs: bool) -> Self {
+        self.include_xattrs = include_xattrs;
+        self
+    }
+    
+    /// Time file reads and hasher updates into a shared profile
+    /// 
+    /// Files are then always read through a buffer, since reads hidden behind
+    /// memory mapping or O_DIRECT can't be timed separately from hashing.
+    pub fn with_profile(mut self, profile: Option<Arc<HashProfile>>) -> Self {
+        self.profile = profile;
+        self
+    }
+    
+    /// Hash with keyed BLAKE2 (only `blake2b` and `blake2s` accept a key)
+    pub fn with_blake2_key(mut self, key: Option<Vec<u8>>) -> Self {
+        self.blake2_key = key;
+        self
+    }
+    
+    /// Get a hasher for `algorithm`, keyed if a BLAKE2 key is set
+    fn hasher(&self, algorithm: &str) -> Result<Box<dyn Hasher>, HashError> {
+        match &self.blake2_key {
+            Some(key) if algorithm.to_lowercase().starts_with("blake2") => HashRegistry::get_blake2_hasher(algorithm, key),
+            Some(_) => Err(HashUtilityError::InvalidArguments {
+                message: format!("A BLAKE2 key can't be used with {}; use blake2b or blake2s", algorithm),
+            }),
+            None => HashRegistry::get_hasher(algorithm),
+        }
+    }
+    
+    /// Read into `buffer`, timing the call when profiling
+    fn timed_read(&self, file: &mut impl Read, buffer: &mut [u8]) -> std::io::Result<usize> {
+        match &self.profile {
+            Some(profile) => {
+                let start = Instant::now();
+                let bytes_read = file.read(buffer)?;
+                profile.add_read(start.elapsed(), bytes_read);
+                Ok(bytes_read)
+            }
+            None => file.read(buffer),
+        }
+    }
+    
+    /// Feed `data` to `update`, timing the call when profiling
+    fn timed_update(&self, data: &[u8], update: &mut dyn FnMut(&[u8])) {
+        match &self.profile {
+            Some(profile) => {
+                let start = Instant::now();
+                update(data);
+                profile.add_hash(start.elapsed());
+            }
+            None => update(data),
+        }
+    }
+    
+    /// Get the read buffer size to use for a file of the given size
+    fn buffer_size_for(&self, file_size: u64) -> usize {
+        if self.adaptive {
+            adaptive_buffer_size(file_size)
+        } else {
+            self.buffer_size
+        }
     }
     
     /// Compute hash from text string
@@ -493,7 +1156,7 @@ impl HashComputer {
         algorithm: &str,
     ) -> Result<HashResult, HashError> {
         // Get hasher for the specified algorithm
-        let mut hasher = HashRegistry::get_hasher(algorithm)?;
+        let mut hasher = self.hasher(algorithm)?;
         
         // Hash the UTF-8 bytes of the text
         hasher.update(text.as_bytes());
@@ -518,7 +1181,7 @@ impl HashComputer {
         // Get hashers for all specified algorithms
         let mut hashers: Vec<(String, Box<dyn Hasher>)> = Vec::new();
         for algorithm in algorithms {
-            let hasher = HashRegistry::get_hasher(algorithm)?;
+            let hasher = self.hasher(algorithm)?;
             hashers.push((algorithm.clone(), hasher));
         }
         
@@ -549,21 +1212,33 @@ impl HashComputer {
         &self,
         algorithm: &str,
     ) -> Result<HashResult, HashError> {
-        use std::io::{stdin, Read};
+        let hash_hex = self.compute_hash_reader(std::io::stdin(), algorithm)?;
         
+        Ok(HashResult {
+            algorithm: algorithm.to_string(),
+            hash: hash_hex,
+            file_path: PathBuf::from("-"), // Use "-" to indicate stdin
+        })
+    }
+    
+    /// Compute hash of any reader using streaming I/O, returning the hex digest
+    /// 
+    /// Data is always read through a buffer of the configured size (never memory mapped).
+    pub fn compute_hash_reader<R: Read>(
+        &self,
+        mut reader: R,
+        algorithm: &str,
+    ) -> Result<String, HashError> {
         // Get hasher for the specified algorithm
-        let mut hasher = HashRegistry::get_hasher(algorithm)?;
-        
-        // Get stdin handle
-        let mut stdin = stdin();
+        let mut hasher = self.hasher(algorithm)?;
         
         // Create buffer for streaming reads
         let mut buffer = vec![0u8; self.buffer_size];
         
-        // Stream stdin data through hasher
+        // Stream data through hasher
         loop {
-            let bytes_read = stdin.read(&mut buffer).map_err(|e| {
-                HashUtilityError::from_io_error(e, "reading from stdin", None)
+            let bytes_read = reader.read(&mut buffer).map_err(|e| {
+                HashUtilityError::from_io_error(e, "reading input", None)
             })?;
             if bytes_read == 0 {
                 break;
@@ -573,19 +1248,13 @@ impl HashComputer {
         
         // Finalize hash and convert to hex
         let hash_bytes = hasher.finalize();
-        let hash_hex = bytes_to_hex(&hash_bytes);
-        
-        Ok(HashResult {
-            algorithm: algorithm.to_string(),
-            hash: hash_hex,
-            file_path: PathBuf::from("-"), // Use "-" to indicate stdin
-        })
+        Ok(bytes_to_hex(&hash_bytes))
     }
     
     /// Compute hash for a single file using streaming I/O or memory mapping
     /// 
     /// For files smaller than 2GB, uses memory mapping to avoid kernel-to-userspace copy overhead.
-    /// For files larger than 2GB, falls back to buffered reading with 1MB buffer.
+    /// For files larger than 2GB, falls back to buffered reading sized by `adaptive_buffer_size`.
     /// 
     /// # Safety
     /// 
@@ -611,26 +1280,47 @@ impl HashComputer {
         show_progress: bool,
     ) -> Result<HashResult, HashError> {
         // Get hasher for the specified algorithm
-        let mut hasher = HashRegistry::get_hasher(algorithm)?;
+        let mut hasher = self.hasher(algorithm)?;
         
         // Open file for reading with better error context
-        let file = File::open(path).map_err(|e| {
+        let file = File::open(extended_length_path(path)).map_err(|e| {
             HashUtilityError::from_io_error(e, "reading", Some(path.to_path_buf()))
         })?;
         
+        // Let the kernel read ahead aggressively when hashing from spinning disks
+        if self.sequential_hint {
+            advise_sequential(&file);
+        }
+        
         // Get file size to determine whether to use memory mapping
-        let file_size = file.metadata()
-            .map_err(|e| HashUtilityError::from_io_error(e, "reading metadata", Some(path.to_path_buf())))?
-            .len();
+        let metadata = file.metadata()
+            .map_err(|e| HashUtilityError::from_io_error(e, "reading metadata", Some(path.to_path_buf())))?;
+        
+        // Block devices and other special files report a size of zero, so stream them to EOF
+        if !metadata.is_file() {
+            return Ok(HashResult {
+                algorithm: algorithm.to_string(),
+                hash: self.compute_hash_reader(file, algorithm)?,
+                file_path: path.to_path_buf(),
+            });
+        }
+        let file_size = metadata.len();
         
         // Determine if we should show progress bar
         let should_show_progress = show_progress 
             && file_size > PROGRESS_BAR_THRESHOLD 
             && std::io::stdout().is_terminal();
         
-        // Use memory mapping for files smaller than 2GB
-        if file_size > 0 && file_size < MMAP_THRESHOLD {
-            // Try to memory map the file
+        // Try direct I/O first if requested; it leaves the hasher untouched if unavailable
+        let hashed_direct = self.direct_io
+            && self.profile.is_none()
+            && file_size > 0
+            && self.try_hash_with_direct_io(path, file_size, should_show_progress, &mut |chunk| hasher.update(chunk))?;
+        
+        if hashed_direct {
+            // File contents were already fed to the hasher
+        } else if file_size > 0 && file_size < MMAP_THRESHOLD && self.profile.is_none() {
+            // Use memory mapping for files smaller than 2GB
             match unsafe { Mmap::map(&file) } {
                 Ok(mmap) => {
                     // Hash the entire mapped file in one go
@@ -642,7 +1332,7 @@ impl HashComputer {
                     if should_show_progress {
                         self.hash_with_buffered_io_progress(&mut hasher, file, path, file_size)?;
                     } else {
-                        self.hash_with_buffered_io(&mut hasher, file, path)?;
+                        self.hash_with_buffered_io(&mut hasher, file, path, file_size)?;
                     }
                 }
             }
@@ -651,10 +1341,15 @@ impl HashComputer {
             if should_show_progress {
                 self.hash_with_buffered_io_progress(&mut hasher, file, path, file_size)?;
             } else {
-                self.hash_with_buffered_io(&mut hasher, file, path)?;
+                self.hash_with_buffered_io(&mut hasher, file, path, file_size)?;
             }
         }
         
+        // Fold extended attributes into the hash after the content, if requested
+        if self.include_xattrs {
+            fold_xattrs(path, &mut |chunk| hasher.update(chunk))?;
+        }
+        
         // Finalize hash and convert to hex
         let hash_bytes = hasher.finalize();
         let hash_hex = bytes_to_hex(&hash_bytes);
@@ -666,15 +1361,83 @@ impl HashComputer {
         })
     }
     
-    /// Helper method to hash a file using buffered I/O
-    fn hash_with_buffered_io(
+    /// Feed several files through one hasher in the given order, returning the hex digest
+    /// 
+    /// Each entry is hashed as its name, a NUL byte, then the file's contents,
+    /// so the digest changes if any name, the order or any file's bytes change.
+    pub fn compute_combined_hash(
+        &self,
+        files: &[(String, PathBuf)],
+        algorithm: &str,
+    ) -> Result<String, HashError> {
+        let mut hasher = self.hasher(algorithm)?;
+        
+        for (name, path) in files {
+            hasher.update(name.as_bytes());
+            hasher.update(&[0]);
+            
+            let file = File::open(extended_length_path(path)).map_err(|e| {
+                HashUtilityError::from_io_error(e, "reading", Some(path.to_path_buf()))
+            })?;
+            let file_size = file.metadata().map(|metadata| metadata.len()).unwrap_or(0);
+            self.hash_with_buffered_io(&mut hasher, file, path, file_size)?;
+        }
+        
+        Ok(bytes_to_hex(&hasher.finalize()))
+    }
+    
+    /// Hash one file, saving the hasher state to `checkpoint` every `interval` bytes (`--checkpoint`)
+    /// 
+    /// If the checkpoint already exists, hashing resumes from the offset it records,
+    /// as long as it was made for the same algorithm and a file of the same size and
+    /// mtime. The checkpoint is removed once the hash is complete. Only the SHA-2
+    /// family can be checkpointed (see `HashRegistry::get_resumable_hasher`).
+    pub fn compute_hash_checkpointed(
         &self,
-        hasher: &mut Box<dyn Hasher>,
-        mut file: File,
         path: &Path,
-    ) -> Result<(), HashError> {
-        let mut buffer = vec![0u8; self.buffer_size];
+        algorithm: &str,
+        checkpoint: &Path,
+        interval: u64,
+    ) -> Result<HashResult, HashError> {
+        use std::io::SeekFrom;
+        
+        let mut hasher = HashRegistry::get_resumable_hasher(algorithm)?;
         
+        let mut file = File::open(extended_length_path(path)).map_err(|e| {
+            HashUtilityError::from_io_error(e, "reading", Some(path.to_path_buf()))
+        })?;
+        let metadata = file.metadata()
+            .map_err(|e| HashUtilityError::from_io_error(e, "reading metadata", Some(path.to_path_buf())))?;
+        let file_size = metadata.len();
+        let file_modified = metadata.modified().ok().map(|time| {
+            chrono::DateTime::<chrono::Utc>::from(time).to_rfc3339_opts(chrono::SecondsFormat::Nanos, true)
+        });
+        
+        // Pick up where an interrupted run left off
+        let mut offset = 0;
+        if checkpoint.exists() {
+            let saved = HashCheckpoint::read(checkpoint)?;
+            let state = hex_to_bytes(&saved.state);
+            let fits = saved.algorithm.eq_ignore_ascii_case(algorithm)
+                && saved.file_size == file_size
+                && saved.file_modified == file_modified
+                && saved.offset <= file_size;
+            if !fits || state.and_then(|state| hasher.restore_state(&state)).is_none() {
+                return Err(HashUtilityError::InvalidArguments {
+                    message: format!(
+                        "Checkpoint '{}' doesn't match {} of {} (delete it to start over)",
+                        checkpoint.display(), algorithm, path.display()
+                    ),
+                });
+            }
+            offset = saved.offset;
+            file.seek(SeekFrom::Start(offset)).map_err(|e| {
+                HashUtilityError::from_io_error(e, "reading", Some(path.to_path_buf()))
+            })?;
+        }
+        
+        let mut buffer = vec![0u8; self.buffer_size_for(file_size)];
+        let mut last_saved = offset;
         loop {
             let bytes_read = file.read(&mut buffer).map_err(|e| {
                 HashUtilityError::from_io_error(e, "reading", Some(path.to_path_buf()))
@@ -683,13 +1446,65 @@ impl HashComputer {
                 break;
             }
             hasher.update(&buffer[..bytes_read]);
+            offset += bytes_read as u64;
+            
+            if offset - last_saved >= interval {
+                HashCheckpoint {
+                    algorithm: algorithm.to_string(),
+                    file_size,
+                    file_modified: file_modified.clone(),
+                    offset,
+                    state: bytes_to_hex(&hasher.serialize_state().unwrap_or_default()),
+                }
+                .write(checkpoint)?;
+                last_saved = offset;
+            }
         }
         
-        Ok(())
+        // Fold extended attributes into the hash after the content, if requested
+        if self.include_xattrs {
+            fold_xattrs(path, &mut |chunk| hasher.update(chunk))?;
+        }
+        
+        let hash = bytes_to_hex(&hasher.finalize());
+        if checkpoint.exists() {
+            std::fs::remove_file(checkpoint).map_err(|e| {
+                HashUtilityError::from_io_error(e, "removing checkpoint", Some(checkpoint.to_path_buf()))
+            })?;
+        }
+        
+        Ok(HashResult {
+            algorithm: algorithm.to_string(),
+            hash,
+            file_path: path.to_path_buf(),
+        })
     }
     
-    /// Helper method to hash a file using buffered I/O with progress bar
-    fn hash_with_buffered_io_progress(
+    /// Helper method to hash a file using buffered I/O
+    fn hash_with_buffered_io(
+        &self,
+        hasher: &mut Box<dyn Hasher>,
+        mut file: File,
+        path: &Path,
+        file_size: u64,
+    ) -> Result<(), HashError> {
+        let mut buffer = vec![0u8; self.buffer_size_for(file_size)];
+        
+        loop {
+            let bytes_read = self.timed_read(&mut file, &mut buffer).map_err(|e| {
+                HashUtilityError::from_io_error(e, "reading", Some(path.to_path_buf()))
+            })?;
+            if bytes_read == 0 {
+                break;
+            }
+            self.timed_update(&buffer[..bytes_read], &mut |chunk| hasher.update(chunk));
+        }
+        
+        Ok(())
+    }
+    
+    /// Helper method to hash a file using buffered I/O with progress bar
+    fn hash_with_buffered_io_progress(
         &self,
         hasher: &mut Box<dyn Hasher>,
         mut file: File,
@@ -709,20 +1524,20 @@ impl HashComputer {
         );
         pb.set_message(format!("Hashing: {}", path.display()));
         
-        let mut buffer = vec![0u8; self.buffer_size];
+        let mut buffer = vec![0u8; self.buffer_size_for(file_size)];
         let mut bytes_processed = 0u64;
         let mut last_update = Instant::now();
         let update_interval = Duration::from_millis(PROGRESS_UPDATE_INTERVAL_MS);
         
         loop {
-            let bytes_read = file.read(&mut buffer).map_err(|e| {
+            let bytes_read = self.timed_read(&mut file, &mut buffer).map_err(|e| {
                 pb.finish_and_clear();
                 HashUtilityError::from_io_error(e, "reading", Some(path.to_path_buf()))
             })?;
             if bytes_read == 0 {
                 break;
             }
-            hasher.update(&buffer[..bytes_read]);
+            self.timed_update(&buffer[..bytes_read], &mut |chunk| hasher.update(chunk));
             bytes_processed += bytes_read as u64;
             
             // Update progress bar at the specified interval
@@ -739,30 +1554,29 @@ impl HashComputer {
         Ok(())
     }
     
-    /// Compute multiple hashes from stdin in a single pass
-    pub fn compute_multiple_hashes_stdin(
+    /// Compute multiple hashes from any reader in a single streaming pass
+    /// 
+    /// `source` is reported as the file path of every result.
+    pub fn compute_multiple_hashes_reader<R: Read>(
         &self,
+        mut reader: R,
         algorithms: &[String],
+        source: &Path,
     ) -> Result<Vec<HashResult>, HashError> {
-        use std::io::{stdin, Read};
-        
         // Get hashers for all specified algorithms
         let mut hashers: Vec<(String, Box<dyn Hasher>)> = Vec::new();
         for algorithm in algorithms {
-            let hasher = HashRegistry::get_hasher(algorithm)?;
+            let hasher = self.hasher(algorithm)?;
             hashers.push((algorithm.clone(), hasher));
         }
         
-        // Get stdin handle
-        let mut stdin = stdin();
-        
         // Create buffer for streaming reads
         let mut buffer = vec![0u8; self.buffer_size];
         
-        // Stream stdin data through all hashers in single pass
+        // Stream data through all hashers in single pass
         loop {
-            let bytes_read = stdin.read(&mut buffer).map_err(|e| {
-                HashUtilityError::from_io_error(e, "reading from stdin", None)
+            let bytes_read = reader.read(&mut buffer).map_err(|e| {
+                HashUtilityError::from_io_error(e, "reading input", None)
             })?;
             if bytes_read == 0 {
                 break;
@@ -783,34 +1597,65 @@ impl HashComputer {
             results.push(HashResult {
                 algorithm,
                 hash: hash_hex,
-                file_path: PathBuf::from("-"), // Use "-" to indicate stdin
+                file_path: source.to_path_buf(),
             });
         }
         
         Ok(results)
     }
     
-    /// Compute multiple hashes for a single file in a single pass
-    /// 
-    /// For files smaller than 2GB, uses memory mapping to avoid kernel-to-userspace copy overhead.
-    /// For files larger than 2GB, falls back to buffered reading with 1MB buffer.
-    /// 
-    /// # Safety
+    /// Compute multiple hashes of the byte range starting at `skip`, `count` bytes long
+    /// (to EOF if `count` is None)
     /// 
-    /// Memory mapping assumes the file will not be modified by other processes during hashing.
-    /// If the file is modified concurrently, the hash results may be inconsistent.
-    pub fn compute_multiple_hashes(
+    /// Reads are streamed until `count` bytes or EOF, never trusting the reported size,
+    /// so this works for block devices and endless sources like `/dev/zero`.
+    pub fn compute_multiple_hashes_range(
         &self,
         path: &Path,
         algorithms: &[String],
+        skip: u64,
+        count: Option<u64>,
     ) -> Result<Vec<HashResult>, HashError> {
-        self.compute_multiple_hashes_with_progress(path, algorithms, false)
+        // Get hashers for all specified algorithms
+        let mut hashers: Vec<(String, Box<dyn Hasher>)> = Vec::new();
+        for algorithm in algorithms {
+            let hasher = self.hasher(algorithm)?;
+            hashers.push((algorithm.clone(), hasher));
+        }
+        
+        let mut file = File::open(extended_length_path(path)).map_err(|e| {
+            HashUtilityError::from_io_error(e, "reading", Some(path.to_path_buf()))
+        })?;
+        
+        self.read_region(&mut file, skip, count.unwrap_or(u64::MAX), path, &mut |chunk| {
+            for (_, hasher) in hashers.iter_mut() {
+                hasher.update(chunk);
+            }
+        })?;
+        
+        // Finalize all hashes and collect results
+        Ok(hashers
+            .into_iter()
+            .map(|(algorithm, hasher)| HashResult {
+                algorithm,
+                hash: bytes_to_hex(&hasher.finalize()),
+                file_path: path.to_path_buf(),
+            })
+            .collect())
     }
     
-    /// Compute multiple hashes for a single file with optional progress bar
+    /// Compute multiple hashes for a single file in a single pass, with optional progress bar
+    /// 
+    /// For files smaller than 2GB, uses memory mapping to avoid kernel-to-userspace copy overhead.
+    /// For files larger than 2GB, falls back to buffered reading sized by `adaptive_buffer_size`.
     /// 
     /// If show_progress is true and the file is larger than 1GB and stdout is a TTY,
     /// displays a progress bar that updates 10 times per second.
+    /// 
+    /// # Safety
+    /// 
+    /// Memory mapping assumes the file will not be modified by other processes during hashing.
+    /// If the file is modified concurrently, the hash results may be inconsistent.
     pub fn compute_multiple_hashes_with_progress(
         &self,
         path: &Path,
@@ -820,54 +1665,90 @@ impl HashComputer {
         // Get hashers for all specified algorithms
         let mut hashers: Vec<(String, Box<dyn Hasher>)> = Vec::new();
         for algorithm in algorithms {
-            let hasher = HashRegistry::get_hasher(algorithm)?;
+            let hasher = self.hasher(algorithm)?;
             hashers.push((algorithm.clone(), hasher));
         }
         
         // Open file for reading with better error context
-        let file = File::open(path).map_err(|e| {
+        let file = File::open(extended_length_path(path)).map_err(|e| {
             HashUtilityError::from_io_error(e, "reading", Some(path.to_path_buf()))
         })?;
         
         // Get file size to determine whether to use memory mapping
-        let file_size = file.metadata()
-            .map_err(|e| HashUtilityError::from_io_error(e, "reading metadata", Some(path.to_path_buf())))?
-            .len();
+        let metadata = file.metadata()
+            .map_err(|e| HashUtilityError::from_io_error(e, "reading metadata", Some(path.to_path_buf())))?;
+        
+        // Block devices and other special files report a size of zero, so stream them to EOF
+        if !metadata.is_file() {
+            return self.compute_multiple_hashes_reader(file, algorithms, path);
+        }
+        let file_size = metadata.len();
         
         // Determine if we should show progress bar
         let should_show_progress = show_progress 
             && file_size > PROGRESS_BAR_THRESHOLD 
             && std::io::stdout().is_terminal();
         
-        // Use memory mapping for files smaller than 2GB
-        if file_size > 0 && file_size < MMAP_THRESHOLD {
-            // Try to memory map the file
+        // Spread hashers across threads only when there are several of them and the
+        // file is large enough for the thread overhead to pay off
+        let concurrent = hashers.len() > 1 && file_size >= CONCURRENT_HASH_THRESHOLD;
+        
+        // Try direct I/O first if requested; it leaves the hashers untouched if unavailable
+        let hashed_direct = self.direct_io
+            && file_size > 0
+            && self.try_hash_with_direct_io(path, file_size, should_show_progress, &mut |chunk| {
+                for (_, hasher) in hashers.iter_mut() {
+                    hasher.update(chunk);
+                }
+            })?;
+        
+        if hashed_direct {
+            // File contents were already fed to the hashers
+        } else if file_size > 0 && file_size < MMAP_THRESHOLD {
+            // Use memory mapping for files smaller than 2GB
             match unsafe { Mmap::map(&file) } {
                 Ok(mmap) => {
                     // Hash the entire mapped file with all hashers
                     // Note: Progress bar not shown for mmap as it's very fast
-                    for (_, hasher) in &mut hashers {
-                        hasher.update(&mmap[..]);
+                    if concurrent {
+                        update_hashers_concurrently(&mut hashers, &mmap[..]);
+                    } else {
+                        for (_, hasher) in &mut hashers {
+                            hasher.update(&mmap[..]);
+                        }
                     }
                 }
                 Err(_) => {
                     // Fall back to buffered reading if mmap fails
-                    if should_show_progress {
+                    if concurrent {
+                        self.hash_multiple_with_threaded_io(&mut hashers, file, path, file_size, should_show_progress)?;
+                    } else if should_show_progress {
                         self.hash_multiple_with_buffered_io_progress(&mut hashers, file, path, file_size)?;
                     } else {
-                        self.hash_multiple_with_buffered_io(&mut hashers, file, path)?;
+                        self.hash_multiple_with_buffered_io(&mut hashers, file, path, file_size)?;
                     }
                 }
             }
         } else {
             // Use buffered reading for large files (>2GB) or empty files
-            if should_show_progress {
+            if concurrent {
+                self.hash_multiple_with_threaded_io(&mut hashers, file, path, file_size, should_show_progress)?;
+            } else if should_show_progress {
                 self.hash_multiple_with_buffered_io_progress(&mut hashers, file, path, file_size)?;
             } else {
-                self.hash_multiple_with_buffered_io(&mut hashers, file, path)?;
+                self.hash_multiple_with_buffered_io(&mut hashers, file, path, file_size)?;
             }
         }
         
+        // Fold extended attributes into every hash after the content, if requested
+        if self.include_xattrs {
+            fold_xattrs(path, &mut |chunk| {
+                for (_, hasher) in hashers.iter_mut() {
+                    hasher.update(chunk);
+                }
+            })?;
+        }
+        
         // Finalize all hashes and collect results
         let mut results = Vec::new();
         for (algorithm, hasher) in hashers {
@@ -890,8 +1771,9 @@ impl HashComputer {
         hashers: &mut [(String, Box<dyn Hasher>)],
         mut file: File,
         path: &Path,
+        file_size: u64,
     ) -> Result<(), HashError> {
-        let mut buffer = vec![0u8; self.buffer_size];
+        let mut buffer = vec![0u8; self.buffer_size_for(file_size)];
         
         loop {
             let bytes_read = file.read(&mut buffer).map_err(|e| {
@@ -931,7 +1813,7 @@ impl HashComputer {
         );
         pb.set_message(format!("Hashing: {}", path.display()));
         
-        let mut buffer = vec![0u8; self.buffer_size];
+        let mut buffer = vec![0u8; self.buffer_size_for(file_size)];
         let mut bytes_processed = 0u64;
         let mut last_update = Instant::now();
         let update_interval = Duration::from_millis(PROGRESS_UPDATE_INTERVAL_MS);
@@ -966,6 +1848,161 @@ impl HashComputer {
         Ok(())
     }
     
+    /// Helper method to hash a file with multiple hashers on separate threads
+    /// 
+    /// The file is still read once: each chunk is shared with one worker thread per
+    /// algorithm through a bounded channel, so CPU-bound hashers run in parallel
+    /// while memory use stays limited to a few chunks per worker.
+    fn hash_multiple_with_threaded_io(
+        &self,
+        hashers: &mut [(String, Box<dyn Hasher>)],
+        mut file: File,
+        path: &Path,
+        file_size: u64,
+        show_progress: bool,
+    ) -> Result<(), HashError> {
+        use crossbeam_channel::bounded;
+        use std::sync::Arc;
+        use std::time::{Duration, Instant};
+        
+        // Create progress bar if requested
+        let pb = if show_progress {
+            Some(create_file_progress_bar(path, file_size))
+        } else {
+            None
+        };
+        
+        let result = std::thread::scope(|scope| {
+            // Spawn one worker per hasher, each consuming chunks from its own channel
+            let mut senders = Vec::with_capacity(hashers.len());
+            for (_, hasher) in hashers.iter_mut() {
+                let (sender, receiver) = bounded::<Arc<Vec<u8>>>(CONCURRENT_HASH_CHANNEL_DEPTH);
+                senders.push(sender);
+                scope.spawn(move || {
+                    for chunk in receiver {
+                        hasher.update(&chunk);
+                    }
+                });
+            }
+            
+            let mut bytes_processed = 0u64;
+            let mut last_update = Instant::now();
+            let update_interval = Duration::from_millis(PROGRESS_UPDATE_INTERVAL_MS);
+            
+            loop {
+                let mut buffer = vec![0u8; self.buffer_size_for(file_size)];
+                let bytes_read = file.read(&mut buffer).map_err(|e| {
+                    HashUtilityError::from_io_error(e, "reading", Some(path.to_path_buf()))
+                })?;
+                if bytes_read == 0 {
+                    break;
+                }
+                buffer.truncate(bytes_read);
+                
+                // Share the same chunk with every worker
+                let chunk = Arc::new(buffer);
+                for sender in &senders {
+                    // A send only fails if the worker panicked, which the scope re-raises on join
+                    let _ = sender.send(Arc::clone(&chunk));
+                }
+                
+                bytes_processed += bytes_read as u64;
+                
+                // Update progress bar at the specified interval
+                if let Some(pb) = &pb {
+                    let now = Instant::now();
+                    if now.duration_since(last_update) >= update_interval {
+                        pb.set_position(bytes_processed);
+                        last_update = now;
+                    }
+                }
+            }
+            
+            // Dropping the senders closes the channels so workers finish before the scope joins
+            drop(senders);
+            Ok(())
+        });
+        
+        // Finish progress bar
+        if let Some(pb) = pb {
+            pb.finish_and_clear();
+        }
+        
+        result
+    }
+    
+    /// Helper method to feed a file to `update` using O_DIRECT reads
+    /// 
+    /// Bypassing the page cache keeps huge sequential reads from evicting the rest
+    /// of the system's cached data. Returns Ok(false) without passing any data to
+    /// `update` if the file can't be read this way (unsupported platform, or a
+    /// filesystem such as tmpfs that rejects O_DIRECT) so the caller can fall back.
+    fn try_hash_with_direct_io(
+        &self,
+        path: &Path,
+        file_size: u64,
+        show_progress: bool,
+        update: &mut dyn FnMut(&[u8]),
+    ) -> Result<bool, HashError> {
+        use std::time::{Duration, Instant};
+        
+        let mut file = match open_direct(path) {
+            Ok(file) => file,
+            Err(_) => return Ok(false),
+        };
+        
+        // O_DIRECT needs the buffer address and read size aligned to the block size,
+        // so over-allocate and hash through an aligned window of the allocation
+        let buffer_len = self.buffer_size_for(file_size).div_ceil(DIRECT_IO_ALIGNMENT) * DIRECT_IO_ALIGNMENT;
+        let mut storage = vec![0u8; buffer_len + DIRECT_IO_ALIGNMENT];
+        let offset = storage.as_ptr().align_offset(DIRECT_IO_ALIGNMENT);
+        let buffer = &mut storage[offset..offset + buffer_len];
+        
+        // Read the first chunk before creating the progress bar, since some
+        // filesystems only reject O_DIRECT at read time
+        let mut bytes_read = match file.read(buffer) {
+            Ok(bytes_read) => bytes_read,
+            Err(_) => return Ok(false),
+        };
+        
+        let pb = if show_progress {
+            Some(create_file_progress_bar(path, file_size))
+        } else {
+            None
+        };
+        let mut bytes_processed = 0u64;
+        let mut last_update = Instant::now();
+        let update_interval = Duration::from_millis(PROGRESS_UPDATE_INTERVAL_MS);
+        
+        while bytes_read > 0 {
+            update(&buffer[..bytes_read]);
+            bytes_processed += bytes_read as u64;
+            
+            // Update progress bar at the specified interval
+            if let Some(pb) = &pb {
+                let now = Instant::now();
+                if now.duration_since(last_update) >= update_interval {
+                    pb.set_position(bytes_processed);
+                    last_update = now;
+                }
+            }
+            
+            bytes_read = file.read(buffer).map_err(|e| {
+                if let Some(pb) = &pb {
+                    pb.finish_and_clear();
+                }
+                HashUtilityError::from_io_error(e, "reading", Some(path.to_path_buf()))
+            })?;
+        }
+        
+        // Finish progress bar
+        if let Some(pb) = pb {
+            pb.finish_and_clear();
+        }
+        
+        Ok(true)
+    }
+    
     /// Compute hash for a file using fast mode (sampling strategy)
     /// 
     /// For files larger than 300MB, samples three 100MB regions:
@@ -974,17 +2011,42 @@ impl HashComputer {
     /// - Last 100MB
     /// 
     /// For files smaller than 300MB, uses the full file.
+    /// 
+    /// This is the original (`fast`) scheme; new databases use `compute_hash_fast_v2`.
     pub fn compute_hash_fast(
         &self,
         path: &Path,
         algorithm: &str,
     ) -> Result<HashResult, HashError> {
+        self.compute_hash_sampled(path, algorithm, false)
+    }
+    
+    /// Compute a fast mode hash that also covers the total file size (`fast2`)
+    /// 
+    /// Sampled files that only differ outside the three regions no longer collide
+    /// when their sizes differ. Files below the sampling threshold are hashed in
+    /// full exactly as in `compute_hash_fast`, so they still match a normal hash.
+    pub fn compute_hash_fast_v2(
+        &self,
+        path: &Path,
+        algorithm: &str,
+    ) -> Result<HashResult, HashError> {
+        self.compute_hash_sampled(path, algorithm, true)
+    }
+    
+    /// Shared fast mode implementation, optionally folding in the size of sampled files
+    fn compute_hash_sampled(
+        &self,
+        path: &Path,
+        algorithm: &str,
+        fold_size: bool,
+    ) -> Result<HashResult, HashError> {
         
         // Get hasher for the specified algorithm
-        let mut hasher = HashRegistry::get_hasher(algorithm)?;
+        let mut hasher = self.hasher(algorithm)?;
         
         // Open file for reading with better error context
-        let mut file = File::open(path).map_err(|e| {
+        let mut file = File::open(extended_length_path(path)).map_err(|e| {
             HashUtilityError::from_io_error(e, "reading", Some(path.to_path_buf()))
         })?;
         
@@ -995,29 +2057,39 @@ impl HashComputer {
         
         // If file is smaller than threshold, hash the entire file
         if file_size < FAST_MODE_THRESHOLD {
-            let mut buffer = vec![0u8; self.buffer_size];
+            let mut buffer = vec![0u8; self.buffer_size_for(file_size)];
             loop {
-                let bytes_read = file.read(&mut buffer).map_err(|e| {
+                let bytes_read = self.timed_read(&mut file, &mut buffer).map_err(|e| {
                     HashUtilityError::from_io_error(e, "reading", Some(path.to_path_buf()))
                 })?;
                 if bytes_read == 0 {
                     break;
                 }
-                hasher.update(&buffer[..bytes_read]);
+                self.timed_update(&buffer[..bytes_read], &mut |chunk| hasher.update(chunk));
             }
         } else {
             // Sample three regions: first 100MB, middle 100MB, last 100MB
             
             // Read first 100MB
-            self.read_region(&mut file, &mut hasher, 0, FAST_MODE_SAMPLE_SIZE, path)?;
+            self.read_region(&mut file, 0, FAST_MODE_SAMPLE_SIZE, path, &mut |chunk| hasher.update(chunk))?;
             
             // Calculate middle region: centered at file_size/2
             let middle_start = (file_size / 2).saturating_sub(FAST_MODE_SAMPLE_SIZE / 2);
-            self.read_region(&mut file, &mut hasher, middle_start, FAST_MODE_SAMPLE_SIZE, path)?;
+            self.read_region(&mut file, middle_start, FAST_MODE_SAMPLE_SIZE, path, &mut |chunk| hasher.update(chunk))?;
             
             // Read last 100MB
             let last_start = file_size.saturating_sub(FAST_MODE_SAMPLE_SIZE);
-            self.read_region(&mut file, &mut hasher, last_start, FAST_MODE_SAMPLE_SIZE, path)?;
+            self.read_region(&mut file, last_start, FAST_MODE_SAMPLE_SIZE, path, &mut |chunk| hasher.update(chunk))?;
+            
+            // The samples alone don't cover the unread parts, so at least bind the total size
+            if fold_size {
+                hasher.update(&file_size.to_le_bytes());
+            }
+        }
+        
+        // Fold extended attributes into the hash after the content, if requested
+        if self.include_xattrs {
+            fold_xattrs(path, &mut |chunk| hasher.update(chunk))?;
         }
         
         // Finalize hash and convert to hex
@@ -1031,14 +2103,66 @@ impl HashComputer {
         })
     }
     
-    /// Helper function to read a specific region of a file
+    /// Compute a hash of a symlink's target path rather than the content it points to
+    /// 
+    /// The target is hashed with BLAKE3 and the result is tagged with the `symlink`
+    /// algorithm, so repointing a link changes its hash even if the link dangles.
+    pub fn compute_symlink_hash(&self, path: &Path) -> Result<HashResult, HashError> {
+        let target = std::fs::read_link(path)
+            .map_err(|e| HashUtilityError::from_io_error(e, "reading symlink", Some(path.to_path_buf())))?;
+        
+        let mut hasher = HashRegistry::get_hasher("blake3")?;
+        hasher.update(target.as_os_str().as_encoded_bytes());
+        
+        Ok(HashResult {
+            algorithm: SYMLINK_ALGORITHM.to_string(),
+            hash: bytes_to_hex(&hasher.finalize()),
+            file_path: path.to_path_buf(),
+        })
+    }
+    
+    /// Compute a structure-only hash from a file's name, size and modification time
+    /// 
+    /// The contents are never read, so this is very fast but only detects changes
+    /// that alter the size or modification time. The directory part of the path is
+    /// left out so the hash does not depend on where the tree is scanned from.
+    pub fn compute_structure_hash(
+        &self,
+        path: &Path,
+        algorithm: &str,
+    ) -> Result<HashResult, HashError> {
+        let mut hasher = self.hasher(algorithm)?;
+        
+        let metadata = std::fs::metadata(extended_length_path(path))
+            .map_err(|e| HashUtilityError::from_io_error(e, "reading metadata", Some(path.to_path_buf())))?;
+        let modified = metadata.modified()
+            .ok()
+            .and_then(|time| time.duration_since(std::time::UNIX_EPOCH).ok())
+            .unwrap_or_default();
+        
+        // NUL-separated so that no two (name, size, mtime) triples produce the same input
+        if let Some(name) = path.file_name() {
+            hasher.update(name.as_encoded_bytes());
+        }
+        hasher.update(format!("\0{}\0{}.{:09}", metadata.len(), modified.as_secs(), modified.subsec_nanos()).as_bytes());
+        
+        Ok(HashResult {
+            algorithm: algorithm.to_string(),
+            hash: bytes_to_hex(&hasher.finalize()),
+            file_path: path.to_path_buf(),
+        })
+    }
+    
+    /// Helper function to read a specific region of a file, passing each chunk to `update`
+    /// 
+    /// Stops early at EOF, so a `length` of `u64::MAX` reads to the end.
     fn read_region(
         &self,
         file: &mut File,
-        hasher: &mut Box<dyn Hasher>,
         start: u64,
         length: u64,
         path: &Path,
+        update: &mut dyn FnMut(&[u8]),
     ) -> Result<(), HashError> {
         
         // Seek to the start position
@@ -1046,19 +2170,19 @@ impl HashComputer {
             .map_err(|e| HashUtilityError::from_io_error(e, "seeking", Some(path.to_path_buf())))?;
         
         // Read up to 'length' bytes
-        let mut buffer = vec![0u8; self.buffer_size];
+        let mut buffer = vec![0u8; self.buffer_size_for(length)];
         let mut bytes_remaining = length;
         
         while bytes_remaining > 0 {
             let to_read = std::cmp::min(bytes_remaining, buffer.len() as u64) as usize;
-            let bytes_read = file.read(&mut buffer[..to_read])
+            let bytes_read = self.timed_read(file, &mut buffer[..to_read])
                 .map_err(|e| HashUtilityError::from_io_error(e, "reading", Some(path.to_path_buf())))?;
             
             if bytes_read == 0 {
                 break; // End of file
             }
             
-            hasher.update(&buffer[..bytes_read]);
+            self.timed_update(&buffer[..bytes_read], update);
             bytes_remaining -= bytes_read as u64;
         }
         
@@ -1072,6 +2196,117 @@ impl Default for HashComputer {
     }
 }
 
+/// Create the per-file progress bar used for large buffered reads
+fn create_file_progress_bar(path: &Path, file_size: u64) -> indicatif::ProgressBar {
+    use indicatif::{ProgressBar, ProgressStyle};
+    
+    let pb = ProgressBar::new(file_size);
+    pb.set_style(
+        ProgressStyle::default_bar()
+            .template("{msg}\n[{elapsed_precise}] [{bar:40.cyan/blue}] {bytes}/{total_bytes} ({eta})")
+            .unwrap()
+            .progress_chars("#>-")
+    );
+    pb.set_message(format!("Hashing: {}", path.display()));
+    pb
+}
+
+/// Open a file for reading with O_DIRECT so reads bypass the page cache
+#[cfg(target_os = "linux")]
+fn open_direct(path: &Path) -> std::io::Result<File> {
+    use std::os::unix::fs::OpenOptionsExt;
+    
+    std::fs::OpenOptions::new()
+        .read(true)
+        .custom_flags(libc::O_DIRECT)
+        .open(path)
+}
+
+/// Direct I/O is only implemented on Linux; other platforms use normal reads
+#[cfg(not(target_os = "linux"))]
+fn open_direct(_path: &Path) -> std::io::Result<File> {
+    Err(std::io::Error::new(
+        std::io::ErrorKind::Unsupported,
+        "direct I/O is not supported on this platform",
+    ))
+}
+
+/// Advise the kernel that a file will be read sequentially from start to end
+/// 
+/// This is only a hint: failures are ignored and hashing proceeds normally.
+#[cfg(target_os = "linux")]
+fn advise_sequential(file: &File) {
+    use std::os::unix::io::AsRawFd;
+    
+    // Offset 0 and length 0 apply the advice to the whole file
+    unsafe {
+        libc::posix_fadvise(file.as_raw_fd(), 0, 0, libc::POSIX_FADV_SEQUENTIAL);
+    }
+}
+
+/// posix_fadvise isn't available on this platform; the hint is a no-op
+#[cfg(not(target_os = "linux"))]
+fn advise_sequential(_file: &File) {}
+
+/// Feed a file's extended attributes to `update` in a canonical encoding
+/// 
+/// Attributes are sorted by name and each name and value is prefixed with its
+/// length as a little-endian u64, so different attribute sets can't collide.
+/// Files without extended attributes add nothing, keeping their plain content hash.
+fn fold_xattrs(path: &Path, update: &mut dyn FnMut(&[u8])) -> Result<(), HashError> {
+    let mut attributes = read_xattrs(path).map_err(|e| {
+        HashUtilityError::from_io_error(e, "reading extended attributes of", Some(path.to_path_buf()))
+    })?;
+    attributes.sort();
+    
+    for (name, value) in attributes {
+        update(&(name.len() as u64).to_le_bytes());
+        update(&name);
+        update(&(value.len() as u64).to_le_bytes());
+        update(&value);
+    }
+    
+    Ok(())
+}
+
+/// Read all extended attributes of a file as (name, value) pairs
+#[cfg(any(target_os = "linux", target_os = "macos"))]
+fn read_xattrs(path: &Path) -> std::io::Result<Vec<(Vec<u8>, Vec<u8>)>> {
+    use std::os::unix::ffi::OsStrExt;
+    
+    let names = match xattr::list(path) {
+        Ok(names) => names,
+        // Filesystems without xattr support simply have no attributes to fold in
+        Err(err) if err.raw_os_error() == Some(libc::ENOTSUP) => return Ok(Vec::new()),
+        Err(err) => return Err(err),
+    };
+    
+    let mut attributes = Vec::new();
+    for name in names {
+        // An attribute removed since the listing no longer belongs to the file
+        if let Some(value) = xattr::get(path, &name)? {
+            attributes.push((name.as_bytes().to_vec(), value));
+        }
+    }
+    
+    Ok(attributes)
+}
+
+/// Extended attributes are not supported on this platform
+#[cfg(not(any(target_os = "linux", target_os = "macos")))]
+fn read_xattrs(_path: &Path) -> std::io::Result<Vec<(Vec<u8>, Vec<u8>)>> {
+    Ok(Vec::new())
+}
+
+/// Update every hasher with the same in-memory data, one thread per hasher
+fn update_hashers_concurrently(hashers: &mut [(String, Box<dyn Hasher>)], data: &[u8]) {
+    std::thread::scope(|scope| {
+        for (_, hasher) in hashers.iter_mut() {
+            scope.spawn(move || hasher.update(data));
+        }
+    });
+}
+
 /// Convert bytes to hexadecimal string
 fn bytes_to_hex(bytes: &[u8]) -> String {
     bytes.iter()
@@ -1079,12 +2314,31 @@ fn bytes_to_hex(bytes: &[u8]) -> String {
         .collect()
 }
 
+/// Convert a hexadecimal string to bytes, or None if it has an odd length or non-hex characters
+pub fn hex_to_bytes(hex: &str) -> Option<Vec<u8>> {
+    if !hex.len().is_multiple_of(2) || !hex.is_ascii() {
+        return None;
+    }
+    (0..hex.len())
+        .step_by(2)
+        .map(|i| u8::from_str_radix(&hex[i..i + 2], 16).ok())
+        .collect()
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
     use std::io::Write;
     use std::fs;
 
+    #[test]
+    fn test_expected_hex_len() {
+        assert_eq!(HashRegistry::expected_hex_len("md5"), Some(32));
+        assert_eq!(HashRegistry::expected_hex_len("SHA256"), Some(64));
+        assert_eq!(HashRegistry::expected_hex_len("xxh3"), Some(16));
+        assert_eq!(HashRegistry::expected_hex_len("nope"), None);
+    }
+    
     #[test]
     fn test_compute_hash_sha256() {
         // Create a temporary test file
@@ -1115,7 +2369,7 @@ mod tests {
         // Compute multiple hashes
         let computer = HashComputer::new();
         let algorithms = vec!["md5".to_string(), "sha256".to_string()];
-        let results = computer.compute_multiple_hashes(Path::new(temp_file), &algorithms).unwrap();
+        let results = computer.compute_multiple_hashes_with_progress(Path::new(temp_file), &algorithms, false).unwrap();
         
         // Verify results
         assert_eq!(results.len(), 2);
@@ -1250,6 +2504,37 @@ mod tests {
         fs::remove_file(temp_file).unwrap();
     }
     
+    #[test]
+    fn test_compute_hash_fast_v2_folds_size() {
+        // Sparse all-zero files: every sampled region is identical, only the sizes differ
+        let files = ["test_fast_v2_a.bin", "test_fast_v2_b.bin"];
+        let sizes = [FAST_MODE_THRESHOLD + 1024 * 1024, FAST_MODE_THRESHOLD + 2 * 1024 * 1024];
+        for (file, size) in files.iter().zip(sizes) {
+            fs::File::create(file).unwrap().set_len(size).unwrap();
+        }
+        
+        let computer = HashComputer::new();
+        let v1: Vec<_> = files.iter().map(|f| computer.compute_hash_fast(Path::new(f), "xxh3").unwrap().hash).collect();
+        let v2: Vec<_> = files.iter().map(|f| computer.compute_hash_fast_v2(Path::new(f), "xxh3").unwrap().hash).collect();
+        
+        // The original scheme cannot tell them apart; v2 can
+        assert_eq!(v1[0], v1[1]);
+        assert_ne!(v2[0], v2[1]);
+        assert_ne!(v1[0], v2[0]);
+        
+        for file in files {
+            fs::remove_file(file).unwrap();
+        }
+        
+        // Small files are still hashed in full, matching a normal hash
+        let small = "test_fast_v2_small.txt";
+        fs::write(small, b"hello world").unwrap();
+        let normal = computer.compute_hash(Path::new(small), "sha256").unwrap();
+        let fast2 = computer.compute_hash_fast_v2(Path::new(small), "sha256").unwrap();
+        assert_eq!(fast2.hash, normal.hash);
+        fs::remove_file(small).unwrap();
+    }
+    
     #[test]
     fn test_compute_hash_stdin_equivalence() {
         use std::io::Write;
@@ -1280,25 +2565,6 @@ mod tests {
         assert_eq!(file_result.algorithm, "sha256");
     }
     
-    #[test]
-    fn test_compute_multiple_hashes_stdin_structure() {
-        // Test that compute_multiple_hashes_stdin returns correct structure
-        // We can't easily test actual stdin reading in unit tests, but we can
-        // verify the method signature and basic structure
-        
-        // This is more of a compilation test to ensure the API exists
-        let computer = HashComputer::new();
-        let algorithms = vec!["sha256".to_string(), "md5".to_string()];
-        
-        // We can't actually call this without stdin, but we verify it compiles
-        // and the structure is correct
-        let _ = &computer;
-        let _ = &algorithms;
-        
-        // Just verify the computer was created successfully
-        assert_eq!(computer.buffer_size, 1024 * 1024);
-    }
-    
     #[test]
     fn test_compute_hash_text() {
         let computer = HashComputer::new();
@@ -1442,4 +2708,517 @@ mod tests {
         assert_eq!(xxh128.post_quantum, false);
         assert_eq!(xxh128.cryptographic, false);
     }
+    
+    #[test]
+    fn test_filter_algorithms_no_filters() {
+        let all = HashRegistry::list_algorithms();
+        let filtered = HashRegistry::filter_algorithms(false, false);
+        
+        assert_eq!(filtered.len(), all.len());
+    }
+    
+    #[test]
+    fn test_filter_algorithms_crypto_only() {
+        let filtered = HashRegistry::filter_algorithms(true, false);
+        
+        assert!(!filtered.is_empty());
+        assert!(filtered.iter().all(|a| a.cryptographic));
+        assert!(!filtered.iter().any(|a| a.name == "XXH3" || a.name == "XXH128"));
+    }
+    
+    #[test]
+    fn test_filter_algorithms_pq_only() {
+        let filtered = HashRegistry::filter_algorithms(false, true);
+        
+        // Every post-quantum algorithm in the registry must be returned, and nothing else
+        let expected: Vec<String> = HashRegistry::list_algorithms()
+            .into_iter()
+            .filter(|a| a.post_quantum)
+            .map(|a| a.name)
+            .collect();
+        let names: Vec<String> = filtered.iter().map(|a| a.name.clone()).collect();
+        assert_eq!(names, expected);
+        
+        // Currently that is exactly the SHA-3 family
+        assert!(names.iter().all(|n| n.starts_with("SHA3-")));
+        assert_eq!(names.len(), 4);
+        
+        // The registry's PQ flag agrees with is_post_quantum for every listed name
+        for name in &names {
+            assert!(HashRegistry::is_post_quantum(name));
+        }
+    }
+    
+    #[test]
+    fn test_filter_algorithms_combined() {
+        let pq = HashRegistry::filter_algorithms(false, true);
+        let both = HashRegistry::filter_algorithms(true, true);
+        
+        // Both flags = cryptographic AND post-quantum
+        assert!(both.iter().all(|a| a.cryptographic && a.post_quantum));
+        assert_eq!(both.len(), pq.iter().filter(|a| a.cryptographic).count());
+    }
+    
+    #[test]
+    fn test_canonical_name() {
+        assert_eq!(HashRegistry::canonical_name("SHA-256"), "sha256");
+        assert_eq!(HashRegistry::canonical_name("sha256"), "sha256");
+        assert_eq!(HashRegistry::canonical_name("Sha3-512"), "sha3-512");
+        assert_eq!(HashRegistry::canonical_name("BLAKE2b-512:256"), "blake2b:256");
+        assert_eq!(HashRegistry::canonical_name("no-such-hash"), "no-such-hash");
+        
+        // Every advertised alias maps to the first (canonical) one
+        for algo in HashRegistry::list_algorithms() {
+            for alias in &algo.aliases {
+                assert_eq!(HashRegistry::canonical_name(&alias.to_uppercase()), algo.aliases[0]);
+            }
+        }
+    }
+    
+    #[test]
+    fn test_all_aliases_resolve() {
+        // Every alias advertised by list_algorithms must be accepted by get_hasher
+        // and produce a hasher with the advertised output size
+        for algo in HashRegistry::list_algorithms() {
+            assert!(!algo.aliases.is_empty(), "{} has no aliases", algo.name);
+            
+            for alias in &algo.aliases {
+                let hasher = HashRegistry::get_hasher(alias)
+                    .unwrap_or_else(|_| panic!("Alias {} for {} does not resolve", alias, algo.name));
+                assert_eq!(hasher.output_size() * 8, algo.output_bits, "Alias {} has wrong output size", alias);
+            }
+        }
+    }
+    
+    #[test]
+    fn test_canonical_names_are_aliases() {
+        // The canonical (display) name should itself be one of the accepted aliases
+        for algo in HashRegistry::list_algorithms() {
+            assert!(
+                algo.aliases.contains(&algo.name.to_lowercase()),
+                "{} is missing its canonical name from aliases",
+                algo.name
+            );
+        }
+    }
+    
+    #[test]
+    fn test_self_test_all_pass() {
+        let results = HashRegistry::run_self_test();
+        
+        assert_eq!(results.len(), HashRegistry::list_algorithms().len());
+        for result in &results {
+            assert!(
+                result.passed,
+                "{} failed self-test: expected {}, got {}",
+                result.algorithm, result.expected, result.actual
+            );
+        }
+    }
+    
+    #[test]
+    fn test_test_vectors_match_registry() {
+        // Every vector must name a registered algorithm, with a digest of the advertised size
+        let algorithms = HashRegistry::list_algorithms();
+        for vector in TEST_VECTORS {
+            let algo = algorithms.iter().find(|a| a.name == vector.algorithm)
+                .unwrap_or_else(|| panic!("Vector for unknown algorithm {}", vector.algorithm));
+            assert_eq!(vector.expected.len() * 4, algo.output_bits);
+        }
+    }
+    
+    #[test]
+    fn test_concurrent_multiple_hashes_match_sequential() {
+        let test_file = "test_concurrent_multi_hash.bin";
+        let data: Vec<u8> = (0..300_000u32).map(|i| (i % 251) as u8).collect();
+        fs::write(test_file, &data).unwrap();
+        
+        let algorithms = ["sha256", "sha512", "blake2b", "blake3", "xxh3", "md5"];
+        let make_hashers = || -> Vec<(String, Box<dyn Hasher>)> {
+            algorithms.iter()
+                .map(|a| (a.to_string(), HashRegistry::get_hasher(a).unwrap()))
+                .collect()
+        };
+        let finalize = |hashers: Vec<(String, Box<dyn Hasher>)>| -> Vec<(String, String)> {
+            hashers.into_iter()
+                .map(|(a, h)| (a, bytes_to_hex(&h.finalize())))
+                .collect()
+        };
+        
+        // Small buffer so the threaded path processes many chunks
+        let computer = HashComputer::with_buffer_size(4096);
+        
+        let mut sequential = make_hashers();
+        computer.hash_multiple_with_buffered_io(&mut sequential, File::open(test_file).unwrap(), Path::new(test_file), data.len() as u64).unwrap();
+        let sequential = finalize(sequential);
+        
+        let mut threaded = make_hashers();
+        computer.hash_multiple_with_threaded_io(&mut threaded, File::open(test_file).unwrap(), Path::new(test_file), data.len() as u64, false).unwrap();
+        let threaded = finalize(threaded);
+        
+        let mut in_memory = make_hashers();
+        update_hashers_concurrently(&mut in_memory, &data);
+        let in_memory = finalize(in_memory);
+        
+        fs::remove_file(test_file).unwrap();
+        
+        assert_eq!(threaded, sequential);
+        assert_eq!(in_memory, sequential);
+    }
+    
+    #[test]
+    fn test_concurrent_threaded_io_empty_file() {
+        let test_file = "test_concurrent_empty.bin";
+        fs::write(test_file, b"").unwrap();
+        
+        let computer = HashComputer::new();
+        let mut hashers: Vec<(String, Box<dyn Hasher>)> = vec![
+            ("sha256".to_string(), HashRegistry::get_hasher("sha256").unwrap()),
+            ("blake3".to_string(), HashRegistry::get_hasher("blake3").unwrap()),
+        ];
+        computer.hash_multiple_with_threaded_io(&mut hashers, File::open(test_file).unwrap(), Path::new(test_file), 0, false).unwrap();
+        
+        fs::remove_file(test_file).unwrap();
+        
+        let (_, sha256) = hashers.remove(0);
+        assert_eq!(
+            bytes_to_hex(&sha256.finalize()),
+            "e3b0c44298fc1c149afbf4c8996fb92427ae41e4649b934ca495991b7852b855"
+        );
+    }
+    
+    #[test]
+    fn test_adaptive_buffer_size() {
+        assert_eq!(adaptive_buffer_size(0), 16 * 1024);
+        assert_eq!(adaptive_buffer_size(64 * 1024 - 1), 16 * 1024);
+        assert_eq!(adaptive_buffer_size(64 * 1024), 64 * 1024);
+        assert_eq!(adaptive_buffer_size(1024 * 1024), 256 * 1024);
+        assert_eq!(adaptive_buffer_size(64 * 1024 * 1024), 1024 * 1024);
+        assert_eq!(adaptive_buffer_size(1024 * 1024 * 1024), 4 * 1024 * 1024);
+        assert_eq!(adaptive_buffer_size(50 * 1024 * 1024 * 1024), 4 * 1024 * 1024);
+        
+        // Buffer size never decreases as files get bigger
+        let sizes = [0u64, 1_000, 100_000, 10_000_000, 500_000_000, 5_000_000_000];
+        for pair in sizes.windows(2) {
+            assert!(adaptive_buffer_size(pair[0]) <= adaptive_buffer_size(pair[1]));
+        }
+    }
+    
+    #[test]
+    fn test_buffer_size_for_fixed_and_adaptive() {
+        let adaptive = HashComputer::new();
+        assert_eq!(adaptive.buffer_size_for(1000), 16 * 1024);
+        
+        let fixed = HashComputer::with_buffer_size(8192);
+        assert_eq!(fixed.buffer_size_for(1000), 8192);
+        assert_eq!(fixed.buffer_size_for(5_000_000_000), 8192);
+    }
+    
+    #[test]
+    fn test_adaptive_buffer_hashes_unchanged() {
+        // Hash files straddling each buffer size boundary through the buffered path
+        // with adaptive, tiny fixed, and default fixed buffers; results must agree
+        let sizes = [0usize, 1, 16 * 1024 + 7, 64 * 1024 + 3, 1024 * 1024 + 11, 3 * 1024 * 1024];
+        let computers = [
+            HashComputer::new(),
+            HashComputer::with_buffer_size(1000),
+            HashComputer::with_buffer_size(DEFAULT_BUFFER_SIZE),
+        ];
+        
+        for (i, size) in sizes.iter().enumerate() {
+            let test_file = format!("test_adaptive_buffer_{}.bin", i);
+            let data: Vec<u8> = (0..*size).map(|b| (b % 253) as u8).collect();
+            fs::write(&test_file, &data).unwrap();
+            
+            let hashes: Vec<String> = computers.iter()
+                .map(|computer| {
+                    let mut hasher = HashRegistry::get_hasher("sha256").unwrap();
+                    let file = File::open(&test_file).unwrap();
+                    computer.hash_with_buffered_io(&mut hasher, file, Path::new(&test_file), *size as u64).unwrap();
+                    bytes_to_hex(&hasher.finalize())
+                })
+                .collect();
+            
+            // Reference: hash the whole buffer in one update
+            let mut reference = HashRegistry::get_hasher("sha256").unwrap();
+            reference.update(&data);
+            let reference = bytes_to_hex(&reference.finalize());
+            
+            fs::remove_file(&test_file).unwrap();
+            
+            for hash in &hashes {
+                assert_eq!(hash, &reference, "Mismatch for file size {}", size);
+            }
+        }
+    }
+    
+    #[cfg(unix)]
+    #[test]
+    fn test_direct_io_matches_normal_io() {
+        // Sizes include an empty file, a partial block, and several buffers plus a tail
+        let sizes = [0usize, 100, 4096, 300_000];
+        
+        for (i, size) in sizes.iter().enumerate() {
+            let test_file = format!("test_direct_io_{}.bin", i);
+            let data: Vec<u8> = (0..*size).map(|b| (b % 241) as u8).collect();
+            fs::write(&test_file, &data).unwrap();
+            
+            let normal = HashComputer::new();
+            let direct = HashComputer::new().with_direct_io(true);
+            let direct_small_buffer = HashComputer::with_buffer_size(5000).with_direct_io(true);
+            
+            let expected = normal.compute_hash(Path::new(&test_file), "sha256").unwrap();
+            let actual = direct.compute_hash(Path::new(&test_file), "sha256").unwrap();
+            let actual_small = direct_small_buffer.compute_hash(Path::new(&test_file), "sha256").unwrap();
+            
+            let algorithms = vec!["md5".to_string(), "blake3".to_string()];
+            let expected_multi = normal.compute_multiple_hashes_with_progress(Path::new(&test_file), &algorithms, false).unwrap();
+            let actual_multi = direct.compute_multiple_hashes_with_progress(Path::new(&test_file), &algorithms, false).unwrap();
+            
+            fs::remove_file(&test_file).unwrap();
+            
+            assert_eq!(actual.hash, expected.hash, "Mismatch for file size {}", size);
+            assert_eq!(actual_small.hash, expected.hash, "Mismatch for file size {}", size);
+            for (a, e) in actual_multi.iter().zip(expected_multi.iter()) {
+                assert_eq!(a.hash, e.hash, "Mismatch for {} at file size {}", a.algorithm, size);
+            }
+        }
+    }
+    
+    #[test]
+    fn test_compute_multiple_hashes_range() {
+        let test_file = "test_hash_range.bin";
+        let data: Vec<u8> = (0..64u8).collect();
+        fs::write(test_file, &data).unwrap();
+        
+        // Bytes [10..20) must hash exactly like the slice itself
+        let computer = HashComputer::new();
+        let algorithms = vec!["sha256".to_string(), "md5".to_string()];
+        let results = computer.compute_multiple_hashes_range(Path::new(test_file), &algorithms, 10, Some(10)).unwrap();
+        for result in &results {
+            let expected = computer.compute_hash_reader(&data[10..20], &result.algorithm).unwrap();
+            assert_eq!(result.hash, expected, "Mismatch for {}", result.algorithm);
+        }
+        
+        // Without a count the range runs to EOF, and a count past EOF is clamped
+        let tail = computer.compute_multiple_hashes_range(Path::new(test_file), &algorithms[..1], 50, None).unwrap();
+        let clamped = computer.compute_multiple_hashes_range(Path::new(test_file), &algorithms[..1], 50, Some(1000)).unwrap();
+        let expected = computer.compute_hash_reader(&data[50..], "sha256").unwrap();
+        assert_eq!(tail[0].hash, expected);
+        assert_eq!(clamped[0].hash, expected);
+        
+        fs::remove_file(test_file).unwrap();
+    }
+    
+    #[cfg(unix)]
+    #[test]
+    fn test_hash_special_files() {
+        // /dev/zero never ends, so a byte limit is required
+        let count = 1024 * 1024;
+        let mut expected = HashRegistry::get_hasher("sha256").unwrap();
+        expected.update(&vec![0u8; count]);
+        let expected = bytes_to_hex(&expected.finalize());
+        
+        let computer = HashComputer::new();
+        let results = computer
+            .compute_multiple_hashes_range(Path::new("/dev/zero"), &["sha256".to_string()], 0, Some(count as u64))
+            .unwrap();
+        assert_eq!(results[0].hash, expected);
+        assert_eq!(results[0].file_path, PathBuf::from("/dev/zero"));
+        
+        // Character devices report no size and are streamed to EOF
+        let empty = computer.compute_hash(Path::new("/dev/null"), "sha256").unwrap();
+        assert_eq!(empty.hash, "e3b0c44298fc1c149afbf4c8996fb92427ae41e4649b934ca495991b7852b855");
+        let empty = computer.compute_multiple_hashes_with_progress(Path::new("/dev/null"), &["sha256".to_string()], false).unwrap();
+        assert_eq!(empty[0].hash, "e3b0c44298fc1c149afbf4c8996fb92427ae41e4649b934ca495991b7852b855");
+    }
+    
+    #[cfg(any(target_os = "linux", target_os = "macos"))]
+    #[test]
+    fn test_xattrs_change_hash() {
+        let test_file = "test_xattrs_hash.txt";
+        fs::write(test_file, b"xattr content").unwrap();
+        
+        let plain = HashComputer::new();
+        let with_xattrs = HashComputer::new().with_xattrs(true);
+        let path = Path::new(test_file);
+        
+        // Without any attributes both modes agree
+        let before_plain = plain.compute_hash(path, "sha256").unwrap().hash;
+        let before_xattrs = with_xattrs.compute_hash(path, "sha256").unwrap().hash;
+        assert_eq!(before_plain, before_xattrs);
+        
+        // Set a user attribute; skip if the filesystem doesn't support it
+        if xattr::set(path, "user.quichash.test", b"tagged").is_err() {
+            fs::remove_file(test_file).unwrap();
+            eprintln!("Skipping xattr test: filesystem does not support user xattrs");
+            return;
+        }
+        
+        let after_plain = plain.compute_hash(path, "sha256").unwrap().hash;
+        let after_xattrs = with_xattrs.compute_hash(path, "sha256").unwrap().hash;
+        let algorithms = vec!["sha256".to_string()];
+        let after_multi = with_xattrs.compute_multiple_hashes_with_progress(path, &algorithms, false).unwrap();
+        let after_fast = with_xattrs.compute_hash_fast(path, "sha256").unwrap().hash;
+        
+        fs::remove_file(test_file).unwrap();
+        
+        // Content hash is unchanged, but the xattr-aware hash now differs
+        assert_eq!(after_plain, before_plain);
+        assert_ne!(after_xattrs, before_xattrs);
+        assert_eq!(after_multi[0].hash, after_xattrs);
+        assert_eq!(after_fast, after_xattrs);
+    }
+    
+    #[test]
+    fn test_blake2_variable_length() {
+        let computer = HashComputer::new();
+        let result = computer.compute_hash_text("abc", "blake2b:256").unwrap();
+        assert_eq!(result.hash, "bddd813c634239723171ef3fee98579b94964e3bb1cb3e427262c8c068d52319");
+        
+        // Full length matches the fixed-size algorithm
+        let full = computer.compute_hash_text("abc", "blake2b:512").unwrap();
+        let fixed = computer.compute_hash_text("abc", "blake2b").unwrap();
+        assert_eq!(full.hash, fixed.hash);
+        
+        // Lengths must be whole bytes within the algorithm's maximum
+        assert!(HashRegistry::get_hasher("blake2b:7").is_err());
+        assert!(HashRegistry::get_hasher("blake2b:520").is_err());
+        assert!(HashRegistry::get_hasher("blake2s:512").is_err());
+        assert!(HashRegistry::get_hasher("sha256:256").is_err());
+    }
+    
+    #[test]
+    fn test_sha2_state_roundtrip() {
+        let data: Vec<u8> = (0..10_000u32).map(|i| (i * 31 % 251) as u8).collect();
+        
+        for algorithm in ["sha224", "sha256", "sha384", "sha512"] {
+            // The stock sha2 hasher is the reference for the hand-padded one
+            let mut whole = HashRegistry::get_hasher(algorithm).unwrap();
+            whole.update(&data);
+            let expected = whole.finalize();
+            
+            // Split at a point that leaves a partial block buffered
+            let mut first = HashRegistry::get_resumable_hasher(algorithm).unwrap();
+            first.update(&data[..4099]);
+            let state = first.serialize_state().unwrap();
+            
+            let mut second = HashRegistry::get_resumable_hasher(algorithm).unwrap();
+            second.restore_state(&state).unwrap();
+            second.update(&data[4099..]);
+            assert_eq!(second.finalize(), expected, "{}", algorithm);
+        }
+        
+        // A state from another variant or a truncated state is rejected
+        let state = HashRegistry::get_resumable_hasher("sha256").unwrap().serialize_state().unwrap();
+        assert!(HashRegistry::get_resumable_hasher("sha224").unwrap().restore_state(&state).is_none());
+        assert!(HashRegistry::get_resumable_hasher("sha256").unwrap().restore_state(&state[..20]).is_none());
+        
+        // Regular hashers don't save their state, and other algorithms have no resumable form
+        assert!(HashRegistry::get_hasher("sha256").unwrap().serialize_state().is_none());
+        assert!(HashRegistry::get_resumable_hasher("blake3").is_err());
+    }
+    
+    #[test]
+    fn test_checkpointed_hash_resumes() {
+        let test_dir = "test_checkpointed_hash";
+        fs::create_dir_all(test_dir).unwrap();
+        let file = Path::new(test_dir).join("big.bin");
+        let checkpoint = Path::new(test_dir).join("big.ckpt");
+        let data: Vec<u8> = (0..200_000u32).map(|i| (i % 253) as u8).collect();
+        fs::write(&file, &data).unwrap();
+        let expected = HashComputer::new().compute_hash(&file, "sha256").unwrap().hash;
+        let modified = fs::metadata(&file).unwrap().modified().unwrap();
+        let file_modified = Some(chrono::DateTime::<chrono::Utc>::from(modified).to_rfc3339_opts(chrono::SecondsFormat::Nanos, true));
+        
+        // Simulate an interrupted run by saving the state after the first half
+        let mut hasher = HashRegistry::get_resumable_hasher("sha256").unwrap();
+        hasher.update(&data[..100_000]);
+        HashCheckpoint {
+            algorithm: "sha256".to_string(),
+            file_size: data.len() as u64,
+            file_modified: file_modified.clone(),
+            offset: 100_000,
+            state: bytes_to_hex(&hasher.serialize_state().unwrap()),
+        }
+        .write(&checkpoint)
+        .unwrap();
+        
+        let computer = HashComputer::with_buffer_size(4096);
+        let result = computer.compute_hash_checkpointed(&file, "sha256", &checkpoint, 16 * 1024).unwrap();
+        assert_eq!(result.hash, expected);
+        assert!(!checkpoint.exists());
+        
+        // A fresh run saves checkpoints along the way and removes them at the end
+        let result = computer.compute_hash_checkpointed(&file, "sha256", &checkpoint, 16 * 1024).unwrap();
+        assert_eq!(result.hash, expected);
+        assert!(!checkpoint.exists());
+        
+        // A checkpoint for a file modified since, even at the same size, is refused
+        HashCheckpoint {
+            algorithm: "sha256".to_string(),
+            file_size: data.len() as u64,
+            file_modified: Some("2000-01-01T00:00:00.000000000Z".to_string()),
+            offset: 100_000,
+            state: bytes_to_hex(&hasher.serialize_state().unwrap()),
+        }
+        .write(&checkpoint)
+        .unwrap();
+        assert!(computer.compute_hash_checkpointed(&file, "sha256", &checkpoint, 16 * 1024).is_err());
+        
+        // A checkpoint for a different algorithm is refused rather than producing a wrong hash
+        HashCheckpoint {
+            algorithm: "sha512".to_string(),
+            file_size: data.len() as u64,
+            file_modified,
+            offset: 0,
+            state: String::new(),
+        }
+        .write(&checkpoint)
+        .unwrap();
+        assert!(computer.compute_hash_checkpointed(&file, "sha256", &checkpoint, 16 * 1024).is_err());
+        
+        // Algorithms without serializable state are rejected up front
+        fs::remove_file(&checkpoint).unwrap();
+        assert!(computer.compute_hash_checkpointed(&file, "blake3", &checkpoint, 16 * 1024).is_err());
+        
+        // Cleanup
+        fs::remove_dir_all(test_dir).unwrap();
+    }
+    
+    #[test]
+    fn test_blake2_keyed_vectors() {
+        // Keyed test vectors from the BLAKE2 reference implementation
+        let key_b: Vec<u8> = (0..64).collect();
+        let computer = HashComputer::new().with_blake2_key(Some(key_b));
+        assert_eq!(
+            computer.compute_hash_text("", "blake2b").unwrap().hash,
+            "10ebb67700b1868efb4417987acf4690ae9d972fb7a590c2f02871799aaa4786b5e996e8f0f4eb981fc214b005f42d2ff4233499391653df7aefcbc13fc51568"
+        );
+        let results = computer.compute_multiple_hashes_reader(&[0u8][..], &["blake2b".to_string()], Path::new("<stdin>")).unwrap();
+        assert_eq!(
+            results[0].hash,
+            "961f6dd1e4dd30f63901690c512e78e4b45e4742ed197c3c5e45c549fd25f2e4187b0bc9fe30492b16b0d0bc4ef9b0f34c7003fac09a5ef1532e69430234cebd"
+        );
+        
+        let key_s: Vec<u8> = (0..32).collect();
+        let computer = HashComputer::new().with_blake2_key(Some(key_s));
+        assert_eq!(
+            computer.compute_hash_text("", "blake2s").unwrap().hash,
+            "48a8997da407876b3d79c0d92325ad3b89cbb754d86ab71aee047ad345fd2c49"
+        );
+        
+        // Keys are rejected for non-BLAKE2 algorithms and when too long
+        assert!(computer.compute_hash_text("", "sha256").is_err());
+        let long_key = HashComputer::new().with_blake2_key(Some(vec![0u8; 33]));
+        assert!(long_key.compute_hash_text("", "blake2s").is_err());
+    }
+    
+    #[test]
+    fn test_hex_to_bytes() {
+        assert_eq!(hex_to_bytes("00ff1A"), Some(vec![0x00, 0xff, 0x1a]));
+        assert_eq!(hex_to_bytes(""), Some(vec![]));
+        assert_eq!(hex_to_bytes("abc"), None);
+        assert_eq!(hex_to_bytes("zz"), None);
+    }
 }
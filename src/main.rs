@@ -11,6 +11,13 @@ mod wildcard;
 mod compare;
 mod dedup;
 mod analyze;
+mod time_utils;
+mod prune;
+mod split;
+mod url_input;
+mod archive;
+mod check;
+mod decompress;
 
 use cli::{parse_args, Command};
 use hash::{HashComputer, HashRegistry};
@@ -21,6 +28,8 @@ use database::DatabaseFormat;
 use error::HashUtilityError;
 use std::path::{Path, PathBuf};
 use std::process;
+use std::sync::Arc;
+use std::sync::atomic::{AtomicBool, Ordering};
 use std::io::IsTerminal;
 
 fn main() {
@@ -28,14 +37,14 @@ fn main() {
     let cli = match parse_args() {
         Ok(cli) => cli,
         Err(e) => {
-            eprintln!("Error: {}", e);
+            eprintln!("{}", e);
             process::exit(1);
         }
     };
     
     // Check if running with no arguments and stdin is a terminal (not piped)
     // If so, show help instead of waiting for stdin
-    if cli.command.is_none() && cli.file.is_none() && cli.text.is_none() && std::io::stdin().is_terminal() {
+    if cli.command.is_none() && cli.file.is_none() && cli.text.is_none() && cli.verify_url.is_none() && cli.archive.is_none() && cli.check.is_none() && std::io::stdin().is_terminal() {
         // Show full help by simulating --help flag
         use clap::CommandFactory;
         let mut cmd = cli::Cli::command();
@@ -44,70 +53,345 @@ fn main() {
         process::exit(0);
     }
     
+    // Resolve platform-specific options before the command is moved out of cli
+    let direct_io = cli.direct_io_requested();
+    let include_xattrs = cli.include_xattrs_requested();
+    let ignore_case = cli.ignore_case || wildcard::case_insensitive_by_default();
+    if cli.si {
+        path_utils::SizeUnits::Decimal.set_global();
+    }
+    let json_errors = match cli.error_format.to_lowercase().as_str() {
+        "text" => false,
+        "json" => true,
+        _ => {
+            eprintln!("{}", HashUtilityError::InvalidArguments {
+                message: format!("Invalid error format '{}'. Valid formats are: text, json", cli.error_format),
+            });
+            process::exit(1);
+        }
+    };
+    
+    // Like cmp, equal keeps status 1 for "different" and reports errors with status 2
+    let error_status = if matches!(cli.command, Some(Command::Equal { .. })) { 2 } else { 1 };
+    
     // Dispatch to appropriate handler
     let result = match cli.command {
-        Some(Command::Scan { directory, algorithm, database, hdd, fast, format, json, compress }) => {
-            handle_scan_command(&directory, &algorithm, &database, !hdd, fast, &format, json, compress)
+        Some(Command::Scan { directory, algorithm, database, hdd, fast, format, json, compress, since, store_permissions, with_timestamp, skip_dir, path_style, with_header, structure_only, root, timeout, profile, verify_after, warn_duplicates, algorithm_map, binary, report_slow, append, dedupe_entries, progress_by, progress_template, exclude_from, count_only, paths_relative_to_cwd, hash_symlink_targets, .. }) => {
+            handle_scan_command(ScanOptions {
+                directory_pattern: directory.as_deref(),
+                root_specs: &root,
+                algorithm: &algorithm,
+                output: &database,
+                parallel: !hdd,
+                fast,
+                format_str: &format,
+                json,
+                compress,
+                direct_io,
+                since: since.as_deref(),
+                include_xattrs,
+                store_permissions,
+                with_timestamp,
+                ignore_case,
+                skip_dirs: &skip_dir,
+                path_style_str: &path_style,
+                with_header,
+                structure_only,
+                timeout: timeout.as_deref(),
+                profile,
+                verify_after,
+                warn_duplicates,
+                algorithm_map_specs: &algorithm_map,
+                binary,
+                report_slow: report_slow.unwrap_or(0),
+                append,
+                dedupe_entries: dedupe_entries || append,
+                progress_by_str: &progress_by,
+                progress_template: scan::checked_progress_template(progress_template),
+                exclude_from: exclude_from.as_deref(),
+                count_only,
+                paths_relative_to_cwd,
+                hash_symlink_targets,
+            })
         }
-        Some(Command::Verify { database, directory, hdd, json }) => {
-            handle_verify_command(&database, &directory, !hdd, json)
+        Some(Command::Verify { database, directory, hdd, json, no_progress, intersection_only, path_map, two_pass, normalize_unicode, verify_sample, seed, compare_sizes, file, lenient, merge_databases, algorithm, strict, skip_dir, exclude_from, .. }) => {
+            handle_verify_command(VerifyOptions {
+                database_pattern: &database,
+                directory_pattern: &directory,
+                parallel: !hdd,
+                json,
+                include_xattrs,
+                ignore_case,
+                show_progress: !no_progress,
+                intersection_only,
+                path_map_specs: &path_map,
+                two_pass,
+                normalize_unicode: normalize_unicode.as_deref(),
+                verify_sample,
+                seed,
+                compare_sizes,
+                file: file.as_deref(),
+                lenient,
+                merge_databases,
+                algorithm,
+                strict,
+                skip_dirs: &skip_dir,
+                exclude_from: exclude_from.as_deref(),
+            })
         }
-        Some(Command::Benchmark { size_mb, json }) => {
-            handle_benchmark_command(size_mb, json)
+        Some(Command::Prune { database, directory, output }) => {
+            handle_prune_command(&database, &directory, output.as_deref())
         }
-        Some(Command::List { json }) => {
-            handle_list_command(json)
+        Some(Command::Split { database, shards, output }) => {
+            handle_split_command(&database, shards, output.as_deref())
         }
-        Some(Command::Compare { database1, database2, output, format }) => {
-            handle_compare_command(&database1, &database2, output.as_deref(), &format)
+        Some(Command::Benchmark { size_mb, json, sweep, algorithm, warmup, runs, color }) => {
+            if sweep {
+                handle_benchmark_sweep_command(&algorithm, size_mb, json)
+            } else {
+                handle_benchmark_command(size_mb, json, warmup, runs, &color)
+            }
+        }
+        Some(Command::List { json, crypto_only, pq_only, show_aliases }) => {
+            handle_list_command(json, crypto_only, pq_only, show_aliases)
+        }
+        Some(Command::Compare { database1, database2, output, format, skip_duplicates, no_progress, strict, canonicalize_paths, ref_dir, normalize_unicode }) => {
+            let canonicalize_base = canonicalize_paths.then(|| ref_dir.unwrap_or_else(|| PathBuf::from(".")));
+            handle_compare_command(CompareOptions {
+                database1: &database1,
+                database2: &database2,
+                output: output.as_deref(),
+                format: &format,
+                detect_duplicates: !skip_duplicates,
+                show_progress: !no_progress,
+                strict,
+                canonicalize_base,
+                normalize_unicode: normalize_unicode.as_deref(),
+            })
+        }
+        Some(Command::CompareDir { database, directory, output, format, hdd, skip_dir, skip_duplicates, no_progress }) => {
+            handle_compare_dir_command(CompareDirOptions {
+                database: &database,
+                directory: &directory,
+                output: output.as_deref(),
+                format: &format,
+                parallel: !hdd,
+                detect_duplicates: !skip_duplicates,
+                show_progress: !no_progress,
+                skip_dirs: &skip_dir,
+            })
+        }
+        Some(Command::CompareMulti { databases, output, format }) => {
+            handle_compare_multi_command(&databases, output.as_deref(), &format)
         }
         Some(Command::Version) => {
             handle_version_command()
         }
-        Some(Command::Dedup { directory, fast, output, json }) => {
-            handle_dedup_command(&directory, fast, output.as_deref(), json)
+        Some(Command::Selftest) => {
+            handle_selftest_command()
+        }
+        Some(Command::Equal { inputs, text, algorithm }) => {
+            handle_equal_command(&inputs, &text, &algorithm)
+        }
+        Some(Command::Dedup { directory, fast, output, json, json_ndjson, skip_dir, prefer_dir, continue_from, min_duplicate_size, sort, reverse, progress_template, count_only, spill }) => {
+            handle_dedup_command(DedupOptions {
+                directory: &directory,
+                fast,
+                output: output.as_deref(),
+                json,
+                json_ndjson,
+                skip_dirs: &skip_dir,
+                prefer_dirs: &prefer_dir,
+                continue_from,
+                min_duplicate_size,
+                sort: &sort,
+                reverse,
+                progress_template: scan::checked_progress_template(progress_template),
+                count_only,
+                spill,
+            })
         }
         Some(Command::Analyze { database, json, output }) => {
             handle_analyze_command(&database, json, output.as_deref())
         }
+        Some(Command::Stats { database, json }) => {
+            handle_stats_command(&database, json)
+        }
+        None if cli.check.is_some() => {
+            handle_check_command(CheckOptions {
+                checksum_file: cli.check.as_deref().unwrap(),
+                algorithms: &cli.algorithms,
+                algorithm_given: cli.algorithm_given,
+                json: cli.json,
+                ignore_missing: cli.ignore_missing,
+                strict: cli.strict,
+                lenient: cli.lenient,
+                buffer_size: cli.buffer_size,
+                direct_io,
+                include_xattrs,
+            })
+        }
+        None if cli.stdin_filenames => {
+            handle_stdin_filenames_command(&cli.algorithms, cli.fast, cli.buffer_size, direct_io, include_xattrs)
+        }
+        None if cli.verify_url.is_some() => {
+            handle_verify_url_command(cli.verify_url.as_deref().unwrap(), cli.expected.as_deref(), &cli.algorithms, cli.json, cli.buffer_size)
+        }
         None => {
             // No subcommand means hash mode (default)
-            handle_hash_command(cli.file.as_deref(), cli.text.as_deref(), &cli.algorithms, cli.output.as_deref(), cli.fast, cli.json)
+            handle_hash_command(HashOptions {
+                file_pattern: cli.file.as_deref(),
+                text: cli.text.as_deref(),
+                archive: cli.archive.as_deref(),
+                algorithms: &cli.algorithms,
+                output: cli.output.as_deref(),
+                fast: cli.fast,
+                json: cli.json,
+                buffer_size: cli.buffer_size,
+                direct_io,
+                include_xattrs,
+                ignore_case,
+                skip: cli.skip.map(|skip| skip as u64),
+                count: cli.count.map(|count| count as u64),
+                decompress: cli.decompress,
+                no_trailing_newline: cli.no_trailing_newline,
+                hash_only: cli.hash_only,
+                blake2_key: cli.blake2_key.as_deref(),
+                checkpoint: cli.checkpoint.as_deref(),
+                checkpoint_interval: cli.checkpoint_interval as u64,
+                combined: cli.combined,
+            })
         }
     };
     
     // Handle errors
     if let Err(e) = result {
-        eprintln!("Error: {}", e);
-        process::exit(1);
+        if json_errors {
+            eprintln!("{}", e.to_json());
+        } else {
+            eprintln!("{}", e);
+        }
+        process::exit(error_status);
     }
 }
 
-/// Handle the hash command: compute and display hash(es) for a file, text, or stdin
-fn handle_hash_command(
-    file_pattern: Option<&str>,
-    text: Option<&str>,
-    algorithms: &[String],
-    output: Option<&std::path::Path>,
+/// Options for the hash command, resolved from the top-level command-line flags
+struct HashOptions<'a> {
+    file_pattern: Option<&'a str>,
+    text: Option<&'a str>,
+    archive: Option<&'a Path>,
+    algorithms: &'a [String],
+    output: Option<&'a Path>,
     fast: bool,
     json: bool,
-) -> Result<(), HashUtilityError> {
-    let computer = HashComputer::new();
+    buffer_size: Option<usize>,
+    direct_io: bool,
+    include_xattrs: bool,
+    ignore_case: bool,
+    skip: Option<u64>,
+    count: Option<u64>,
+    decompress: bool,
+    no_trailing_newline: bool,
+    hash_only: bool,
+    /// BLAKE2 key as hex digits, as given on the command line
+    blake2_key: Option<&'a str>,
+    checkpoint: Option<&'a Path>,
+    checkpoint_interval: u64,
+    combined: bool,
+}
+
+/// Handle the hash command: compute and display hash(es) for a file, text, or stdin
+fn handle_hash_command(options: HashOptions) -> Result<(), HashUtilityError> {
+    use std::io::Read;
+    
+    let HashOptions {
+        file_pattern, text, archive, algorithms, output, fast, json, buffer_size, direct_io, include_xattrs, ignore_case,
+        skip, count, decompress, no_trailing_newline, hash_only, blake2_key, checkpoint, checkpoint_interval, combined,
+    } = options;
+    
+    let blake2_key = blake2_key
+        .map(|key| {
+            hash::hex_to_bytes(key).ok_or_else(|| HashUtilityError::InvalidArguments {
+                message: format!("Invalid BLAKE2 key '{}': expected pairs of hex digits", key),
+            })
+        })
+        .transpose()?;
+    
+    // Use a fixed buffer if requested, otherwise size it per file
+    let computer = match buffer_size {
+        Some(size) => HashComputer::with_buffer_size(size),
+        None => HashComputer::new(),
+    }
+    .with_direct_io(direct_io)
+    .with_xattrs(include_xattrs)
+    .with_blake2_key(blake2_key);
     
     // Compute hashes for all specified algorithms
     let results = match (file_pattern, text) {
+        (Some(url), None) if url_input::is_url(url) => {
+            // Stream the download through the hashers (fast mode needs random access)
+            if fast {
+                return Err(HashUtilityError::InvalidArguments {
+                    message: "Fast mode is not supported when hashing a URL".to_string(),
+                });
+            }
+            let mut body = url_input::open_url(url)?;
+            skip_bytes(&mut body, skip)?;
+            match count {
+                Some(count) => computer.compute_multiple_hashes_reader(body.take(count), algorithms, std::path::Path::new(url))?,
+                None => computer.compute_multiple_hashes_reader(body, algorithms, std::path::Path::new(url))?,
+            }
+        }
+        (Some(pattern), None) if combined => {
+            // One hash per matched directory, over all of its files in path order
+            let engine = ScanEngine::new();
+            let mut all_results = Vec::new();
+            for directory in wildcard::expand_pattern(pattern, ignore_case)? {
+                if !directory.is_dir() {
+                    return Err(HashUtilityError::InvalidArguments {
+                        message: format!("--combined needs a directory, but '{}' is not one", directory.display()),
+                    });
+                }
+                for algorithm in algorithms {
+                    all_results.push(engine.combined_hash(&directory, algorithm)?);
+                }
+            }
+            all_results
+        }
         (Some(pattern), None) => {
             // Expand wildcard pattern to get list of files
-            let files = wildcard::expand_pattern(pattern)?;
+            let files = wildcard::expand_pattern(pattern, ignore_case)?;
             
             // Determine if we should show progress (only for single file)
             let show_progress = files.len() == 1;
             
+            // A checkpoint holds the state of one hasher over one file
+            if checkpoint.is_some() && (files.len() != 1 || algorithms.len() != 1) {
+                return Err(HashUtilityError::InvalidArguments {
+                    message: "--checkpoint needs exactly one file and one algorithm".to_string(),
+                });
+            }
+            
             // Hash all matched files
             let mut all_results = Vec::new();
             for file_path in files {
-                if fast {
-                    // Use fast mode for each algorithm
+                // Compressed files are streamed through their decoder; other files hash as usual
+                let decompressed = if decompress { decompress::open_decompressed(&file_path)? } else { None };
+                
+                if let Some(mut reader) = decompressed {
+                    skip_bytes(&mut reader, skip)?;
+                    let file_results = match count {
+                        Some(count) => computer.compute_multiple_hashes_reader(reader.take(count), algorithms, &file_path)?,
+                        None => computer.compute_multiple_hashes_reader(reader, algorithms, &file_path)?,
+                    };
+                    all_results.extend(file_results);
+                } else if let Some(checkpoint) = checkpoint {
+                    all_results.push(computer.compute_hash_checkpointed(&file_path, &algorithms[0], checkpoint, checkpoint_interval)?);
+                } else if skip.is_some() || count.is_some() {
+                    // Hash only the requested byte range; a count also bounds endless devices
+                    all_results.extend(computer.compute_multiple_hashes_range(&file_path, algorithms, skip.unwrap_or(0), count)?);
+                } else if fast {
+                    // Use fast mode for each algorithm; printed hashes keep the original sampling scheme
                     for algorithm in algorithms {
                         all_results.push(computer.compute_hash_fast(&file_path, algorithm)?);
                     }
@@ -129,13 +413,24 @@ fn handle_hash_command(
             computer.compute_multiple_hashes_text(text_input, algorithms)?
         }
         (None, None) => {
-            // Hash from stdin (fast mode not supported for stdin)
+            // Hash archive members or stdin (fast mode needs random access, so neither supports it)
             if fast {
+                let source = if archive.is_some() { "hashing an archive" } else { "reading from stdin" };
                 return Err(HashUtilityError::InvalidArguments {
-                    message: "Fast mode is not supported when reading from stdin".to_string(),
+                    message: format!("Fast mode is not supported when {}", source),
                 });
             }
-            computer.compute_multiple_hashes_stdin(algorithms)?
+            match archive {
+                Some(archive_path) => archive::hash_archive(archive_path, &computer, algorithms)?,
+                None => {
+                    let mut stdin = std::io::stdin().lock();
+                    skip_bytes(&mut stdin, skip)?;
+                    match count {
+                        Some(count) => computer.compute_multiple_hashes_reader(stdin.take(count), algorithms, Path::new("-"))?,
+                        None => computer.compute_multiple_hashes_reader(stdin, algorithms, Path::new("-"))?,
+                    }
+                }
+            }
         }
         (Some(_), Some(_)) => {
             // This should be prevented by clap's conflicts_with, but handle it anyway
@@ -181,8 +476,13 @@ fn handle_hash_command(
         // Plain text output
         let mut output_lines = Vec::new();
         
-        // Group results by file path for better formatting when multiple algorithms are used
-        if algorithms.len() > 1 {
+        if hash_only {
+            // Bare hashes for scripting, one per file and algorithm in the order they were computed
+            for result in &results {
+                output_lines.push(result.hash.clone());
+            }
+        } else if algorithms.len() > 1 {
+            // Group results by file path for better formatting when multiple algorithms are used
             // Multiple algorithms - show algorithm name with each hash
             use std::collections::HashMap;
             let mut by_file: HashMap<PathBuf, Vec<&hash::HashResult>> = HashMap::new();
@@ -213,7 +513,12 @@ fn handle_hash_command(
             }
         }
         
-        output_lines.join("\n") + "\n"
+        // Terminate the last line like sha256sum does, unless asked not to
+        let mut output = output_lines.join("\n");
+        if !no_trailing_newline {
+            output.push('\n');
+        }
+        output
     };
     
     // Write to output destination
@@ -230,107 +535,531 @@ fn handle_hash_command(
     Ok(())
 }
 
-/// Handle the scan command: scan directory and write database
-fn handle_scan_command(
-    directory_pattern: &str,
-    algorithm: &str,
-    output: &std::path::Path,
+/// Discard the first `skip` bytes of a stream that cannot seek (stdin, downloads)
+fn skip_bytes(reader: &mut impl std::io::Read, skip: Option<u64>) -> Result<(), HashUtilityError> {
+    if let Some(skip) = skip {
+        std::io::copy(&mut std::io::Read::take(reader, skip), &mut std::io::sink()).map_err(|e| {
+            HashUtilityError::from_io_error(e, "skipping input", None)
+        })?;
+    }
+    Ok(())
+}
+
+/// Handle --stdin-filenames: hash each file named on stdin and print its line as soon as it is done
+/// 
+/// Runs until stdin is closed. Files that can't be hashed are reported on stderr
+/// without stopping the worker; the exit code is 1 if any failed.
+fn handle_stdin_filenames_command(
+    algorithms: &[String],
+    fast: bool,
+    buffer_size: Option<usize>,
+    direct_io: bool,
+    include_xattrs: bool,
+) -> Result<(), HashUtilityError> {
+    use std::io::{BufRead, Write};
+    
+    let algorithm = match algorithms {
+        [algorithm] => algorithm,
+        _ => {
+            return Err(HashUtilityError::InvalidArguments {
+                message: "--stdin-filenames takes exactly one algorithm".to_string(),
+            });
+        }
+    };
+    // Fail on an unknown algorithm up front rather than once per line
+    HashRegistry::get_hasher(algorithm)?;
+    
+    let computer = match buffer_size {
+        Some(size) => HashComputer::with_buffer_size(size),
+        None => HashComputer::new(),
+    }
+    .with_direct_io(direct_io)
+    .with_xattrs(include_xattrs);
+    
+    let write_error = |e| HashUtilityError::from_io_error(e, "writing output", None);
+    let mut stdout = std::io::stdout().lock();
+    let mut failed = 0;
+    for line in std::io::stdin().lock().lines() {
+        let line = line.map_err(|e| HashUtilityError::from_io_error(e, "reading file names from stdin", None))?;
+        let name = line.trim_end_matches('\r');
+        if name.is_empty() {
+            continue;
+        }
+        
+        let path = Path::new(name);
+        let result = if fast {
+            computer.compute_hash_fast(path, algorithm)
+        } else {
+            computer.compute_hash(path, algorithm)
+        };
+        match result {
+            Ok(result) => {
+                writeln!(stdout, "{}  {}", result.hash, name).map_err(write_error)?;
+                // The consumer is waiting on this line, so don't let it sit in the buffer
+                stdout.flush().map_err(write_error)?;
+            }
+            Err(e) => {
+                eprintln!("{}", e);
+                failed += 1;
+            }
+        }
+    }
+    
+    if failed > 0 {
+        process::exit(1);
+    }
+    
+    Ok(())
+}
+
+/// Handle --verify-url: download a file and compare its hash with the published checksum
+fn handle_verify_url_command(
+    url: &str,
+    expected: Option<&str>,
+    algorithms: &[String],
+    json: bool,
+    buffer_size: Option<usize>,
+) -> Result<(), HashUtilityError> {
+    let algorithm = match algorithms {
+        [algorithm] => algorithm,
+        _ => {
+            return Err(HashUtilityError::InvalidArguments {
+                message: "--verify-url takes exactly one algorithm".to_string(),
+            });
+        }
+    };
+    
+    let computer = match buffer_size {
+        Some(size) => HashComputer::with_buffer_size(size),
+        None => HashComputer::new(),
+    };
+    let result = url_input::verify_url(&computer, url, algorithm, expected)?;
+    
+    if json {
+        #[derive(serde::Serialize)]
+        struct VerifyUrlOutput<'a> {
+            #[serde(flatten)]
+            result: &'a url_input::UrlVerification,
+            passed: bool,
+            timestamp: String,
+        }
+        
+        let output = VerifyUrlOutput {
+            result: &result,
+            passed: result.passed(),
+            timestamp: chrono::Utc::now().to_rfc3339(),
+        };
+        let json_output = serde_json::to_string_pretty(&output).map_err(|e| {
+            HashUtilityError::InvalidArguments {
+                message: format!("Failed to serialize JSON: {}", e),
+            }
+        })?;
+        println!("{}", json_output);
+    } else if result.passed() {
+        println!("{}: OK", url);
+    } else {
+        println!("{}: FAILED", url);
+        println!("  Expected: {}", result.expected);
+        println!("  Actual:   {}", result.actual);
+    }
+    
+    if !result.passed() {
+        process::exit(1);
+    }
+    
+    Ok(())
+}
+
+/// Options for --check, resolved from the top-level command-line flags
+struct CheckOptions<'a> {
+    checksum_file: &'a Path,
+    algorithms: &'a [String],
+    /// -a was given explicitly; otherwise each line's algorithm is inferred from its hash length
+    algorithm_given: bool,
+    json: bool,
+    ignore_missing: bool,
+    strict: bool,
+    lenient: bool,
+    buffer_size: Option<usize>,
+    direct_io: bool,
+    include_xattrs: bool,
+}
+
+/// Handle --check: verify the files listed in a checksum file
+fn handle_check_command(options: CheckOptions) -> Result<(), HashUtilityError> {
+    let CheckOptions { checksum_file, algorithms, algorithm_given, json, ignore_missing, strict, lenient, buffer_size, direct_io, include_xattrs } = options;
+    
+    let algorithm = match algorithms {
+        _ if !algorithm_given => None,
+        [algorithm] => Some(algorithm.as_str()),
+        _ => {
+            return Err(HashUtilityError::InvalidArguments {
+                message: "--check takes exactly one algorithm".to_string(),
+            });
+        }
+    };
+    
+    let computer = match buffer_size {
+        Some(size) => HashComputer::with_buffer_size(size),
+        None => HashComputer::new(),
+    }
+    .with_direct_io(direct_io)
+    .with_xattrs(include_xattrs);
+    let report = check::CheckEngine::new(computer)
+        .with_ignore_missing(ignore_missing)
+        .with_strict(strict)
+        .with_lenient(lenient)
+        .check(checksum_file, algorithm)?;
+    
+    if json {
+        let json_output = report.to_json().map_err(|e| {
+            HashUtilityError::InvalidArguments {
+                message: format!("Failed to serialize JSON: {}", e),
+            }
+        })?;
+        println!("{}", json_output);
+    } else {
+        print!("{}", report.to_plain_text());
+    }
+    
+    if !report.passed() {
+        process::exit(1);
+    }
+    
+    Ok(())
+}
+
+/// Read versus hashing time reported by `scan --profile` (summed over all hashing threads)
+#[derive(serde::Serialize)]
+struct ScanProfile {
+    read_secs: f64,
+    hash_secs: f64,
+    bytes_read: u64,
+}
+
+/// Options for the scan command, resolved from its command-line flags
+struct ScanOptions<'a> {
+    directory_pattern: Option<&'a str>,
+    /// `--root DIR:MODE` specs, parsed by the handler
+    root_specs: &'a [String],
+    algorithm: &'a str,
+    output: &'a Path,
     parallel: bool,
     fast: bool,
-    format_str: &str,
+    format_str: &'a str,
     json: bool,
     compress: bool,
-) -> Result<(), HashUtilityError> {
+    direct_io: bool,
+    since: Option<&'a str>,
+    include_xattrs: bool,
+    store_permissions: bool,
+    with_timestamp: bool,
+    ignore_case: bool,
+    skip_dirs: &'a [String],
+    path_style_str: &'a str,
+    with_header: bool,
+    structure_only: bool,
+    timeout: Option<&'a str>,
+    profile: bool,
+    verify_after: bool,
+    warn_duplicates: bool,
+    /// `--algorithm-map EXT=ALGORITHM` specs, parsed by the handler
+    algorithm_map_specs: &'a [String],
+    binary: bool,
+    report_slow: usize,
+    append: bool,
+    dedupe_entries: bool,
+    progress_by_str: &'a str,
+    progress_template: Option<String>,
+    exclude_from: Option<&'a Path>,
+    count_only: bool,
+    paths_relative_to_cwd: bool,
+    hash_symlink_targets: bool,
+}
+
+/// Handle the scan command: scan directory and write database
+fn handle_scan_command(options: ScanOptions) -> Result<(), HashUtilityError> {
+    let ScanOptions {
+        directory_pattern, root_specs, algorithm, output, parallel, fast, format_str, json, compress, direct_io, since,
+        include_xattrs, store_permissions, with_timestamp, ignore_case, skip_dirs, path_style_str, with_header,
+        structure_only, timeout, profile, verify_after, warn_duplicates, algorithm_map_specs, binary, report_slow, append,
+        dedupe_entries, progress_by_str, progress_template, exclude_from, count_only, paths_relative_to_cwd,
+        hash_symlink_targets,
+    } = options;
+    
+    // Parse the modification time cutoff, if any
+    let since = since.map(time_utils::parse_since).transpose()?;
+    
+    // Patterns from --exclude-from follow the same case rules as wildcard arguments
+    let exclude_patterns = exclude_from
+        .map(|path| wildcard::ExcludePatterns::from_file(path, ignore_case))
+        .transpose()?
+        .unwrap_or_default();
+    
+    // The timeout covers the whole scan, across all roots
+    let timeout = timeout.map(time_utils::parse_duration).transpose()?;
+    let deadline = timeout.map(|timeout| std::time::Instant::now() + timeout);
+    
+    // Ctrl-C stops the scan cleanly so everything hashed so far is written; a second Ctrl-C aborts
+    let cancel = Arc::new(AtomicBool::new(false));
+    let cancel_handler = Arc::clone(&cancel);
+    if let Err(e) = ctrlc::set_handler(move || {
+        if cancel_handler.swap(true, Ordering::Relaxed) {
+            process::exit(130);
+        }
+        eprintln!("\nInterrupted, finishing up and saving partial results (press Ctrl-C again to abort)...");
+    }) {
+        eprintln!("Warning: Failed to install Ctrl-C handler: {}", e);
+    }
+    
     // Parse format string
     let format = match format_str.to_lowercase().as_str() {
         "standard" => DatabaseFormat::Standard,
         "hashdeep" => DatabaseFormat::Hashdeep,
+        "tsv" => DatabaseFormat::Tsv,
+        "coreutils" => DatabaseFormat::Coreutils,
+        "json-db" => DatabaseFormat::JsonDb,
         _ => {
             return Err(HashUtilityError::InvalidArguments {
-                message: format!("Invalid format '{}'. Valid formats are: standard, hashdeep", format_str),
+                message: format!("Invalid format '{}'. Valid formats are: standard, hashdeep, tsv, coreutils, json-db", format_str),
             });
         }
     };
     
-    // Expand wildcard pattern to get list of directories
-    let directories = wildcard::expand_pattern(directory_pattern)?;
+    // Parse path style string
+    let path_style = match path_style_str.to_lowercase().as_str() {
+        "native" => path_utils::PathStyle::Native,
+        "bare" => path_utils::PathStyle::Bare,
+        "dot" => path_utils::PathStyle::Dot,
+        _ => {
+            return Err(HashUtilityError::InvalidArguments {
+                message: format!("Invalid path style '{}'. Valid styles are: native, bare, dot", path_style_str),
+            });
+        }
+    };
+    
+    // Parse progress unit string
+    let progress_by = match progress_by_str.to_lowercase().as_str() {
+        "files" => scan::ProgressBy::Files,
+        "bytes" => scan::ProgressBy::Bytes,
+        _ => {
+            return Err(HashUtilityError::InvalidArguments {
+                message: format!("Invalid progress unit '{}'. Valid units are: files, bytes", progress_by_str),
+            });
+        }
+    };
+    
+    // Only the standard format has columns for permissions and timestamps
+    if store_permissions && format != DatabaseFormat::Standard {
+        return Err(HashUtilityError::InvalidArguments {
+            message: "--store-permissions is only supported with the standard format".to_string(),
+        });
+    }
+    if with_timestamp && format != DatabaseFormat::Standard {
+        return Err(HashUtilityError::InvalidArguments {
+            message: "--with-timestamp is only supported with the standard format".to_string(),
+        });
+    }
+    if with_header && format != DatabaseFormat::Standard {
+        return Err(HashUtilityError::InvalidArguments {
+            message: "--with-header is only supported with the standard format".to_string(),
+        });
+    }
+    if structure_only && format != DatabaseFormat::Standard {
+        return Err(HashUtilityError::InvalidArguments {
+            message: "--structure-only is only supported with the standard format".to_string(),
+        });
+    }
+    if hash_symlink_targets && format != DatabaseFormat::Standard {
+        return Err(HashUtilityError::InvalidArguments {
+            message: "--hash-symlink-targets is only supported with the standard format".to_string(),
+        });
+    }
+    
+    // Hashdeep files name their algorithm once in the header, and coreutils lines not at all, so every entry must share it
+    let algorithm_map = algorithm_map_specs
+        .iter()
+        .map(|spec| scan::AlgorithmMap::parse(spec))
+        .collect::<Result<Vec<_>, _>>()?;
+    if !algorithm_map.is_empty() && matches!(format, DatabaseFormat::Hashdeep | DatabaseFormat::Coreutils) {
+        return Err(HashUtilityError::InvalidArguments {
+            message: format!("--algorithm-map is not supported with the {} format", format_str.to_lowercase()),
+        });
+    }
+    
+    // Coreutils and tsv lines have no mode column, so sampled hashes couldn't be told apart from full ones
+    if fast && matches!(format, DatabaseFormat::Coreutils | DatabaseFormat::Tsv) {
+        return Err(HashUtilityError::InvalidArguments {
+            message: format!("--fast is not supported with the {} format", format_str.to_lowercase()),
+        });
+    }
+    if binary && format != DatabaseFormat::Coreutils {
+        return Err(HashUtilityError::InvalidArguments {
+            message: "--binary is only supported with the coreutils format".to_string(),
+        });
+    }
+    
+    // Expand wildcard pattern to get list of directories; these use the global --hdd setting
+    let mut roots = Vec::new();
+    if let Some(pattern) = directory_pattern {
+        for path in wildcard::expand_pattern(pattern, ignore_case)? {
+            roots.push(scan::ScanRoot { path, parallel });
+        }
+    }
+    
+    // --root entries carry their own parallelism
+    for spec in root_specs {
+        roots.push(scan::ScanRoot::parse(spec)?);
+    }
     
     // Verify all matched paths are directories
-    for dir in &directories {
-        if !dir.is_dir() {
+    for root in &roots {
+        if !root.path.is_dir() {
             return Err(HashUtilityError::InvalidArguments {
-                message: format!("Path '{}' is not a directory", dir.display()),
+                message: format!("Path '{}' is not a directory", root.path.display()),
             });
         }
     }
     
-    let engine = ScanEngine::with_parallel(parallel)
-        .with_fast_mode(fast)
-        .with_format(format);
+    // The read-back check resolves database paths against a single directory
+    if verify_after && roots.len() > 1 {
+        return Err(HashUtilityError::InvalidArguments {
+            message: "--verify-after is only supported when scanning a single directory".to_string(),
+        });
+    }
+    
+    // A JSON database is one document, so it can't be added to or written in pieces
+    if format == DatabaseFormat::JsonDb && (append || dedupe_entries || roots.len() > 1) {
+        return Err(HashUtilityError::InvalidArguments {
+            message: "The json-db format is written in one go: scan a single directory, without --append or --dedupe-entries".to_string(),
+        });
+    }
+    
+    // One profile accumulates read and hashing time across every root and thread
+    let profile = profile.then(|| Arc::new(hash::HashProfile::new()));
+    
+    // Each root gets an engine configured for its own storage
+    let engine_for = |root: &scan::ScanRoot| {
+        ScanEngine::with_parallel(root.parallel)
+            .with_fast_mode(fast)
+            .with_format(format)
+            .with_binary_marker(binary)
+            .with_direct_io(direct_io)
+            .with_since(since)
+            .with_xattrs(include_xattrs)
+            .with_store_permissions(store_permissions)
+            .with_timestamp(with_timestamp)
+            .with_skip_dirs(skip_dirs.to_vec())
+            .with_exclude_patterns(exclude_patterns.clone())
+            .with_algorithm_map(algorithm_map.clone())
+            .with_path_style(path_style)
+            .with_paths_relative_to_cwd(paths_relative_to_cwd)
+            .with_header(with_header)
+            .with_structure_only(structure_only)
+            .with_hash_symlink_targets(hash_symlink_targets)
+            .with_deadline(deadline)
+            .with_cancel_flag(Arc::clone(&cancel))
+            .with_profile(profile.clone())
+            .with_report_slow(report_slow)
+            .with_progress_by(progress_by)
+            .with_progress_template(progress_template.clone())
+            .with_append(append)
+            .with_dedupe_entries(dedupe_entries)
+    };
+    
+    // Walk the roots with the same filters, but stop short of hashing or creating the database
+    if count_only {
+        let mut files = 0;
+        let mut total_bytes = 0;
+        for root in &roots {
+            let (count, bytes) = engine_for(root).count_files(&root.path, output)?;
+            files += count;
+            total_bytes += bytes;
+        }
+        return print_file_count(files, total_bytes, json);
+    }
     
     // Scan all matched directories and aggregate stats
     let mut total_stats = scan::ScanStats {
         files_processed: 0,
         files_failed: 0,
+        files_permission_denied: 0,
+        empty_files: 0,
         total_bytes: 0,
         duration: std::time::Duration::new(0, 0),
+        timed_out: false,
+        interrupted: false,
+        slowest_files: Vec::new(),
+        duplicates_removed: 0,
+        duplicate_conflicts: Vec::new(),
     };
     
     // For multiple directories, we need to handle output differently
-    if directories.len() > 1 {
-        // Create the output file first (this will overwrite if it exists)
-        std::fs::File::create(output).map_err(|e| {
-            HashUtilityError::from_io_error(e, "creating output file", Some(output.to_path_buf()))
-        })?;
+    if roots.len() > 1 {
+        // Create the output file first (this will overwrite if it exists), unless adding to it
+        if !append {
+            std::fs::File::create(output).map_err(|e| {
+                HashUtilityError::from_io_error(e, "creating output file", Some(output.to_path_buf()))
+            })?;
+        }
         
-        // Scan each directory and append to the output file
-        for (idx, directory) in directories.iter().enumerate() {
-            // For the first directory, use normal mode (create/overwrite)
-            // For subsequent directories, we need to append
-            let temp_output = if idx == 0 {
-                output.to_path_buf()
-            } else {
-                // Create a temporary file for this directory's results
-                let temp_path = output.with_extension(format!("tmp{}", idx));
-                temp_path
-            };
-            
-            let stats = engine.scan_directory(directory, algorithm, &temp_output)?;
-            
-            // If we used a temp file, append its contents to the main output
-            if idx > 0 {
-                let temp_contents = std::fs::read_to_string(&temp_output).map_err(|e| {
-                    HashUtilityError::from_io_error(e, "reading temp file", Some(temp_output.clone()))
-                })?;
-                
-                use std::io::Write;
-                let mut output_file = std::fs::OpenOptions::new()
-                    .append(true)
-                    .open(output)
-                    .map_err(|e| {
-                        HashUtilityError::from_io_error(e, "opening output file for append", Some(output.to_path_buf()))
-                    })?;
-                
-                output_file.write_all(temp_contents.as_bytes()).map_err(|e| {
-                    HashUtilityError::from_io_error(e, "appending to output file", Some(output.to_path_buf()))
-                })?;
-                
-                // Remove the temp file
-                std::fs::remove_file(&temp_output).ok();
+        // Scan each directory and append to the output file; only the first root writes a header
+        for root in &roots {
+            // Leave the remaining roots unscanned once the deadline has passed or on Ctrl-C
+            if total_stats.timed_out || total_stats.interrupted {
+                break;
             }
             
+            let stats = engine_for(root)
+                .with_append(true)
+                .scan_directory(&root.path, algorithm, output)?;
+            
             total_stats.files_processed += stats.files_processed;
             total_stats.files_failed += stats.files_failed;
+            total_stats.files_permission_denied += stats.files_permission_denied;
+            total_stats.empty_files += stats.empty_files;
             total_stats.total_bytes += stats.total_bytes;
             total_stats.duration += stats.duration;
+            total_stats.timed_out |= stats.timed_out;
+            total_stats.interrupted |= stats.interrupted;
+            
+            // Keep the slowest files across all roots
+            total_stats.slowest_files.extend(stats.slowest_files);
+            total_stats.slowest_files.sort_by_key(|file| std::cmp::Reverse(file.duration));
+            total_stats.slowest_files.truncate(report_slow);
+            
+            total_stats.duplicates_removed += stats.duplicates_removed;
+            total_stats.duplicate_conflicts.extend(stats.duplicate_conflicts);
         }
     } else {
         // Single directory - use normal scan
-        let stats = engine.scan_directory(&directories[0], algorithm, output)?;
+        let stats = engine_for(&roots[0]).scan_directory(&roots[0].path, algorithm, output)?;
         total_stats = stats;
     }
     
     let stats = total_stats;
+    let interrupted = stats.interrupted;
+    
+    // Split the hashing work into time spent reading and time spent in the hasher
+    let profile_output = profile.map(|profile| {
+        let read_secs = profile.read_time().as_secs_f64();
+        let hash_secs = profile.hash_time().as_secs_f64();
+        let total_secs = read_secs + hash_secs;
+        let percent = |secs: f64| if total_secs > 0.0 { secs / total_secs * 100.0 } else { 0.0 };
+        
+        if !json {
+            println!("\nProfile ({} threads):", rayon::current_num_threads());
+            println!("  Read:    {:.3}s ({:.1}%)", read_secs, percent(read_secs));
+            println!("  Hashing: {:.3}s ({:.1}%)", hash_secs, percent(hash_secs));
+            if read_secs > hash_secs {
+                println!("  I/O bound: faster storage (or --hdd on spinning disks) will help more than a faster algorithm");
+            } else {
+                println!("  CPU bound: a faster algorithm (or fast mode) will help more than faster storage");
+            }
+        }
+        
+        ScanProfile { read_secs, hash_secs, bytes_read: profile.bytes() }
+    });
     
     // Compress the database if requested
     let final_output = if compress {
@@ -350,37 +1079,93 @@ fn handle_scan_command(
         output.to_path_buf()
     };
     
+    // Re-read everything that was just hashed, unless the scan was cut short by Ctrl-C
+    let read_back = if verify_after && !interrupted {
+        if !json {
+            println!("\nRe-reading files to confirm the written hashes...");
+        }
+        let engine = VerifyEngine::with_parallel(roots[0].parallel)
+            .with_xattrs(include_xattrs)
+            .with_progress(!json);
+        Some(engine.read_back(&final_output, &roots[0].path)?)
+    } else {
+        None
+    };
+    let read_back_failed = read_back.as_ref().is_some_and(|report| report.has_issues());
+    
+    if let Some(report) = read_back.as_ref().filter(|_| !json) {
+        if read_back_failed {
+            // Hashes that don't survive an immediate re-read point at the storage, not the files
+            report.display();
+            eprintln!("WARNING: Read-back check failed; the storage may be returning unstable data");
+        } else {
+            println!("Read-back check passed: {} files confirmed", report.matches);
+        }
+    }
+    
+    // Group the database just written by hash to point out files with the same content
+    let duplicate_groups = if warn_duplicates {
+        let entries = database::DatabaseHandler::read_database(&final_output)?;
+        compare::CompareEngine::find_duplicates(&entries)
+    } else {
+        Vec::new()
+    };
+    
+    if !duplicate_groups.is_empty() && !json {
+        let files: usize = duplicate_groups.iter().map(|group| group.count).sum();
+        println!("\nWarning: {} files have identical content in {} groups:", files, duplicate_groups.len());
+        for group in &duplicate_groups {
+            println!("  {} ({} files)", group.hash, group.count);
+            for path in &group.paths {
+                println!("    {}", path.display());
+            }
+        }
+    }
+    
     // Output results in JSON if requested
     if json {
         #[derive(serde::Serialize)]
         struct ScanOutput {
             stats: scan::ScanStats,
+            #[serde(skip_serializing_if = "Option::is_none")]
+            profile: Option<ScanProfile>,
+            #[serde(skip_serializing_if = "Option::is_none")]
+            read_back: Option<verify::VerifyReport>,
+            #[serde(skip_serializing_if = "Vec::is_empty")]
+            duplicate_groups: Vec<compare::DuplicateGroup>,
             metadata: ScanMetadata,
         }
         
         #[derive(serde::Serialize)]
         struct ScanMetadata {
             timestamp: String,
-            directory_pattern: String,
+            directory_pattern: Option<String>,
             directories_scanned: Vec<std::path::PathBuf>,
             algorithm: String,
             output_file: std::path::PathBuf,
             parallel: bool,
             fast_mode: bool,
+            structure_only: bool,
             format: String,
+            with_timestamp: bool,
         }
         
         let output = ScanOutput {
             stats,
+            profile: profile_output,
+            read_back,
+            duplicate_groups,
             metadata: ScanMetadata {
                 timestamp: chrono::Utc::now().to_rfc3339(),
-                directory_pattern: directory_pattern.to_string(),
-                directories_scanned: directories,
+                directory_pattern: directory_pattern.map(str::to_string),
+                directories_scanned: roots.into_iter().map(|root| root.path).collect(),
                 algorithm: algorithm.to_string(),
                 output_file: final_output,
                 parallel,
                 fast_mode: fast,
+                structure_only,
                 format: format_str.to_string(),
+                with_timestamp,
             },
         };
         
@@ -393,28 +1178,129 @@ fn handle_scan_command(
         println!("{}", json_output);
     }
     
+    // Like other tools, report an interrupted run with the SIGINT exit status
+    if interrupted {
+        process::exit(130);
+    }
+    if read_back_failed {
+        process::exit(1);
+    }
+    
     Ok(())
 }
 
-/// Handle the verify command: compare database with directory
-fn handle_verify_command(
-    database_pattern: &str,
-    directory_pattern: &str,
+/// Print the result of `--count-only`: how many files would be hashed, and their total size
+fn print_file_count(files: usize, total_bytes: u64, json: bool) -> Result<(), HashUtilityError> {
+    if json {
+        let output = serde_json::json!({ "files": files, "total_bytes": total_bytes });
+        let json_output = serde_json::to_string_pretty(&output).map_err(|e| {
+            HashUtilityError::InvalidArguments {
+                message: format!("Failed to serialize JSON: {}", e),
+            }
+        })?;
+        println!("{}", json_output);
+    } else {
+        println!("Files: {}", files);
+        println!("Total bytes: {} ({})", total_bytes, path_utils::format_size(total_bytes));
+    }
+    Ok(())
+}
+
+/// Options for the verify command, resolved from its command-line flags
+struct VerifyOptions<'a> {
+    database_pattern: &'a str,
+    directory_pattern: &'a str,
     parallel: bool,
     json: bool,
-) -> Result<(), HashUtilityError> {
-    let engine = VerifyEngine::with_parallel(parallel);
+    include_xattrs: bool,
+    ignore_case: bool,
+    show_progress: bool,
+    intersection_only: bool,
+    /// `--path-map OLD=NEW` specs, parsed by the handler
+    path_map_specs: &'a [String],
+    two_pass: bool,
+    normalize_unicode: Option<&'a str>,
+    verify_sample: Option<f64>,
+    seed: Option<u64>,
+    compare_sizes: bool,
+    file: Option<&'a Path>,
+    lenient: bool,
+    merge_databases: bool,
+    algorithm: Option<String>,
+    strict: bool,
+    skip_dirs: &'a [String],
+    exclude_from: Option<&'a Path>,
+}
+
+/// Handle the verify command: compare database with directory
+fn handle_verify_command(options: VerifyOptions) -> Result<(), HashUtilityError> {
+    let VerifyOptions {
+        database_pattern, directory_pattern, parallel, json, include_xattrs, ignore_case, show_progress, intersection_only,
+        path_map_specs, two_pass, normalize_unicode, verify_sample, seed, compare_sizes, file, lenient, merge_databases,
+        algorithm, strict, skip_dirs, exclude_from,
+    } = options;
+    
+    let path_maps = path_map_specs
+        .iter()
+        .map(|spec| verify::PathMap::parse(spec))
+        .collect::<Result<Vec<_>, _>>()?;
+    let unicode_normalization = normalize_unicode.map(path_utils::UnicodeNormalization::parse).transpose()?;
+    
+    // Without --seed, pick one from the clock; it is shown in the report so the sample can be repeated
+    let sample = verify_sample
+        .map(|percent| {
+            let seed = seed.unwrap_or_else(|| {
+                std::time::SystemTime::now()
+                    .duration_since(std::time::UNIX_EPOCH)
+                    .map(|elapsed| elapsed.as_nanos() as u64)
+                    .unwrap_or(0)
+            });
+            verify::VerifySample::new(percent, seed)
+        })
+        .transpose()?;
+    
+    // Patterns from --exclude-from follow the same case rules as in scan
+    let exclude_patterns = exclude_from
+        .map(|path| wildcard::ExcludePatterns::from_file(path, ignore_case))
+        .transpose()?
+        .unwrap_or_default();
+    
+    let engine = VerifyEngine::with_parallel(parallel)
+        .with_xattrs(include_xattrs)
+        .with_progress(show_progress)
+        .with_path_maps(path_maps)
+        .with_two_pass(two_pass)
+        .with_unicode_normalization(unicode_normalization)
+        .with_sample(sample)
+        .with_compare_sizes(compare_sizes)
+        .with_lenient(lenient)
+        .with_expected_algorithm(algorithm, strict)
+        .with_skip_dirs(skip_dirs.to_vec())
+        .with_exclude_patterns(exclude_patterns);
+    
+    // A database of '-' is read from stdin, once, and checked against every directory
+    let stdin_database = if database_pattern == "-" {
+        Some(database::DatabaseHandler::read_database_from_with(std::io::stdin().lock(), Path::new("<stdin>"), lenient)?)
+    } else {
+        None
+    };
     
     // Expand wildcard patterns
-    let databases = wildcard::expand_pattern(database_pattern)?;
-    let directories = wildcard::expand_pattern(directory_pattern)?;
+    let databases = if stdin_database.is_some() {
+        vec![PathBuf::from("-")]
+    } else {
+        wildcard::expand_pattern(database_pattern, ignore_case)?
+    };
+    let directories = wildcard::expand_pattern(directory_pattern, ignore_case)?;
     
     // Verify all matched paths are valid
-    for db in &databases {
-        if !db.is_file() {
-            return Err(HashUtilityError::InvalidArguments {
-                message: format!("Database path '{}' is not a file", db.display()),
-            });
+    if stdin_database.is_none() {
+        for db in &databases {
+            if !db.is_file() {
+                return Err(HashUtilityError::InvalidArguments {
+                    message: format!("Database path '{}' is not a file", db.display()),
+                });
+            }
         }
     }
     
@@ -426,12 +1312,35 @@ fn handle_verify_command(
         }
     }
     
+    // Each database is checked on its own, unless --merge-databases makes them one
+    let database_groups: Vec<&[PathBuf]> = if merge_databases {
+        vec![&databases[..]]
+    } else {
+        databases.chunks(1).collect()
+    };
+    
     // Run verification for all combinations of databases and directories
     let mut all_reports = Vec::new();
     
-    for database in &databases {
+    for group in database_groups {
+        let database = match group {
+            [database] => database.clone(),
+            _ => PathBuf::from(format!("{} merged databases", group.len())),
+        };
         for directory in &directories {
-            let report = engine.verify(database, directory)?;
+            let mut report = match (&stdin_database, file) {
+                (Some(entries), Some(file)) => engine.verify_file_entries(entries, directory, file)?,
+                (None, Some(file)) if group.len() > 1 => {
+                    engine.verify_file_entries(&database::DatabaseHandler::read_merged(group, lenient)?, directory, file)?
+                }
+                (None, Some(file)) => engine.verify_file(&database, directory, file)?,
+                (Some(entries), None) => engine.verify_entries(entries, directory)?,
+                (None, None) if group.len() > 1 => engine.verify_merged(group, directory)?,
+                (None, None) => engine.verify(&database, directory)?,
+            };
+            if intersection_only {
+                report = report.into_intersection();
+            }
             all_reports.push((database.clone(), directory.clone(), report));
         }
     }
@@ -448,6 +1357,14 @@ fn handle_verify_command(
             mismatches: Vec::new(),
             missing_files: Vec::new(),
             new_files: Vec::new(),
+            permission_changes: Vec::new(),
+            timestamp_drifts: Vec::new(),
+            corrupt_entries: Vec::new(),
+            transient_mismatches: Vec::new(),
+            sample: None,
+            size_mismatches: Vec::new(),
+            sizes_only: compare_sizes,
+            intersection_only,
         };
         
         for (db, dir, report) in &all_reports {
@@ -458,6 +1375,11 @@ fn handle_verify_command(
             aggregated_report.mismatches.extend(report.mismatches.clone());
             aggregated_report.missing_files.extend(report.missing_files.clone());
             aggregated_report.new_files.extend(report.new_files.clone());
+            aggregated_report.permission_changes.extend(report.permission_changes.clone());
+            aggregated_report.timestamp_drifts.extend(report.timestamp_drifts.clone());
+            aggregated_report.corrupt_entries.extend(report.corrupt_entries.clone());
+            aggregated_report.transient_mismatches.extend(report.transient_mismatches.clone());
+            aggregated_report.size_mismatches.extend(report.size_mismatches.clone());
         }
         
         // Use the first database and directory for metadata
@@ -466,6 +1388,8 @@ fn handle_verify_command(
     };
     
     let report = report;
+    // Only content changes of overlapping files decide the outcome here
+    let failed = intersection_only && report.has_issues();
     
     // Output results based on format
     if json {
@@ -507,12 +1431,17 @@ fn handle_verify_command(
         report.display();
     }
     
+    if failed {
+        process::exit(1);
+    }
+    
     Ok(())
 }
 
 /// Handle the benchmark command: run performance tests
-fn handle_benchmark_command(size_mb: usize, json: bool) -> Result<(), HashUtilityError> {
-    let engine = BenchmarkEngine::new();
+fn handle_benchmark_command(size_mb: usize, json: bool, warmup: usize, runs: usize, color: &str) -> Result<(), HashUtilityError> {
+    let engine = BenchmarkEngine::new().with_warmup(warmup).with_runs(runs);
+    let color = benchmark::ColorMode::parse(color)?;
     
     if !json {
         println!("Running benchmarks with {} MB of test data...", size_mb);
@@ -534,6 +1463,8 @@ fn handle_benchmark_command(size_mb: usize, json: bool) -> Result<(), HashUtilit
             timestamp: String,
             data_size_mb: usize,
             algorithm_count: usize,
+            warmup: usize,
+            runs: usize,
         }
         
         let output = BenchmarkOutput {
@@ -542,6 +1473,8 @@ fn handle_benchmark_command(size_mb: usize, json: bool) -> Result<(), HashUtilit
                 timestamp: chrono::Utc::now().to_rfc3339(),
                 data_size_mb: size_mb,
                 algorithm_count: results.len(),
+                warmup,
+                runs,
             },
         };
         
@@ -554,15 +1487,64 @@ fn handle_benchmark_command(size_mb: usize, json: bool) -> Result<(), HashUtilit
         println!("{}", json_output);
     } else {
         // Display results in plain text
-        engine.display_results(&results);
+        engine.display_results(&results, color.enabled());
+    }
+    
+    Ok(())
+}
+
+/// Handle the benchmark --sweep command: measure throughput at several buffer sizes
+fn handle_benchmark_sweep_command(algorithm: &str, size_mb: usize, json: bool) -> Result<(), HashUtilityError> {
+    let engine = BenchmarkEngine::new();
+    
+    if !json {
+        println!("Sweeping buffer sizes for {} with {} MB of test data...", algorithm, size_mb);
+    }
+    
+    let results = engine.run_buffer_sweep(algorithm, size_mb)?;
+    
+    if json {
+        #[derive(serde::Serialize)]
+        struct SweepOutput {
+            results: Vec<benchmark::BufferSweepResult>,
+            best_buffer_size: Option<usize>,
+            metadata: SweepMetadata,
+        }
+        
+        #[derive(serde::Serialize)]
+        struct SweepMetadata {
+            timestamp: String,
+            algorithm: String,
+            data_size_mb: usize,
+        }
+        
+        let output = SweepOutput {
+            best_buffer_size: benchmark::best_buffer_size(&results),
+            results,
+            metadata: SweepMetadata {
+                timestamp: chrono::Utc::now().to_rfc3339(),
+                algorithm: algorithm.to_string(),
+                data_size_mb: size_mb,
+            },
+        };
+        
+        let json_output = serde_json::to_string_pretty(&output).map_err(|e| {
+            HashUtilityError::InvalidArguments {
+                message: format!("Failed to serialize JSON: {}", e),
+            }
+        })?;
+        
+        println!("{}", json_output);
+    } else {
+        engine.display_sweep_results(algorithm, &results);
     }
     
     Ok(())
 }
 
 /// Handle the list command: display available algorithms
-fn handle_list_command(json: bool) -> Result<(), HashUtilityError> {
-    let algorithms = HashRegistry::list_algorithms();
+fn handle_list_command(json: bool, crypto_only: bool, pq_only: bool, show_aliases: bool) -> Result<(), HashUtilityError> {
+    let algorithms = HashRegistry::filter_algorithms(crypto_only, pq_only);
     
     if json {
         #[derive(serde::Serialize)]
@@ -594,13 +1576,22 @@ fn handle_list_command(json: bool) -> Result<(), HashUtilityError> {
         println!("{}", json_output);
     } else {
         println!("\nAvailable Hash Algorithms:\n");
-        println!("{:<20} {:>12} {:>15} {:>15}", "Algorithm", "Output Bits", "Post-Quantum", "Cryptographic");
-        println!("{}", "-".repeat(65));
+        if show_aliases {
+            println!("{:<20} {:>12} {:>15} {:>15}  Aliases", "Algorithm", "Output Bits", "Post-Quantum", "Cryptographic");
+            println!("{}", "-".repeat(90));
+        } else {
+            println!("{:<20} {:>12} {:>15} {:>15}", "Algorithm", "Output Bits", "Post-Quantum", "Cryptographic");
+            println!("{}", "-".repeat(65));
+        }
         
         for algo in algorithms {
             let pq_status = if algo.post_quantum { "Yes" } else { "No" };
             let crypto_status = if algo.cryptographic { "Yes" } else { "No" };
-            println!("{:<20} {:>12} {:>15} {:>15}", algo.name, algo.output_bits, pq_status, crypto_status);
+            if show_aliases {
+                println!("{:<20} {:>12} {:>15} {:>15}  {}", algo.name, algo.output_bits, pq_status, crypto_status, algo.aliases.join(", "));
+            } else {
+                println!("{:<20} {:>12} {:>15} {:>15}", algo.name, algo.output_bits, pq_status, crypto_status);
+            }
         }
         
         println!();
@@ -609,19 +1600,74 @@ fn handle_list_command(json: bool) -> Result<(), HashUtilityError> {
     Ok(())
 }
 
+/// Options for the compare command, resolved from its command-line flags
+struct CompareOptions<'a> {
+    database1: &'a Path,
+    database2: &'a Path,
+    output: Option<&'a Path>,
+    format: &'a str,
+    detect_duplicates: bool,
+    show_progress: bool,
+    strict: bool,
+    /// Directory relative paths are resolved against before canonicalizing (`--canonicalize-paths`)
+    canonicalize_base: Option<PathBuf>,
+    normalize_unicode: Option<&'a str>,
+}
+
 /// Handle the compare command: compare two hash databases
-fn handle_compare_command(
-    database1: &Path,
-    database2: &Path,
-    output: Option<&Path>,
-    format: &str,
-) -> Result<(), HashUtilityError> {
+fn handle_compare_command(options: CompareOptions) -> Result<(), HashUtilityError> {
     use compare::CompareEngine;
 
+    let CompareOptions { database1, database2, output, format, detect_duplicates, show_progress, strict, canonicalize_base, normalize_unicode } = options;
+
+    let unicode_normalization = normalize_unicode.map(path_utils::UnicodeNormalization::parse).transpose()?;
+
     // Create compare engine and run comparison
-    let engine = CompareEngine::new();
+    let engine = CompareEngine::new()
+        .with_duplicates(detect_duplicates)
+        .with_progress(show_progress)
+        .with_strict(strict)
+        .with_canonicalize_paths(canonicalize_base)
+        .with_unicode_normalization(unicode_normalization);
     let report = engine.compare(database1, database2)?;
 
+    // Comparing hashes from different algorithms is almost always a mistake
+    if let Some(warning) = &report.algorithm_warning {
+        eprintln!("Warning: {} (use --strict to fail instead)", warning);
+    }
+
+    write_compare_report(&report, format, output)
+}
+
+/// Options for the compare-dir command, resolved from its command-line flags
+struct CompareDirOptions<'a> {
+    database: &'a Path,
+    directory: &'a Path,
+    output: Option<&'a Path>,
+    format: &'a str,
+    parallel: bool,
+    detect_duplicates: bool,
+    show_progress: bool,
+    skip_dirs: &'a [String],
+}
+
+/// Handle the compare-dir command: diff a database against the live contents of a directory
+fn handle_compare_dir_command(options: CompareDirOptions) -> Result<(), HashUtilityError> {
+    use compare::CompareEngine;
+
+    let CompareDirOptions { database, directory, output, format, parallel, detect_duplicates, show_progress, skip_dirs } = options;
+
+    let scanner = ScanEngine::with_parallel(parallel).with_skip_dirs(skip_dirs.to_vec());
+    let engine = CompareEngine::new()
+        .with_duplicates(detect_duplicates)
+        .with_progress(show_progress);
+    let report = engine.compare_directory(database, directory, scanner)?;
+
+    write_compare_report(&report, format, output)
+}
+
+/// Write a compare report in the requested format, to a file (with a summary on stdout) or to stdout
+fn write_compare_report(report: &compare::CompareReport, format: &str, output: Option<&Path>) -> Result<(), HashUtilityError> {
     // Format output based on requested format
     let output_content = match format.to_lowercase().as_str() {
         "plain-text" | "plain" | "text" => {
@@ -637,9 +1683,12 @@ fn handle_compare_command(
         "hashdeep" => {
             report.to_hashdeep()
         }
+        "unified" => {
+            report.to_unified()
+        }
         _ => {
             return Err(HashUtilityError::InvalidArguments {
-                message: format!("Invalid format '{}'. Valid formats are: plain-text, json, hashdeep", format),
+                message: format!("Invalid format '{}'. Valid formats are: plain-text, json, hashdeep, unified", format),
             });
         }
     };
@@ -670,6 +1719,56 @@ fn handle_compare_command(
     Ok(())
 }
 
+/// Handle the compare-multi command: compare a file set across several databases
+fn handle_compare_multi_command(
+    databases: &[PathBuf],
+    output: Option<&Path>,
+    format: &str,
+) -> Result<(), HashUtilityError> {
+    use compare::CompareEngine;
+
+    let engine = CompareEngine::new();
+    let report = engine.compare_multi(databases)?;
+
+    // Format output based on requested format
+    let output_content = match format.to_lowercase().as_str() {
+        "plain-text" | "plain" | "text" => {
+            report.to_plain_text()
+        }
+        "json" => {
+            report.to_json().map_err(|e| {
+                HashUtilityError::InvalidArguments {
+                    message: format!("Failed to serialize JSON: {}", e),
+                }
+            })?
+        }
+        _ => {
+            return Err(HashUtilityError::InvalidArguments {
+                message: format!("Invalid format '{}'. Valid formats are: plain-text, json", format),
+            });
+        }
+    };
+
+    // Write to output destination
+    if let Some(output_path) = output {
+        std::fs::write(output_path, output_content).map_err(|e| {
+            HashUtilityError::from_io_error(e, "writing output", Some(output_path.to_path_buf()))
+        })?;
+
+        // Display summary to stdout
+        println!("Comparison report written to: {}", output_path.display());
+        println!("\nSummary:");
+        println!("  Databases:  {}", report.databases.len());
+        println!("  Files:      {}", report.files.len());
+        println!("  Unchanged:  {} files", report.unchanged_count);
+        println!("  Changed:    {} files", report.changed_count);
+    } else {
+        print!("{}", output_content);
+    }
+
+    Ok(())
+}
+
 /// Handle the version command: display version information
 fn handle_version_command() -> Result<(), HashUtilityError> {
     // Get version from Cargo.toml at compile time
@@ -681,19 +1780,206 @@ fn handle_version_command() -> Result<(), HashUtilityError> {
     Ok(())
 }
 
-/// Handle the dedup command: find duplicate files in a directory
-fn handle_dedup_command(
+/// Handle the selftest command: check every algorithm against its known-answer vector
+fn handle_selftest_command() -> Result<(), HashUtilityError> {
+    let results = HashRegistry::run_self_test();
+    
+    println!("\nRunning self-test...\n");
+    
+    for result in &results {
+        if result.passed {
+            println!("{:<20} PASS", result.algorithm);
+        } else {
+            println!("{:<20} FAIL", result.algorithm);
+            println!("  Expected: {}", result.expected);
+            println!("  Actual:   {}", result.actual);
+        }
+    }
+    
+    let failed = results.iter().filter(|r| !r.passed).count();
+    println!();
+    
+    if failed > 0 {
+        println!("Self-test FAILED: {} of {} algorithms", failed, results.len());
+        process::exit(1);
+    }
+    
+    println!("Self-test passed: all {} algorithms OK", results.len());
+    
+    Ok(())
+}
+
+/// One side of `hash equal`
+enum EqualInput {
+    File(PathBuf),
+    Stdin,
+    Text(String),
+}
+
+impl EqualInput {
+    /// How the input is named in the output
+    fn label(&self) -> String {
+        match self {
+            EqualInput::File(path) => path.display().to_string(),
+            EqualInput::Stdin => "-".to_string(),
+            EqualInput::Text(text) => format!("text {:?}", text),
+        }
+    }
+}
+
+/// Handle the equal command: hash two inputs with one algorithm and exit 1 if they differ
+/// 
+/// Errors are returned to main, which exits with status 2 for this command.
+fn handle_equal_command(inputs: &[String], texts: &[String], algorithm: &str) -> Result<(), HashUtilityError> {
+    let sides: Vec<EqualInput> = inputs
+        .iter()
+        .map(|input| if input == "-" { EqualInput::Stdin } else { EqualInput::File(PathBuf::from(input)) })
+        .chain(texts.iter().cloned().map(EqualInput::Text))
+        .collect();
+    let [first, second] = sides.as_slice() else {
+        return Err(HashUtilityError::InvalidArguments {
+            message: format!("equal takes exactly two inputs (files, '-' or --text), got {}", sides.len()),
+        });
+    };
+    if matches!((first, second), (EqualInput::Stdin, EqualInput::Stdin)) {
+        return Err(HashUtilityError::InvalidArguments {
+            message: "Only one input can be read from stdin".to_string(),
+        });
+    }
+    
+    // The same file is trivially equal to itself, so don't read it twice
+    if let (EqualInput::File(a), EqualInput::File(b)) = (first, second) {
+        if let (Ok(a_canonical), Ok(b_canonical)) = (a.canonicalize(), b.canonicalize()) {
+            if a_canonical == b_canonical && a_canonical.is_file() {
+                println!("Identical: {} and {} are the same file", a.display(), b.display());
+                return Ok(());
+            }
+        }
+    }
+    
+    let computer = HashComputer::new();
+    let hash = |input: &EqualInput| -> Result<String, HashUtilityError> {
+        let result = match input {
+            EqualInput::File(path) => computer.compute_hash(path, algorithm)?,
+            EqualInput::Stdin => computer.compute_hash_stdin(algorithm)?,
+            EqualInput::Text(text) => computer.compute_hash_text(text, algorithm)?,
+        };
+        Ok(result.hash)
+    };
+    let first_hash = hash(first)?;
+    let second_hash = hash(second)?;
+    
+    if first_hash == second_hash {
+        println!("Identical ({}: {})", algorithm, first_hash);
+        return Ok(());
+    }
+    
+    println!("Different ({}):", algorithm);
+    println!("  {}  {}", first_hash, first.label());
+    println!("  {}  {}", second_hash, second.label());
+    process::exit(1);
+}
+
+/// Handle the prune command: drop database entries for files that no longer exist
+fn handle_prune_command(
+    database: &Path,
     directory: &Path,
-    fast: bool,
     output: Option<&Path>,
-    json: bool,
 ) -> Result<(), HashUtilityError> {
-    use dedup::DedupEngine;
+    use prune::PruneEngine;
+    
+    // Default to rewriting the database in place
+    let output = output.unwrap_or(database);
+    
+    let engine = PruneEngine::new();
+    let stats = engine.prune(database, directory, output)?;
+    
+    for path in &stats.pruned_paths {
+        println!("  - {}", path.display());
+    }
+    
+    println!("\nPruned {} stale entries ({} of {} kept)", stats.pruned_count(), stats.kept_entries, stats.total_entries);
+    println!("Database written to: {}", output.display());
+    
+    Ok(())
+}
+
+/// Handle the split command: partition a database into shards
+fn handle_split_command(
+    database: &Path,
+    shards: usize,
+    output: Option<&Path>,
+) -> Result<(), HashUtilityError> {
+    use split::SplitEngine;
+    
+    // Default to shards next to the database, named after its uncompressed form
+    let prefix = match output {
+        Some(prefix) => prefix.to_path_buf(),
+        None if database::DatabaseHandler::is_compressed(database) => database.with_extension(""),
+        None => database.to_path_buf(),
+    };
+    
+    let engine = SplitEngine::new();
+    let stats = engine.split(database, shards, &prefix)?;
+    
+    for (path, entries) in stats.shard_paths.iter().zip(&stats.shard_entries) {
+        println!("  {} ({} entries)", path.display(), entries);
+    }
+    
+    println!("\nSplit {} entries into {} shards", stats.total_entries, stats.shard_paths.len());
+    
+    Ok(())
+}
+
+/// Options for the dedup command, resolved from its command-line flags
+struct DedupOptions<'a> {
+    directory: &'a Path,
+    fast: bool,
+    output: Option<&'a Path>,
+    json: bool,
+    json_ndjson: bool,
+    skip_dirs: &'a [String],
+    prefer_dirs: &'a [PathBuf],
+    continue_from: Option<PathBuf>,
+    min_duplicate_size: Option<usize>,
+    sort: &'a str,
+    reverse: bool,
+    progress_template: Option<String>,
+    count_only: bool,
+    spill: bool,
+}
+
+/// Handle the dedup command: find duplicate files in a directory
+fn handle_dedup_command(options: DedupOptions) -> Result<(), HashUtilityError> {
+    use dedup::{DedupEngine, DedupSort};
+    
+    let DedupOptions {
+        directory, fast, output, json, json_ndjson, skip_dirs, prefer_dirs, continue_from, min_duplicate_size, sort, reverse,
+        progress_template, count_only, spill,
+    } = options;
+    
+    let sort = DedupSort::parse(sort)?;
     
     // Create dedup engine with appropriate settings
     let engine = DedupEngine::new()
         .with_fast_mode(fast)
-        .with_parallel(true); // Always use parallel for better performance
+        .with_parallel(true) // Always use parallel for better performance
+        .with_skip_dirs(skip_dirs.to_vec())
+        .with_prefer_dirs(prefer_dirs.to_vec())
+        .with_cache(continue_from)
+        .with_min_duplicate_size(min_duplicate_size.unwrap_or(0) as u64)
+        .with_sort(sort, reverse)
+        .with_progress_template(progress_template)
+        .with_spill(spill);
+    
+    if count_only {
+        let (files, total_bytes) = engine.count_files(directory)?;
+        return print_file_count(files, total_bytes, json);
+    }
+    
+    if json_ndjson {
+        return stream_dedup_ndjson(&engine, directory, output);
+    }
     
     // Find duplicates
     let report = engine.find_duplicates(directory)?;
@@ -716,25 +2002,28 @@ fn handle_dedup_command(
         writeln!(&mut output_str, "Summary:").unwrap();
         writeln!(&mut output_str, "  Files scanned:     {}", report.stats.files_scanned).unwrap();
         writeln!(&mut output_str, "  Files failed:      {}", report.stats.files_failed).unwrap();
-        writeln!(&mut output_str, "  Total bytes:       {} ({:.2} MB)", 
-            report.stats.total_bytes, 
-            report.stats.total_bytes as f64 / 1_048_576.0
+        if report.stats.files_cached > 0 {
+            writeln!(&mut output_str, "  Cached hashes:     {}", report.stats.files_cached).unwrap();
+        }
+        writeln!(&mut output_str, "  Total bytes:       {} ({})",
+            report.stats.total_bytes,
+            path_utils::format_size(report.stats.total_bytes)
         ).unwrap();
         writeln!(&mut output_str, "  Duplicate groups:  {}", report.stats.duplicate_groups).unwrap();
         writeln!(&mut output_str, "  Duplicate files:   {}", report.stats.duplicate_files).unwrap();
-        writeln!(&mut output_str, "  Wasted space:      {} ({:.2} MB)", 
-            report.stats.wasted_space, 
-            report.stats.wasted_space as f64 / 1_048_576.0
+        writeln!(&mut output_str, "  Wasted space:      {} ({})",
+            report.stats.wasted_space,
+            path_utils::format_size(report.stats.wasted_space)
         ).unwrap();
         writeln!(&mut output_str, "  Duration:          {:.2}s", report.stats.duration.as_secs_f64()).unwrap();
         
         if report.stats.duration.as_secs_f64() > 0.0 {
-            let throughput_mbps = (report.stats.total_bytes as f64 / 1_048_576.0) / report.stats.duration.as_secs_f64();
-            writeln!(&mut output_str, "  Throughput:        {:.2} MB/s", throughput_mbps).unwrap();
+            let throughput = path_utils::format_throughput(report.stats.total_bytes, report.stats.duration);
+            writeln!(&mut output_str, "  Throughput:        {}", throughput).unwrap();
         }
         
         if !report.duplicate_groups.is_empty() {
-            writeln!(&mut output_str, "\nDuplicate Groups (sorted by wasted space):").unwrap();
+            writeln!(&mut output_str, "\nDuplicate Groups (sorted by {}):", report.sort.description()).unwrap();
             for group in &report.duplicate_groups {
                 writeln!(&mut output_str, "\n  Hash: {} ({} files, {} bytes each, {} bytes wasted)", 
                     group.hash, 
@@ -743,7 +2032,11 @@ fn handle_dedup_command(
                     group.wasted_space
                 ).unwrap();
                 for path in &group.paths {
-                    writeln!(&mut output_str, "    {}", path.display()).unwrap();
+                    if *path == group.keeper {
+                        writeln!(&mut output_str, "    {} (keep)", path.display()).unwrap();
+                    } else {
+                        writeln!(&mut output_str, "    {}", path.display()).unwrap();
+                    }
                 }
             }
         } else {
@@ -767,9 +2060,9 @@ fn handle_dedup_command(
         println!("  Files scanned:     {}", report.stats.files_scanned);
         println!("  Duplicate groups:  {}", report.stats.duplicate_groups);
         println!("  Duplicate files:   {}", report.stats.duplicate_files);
-        println!("  Wasted space:      {} ({:.2} MB)",
+        println!("  Wasted space:      {} ({})",
             report.stats.wasted_space,
-            report.stats.wasted_space as f64 / 1_048_576.0
+            path_utils::format_size(report.stats.wasted_space)
         );
     } else {
         // Write to stdout
@@ -779,6 +2072,43 @@ fn handle_dedup_command(
     Ok(())
 }
 
+/// Stream dedup results as newline-delimited JSON, writing each group as soon as it is built
+fn stream_dedup_ndjson(
+    engine: &dedup::DedupEngine,
+    directory: &Path,
+    output: Option<&Path>,
+) -> Result<(), HashUtilityError> {
+    use std::io::{BufWriter, Write};
+    
+    let mut writer: Box<dyn Write> = match output {
+        Some(output_path) => Box::new(BufWriter::new(std::fs::File::create(output_path).map_err(|e| {
+            HashUtilityError::from_io_error(e, "writing output", Some(output_path.to_path_buf()))
+        })?)),
+        None => Box::new(std::io::stdout().lock()),
+    };
+    let write_error = |e| HashUtilityError::from_io_error(e, "writing output", output.map(Path::to_path_buf));
+    
+    let stats = engine.find_duplicates_streaming(directory, |group| {
+        dedup::write_ndjson_group(&mut writer, &group).map_err(write_error)
+    })?;
+    dedup::write_ndjson_stats(&mut writer, &stats).map_err(write_error)?;
+    writer.flush().map_err(write_error)?;
+    
+    if let Some(output_path) = output {
+        println!("Dedup report written to: {}", output_path.display());
+        println!("\nSummary:");
+        println!("  Files scanned:     {}", stats.files_scanned);
+        println!("  Duplicate groups:  {}", stats.duplicate_groups);
+        println!("  Duplicate files:   {}", stats.duplicate_files);
+        println!("  Wasted space:      {} ({})",
+            stats.wasted_space,
+            path_utils::format_size(stats.wasted_space)
+        );
+    }
+    
+    Ok(())
+}
+
 /// Handle the analyze command: analyze a hash database and display statistics
 fn handle_analyze_command(
     database: &Path,
@@ -817,9 +2147,9 @@ fn handle_analyze_command(
         println!("  Duplicate groups:   {}", report.stats.duplicate_groups);
         println!("  Duplicate files:    {}", report.stats.duplicate_files);
         if let Some(savings) = report.stats.potential_savings {
-            println!("  Potential savings:  {} ({:.2} MB)",
+            println!("  Potential savings:  {} ({})",
                 savings,
-                savings as f64 / 1_048_576.0
+                path_utils::format_size(savings)
             );
         }
     } else {
@@ -829,3 +2159,23 @@ fn handle_analyze_command(
 
     Ok(())
 }
+
+/// Handle the stats command: summarize a database
+fn handle_stats_command(database: &Path, json: bool) -> Result<(), HashUtilityError> {
+    use analyze::AnalyzeEngine;
+
+    let stats = AnalyzeEngine::new().stats(database)?;
+
+    if json {
+        let json_output = stats.to_json().map_err(|e| {
+            HashUtilityError::InvalidArguments {
+                message: format!("Failed to serialize JSON: {}", e),
+            }
+        })?;
+        println!("{}", json_output);
+    } else {
+        print!("{}", stats.to_plain_text());
+    }
+
+    Ok(())
+}
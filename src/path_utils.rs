@@ -4,6 +4,10 @@
 
 use std::io;
 use std::path::{Path, PathBuf, Component};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::time::Duration;
+use unicode_normalization::UnicodeNormalization as _;
+use crate::error::HashUtilityError;
 
 /// Normalize a path string by handling both forward and backward slashes
 /// Converts all path separators to the platform-specific separator
@@ -27,6 +31,87 @@ pub fn parse_database_path(path_str: &str) -> PathBuf {
     PathBuf::from(normalized)
 }
 
+/// How relative paths are written to hash databases
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum PathStyle {
+    /// Platform separators, no prefix (e.g. dir\file.txt on Windows)
+    #[default]
+    Native,
+    /// Forward slashes, no prefix (e.g. dir/file.txt)
+    Bare,
+    /// Forward slashes with a leading "./" (e.g. ./dir/file.txt)
+    Dot,
+}
+
+/// Unicode normalization form applied to paths before matching (`--normalize-unicode`)
+/// 
+/// macOS stores file names decomposed (NFD) while Linux and Windows usually keep
+/// them composed (NFC), so the same name can be two different byte strings.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum UnicodeNormalization {
+    /// Canonical composition (é as a single code point)
+    Nfc,
+    /// Canonical decomposition (e followed by a combining accent)
+    Nfd,
+}
+
+impl UnicodeNormalization {
+    /// Parse a form name ('nfc' or 'nfd', case-insensitive)
+    pub fn parse(form: &str) -> Result<Self, HashUtilityError> {
+        match form.to_lowercase().as_str() {
+            "nfc" => Ok(UnicodeNormalization::Nfc),
+            "nfd" => Ok(UnicodeNormalization::Nfd),
+            _ => Err(HashUtilityError::InvalidArguments {
+                message: format!("Invalid Unicode normalization form '{}'. Valid forms are: nfc, nfd", form),
+            }),
+        }
+    }
+    
+    /// Normalize a path; paths that aren't valid UTF-8 are returned unchanged
+    pub fn normalize_path(self, path: &Path) -> PathBuf {
+        let Some(path_str) = path.to_str() else {
+            return path.to_path_buf();
+        };
+        match self {
+            UnicodeNormalization::Nfc => PathBuf::from(path_str.nfc().collect::<String>()),
+            UnicodeNormalization::Nfd => PathBuf::from(path_str.nfd().collect::<String>()),
+        }
+    }
+}
+
+/// Format a path for a database entry in the given style
+/// Absolute paths (files outside the scan root) are always written as-is
+pub fn format_database_path(path: &Path, style: PathStyle) -> PathBuf {
+    if style == PathStyle::Native || path.is_absolute() {
+        return path.to_path_buf();
+    }
+    
+    let bare = path
+        .components()
+        .filter(|component| !matches!(component, Component::CurDir))
+        .map(|component| component.as_os_str().to_string_lossy())
+        .collect::<Vec<_>>()
+        .join("/");
+    
+    match style {
+        PathStyle::Dot => PathBuf::from(format!("./{}", bare)),
+        _ => PathBuf::from(bare),
+    }
+}
+
+/// Canonicalize a path without resolving its last component
+/// A symlink keeps its own name instead of turning into the path of its target
+pub fn canonicalize_link(path: &Path) -> io::Result<PathBuf> {
+    let Some(name) = path.file_name() else {
+        return path.canonicalize();
+    };
+    let parent = match path.parent() {
+        Some(parent) if !parent.as_os_str().is_empty() => parent.canonicalize()?,
+        _ => std::env::current_dir()?.canonicalize()?,
+    };
+    Ok(parent.join(name))
+}
+
 /// Canonicalize a path if it exists, otherwise return the path as-is
 /// This is useful for handling paths that may not exist yet
 pub fn try_canonicalize(path: &Path) -> io::Result<PathBuf> {
@@ -66,8 +151,8 @@ pub fn get_relative_path_cached(path: &Path, canonical_base: &Path) -> io::Resul
     match canonical_path.strip_prefix(canonical_base) {
         Ok(relative) => Ok(relative.to_path_buf()),
         Err(_) => {
-            // If we can't make it relative, return the canonical path
-            Ok(canonical_path)
+            // If we can't make it relative, return the canonical path (without a Windows \\?\ prefix)
+            Ok(strip_extended_length_prefix(&canonical_path))
         }
     }
 }
@@ -126,10 +211,166 @@ pub fn clean_path(path: &Path) -> PathBuf {
     }
 }
 
+/// Prefix that lifts the Windows MAX_PATH (260 character) limit
+const EXTENDED_LENGTH_PREFIX: &str = r"\\?\";
+
+/// Extended-length form of a UNC path (`\\server\share` becomes `\\?\UNC\server\share`)
+const EXTENDED_LENGTH_UNC_PREFIX: &str = r"\\?\UNC\";
+
+/// Convert a path to its Windows extended-length form (`\\?\C:\...`) so files in deep trees can be opened
+/// 
+/// The prefix turns off Windows path normalization, so the path is made absolute
+/// and cleaned of `.` and `..` first. Paths that already carry a prefix, and all
+/// paths on other platforms, are returned unchanged.
+pub fn extended_length_path(path: &Path) -> PathBuf {
+    if !cfg!(windows) || path.as_os_str().to_string_lossy().starts_with(EXTENDED_LENGTH_PREFIX) {
+        return path.to_path_buf();
+    }
+    
+    let absolute = if path.is_absolute() {
+        clean_path(path)
+    } else {
+        match std::env::current_dir() {
+            Ok(cwd) => clean_path(&cwd.join(path)),
+            Err(_) => return path.to_path_buf(),
+        }
+    };
+    
+    // Forward slashes aren't separators once the prefix is added
+    let absolute = normalize_path_string(&absolute.to_string_lossy());
+    match absolute.strip_prefix(r"\\") {
+        Some(unc) => PathBuf::from(format!("{}{}", EXTENDED_LENGTH_UNC_PREFIX, unc)),
+        None => PathBuf::from(format!("{}{}", EXTENDED_LENGTH_PREFIX, absolute)),
+    }
+}
+
+/// Remove an extended-length prefix (added by `extended_length_path` or by `canonicalize` on Windows)
+/// 
+/// Paths stored in databases should not carry the prefix, so they stay portable.
+pub fn strip_extended_length_prefix(path: &Path) -> PathBuf {
+    let path_str = path.to_string_lossy();
+    if let Some(unc) = path_str.strip_prefix(EXTENDED_LENGTH_UNC_PREFIX) {
+        PathBuf::from(format!(r"\\{}", unc))
+    } else if let Some(local) = path_str.strip_prefix(EXTENDED_LENGTH_PREFIX) {
+        PathBuf::from(local)
+    } else {
+        path.to_path_buf()
+    }
+}
+
+/// Check whether a directory's name exactly matches one of the names to skip
+/// Used for `--skip-dir` (e.g. node_modules, .git) and never matches on partial names
+pub fn is_skipped_dir(dir: &Path, skip_dirs: &[String]) -> bool {
+    match dir.file_name().and_then(|name| name.to_str()) {
+        Some(name) => skip_dirs.iter().any(|skip| skip == name),
+        None => false,
+    }
+}
+
+/// Unit system for human-readable sizes in reports
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum SizeUnits {
+    /// Powers of 1024: KiB, MiB, GiB, TiB
+    #[default]
+    Binary,
+    /// Powers of 1000: kB, MB, GB, TB (`--si`)
+    Decimal,
+}
+
+/// Set once at startup from `--si` so every report formats sizes the same way
+static DECIMAL_SIZES: AtomicBool = AtomicBool::new(false);
+
+impl SizeUnits {
+    /// Use these units for every size formatted from now on
+    pub fn set_global(self) {
+        DECIMAL_SIZES.store(self == SizeUnits::Decimal, Ordering::Relaxed);
+    }
+    
+    /// Units chosen for this run
+    pub fn global() -> Self {
+        if DECIMAL_SIZES.load(Ordering::Relaxed) {
+            SizeUnits::Decimal
+        } else {
+            SizeUnits::Binary
+        }
+    }
+    
+    fn base(self) -> f64 {
+        match self {
+            SizeUnits::Binary => 1024.0,
+            SizeUnits::Decimal => 1000.0,
+        }
+    }
+    
+    fn labels(self) -> [&'static str; 4] {
+        match self {
+            SizeUnits::Binary => ["KiB", "MiB", "GiB", "TiB"],
+            SizeUnits::Decimal => ["kB", "MB", "GB", "TB"],
+        }
+    }
+}
+
+/// Format a byte count as a human-readable size in the units chosen for this run
+pub fn format_size(bytes: u64) -> String {
+    format_size_in(bytes, SizeUnits::global())
+}
+
+/// Format a byte count as a human-readable size, e.g. "1.50 KiB" or "1.50 kB"
+/// Counts below one kilo unit are shown as plain bytes
+pub fn format_size_in(bytes: u64, units: SizeUnits) -> String {
+    let base = units.base();
+    let mut value = bytes as f64;
+    let mut label = None;
+    for next in units.labels() {
+        if value < base {
+            break;
+        }
+        value /= base;
+        label = Some(next);
+    }
+    
+    match label {
+        Some(label) => format!("{:.2} {}", value, label),
+        None => format!("{} bytes", bytes),
+    }
+}
+
+/// Format the rate of hashing `bytes` in `duration`, e.g. "512.00 MiB/s"
+pub fn format_throughput(bytes: u64, duration: Duration) -> String {
+    format!("{}/s", format_size((bytes as f64 / duration.as_secs_f64()) as u64))
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
     use std::fs;
+    
+    #[test]
+    fn test_format_size_binary() {
+        let units = SizeUnits::Binary;
+        assert_eq!(format_size_in(0, units), "0 bytes");
+        assert_eq!(format_size_in(1023, units), "1023 bytes");
+        assert_eq!(format_size_in(1024, units), "1.00 KiB");
+        assert_eq!(format_size_in(1536, units), "1.50 KiB");
+        assert_eq!(format_size_in(1_000_000, units), "976.56 KiB");
+        assert_eq!(format_size_in(1024 * 1024, units), "1.00 MiB");
+        assert_eq!(format_size_in(1024 * 1024 * 1024, units), "1.00 GiB");
+        assert_eq!(format_size_in(1024u64.pow(4), units), "1.00 TiB");
+        assert_eq!(format_size_in(1024u64.pow(5), units), "1024.00 TiB");
+    }
+    
+    #[test]
+    fn test_format_size_decimal() {
+        let units = SizeUnits::Decimal;
+        assert_eq!(format_size_in(999, units), "999 bytes");
+        assert_eq!(format_size_in(1000, units), "1.00 kB");
+        assert_eq!(format_size_in(1024, units), "1.02 kB");
+        assert_eq!(format_size_in(999_999, units), "1000.00 kB");
+        assert_eq!(format_size_in(1_000_000, units), "1.00 MB");
+        assert_eq!(format_size_in(1024 * 1024, units), "1.05 MB");
+        assert_eq!(format_size_in(1_000_000_000, units), "1.00 GB");
+        assert_eq!(format_size_in(1_000_000_000_000, units), "1.00 TB");
+    }
 
     #[test]
     fn test_normalize_path_string_forward_slash() {
@@ -167,6 +408,17 @@ mod tests {
         }
     }
 
+    #[test]
+    fn test_format_database_path() {
+        let path: PathBuf = ["dir", "sub", "file.txt"].iter().collect();
+        
+        assert_eq!(format_database_path(&path, PathStyle::Native), path);
+        assert_eq!(format_database_path(&path, PathStyle::Bare), PathBuf::from("dir/sub/file.txt"));
+        assert_eq!(format_database_path(&path, PathStyle::Dot), PathBuf::from("./dir/sub/file.txt"));
+        assert_eq!(format_database_path(Path::new("./file.txt"), PathStyle::Dot), PathBuf::from("./file.txt"));
+        assert_eq!(format_database_path(Path::new("./file.txt"), PathStyle::Bare), PathBuf::from("file.txt"));
+    }
+
     #[test]
     fn test_parse_database_path() {
         let input = "path/to\\file.txt";
@@ -312,4 +564,48 @@ mod tests {
         
         assert_eq!(result, PathBuf::from(".."));
     }
+
+    #[test]
+    fn test_strip_extended_length_prefix() {
+        assert_eq!(strip_extended_length_prefix(Path::new(r"\\?\C:\data\file.txt")), PathBuf::from(r"C:\data\file.txt"));
+        assert_eq!(strip_extended_length_prefix(Path::new(r"\\?\UNC\server\share\file.txt")), PathBuf::from(r"\\server\share\file.txt"));
+        assert_eq!(strip_extended_length_prefix(Path::new("dir/file.txt")), PathBuf::from("dir/file.txt"));
+    }
+
+    #[cfg(not(windows))]
+    #[test]
+    fn test_extended_length_path_is_noop_off_windows() {
+        assert_eq!(extended_length_path(Path::new("dir/file.txt")), PathBuf::from("dir/file.txt"));
+        assert_eq!(extended_length_path(Path::new("/abs/file.txt")), PathBuf::from("/abs/file.txt"));
+    }
+
+    #[cfg(windows)]
+    #[test]
+    fn test_extended_length_path() {
+        assert_eq!(extended_length_path(Path::new(r"C:\data\.\sub\..\file.txt")), PathBuf::from(r"\\?\C:\data\file.txt"));
+        assert_eq!(extended_length_path(Path::new(r"\\server\share\file.txt")), PathBuf::from(r"\\?\UNC\server\share\file.txt"));
+        assert_eq!(extended_length_path(Path::new(r"\\?\C:\data")), PathBuf::from(r"\\?\C:\data"));
+
+        let relative = extended_length_path(Path::new("file.txt"));
+        assert!(relative.to_string_lossy().starts_with(EXTENDED_LENGTH_PREFIX));
+        assert!(relative.ends_with("file.txt"));
+    }
+
+
+    #[test]
+    fn test_unicode_normalization() {
+        let nfc = Path::new("caf\u{e9}/r\u{e9}sum\u{e9}.txt");
+        let nfd = Path::new("cafe\u{301}/re\u{301}sume\u{301}.txt");
+        assert_ne!(nfc, nfd);
+        
+        for form in [UnicodeNormalization::Nfc, UnicodeNormalization::Nfd] {
+            assert_eq!(form.normalize_path(nfc), form.normalize_path(nfd));
+        }
+        assert_eq!(UnicodeNormalization::Nfc.normalize_path(nfd), nfc);
+        assert_eq!(UnicodeNormalization::Nfd.normalize_path(nfc), nfd);
+        
+        assert_eq!(UnicodeNormalization::parse("NFD").unwrap(), UnicodeNormalization::Nfd);
+        assert!(UnicodeNormalization::parse("nfkc").is_err());
+    }
+
 }
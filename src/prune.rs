@@ -0,0 +1,200 @@
+// Prune engine module
+// Removes entries for files that no longer exist from a hash database
+
+use std::fs::File;
+use std::io::{BufWriter, Write};
+use std::path::{Path, PathBuf};
+use crate::database::{DatabaseEntry, DatabaseFormat, DatabaseHandler};
+use crate::error::HashUtilityError;
+use crate::path_utils;
+use xz2::write::XzEncoder;
+
+/// Result of pruning a database
+#[derive(Debug, Clone, serde::Serialize)]
+pub struct PruneStats {
+    pub total_entries: usize,
+    pub kept_entries: usize,
+    pub pruned_paths: Vec<PathBuf>,
+}
+
+impl PruneStats {
+    /// Number of stale entries removed from the database
+    pub fn pruned_count(&self) -> usize {
+        self.pruned_paths.len()
+    }
+}
+
+/// Engine for removing stale entries from hash databases
+pub struct PruneEngine;
+
+impl PruneEngine {
+    /// Create a new PruneEngine
+    pub fn new() -> Self {
+        Self
+    }
+
+    /// Rewrite a database keeping only entries whose files still exist
+    ///
+    /// # Arguments
+    /// * `database` - Database to prune (standard format, optionally .xz compressed)
+    /// * `directory` - Directory the database paths are relative to
+    /// * `output` - Where to write the pruned database (may be the same as `database`)
+    ///
+    /// The output is compressed if its path ends in .xz.
+    pub fn prune(
+        &self,
+        database: &Path,
+        directory: &Path,
+        output: &Path,
+    ) -> Result<PruneStats, HashUtilityError> {
+        if !database.exists() {
+            return Err(HashUtilityError::DatabaseNotFound {
+                path: database.to_path_buf(),
+            });
+        }
+
+        if !directory.is_dir() {
+            return Err(HashUtilityError::DirectoryNotFound {
+                path: directory.to_path_buf(),
+            });
+        }
+
+        // Hashdeep and TSV entries carry file sizes that DatabaseEntry does not keep,
+        // so rewriting them would silently lose data
+        if DatabaseHandler::detect_format(database)? != DatabaseFormat::Standard {
+            return Err(HashUtilityError::InvalidArguments {
+                message: "prune only supports standard format databases".to_string(),
+            });
+        }
+
+        // Load the whole database before opening the output, which may be the same file
+        let entries = DatabaseHandler::read_database(database)?;
+        let total_entries = entries.len();
+
+        // Sort for stable, diff-friendly output
+        let mut entries: Vec<_> = entries.into_iter().collect();
+        entries.sort_by(|a, b| a.0.cmp(&b.0));
+
+        let (kept, pruned): (Vec<_>, Vec<_>) = entries
+            .into_iter()
+            .partition(|(path, _)| path_utils::resolve_path(path, directory).exists());
+
+        // Write a sibling temp file and rename it over the output, so an in-place
+        // prune that fails part way never leaves a truncated database behind
+        let mut temp = output.as_os_str().to_owned();
+        temp.push(".tmp");
+        let temp = PathBuf::from(temp);
+
+        let written = Self::write_entries(&temp, DatabaseHandler::is_compressed(output), &kept)
+            .and_then(|_| std::fs::rename(&temp, output));
+        if let Err(e) = written {
+            let _ = std::fs::remove_file(&temp);
+            return Err(HashUtilityError::from_io_error(e, "writing pruned database", Some(output.to_path_buf())));
+        }
+
+        Ok(PruneStats {
+            total_entries,
+            kept_entries: kept.len(),
+            pruned_paths: pruned.into_iter().map(|(path, _)| path).collect(),
+        })
+    }
+
+    /// Write the kept entries to `destination`, xz-compressed if `compress` is set
+    fn write_entries(destination: &Path, compress: bool, entries: &[(PathBuf, DatabaseEntry)]) -> std::io::Result<()> {
+        let file = File::create(destination)?;
+        let mut writer: Box<dyn Write> = if compress {
+            Box::new(BufWriter::new(XzEncoder::new(file, 6)))
+        } else {
+            Box::new(BufWriter::new(file))
+        };
+
+        for (path, entry) in entries {
+            DatabaseHandler::write_entry_with_metadata(
+                &mut writer,
+                &entry.hash,
+                &entry.algorithm,
+                entry.mode(),
+                entry.metadata(),
+                path,
+            )?;
+        }
+
+        writer.flush()?;
+        // Dropping the writer finishes the xz stream
+        drop(writer);
+        Ok(())
+    }
+}
+
+impl Default for PruneEngine {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::fs;
+
+    #[test]
+    fn test_prune_removes_deleted_files() {
+        let test_dir = "test_prune_deleted";
+        let db_path = "test_prune_deleted.db";
+        fs::create_dir_all(format!("{}/sub", test_dir)).unwrap();
+        fs::write(format!("{}/keep.txt", test_dir), b"keep").unwrap();
+        fs::write(format!("{}/sub/also_keep.txt", test_dir), b"also").unwrap();
+        fs::write(format!("{}/gone.txt", test_dir), b"gone").unwrap();
+
+        fs::write(
+            db_path,
+            "aaa  sha256  normal  keep.txt\n\
+             bbb  sha256  fast  perm:0644  mtime:2024-01-15T10:00:00Z  sub/also_keep.txt\n\
+             ccc  sha256  normal  gone.txt\n",
+        )
+        .unwrap();
+
+        fs::remove_file(format!("{}/gone.txt", test_dir)).unwrap();
+
+        let engine = PruneEngine::new();
+        let stats = engine.prune(Path::new(db_path), Path::new(test_dir), Path::new(db_path)).unwrap();
+
+        assert_eq!(stats.total_entries, 3);
+        assert_eq!(stats.kept_entries, 2);
+        assert_eq!(stats.pruned_count(), 1);
+        assert_eq!(stats.pruned_paths[0], PathBuf::from("gone.txt"));
+
+        // Remaining entries keep their metadata
+        let pruned = DatabaseHandler::read_database(Path::new(db_path)).unwrap();
+        assert_eq!(pruned.len(), 2);
+        assert!(!pruned.contains_key(Path::new("gone.txt")));
+        assert_eq!(pruned[Path::new("keep.txt")].hash, "aaa");
+        let also_keep = &pruned[&path_utils::parse_database_path("sub/also_keep.txt")];
+        assert!(also_keep.fast_mode);
+        assert_eq!(also_keep.permissions, Some("0644".to_string()));
+        assert_eq!(also_keep.modified, Some("2024-01-15T10:00:00Z".to_string()));
+
+        // The temp file used for the in-place rewrite has been renamed away
+        assert!(!Path::new("test_prune_deleted.db.tmp").exists());
+
+        // Cleanup
+        fs::remove_file(db_path).unwrap();
+        fs::remove_dir_all(test_dir).unwrap();
+    }
+
+    #[test]
+    fn test_prune_rejects_hashdeep() {
+        let test_dir = "test_prune_hashdeep";
+        let db_path = "test_prune_hashdeep.db";
+        fs::create_dir_all(test_dir).unwrap();
+        fs::write(db_path, "%%%% HASHDEEP-1.0\n%%%% size,sha256,filename\n4,aaa,keep.txt\n").unwrap();
+
+        let engine = PruneEngine::new();
+        let result = engine.prune(Path::new(db_path), Path::new(test_dir), Path::new(db_path));
+        assert!(matches!(result, Err(HashUtilityError::InvalidArguments { .. })));
+
+        // Cleanup
+        fs::remove_file(db_path).unwrap();
+        fs::remove_dir_all(test_dir).unwrap();
+    }
+}
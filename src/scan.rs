@@ -1,16 +1,20 @@
 // Directory scanning module
 // Handles recursive directory traversal and hash computation
 
-use crate::hash::HashComputer;
-use crate::database::DatabaseHandler;
+use crate::hash::{HashComputer, HashProfile, HashRegistry, HashResult, SYMLINK_ALGORITHM};
+use crate::database::{DatabaseEntry, DatabaseHandler, DuplicateConflict, EntryMetadata};
 use crate::path_utils;
 use crate::error::HashUtilityError;
 use crate::ignore_handler::IgnoreHandler;
+use crate::wildcard::ExcludePatterns;
+use std::cmp::Reverse;
+use std::collections::{BinaryHeap, HashMap};
 use std::fs::{self, File};
 use std::io::{BufWriter, Write};
 use std::path::{Path, PathBuf};
-use std::time::{Duration, Instant};
+use std::time::{Duration, Instant, SystemTime};
 use std::sync::{Arc, Mutex};
+use std::sync::atomic::{AtomicBool, AtomicU64, Ordering};
 use std::thread;
 use rayon::prelude::*;
 use indicatif::{ProgressBar, ProgressStyle};
@@ -25,9 +29,75 @@ pub type ScanError = HashUtilityError;
 pub struct ScanStats {
     pub files_processed: usize,
     pub files_failed: usize,
+    /// Files that could not be opened due to missing permissions (not counted in files_failed)
+    pub files_permission_denied: usize,
+    /// Files that were hashed successfully but have size zero
+    pub empty_files: usize,
     pub total_bytes: u64,
     #[serde(serialize_with = "serialize_duration")]
     pub duration: Duration,
+    /// The scan hit its `--timeout` deadline; the database holds only the files hashed before it
+    pub timed_out: bool,
+    /// The scan was cancelled (Ctrl-C); the database holds only the files hashed before it
+    pub interrupted: bool,
+    /// With `--report-slow N`: the N files that took longest to hash, slowest first
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub slowest_files: Vec<SlowFile>,
+    /// With `--dedupe-entries`: earlier entries dropped because their path was written again
+    pub duplicates_removed: usize,
+    /// Dropped duplicates whose hash differed from the entry that was kept
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub duplicate_conflicts: Vec<DuplicateConflict>,
+}
+
+/// A file and how long it took to hash (wall time)
+#[derive(Debug, Clone, PartialEq, serde::Serialize)]
+pub struct SlowFile {
+    pub path: PathBuf,
+    #[serde(serialize_with = "serialize_duration")]
+    pub duration: Duration,
+}
+
+/// Keeps the N slowest files seen so far; shared between hashing threads
+struct SlowestFiles {
+    limit: usize,
+    /// Min-heap on duration, so the fastest of the kept files is evicted first
+    heap: Mutex<BinaryHeap<Reverse<(Duration, PathBuf)>>>,
+}
+
+impl SlowestFiles {
+    fn new(limit: usize) -> Self {
+        Self {
+            limit,
+            heap: Mutex::new(BinaryHeap::with_capacity(limit + 1)),
+        }
+    }
+    
+    /// Record a file's hashing time, keeping it only if it's among the N slowest
+    fn record(&self, path: &Path, duration: Duration) {
+        if self.limit == 0 {
+            return;
+        }
+        let mut heap = self.heap.lock().unwrap();
+        if heap.len() < self.limit {
+            heap.push(Reverse((duration, path.to_path_buf())));
+        } else if heap.peek().is_some_and(|Reverse((fastest, _))| duration > *fastest) {
+            heap.pop();
+            heap.push(Reverse((duration, path.to_path_buf())));
+        }
+    }
+    
+    /// The kept files, slowest first
+    fn into_sorted(self) -> Vec<SlowFile> {
+        // Ascending order of Reverse is descending order of duration
+        self.heap
+            .into_inner()
+            .unwrap()
+            .into_sorted_vec()
+            .into_iter()
+            .map(|Reverse((duration, path))| SlowFile { path, duration })
+            .collect()
+    }
 }
 
 // Helper function to serialize Duration as seconds
@@ -40,6 +110,171 @@ where
 
 use crate::database::DatabaseFormat;
 
+/// A directory to scan with its own parallelism setting (`--root DIR:MODE`)
+/// 
+/// Lets a single scan mix SSD roots (parallel) with HDD roots (sequential).
+#[derive(Debug, Clone, PartialEq)]
+pub struct ScanRoot {
+    pub path: PathBuf,
+    pub parallel: bool,
+}
+
+impl ScanRoot {
+    /// Parse a `DIR:MODE` spec, where MODE is `parallel` (or `ssd`) or `sequential` (or `hdd`)
+    /// 
+    /// The mode is taken from the last colon, so Windows drive letters stay in the path.
+    pub fn parse(spec: &str) -> Result<Self, HashUtilityError> {
+        let invalid = |reason: &str| HashUtilityError::InvalidArguments {
+            message: format!("Invalid root '{}': {} (expected DIR:parallel or DIR:sequential)", spec, reason),
+        };
+        
+        let (path, mode) = spec.rsplit_once(':').ok_or_else(|| invalid("missing mode"))?;
+        if path.is_empty() {
+            return Err(invalid("missing directory"));
+        }
+        let parallel = match mode.to_lowercase().as_str() {
+            "parallel" | "ssd" => true,
+            "sequential" | "hdd" => false,
+            _ => return Err(invalid("unknown mode")),
+        };
+        
+        Ok(Self {
+            path: PathBuf::from(path),
+            parallel,
+        })
+    }
+}
+
+/// An algorithm override for files with a given extension (`--algorithm-map EXT=ALGO`)
+#[derive(Debug, Clone, PartialEq)]
+pub struct AlgorithmMap {
+    /// Lowercase extension without the leading dot
+    pub extension: String,
+    pub algorithm: String,
+}
+
+impl AlgorithmMap {
+    /// Parse an `EXT=ALGO` spec, e.g. `mp4=xxh3` or `.mp4=xxh3`
+    pub fn parse(spec: &str) -> Result<Self, HashUtilityError> {
+        let invalid = |reason: &str| HashUtilityError::InvalidArguments {
+            message: format!("Invalid algorithm map '{}': {} (expected EXT=ALGO)", spec, reason),
+        };
+        
+        let (extension, algorithm) = spec.split_once('=').ok_or_else(|| invalid("missing '='"))?;
+        let extension = extension.trim_start_matches('.').to_lowercase();
+        if extension.is_empty() {
+            return Err(invalid("missing extension"));
+        }
+        if algorithm.is_empty() {
+            return Err(invalid("missing algorithm"));
+        }
+        HashRegistry::get_hasher(algorithm)?;
+        
+        Ok(Self {
+            extension,
+            algorithm: algorithm.to_string(),
+        })
+    }
+}
+
+/// What the scan progress bar counts (`--progress-by`)
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum ProgressBy {
+    /// Advance one step per file
+    #[default]
+    Files,
+    /// Advance by each file's size, so one huge file doesn't stall the bar
+    Bytes,
+}
+
+impl ProgressBy {
+    /// How far a file of `size` bytes moves the bar
+    fn step(self, size: u64) -> u64 {
+        match self {
+            ProgressBy::Files => 1,
+            ProgressBy::Bytes => size,
+        }
+    }
+    
+    /// Bar template once the total is known
+    fn template(self) -> &'static str {
+        match self {
+            ProgressBy::Files => "[{elapsed_precise}] [{bar:40.cyan/blue}] {pos}/{len} files ({percent}%) | Processed: {msg}",
+            ProgressBy::Bytes => "[{elapsed_precise}] [{bar:40.cyan/blue}] {bytes}/{total_bytes} ({percent}%, {bytes_per_sec}) | Processed: {msg}",
+        }
+    }
+}
+
+/// The output database and the files derived from it, which a scan never hashes
+/// 
+/// Covers the database itself, its `.xz` compressed copy and temporary siblings
+/// (`<name>.tmp*`). Files are matched by name and canonical parent directory, so
+/// variants that don't exist yet when the scan starts are still left out.
+struct OutputExclusion {
+    dir: PathBuf,
+    name: String,
+}
+
+impl OutputExclusion {
+    /// Exclusion for `output`, or None if its directory can't be resolved
+    fn new(output: &Path) -> Option<Self> {
+        let name = output.file_name()?.to_string_lossy().into_owned();
+        let parent = output.parent().filter(|parent| !parent.as_os_str().is_empty()).unwrap_or(Path::new("."));
+        let dir = parent.canonicalize().ok()?;
+        Some(Self { dir, name })
+    }
+    
+    /// Whether `path` is the output database or one of its variants
+    fn matches(&self, path: &Path) -> bool {
+        let Some(name) = path.file_name().map(|name| name.to_string_lossy()) else {
+            return false;
+        };
+        let is_variant = name == self.name
+            || name.strip_prefix(self.name.as_str()).is_some_and(|suffix| suffix == ".xz" || suffix.starts_with(".tmp"));
+        
+        // Only resolve the directory for files whose name matched
+        is_variant && path.parent().and_then(|parent| parent.canonicalize().ok()).is_some_and(|dir| dir == self.dir)
+    }
+}
+
+/// Files found by the sequential walk, with symlinks kept aside for `--hash-symlink-targets`
+#[derive(Default)]
+struct CollectedFiles {
+    files: Vec<PathBuf>,
+    /// Sum of the sizes of `files`
+    total_bytes: u64,
+    /// Symlinks that passed the same filters as files (only gathered with `--hash-symlink-targets`)
+    symlinks: Vec<PathBuf>,
+}
+
+/// Filters and limits the streaming walker applies, captured from the engine for its thread
+struct WalkFilters {
+    use_ignore: bool,
+    /// Output database to leave out, along with its `.xz` and `.tmp*` variants
+    exclude_file: Option<PathBuf>,
+    since: Option<SystemTime>,
+    skip_dirs: Vec<String>,
+    exclude_patterns: ExcludePatterns,
+    deadline: Option<Instant>,
+    /// Return the symlinks passing the filters instead of skipping them
+    collect_symlinks: bool,
+}
+
+/// Check a `--progress-template` with indicatif before any bar is drawn
+/// 
+/// A template that doesn't parse is reported as a warning and dropped,
+/// so the scan runs with the default bar instead of failing.
+pub fn checked_progress_template(template: Option<String>) -> Option<String> {
+    let template = template?;
+    match ProgressStyle::default_bar().template(&template) {
+        Ok(_) => Some(template),
+        Err(e) => {
+            eprintln!("Warning: Invalid progress template '{}' ({}), using the default", template, e);
+            None
+        }
+    }
+}
+
 /// Engine for scanning directories and generating hash databases
 pub struct ScanEngine {
     computer: HashComputer,
@@ -47,31 +282,112 @@ pub struct ScanEngine {
     fast_mode: bool,
     use_ignore: bool,
     format: DatabaseFormat,
+    binary_marker: bool,
+    direct_io: bool,
+    since: Option<SystemTime>,
+    include_xattrs: bool,
+    store_permissions: bool,
+    with_timestamp: bool,
+    skip_dirs: Vec<String>,
+    exclude_patterns: ExcludePatterns,
+    algorithm_map: Vec<AlgorithmMap>,
+    path_style: path_utils::PathStyle,
+    paths_relative_to_cwd: bool,
+    with_header: bool,
+    append: bool,
+    dedupe_entries: bool,
+    structure_only: bool,
+    hash_symlink_targets: bool,
+    report_slow: usize,
+    progress_by: ProgressBy,
+    progress_template: Option<String>,
+    deadline: Option<Instant>,
+    /// Set once the deadline passes (or on cancellation) so the walker and hashing threads stop taking new files
+    stop: Arc<AtomicBool>,
+    profile: Option<Arc<HashProfile>>,
+    /// Artificial per-file hashing delay so tests can make a scan outlast its deadline
+    #[cfg(test)]
+    file_delay: Option<Duration>,
 }
 
 impl ScanEngine {
     /// Create a new ScanEngine with default settings
     pub fn new() -> Self {
         Self {
-            computer: HashComputer::new(),
+            computer: HashComputer::new().with_sequential_hint(true),
             parallel: false,
             fast_mode: false,
             use_ignore: true,
             format: DatabaseFormat::Standard,
+            binary_marker: false,
+            direct_io: false,
+            since: None,
+            include_xattrs: false,
+            store_permissions: false,
+            with_timestamp: false,
+            skip_dirs: Vec::new(),
+            exclude_patterns: ExcludePatterns::default(),
+            algorithm_map: Vec::new(),
+            path_style: path_utils::PathStyle::Native,
+            paths_relative_to_cwd: false,
+            with_header: false,
+            append: false,
+            dedupe_entries: false,
+            structure_only: false,
+            hash_symlink_targets: false,
+            report_slow: 0,
+            progress_by: ProgressBy::Files,
+            progress_template: None,
+            deadline: None,
+            stop: Arc::new(AtomicBool::new(false)),
+            profile: None,
+            #[cfg(test)]
+            file_delay: None,
         }
     }
     
     /// Create a new ScanEngine with parallel processing enabled
     pub fn with_parallel(parallel: bool) -> Self {
         Self {
-            computer: HashComputer::new(),
+            // Sequential (HDD) mode hints sequential access to the kernel
+            computer: HashComputer::new().with_sequential_hint(!parallel),
             parallel,
             fast_mode: false,
             use_ignore: true,
             format: DatabaseFormat::Standard,
+            binary_marker: false,
+            direct_io: false,
+            since: None,
+            include_xattrs: false,
+            store_permissions: false,
+            with_timestamp: false,
+            skip_dirs: Vec::new(),
+            exclude_patterns: ExcludePatterns::default(),
+            algorithm_map: Vec::new(),
+            path_style: path_utils::PathStyle::Native,
+            paths_relative_to_cwd: false,
+            with_header: false,
+            append: false,
+            dedupe_entries: false,
+            structure_only: false,
+            hash_symlink_targets: false,
+            report_slow: 0,
+            progress_by: ProgressBy::Files,
+            progress_template: None,
+            deadline: None,
+            stop: Arc::new(AtomicBool::new(false)),
+            profile: None,
+            #[cfg(test)]
+            file_delay: None,
         }
     }
     
+    /// Whether files are hashed in parallel (false for sequential HDD mode)
+    #[cfg(test)]
+    fn is_parallel(&self) -> bool {
+        self.parallel
+    }
+    
     /// Enable or disable fast mode for large file hashing
     pub fn with_fast_mode(mut self, fast_mode: bool) -> Self {
         self.fast_mode = fast_mode;
@@ -90,6 +406,188 @@ impl ScanEngine {
         self
     }
     
+    /// Mark coreutils format entries as binary (`<hash> *<path>`) instead of text (`<hash>  <path>`)
+    pub fn with_binary_marker(mut self, binary_marker: bool) -> Self {
+        self.binary_marker = binary_marker;
+        self
+    }
+    
+    /// Enable or disable direct (page-cache bypassing) I/O when hashing files
+    pub fn with_direct_io(mut self, direct_io: bool) -> Self {
+        self.direct_io = direct_io;
+        self.computer = self.computer.with_direct_io(direct_io);
+        self
+    }
+    
+    /// Include extended attributes in file hashes
+    pub fn with_xattrs(mut self, include_xattrs: bool) -> Self {
+        self.include_xattrs = include_xattrs;
+        self.computer = self.computer.with_xattrs(include_xattrs);
+        self
+    }
+    
+    /// Record file permissions alongside each hash (standard format only)
+    pub fn with_store_permissions(mut self, store_permissions: bool) -> Self {
+        self.store_permissions = store_permissions;
+        self
+    }
+    
+    /// Record each file's modification time (RFC3339) alongside its hash (standard format only)
+    pub fn with_timestamp(mut self, with_timestamp: bool) -> Self {
+        self.with_timestamp = with_timestamp;
+        self
+    }
+    
+    /// Never descend into directories with any of these exact names (e.g. node_modules)
+    pub fn with_skip_dirs(mut self, skip_dirs: Vec<String>) -> Self {
+        self.skip_dirs = skip_dirs;
+        self
+    }
+    
+    /// Leave out files matching any of these patterns (`--exclude-from`)
+    /// 
+    /// Unlike .hashignore files, which live in the tree, these apply to this scan only.
+    pub fn with_exclude_patterns(mut self, exclude_patterns: ExcludePatterns) -> Self {
+        self.exclude_patterns = exclude_patterns;
+        self
+    }
+    
+    /// Hash files with these extensions using their own algorithm instead of the scan's default
+    /// 
+    /// The algorithm is recorded per entry, so verify checks each file the same way.
+    pub fn with_algorithm_map(mut self, algorithm_map: Vec<AlgorithmMap>) -> Self {
+        self.algorithm_map = algorithm_map;
+        self
+    }
+    
+    /// The algorithm for a file: its extension's override, or the default
+    fn algorithm_for<'a>(algorithm_map: &'a [AlgorithmMap], path: &Path, default: &'a str) -> &'a str {
+        let Some(extension) = path.extension() else {
+            return default;
+        };
+        let extension = extension.to_string_lossy().to_lowercase();
+        algorithm_map
+            .iter()
+            .find(|map| map.extension == extension)
+            .map_or(default, |map| map.algorithm.as_str())
+    }
+    
+    /// Write relative paths in the given style (native separators by default)
+    pub fn with_path_style(mut self, path_style: path_utils::PathStyle) -> Self {
+        self.path_style = path_style;
+        self
+    }
+    
+    /// Store paths relative to the current directory instead of the scanned root
+    /// 
+    /// Useful when an absolute directory is scanned but the database is consumed
+    /// from elsewhere. Files outside the current directory keep absolute paths.
+    pub fn with_paths_relative_to_cwd(mut self, paths_relative_to_cwd: bool) -> Self {
+        self.paths_relative_to_cwd = paths_relative_to_cwd;
+        self
+    }
+    
+    /// Directory that stored paths are made relative to: the scanned root, or the current directory
+    fn path_base(&self, canonical_root: &Path) -> Result<PathBuf, ScanError> {
+        if !self.paths_relative_to_cwd {
+            return Ok(canonical_root.to_path_buf());
+        }
+        std::env::current_dir()
+            .and_then(|cwd| cwd.canonicalize())
+            .map_err(|e| HashUtilityError::from_io_error(e, "resolving current directory", None))
+    }
+    
+    /// Hash each file's name, size and mtime instead of its content (standard format only)
+    /// 
+    /// Entries are marked `structure` so verify recomputes them the same way.
+    pub fn with_structure_only(mut self, structure_only: bool) -> Self {
+        self.structure_only = structure_only;
+        self
+    }
+    
+    /// Record symlinks, which are otherwise skipped, by hashing the path they point to (standard format only)
+    /// 
+    /// Entries are tagged with the `symlink` algorithm so verify re-reads the link
+    /// instead of following it, and a repointed link shows up as a mismatch.
+    pub fn with_hash_symlink_targets(mut self, hash_symlink_targets: bool) -> Self {
+        self.hash_symlink_targets = hash_symlink_targets;
+        self
+    }
+    
+    /// Track the `count` files that take longest to hash and report them in the stats (`--report-slow`)
+    pub fn with_report_slow(mut self, count: usize) -> Self {
+        self.report_slow = count;
+        self
+    }
+    
+    /// Advance the progress bar per file or per byte hashed
+    /// 
+    /// Byte mode sums file sizes while files are collected, which costs a
+    /// metadata lookup per file in parallel mode.
+    pub fn with_progress_by(mut self, progress_by: ProgressBy) -> Self {
+        self.progress_by = progress_by;
+        self
+    }
+    
+    /// Draw the progress bar with a custom indicatif template instead of the `progress_by` default
+    /// 
+    /// The template should already have been checked with `checked_progress_template`.
+    pub fn with_progress_template(mut self, progress_template: Option<String>) -> Self {
+        self.progress_template = progress_template;
+        self
+    }
+    
+    /// Start standard format databases with `#` comment lines recording how they were created
+    pub fn with_header(mut self, with_header: bool) -> Self {
+        self.with_header = with_header;
+        self
+    }
+    
+    /// Add entries to the end of an existing database instead of overwriting it (`--append`)
+    /// 
+    /// Headers are only written when the database is new or empty.
+    pub fn with_append(mut self, append: bool) -> Self {
+        self.append = append;
+        self
+    }
+    
+    /// Once the database is written, drop earlier entries for paths that appear again (`--dedupe-entries`)
+    /// 
+    /// The most recent entry for each path is kept; a warning is printed when the dropped hash differs.
+    pub fn with_dedupe_entries(mut self, dedupe_entries: bool) -> Self {
+        self.dedupe_entries = dedupe_entries;
+        self
+    }
+    
+    /// Stop taking new files once this instant passes (`--timeout`)
+    /// 
+    /// Files already hashed are still written, so the partial database stays valid.
+    pub fn with_deadline(mut self, deadline: Option<Instant>) -> Self {
+        self.deadline = deadline;
+        self
+    }
+    
+    /// Share a cancellation flag (set from a Ctrl-C handler) that stops the scan cleanly
+    /// 
+    /// As with a timeout, files already hashed are flushed to the database.
+    pub fn with_cancel_flag(mut self, cancel: Arc<AtomicBool>) -> Self {
+        self.stop = cancel;
+        self
+    }
+    
+    /// Split hashing time between file reads and hasher updates (`--profile`)
+    pub fn with_profile(mut self, profile: Option<Arc<HashProfile>>) -> Self {
+        self.profile = profile.clone();
+        self.computer = self.computer.with_profile(profile);
+        self
+    }
+    
+    /// Only hash files whose modification time is newer than the given cutoff
+    pub fn with_since(mut self, since: Option<SystemTime>) -> Self {
+        self.since = since;
+        self
+    }
+    
     /// Scan a directory recursively and write hash database to output file
     /// 
     /// # Arguments
@@ -124,108 +622,430 @@ impl ScanEngine {
         
         // Collect all files in the directory tree (only for sequential mode)
         println!("Scanning directory: {}", root.display());
-        let files = if !self.parallel {
-            self.collect_files_with_exclusion(root, Some(&output_absolute))?
+        // Walk the extended-length form of the root so deep trees stay readable on Windows
+        let collected = if !self.parallel {
+            self.collect_files_with_exclusion(&path_utils::extended_length_path(root), Some(&output_absolute))?
         } else {
             // For parallel mode, we don't pre-collect files
-            Vec::new()
+            CollectedFiles::default()
         };
         
         if !self.parallel {
-            println!("Found {} files to process", files.len());
+            println!("Found {} files to process", collected.files.len());
         }
         
         if self.fast_mode {
             println!("Fast mode enabled: sampling first, middle, and last 100MB of large files");
         }
         
-        if self.parallel {
-            self.scan_parallel(&files, algorithm, output, &canonical_root, &output_absolute, start_time)
+        if self.structure_only {
+            println!("Structure-only mode: hashing file names, sizes and modification times, not contents");
+        }
+        
+        if let Some(since) = self.since {
+            let cutoff: chrono::DateTime<chrono::Local> = since.into();
+            println!("Only hashing files modified since {}", cutoff.format("%Y-%m-%d %H:%M:%S"));
+        }
+        
+        // Symlinks come from the same walk that found the files
+        let (mut stats, symlinks) = if self.parallel {
+            self.scan_parallel(&collected.files, algorithm, output, &canonical_root, &output_absolute, start_time)?
+        } else {
+            let stats = self.scan_sequential(&collected.files, collected.total_bytes, algorithm, output, &canonical_root, start_time)?;
+            (stats, collected.symlinks)
+        };
+        
+        if self.hash_symlink_targets && !stats.timed_out && !stats.interrupted {
+            let recorded = self.append_symlink_entries(&canonical_root, output, symlinks)?;
+            if recorded > 0 {
+                println!("Recorded {} symlink targets", recorded);
+            }
+            stats.files_processed += recorded;
+        }
+        
+        if self.dedupe_entries {
+            (stats.duplicates_removed, stats.duplicate_conflicts) = self.dedupe_output(output)?;
+        }
+        Ok(stats)
+    }
+    
+    /// Append an entry for each symlink found by the walk, hashing its target path (`--hash-symlink-targets`)
+    /// 
+    /// The walk already applied the .hashignore, --skip-dir and --exclude-from filters to them.
+    /// Returns the number of entries written.
+    fn append_symlink_entries(&self, canonical_root: &Path, output: &Path, mut links: Vec<PathBuf>) -> Result<usize, ScanError> {
+        let write_error = |e: std::io::Error| {
+            HashUtilityError::from_io_error(e, "writing symlink entries", Some(output.to_path_buf()))
+        };
+        
+        let path_base = self.path_base(canonical_root)?;
+        let file = fs::OpenOptions::new().append(true).open(output).map_err(write_error)?;
+        let mut writer = BufWriter::new(file);
+        let mut recorded = 0;
+        
+        links.sort();
+        for link in links {
+            let result = match self.computer.compute_symlink_hash(&link) {
+                Ok(result) => result,
+                Err(e) => {
+                    eprintln!("Warning: Failed to hash symlink {}: {}", link.display(), e);
+                    continue;
+                }
+            };
+            
+            let path_to_write = self.link_database_path(&link, &path_base);
+            
+            DatabaseHandler::write_entry_with_metadata(
+                &mut writer,
+                &result.hash,
+                SYMLINK_ALGORITHM,
+                DatabaseHandler::mode_label(false, false),
+                EntryMetadata::default(),
+                &path_to_write,
+            )
+            .map_err(write_error)?;
+            recorded += 1;
+        }
+        
+        writer.flush().map_err(write_error)?;
+        Ok(recorded)
+    }
+    
+    /// Database path for a symlink, relative to `path_base` like the paths of files
+    fn link_database_path(&self, link: &Path, path_base: &Path) -> PathBuf {
+        // Keep the link's own name: canonicalizing it would name its target instead
+        match path_utils::canonicalize_link(link) {
+            Ok(link) => match link.strip_prefix(path_base) {
+                Ok(rel_path) => path_utils::format_database_path(rel_path, self.path_style),
+                Err(_) => path_utils::strip_extended_length_prefix(&link),
+            },
+            Err(_) => path_utils::strip_extended_length_prefix(link),
+        }
+    }
+    
+    /// Scan a directory and return its entries in memory instead of writing a database
+    /// 
+    /// Entries are keyed by the same relative paths a scan writes and carry the same hash,
+    /// mode, permissions and timestamp, sorted by path. A file whose path has an entry in
+    /// `reference` is hashed the way that entry was made instead (its algorithm, fast or
+    /// structure mode, or symlink target), so it matches if unchanged. Files that can't be
+    /// hashed are reported on stderr and left out. Hashing runs in parallel unless the engine is sequential.
+    pub fn scan_directory_to_entries(
+        &self,
+        root: &Path,
+        algorithm: &str,
+        reference: &HashMap<PathBuf, DatabaseEntry>,
+    ) -> Result<Vec<(PathBuf, DatabaseEntry)>, ScanError> {
+        let canonical_root = root.canonicalize().map_err(|e| {
+            HashUtilityError::from_io_error(e, "scanning directory", Some(root.to_path_buf()))
+        })?;
+        let path_base = self.path_base(&canonical_root)?;
+        let collected = self.collect_files_with_exclusion(&path_utils::extended_length_path(root), None)?;
+        
+        // Symlinks are only collected with --hash-symlink-targets
+        let paths: Vec<(PathBuf, bool)> = collected
+            .files
+            .into_iter()
+            .map(|file| (file, false))
+            .chain(collected.symlinks.into_iter().map(|link| (link, true)))
+            .collect();
+        
+        let hash_entry = |(file_path, is_link): &(PathBuf, bool)| -> Option<(PathBuf, DatabaseEntry)> {
+            // Stop taking new files once the deadline has passed or the scan was cancelled
+            if Self::deadline_reached(self.deadline, &self.stop) {
+                return None;
+            }
+            
+            let path = if *is_link {
+                self.link_database_path(file_path, &path_base)
+            } else {
+                match path_utils::get_relative_path_cached(file_path, &path_base) {
+                    Ok(rel_path) => path_utils::format_database_path(&rel_path, self.path_style),
+                    Err(_) => path_utils::strip_extended_length_prefix(file_path),
+                }
+            };
+            
+            // How this file is hashed: like its reference entry, otherwise with this scan's settings
+            let like = match reference.get(&path) {
+                Some(entry) => entry.clone(),
+                None => DatabaseEntry {
+                    hash: String::new(),
+                    algorithm: if *is_link { SYMLINK_ALGORITHM } else { Self::algorithm_for(&self.algorithm_map, file_path, algorithm) }.to_string(),
+                    fast_mode: self.fast_mode && !is_link,
+                    permissions: None,
+                    modified: None,
+                    size: None,
+                    structure_only: self.structure_only && !is_link,
+                    fast_v2: self.fast_mode && !is_link,
+                },
+            };
+            let result = match like.rehash(&self.computer, file_path) {
+                Ok(result) => result,
+                Err(e) => {
+                    eprintln!("Warning: Failed to hash {}: {}", file_path.display(), e);
+                    return None;
+                }
+            };
+            
+            let metadata = fs::metadata(file_path).ok();
+            let entry = DatabaseEntry {
+                hash: result.hash,
+                permissions: if self.store_permissions { metadata.as_ref().map(DatabaseHandler::format_permissions) } else { None },
+                modified: if self.with_timestamp { metadata.as_ref().and_then(DatabaseHandler::format_modified) } else { None },
+                ..like
+            };
+            Some((path, entry))
+        };
+        
+        let mut entries: Vec<(PathBuf, DatabaseEntry)> = if self.parallel {
+            paths.par_iter().filter_map(hash_entry).collect()
+        } else {
+            paths.iter().filter_map(hash_entry).collect()
+        };
+        entries.sort_by(|a, b| a.0.cmp(&b.0));
+        Ok(entries)
+    }
+    
+    /// Rewrite the output without earlier entries for repeated paths, warning about conflicting hashes
+    fn dedupe_output(&self, output: &Path) -> Result<(usize, Vec<DuplicateConflict>), ScanError> {
+        let content = fs::read_to_string(output).map_err(|e| {
+            HashUtilityError::from_io_error(e, "reading output file", Some(output.to_path_buf()))
+        })?;
+        
+        let (deduped, removed, conflicts) = DatabaseHandler::dedupe_entries(&content, self.format);
+        if removed == 0 {
+            return Ok((0, conflicts));
+        }
+        
+        for conflict in &conflicts {
+            eprintln!(
+                "Warning: {} was listed more than once with different hashes ({} replaced by {}); keeping the most recent",
+                conflict.path.display(),
+                conflict.dropped_hash,
+                conflict.kept_hash
+            );
+        }
+        
+        fs::write(output, deduped).map_err(|e| {
+            HashUtilityError::from_io_error(e, "writing output file", Some(output.to_path_buf()))
+        })?;
+        println!("Removed {} duplicate entries", removed);
+        Ok((removed, conflicts))
+    }
+    
+    /// Whether the scan should stop taking new files, latching the stop flag once the deadline passes
+    /// 
+    /// The flag may also have been set by a cancellation handler.
+    fn deadline_reached(deadline: Option<Instant>, stop: &AtomicBool) -> bool {
+        if stop.load(Ordering::Relaxed) {
+            return true;
+        }
+        if deadline.is_some_and(|deadline| Instant::now() >= deadline) {
+            stop.store(true, Ordering::Relaxed);
+            return true;
+        }
+        false
+    }
+    
+    /// Why the scan stopped early, as (timed_out, interrupted)
+    fn stop_reason(&self) -> (bool, bool) {
+        if !self.stop.load(Ordering::Relaxed) {
+            return (false, false);
+        }
+        let timed_out = self.deadline.is_some_and(|deadline| Instant::now() >= deadline);
+        (timed_out, !timed_out)
+    }
+    
+    /// Print the end-of-scan summary
+    fn print_summary(stats: &ScanStats, files_skipped: usize, output: &Path) {
+        if stats.timed_out {
+            println!("\nScan timed out! Partial results were saved.");
+        } else if stats.interrupted {
+            println!("\nScan interrupted! Partial results were saved.");
         } else {
-            self.scan_sequential(&files, algorithm, output, &canonical_root, start_time)
+            println!("\nScan complete!");
+        }
+        println!("Files processed: {}", stats.files_processed);
+        println!("Files failed: {}", stats.files_failed - files_skipped);
+        println!("Files permission denied: {}", stats.files_permission_denied);
+        println!("Files skipped: {}", files_skipped);
+        println!("Empty files: {}", stats.empty_files);
+        println!("Total bytes: {} ({})", stats.total_bytes, path_utils::format_size(stats.total_bytes));
+        println!("Duration: {:.2}s", stats.duration.as_secs_f64());
+        
+        if !stats.slowest_files.is_empty() {
+            println!("Slowest files:");
+            for file in &stats.slowest_files {
+                println!("  {:>9.3}s  {}", file.duration.as_secs_f64(), file.path.display());
+            }
+        }
+        
+        // Calculate and display throughput
+        if stats.duration.as_secs_f64() > 0.0 {
+            println!("Throughput: {}", path_utils::format_throughput(stats.total_bytes, stats.duration));
         }
+        
+        println!("Output written to: {}", output.display());
+    }
+    
+    /// The invocation recorded in the standard header
+    fn command_line() -> String {
+        std::env::args().collect::<Vec<_>>().join(" ")
+    }
+    
+    /// Open the output database, truncating it unless appending
+    /// 
+    /// Also returns whether the file already had entries, in which case no header is written.
+    fn open_output(&self, output: &Path) -> Result<(File, bool), ScanError> {
+        if !self.append {
+            let file = File::create(output).map_err(|e| {
+                HashUtilityError::from_io_error(e, "creating output file", Some(output.to_path_buf()))
+            })?;
+            return Ok((file, false));
+        }
+        
+        let continuing = fs::metadata(output).map(|metadata| metadata.len() > 0).unwrap_or(false);
+        let file = fs::OpenOptions::new()
+            .create(true)
+            .append(true)
+            .open(output)
+            .map_err(|e| {
+                HashUtilityError::from_io_error(e, "opening output file for append", Some(output.to_path_buf()))
+            })?;
+        Ok((file, continuing))
     }
     
     /// Sequential scan implementation
     fn scan_sequential(
         &self,
         files: &[PathBuf],
+        collected_bytes: u64,
         algorithm: &str,
         output: &Path,
         canonical_root: &Path,
         start_time: Instant,
     ) -> Result<ScanStats, ScanError> {
+        let path_base = self.path_base(canonical_root)?;
+        
         // Open output file for writing
-        let output_file = File::create(output).map_err(|e| {
-            HashUtilityError::from_io_error(e, "creating output file", Some(output.to_path_buf()))
-        })?;
+        let (output_file, continuing) = self.open_output(output)?;
         let mut writer = BufWriter::new(output_file);
         
         // Write hashdeep header if using hashdeep format
-        if self.format == DatabaseFormat::Hashdeep {
+        if self.format == DatabaseFormat::Hashdeep && !continuing {
             DatabaseHandler::write_hashdeep_header(&mut writer, &[algorithm.to_string()])
                 .map_err(|e| {
                     HashUtilityError::from_io_error(e, "writing hashdeep header", Some(output.to_path_buf()))
                 })?;
         }
         
-        // Track statistics
-        let mut files_processed = 0;
-        let mut files_failed = 0;
-        let mut files_skipped = 0;
-        let mut total_bytes = 0u64;
+        // Write the optional standard header
+        if self.format == DatabaseFormat::Standard && self.with_header && !continuing {
+            DatabaseHandler::write_standard_header(&mut writer, algorithm, &Self::command_line())
+                .map_err(|e| {
+                    HashUtilityError::from_io_error(e, "writing database header", Some(output.to_path_buf()))
+                })?;
+        }
+        
+        // Open the JSON array
+        if self.format == DatabaseFormat::JsonDb {
+            DatabaseHandler::write_json_db_start(&mut writer).map_err(|e| {
+                HashUtilityError::from_io_error(e, "writing database", Some(output.to_path_buf()))
+            })?;
+        }
+        
+        // Track statistics
+        let mut files_processed = 0;
+        let mut files_failed = 0;
+        let mut files_permission_denied = 0;
+        let mut empty_files = 0;
+        let mut files_skipped = 0;
+        let mut total_bytes = 0u64;
+        let slowest_files = SlowestFiles::new(self.report_slow);
         
-        // Create progress bar
-        let pb = ProgressBar::new(files.len() as u64);
+        // Create progress bar, sized in files or in bytes
+        let pb = ProgressBar::new(match self.progress_by {
+            ProgressBy::Files => files.len() as u64,
+            ProgressBy::Bytes => collected_bytes,
+        });
         pb.set_style(
             ProgressStyle::default_bar()
-                .template("[{elapsed_precise}] [{bar:40.cyan/blue}] {pos}/{len} files ({percent}%) | Processed: {msg}")
+                .template(self.progress_template.as_deref().unwrap_or(self.progress_by.template()))
                 .unwrap()
                 .progress_chars("=>-")
         );
         
         // Process each file
         for file_path in files.iter() {
+            // Stop taking new files once the deadline has passed or the scan was cancelled
+            if Self::deadline_reached(self.deadline, &self.stop) {
+                break;
+            }
+            
             // Update progress bar with counts instead of filename to avoid encoding issues
             pb.set_message(format!("{} OK, {} failed, {} skipped", files_processed, files_failed, files_skipped));
             
             // Check if file still exists and is accessible before processing
-            let metadata_check = fs::metadata(file_path);
-            if metadata_check.is_err() {
-                files_skipped += 1;
-                pb.inc(1);
-                continue;
+            let progress_step = match fs::metadata(file_path) {
+                Ok(metadata) => self.progress_by.step(metadata.len()),
+                Err(_) => {
+                    files_skipped += 1;
+                    pb.inc(self.progress_by.step(0));
+                    continue;
+                }
+            };
+            
+            let hash_started = Instant::now();
+            
+            #[cfg(test)]
+            if let Some(delay) = self.file_delay {
+                thread::sleep(delay);
             }
             
-            // Compute hash for the file (using fast mode if enabled)
-            let hash_result = if self.fast_mode {
-                self.computer.compute_hash_fast(file_path, algorithm)
+            // Compute hash for the file (from metadata only, or using fast mode if enabled)
+            let file_algorithm = Self::algorithm_for(&self.algorithm_map, file_path, algorithm);
+            let hash_result = if self.structure_only {
+                self.computer.compute_structure_hash(file_path, file_algorithm)
+            } else if self.fast_mode {
+                self.computer.compute_hash_fast_v2(file_path, file_algorithm)
             } else {
-                self.computer.compute_hash(file_path, algorithm)
+                self.computer.compute_hash(file_path, file_algorithm)
             };
             
             match hash_result {
                 Ok(result) => {
+                    slowest_files.record(file_path, hash_started.elapsed());
+                    
                     // Try to get relative path for cleaner database entries
-                    // Use cached version since canonical_root is already canonicalized
-                    let path_to_write = match path_utils::get_relative_path_cached(file_path, canonical_root) {
-                        Ok(rel_path) => rel_path,
-                        Err(_) => file_path.clone(),
+                    // Use cached version since path_base is already canonicalized
+                    let path_to_write = match path_utils::get_relative_path_cached(file_path, &path_base) {
+                        Ok(rel_path) => path_utils::format_database_path(&rel_path, self.path_style),
+                        Err(_) => path_utils::strip_extended_length_prefix(file_path),
                     };
                     
-                    // Get file size for hashdeep format
-                    let file_size = fs::metadata(file_path)
-                        .map(|m| m.len())
-                        .unwrap_or(0);
+                    // Get file size for hashdeep format, plus permissions and mtime if requested
+                    let metadata = fs::metadata(file_path).ok();
+                    let file_size = metadata.as_ref().map(|m| m.len()).unwrap_or(0);
+                    let permissions = if self.store_permissions {
+                        metadata.as_ref().map(DatabaseHandler::format_permissions)
+                    } else {
+                        None
+                    };
+                    let modified = if self.with_timestamp {
+                        metadata.as_ref().and_then(DatabaseHandler::format_modified)
+                    } else {
+                        None
+                    };
                     
                     // Write hash entry to database with metadata
                     let write_result = match self.format {
                         DatabaseFormat::Standard => {
-                            DatabaseHandler::write_entry(
+                            DatabaseHandler::write_entry_with_metadata(
                                 &mut writer,
                                 &result.hash,
-                                algorithm,
-                                self.fast_mode,
+                                file_algorithm,
+                                DatabaseHandler::mode_label(self.fast_mode, self.structure_only),
+                                EntryMetadata { permissions: permissions.as_deref(), modified: modified.as_deref(), size: None },
                                 &path_to_write,
                             )
                         }
@@ -237,6 +1057,34 @@ impl ScanEngine {
                                 &path_to_write,
                             )
                         }
+                        DatabaseFormat::Tsv => {
+                            DatabaseHandler::write_tsv_entry(
+                                &mut writer,
+                                &result.hash,
+                                file_algorithm,
+                                file_size,
+                                &path_to_write,
+                            )
+                        }
+                        DatabaseFormat::Coreutils => {
+                            DatabaseHandler::write_coreutils_entry(
+                                &mut writer,
+                                &result.hash,
+                                self.binary_marker,
+                                &path_to_write,
+                            )
+                        }
+                        DatabaseFormat::JsonDb => {
+                            DatabaseHandler::write_json_db_entry(
+                                &mut writer,
+                                files_processed == 0,
+                                &result.hash,
+                                file_algorithm,
+                                DatabaseHandler::mode_label(self.fast_mode, self.structure_only),
+                                file_size,
+                                &path_to_write,
+                            )
+                        }
                     };
                     
                     if let Err(e) = write_result {
@@ -246,44 +1094,59 @@ impl ScanEngine {
                     } else {
                         files_processed += 1;
                         total_bytes += file_size;
+                        if file_size == 0 {
+                            empty_files += 1;
+                        }
                     }
                 }
                 Err(e) => {
                     // Log error but continue processing
                     eprintln!("Warning: Failed to hash {}: {}", file_path.display(), e);
-                    files_failed += 1;
+                    if matches!(e, HashUtilityError::PermissionDenied { .. }) {
+                        files_permission_denied += 1;
+                    } else {
+                        files_failed += 1;
+                    }
                 }
             }
             
-            pb.inc(1);
+            pb.inc(progress_step);
         }
         
         let duration = start_time.elapsed();
         
-        // Clear progress bar and display summary
-        pb.finish_and_clear();
-        
-        println!("\nScan complete!");
-        println!("Files processed: {}", files_processed);
-        println!("Files failed: {}", files_failed);
-        println!("Files skipped: {}", files_skipped);
-        println!("Total bytes: {} ({:.2} MB)", total_bytes, total_bytes as f64 / 1_048_576.0);
-        println!("Duration: {:.2}s", duration.as_secs_f64());
-        
-        // Calculate and display throughput
-        if duration.as_secs_f64() > 0.0 {
-            let throughput_mbps = (total_bytes as f64 / 1_048_576.0) / duration.as_secs_f64();
-            println!("Throughput: {:.2} MB/s", throughput_mbps);
+        // Close the JSON array
+        if self.format == DatabaseFormat::JsonDb {
+            DatabaseHandler::write_json_db_end(&mut writer, files_processed).map_err(|e| {
+                HashUtilityError::from_io_error(e, "writing database", Some(output.to_path_buf()))
+            })?;
         }
         
-        println!("Output written to: {}", output.display());
+        // Make sure everything hashed so far reaches disk, even when the scan stopped early
+        writer.flush().map_err(|e| {
+            HashUtilityError::from_io_error(e, "flushing output file", Some(output.to_path_buf()))
+        })?;
         
-        Ok(ScanStats {
+        // Clear progress bar and display summary
+        pb.finish_and_clear();
+        
+        let (timed_out, interrupted) = self.stop_reason();
+        let stats = ScanStats {
             files_processed,
             files_failed: files_failed + files_skipped,
+            files_permission_denied,
+            empty_files,
             total_bytes,
             duration,
-        })
+            timed_out,
+            interrupted,
+            slowest_files: slowest_files.into_sorted(),
+            duplicates_removed: 0,
+            duplicate_conflicts: Vec::new(),
+        };
+        Self::print_summary(&stats, files_skipped, output);
+        
+        Ok(stats)
     }
     
     /// Parallel scan implementation using producer-consumer pattern with jwalk and crossbeam-channel
@@ -295,19 +1158,27 @@ impl ScanEngine {
         canonical_root: &Path,
         output_absolute: &Path,
         start_time: Instant,
-    ) -> Result<ScanStats, ScanError> {
+    ) -> Result<(ScanStats, Vec<PathBuf>), ScanError> {
         // Thread-safe counters for progress tracking
         let files_processed = Arc::new(Mutex::new(0usize));
         let files_failed = Arc::new(Mutex::new(0usize));
+        let files_permission_denied = Arc::new(Mutex::new(0usize));
+        let empty_files = Arc::new(Mutex::new(0usize));
         let files_skipped = Arc::new(Mutex::new(0usize));
         let total_bytes = Arc::new(Mutex::new(0u64));
+        let slowest_files = SlowestFiles::new(self.report_slow);
         
         // Create progress bar (we'll update the style once discovery is complete)
+        let progress_by = self.progress_by;
+        let progress_template = self.progress_template.clone();
         let pb = ProgressBar::new(0);
         // Start with "Counting..." style
         pb.set_style(
             ProgressStyle::default_bar()
-                .template("[{elapsed_precise}] Counting... {pos} files found | Processing: {msg}")
+                .template(match progress_by {
+                    ProgressBy::Files => "[{elapsed_precise}] Counting... {pos} files found | Processing: {msg}",
+                    ProgressBy::Bytes => "[{elapsed_precise}] Counting... {bytes} hashed | Processing: {msg}",
+                })
                 .unwrap()
                 .progress_chars("=>-")
         );
@@ -316,33 +1187,60 @@ impl ScanEngine {
         // Larger buffer helps with very large directory scans
         let (sender, receiver) = bounded::<PathBuf>(10000);
         
-        // Track total files discovered
+        // Track total files discovered, and their total size when the bar counts bytes
         let total_files_discovered = Arc::new(Mutex::new(0usize));
+        let total_bytes_discovered = match progress_by {
+            ProgressBy::Files => None,
+            ProgressBy::Bytes => Some(Arc::new(AtomicU64::new(0))),
+        };
         let discovery_complete = Arc::new(Mutex::new(false));
         
-        // Capture fast_mode for use in closure
+        // Capture hashing options for use in closure
         let fast_mode = self.fast_mode;
-        
-        // Clone canonical_root and output_absolute for the walker thread
+        let structure_only = self.structure_only;
+        let direct_io = self.direct_io;
+        let include_xattrs = self.include_xattrs;
+        let store_permissions = self.store_permissions;
+        let with_timestamp = self.with_timestamp;
+        let path_style = self.path_style;
+        let algorithm_map = &self.algorithm_map;
+        let deadline = self.deadline;
+        let profile = self.profile.clone();
+        #[cfg(test)]
+        let file_delay = self.file_delay;
+        
+        // Clone canonical_root and the filters for the walker thread
         let walker_root = canonical_root.to_path_buf();
-        let use_ignore = self.use_ignore;
-        let output_to_exclude = output_absolute.to_path_buf();
+        let filters = WalkFilters {
+            use_ignore: self.use_ignore,
+            exclude_file: Some(output_absolute.to_path_buf()),
+            since: self.since,
+            skip_dirs: self.skip_dirs.clone(),
+            exclude_patterns: self.exclude_patterns.clone(),
+            deadline,
+            collect_symlinks: self.hash_symlink_targets,
+        };
         
         // Clone for walker thread
         let total_files_discovered_walker = Arc::clone(&total_files_discovered);
+        let total_bytes_discovered_walker = total_bytes_discovered.clone();
         let discovery_complete_walker = Arc::clone(&discovery_complete);
         let pb_walker = pb.clone();
+        let stop_walker = Arc::clone(&self.stop);
         
         // Spawn walker thread using jwalk to traverse directories
         let walker_handle = thread::spawn(move || {
-            let result = Self::walk_directory_streaming(&walker_root, sender, use_ignore, Some(&output_to_exclude), Arc::clone(&total_files_discovered_walker));
+            let result = Self::walk_directory_streaming(&walker_root, sender, filters, Arc::clone(&total_files_discovered_walker), total_bytes_discovered_walker.clone(), stop_walker);
             
             // Mark discovery as complete and update progress bar with total and new style
-            let total = *total_files_discovered_walker.lock().unwrap();
-            pb_walker.set_length(total as u64);
+            let total = match &total_bytes_discovered_walker {
+                Some(bytes) => bytes.load(Ordering::Relaxed),
+                None => *total_files_discovered_walker.lock().unwrap() as u64,
+            };
+            pb_walker.set_length(total);
             pb_walker.set_style(
                 ProgressStyle::default_bar()
-                    .template("[{elapsed_precise}] [{bar:40.cyan/blue}] {pos}/{len} files ({percent}%) | Processed: {msg}")
+                    .template(progress_template.as_deref().unwrap_or(progress_by.template()))
                     .unwrap()
                     .progress_chars("=>-")
             );
@@ -354,10 +1252,13 @@ impl ScanEngine {
         // Clone Arc references for use in parallel closure
         let files_processed_clone = Arc::clone(&files_processed);
         let files_failed_clone = Arc::clone(&files_failed);
+        let files_permission_denied_clone = Arc::clone(&files_permission_denied);
+        let empty_files_clone = Arc::clone(&empty_files);
         let files_skipped_clone = Arc::clone(&files_skipped);
         let total_bytes_clone = Arc::clone(&total_bytes);
         let pb_clone = pb.clone();
-        let canonical_root_clone = canonical_root.to_path_buf();
+        let path_base = self.path_base(canonical_root)?;
+        let stop = Arc::clone(&self.stop);
         
         // Use rayon's par_bridge to consume from channel in parallel
         // This starts hashing immediately as files are discovered
@@ -365,15 +1266,22 @@ impl ScanEngine {
             .into_iter()
             .par_bridge()
             .filter_map(|file_path| {
-                // Check if file still exists and is accessible before processing
-                let metadata_check = fs::metadata(&file_path);
-                if metadata_check.is_err() {
-                    let mut skipped = files_skipped_clone.lock().unwrap();
-                    *skipped += 1;
-                    pb_clone.inc(1);
+                // Drain the channel without hashing once the deadline has passed or the scan was cancelled
+                if Self::deadline_reached(deadline, &stop) {
                     return None;
                 }
                 
+                // Check if file still exists and is accessible before processing
+                let progress_step = match fs::metadata(&file_path) {
+                    Ok(metadata) => progress_by.step(metadata.len()),
+                    Err(_) => {
+                        let mut skipped = files_skipped_clone.lock().unwrap();
+                        *skipped += 1;
+                        pb_clone.inc(progress_by.step(0));
+                        return None;
+                    }
+                };
+                
                 // Update progress bar with counts instead of filename to avoid encoding issues
                 let processed = files_processed_clone.lock().unwrap();
                 let failed = files_failed_clone.lock().unwrap();
@@ -383,64 +1291,97 @@ impl ScanEngine {
                 drop(failed);
                 drop(skipped);
                 
-                // Compute hash for the file (using fast mode if enabled)
-                let computer = HashComputer::new();
-                let hash_result = if fast_mode {
-                    computer.compute_hash_fast(&file_path, algorithm)
+                let hash_started = Instant::now();
+                
+                #[cfg(test)]
+                if let Some(delay) = file_delay {
+                    thread::sleep(delay);
+                }
+                
+                // Compute hash for the file (from metadata only, or using fast mode if enabled)
+                let computer = HashComputer::new()
+                    .with_direct_io(direct_io)
+                    .with_xattrs(include_xattrs)
+                    .with_profile(profile.clone());
+                let file_algorithm = Self::algorithm_for(algorithm_map, &file_path, algorithm);
+                let hash_result = if structure_only {
+                    computer.compute_structure_hash(&file_path, file_algorithm)
+                } else if fast_mode {
+                    computer.compute_hash_fast_v2(&file_path, file_algorithm)
                 } else {
-                    computer.compute_hash(&file_path, algorithm)
+                    computer.compute_hash(&file_path, file_algorithm)
                 };
                 
                 let result = match hash_result {
                     Ok(result) => {
+                        slowest_files.record(&file_path, hash_started.elapsed());
+                        
                         // Try to get relative path for cleaner database entries
-                        // Use cached version since canonical_root_clone is already canonicalized
-                        let path_to_write = match path_utils::get_relative_path_cached(&file_path, &canonical_root_clone) {
-                            Ok(rel_path) => rel_path,
-                            Err(_) => file_path.clone(),
+                        // Use cached version since path_base is already canonicalized
+                        let path_to_write = match path_utils::get_relative_path_cached(&file_path, &path_base) {
+                            Ok(rel_path) => path_utils::format_database_path(&rel_path, path_style),
+                            Err(_) => path_utils::strip_extended_length_prefix(&file_path),
                         };
                         
-                        // Track file size
+                        // Track file size and capture permissions and mtime if requested
+                        let mut permissions = None;
+                        let mut modified = None;
+                        let mut file_size = 0;
                         if let Ok(metadata) = fs::metadata(&file_path) {
+                            file_size = metadata.len();
                             let mut bytes = total_bytes_clone.lock().unwrap();
-                            *bytes += metadata.len();
+                            *bytes += file_size;
+                            if file_size == 0 {
+                                *empty_files_clone.lock().unwrap() += 1;
+                            }
+                            if store_permissions {
+                                permissions = Some(DatabaseHandler::format_permissions(&metadata));
+                            }
+                            if with_timestamp {
+                                modified = DatabaseHandler::format_modified(&metadata);
+                            }
                         }
                         
                         // Update success counter
                         let mut processed = files_processed_clone.lock().unwrap();
                         *processed += 1;
                         
-                        Some((result.hash, path_to_write))
+                        Some((result.hash, path_to_write, permissions, modified, file_size, file_algorithm))
                     }
                     Err(e) => {
                         // Log error but continue processing
                         eprintln!("Warning: Failed to hash {}: {}", file_path.display(), e);
                         
-                        // Update failure counter
-                        let mut failed = files_failed_clone.lock().unwrap();
-                        *failed += 1;
+                        // Update failure counter, keeping access problems separate
+                        let counter = if matches!(e, HashUtilityError::PermissionDenied { .. }) {
+                            &files_permission_denied_clone
+                        } else {
+                            &files_failed_clone
+                        };
+                        *counter.lock().unwrap() += 1;
                         
                         None
                     }
                 };
                 
-                pb_clone.inc(1);
+                pb_clone.inc(progress_step);
                 result
             })
             .collect();
         
         // Wait for walker thread to complete
         // Note: The walker thread should already be done since we consumed all items from the channel
-        match walker_handle.join() {
-            Ok(walk_result) => {
-                if let Err(e) = walk_result {
-                    eprintln!("Warning: Walker thread encountered error: {}", e);
-                }
+        let symlinks = match walker_handle.join() {
+            Ok(Ok(symlinks)) => symlinks,
+            Ok(Err(e)) => {
+                eprintln!("Warning: Walker thread encountered error: {}", e);
+                Vec::new()
             }
             Err(e) => {
                 eprintln!("Warning: Walker thread panicked: {:?}", e);
+                Vec::new()
             }
-        }
+        };
         
         let duration = start_time.elapsed();
         
@@ -448,48 +1389,94 @@ impl ScanEngine {
         pb.finish_and_clear();
         
         // Write all results to output file
-        let output_file = File::create(output).map_err(|e| {
-            HashUtilityError::from_io_error(e, "creating output file", Some(output.to_path_buf()))
-        })?;
+        let (output_file, continuing) = self.open_output(output)?;
         let mut writer = BufWriter::new(output_file);
         
         // Write hashdeep header if using hashdeep format
-        if self.format == DatabaseFormat::Hashdeep {
+        if self.format == DatabaseFormat::Hashdeep && !continuing {
             if let Err(e) = DatabaseHandler::write_hashdeep_header(&mut writer, &[algorithm.to_string()]) {
                 eprintln!("Warning: Failed to write hashdeep header: {}", e);
             }
         }
         
+        // Write the optional standard header
+        if self.format == DatabaseFormat::Standard && self.with_header && !continuing {
+            if let Err(e) = DatabaseHandler::write_standard_header(&mut writer, algorithm, &Self::command_line()) {
+                eprintln!("Warning: Failed to write database header: {}", e);
+            }
+        }
+        
+        // Open the JSON array
+        if self.format == DatabaseFormat::JsonDb {
+            DatabaseHandler::write_json_db_start(&mut writer).map_err(|e| {
+                HashUtilityError::from_io_error(e, "writing database", Some(output.to_path_buf()))
+            })?;
+        }
+        
+        let mut entries_written = 0;
         for result in results.iter() {
             let write_result = match self.format {
                 DatabaseFormat::Standard => {
-                    DatabaseHandler::write_entry(
+                    DatabaseHandler::write_entry_with_metadata(
                         &mut writer,
                         &result.0,
-                        algorithm,
-                        fast_mode,
+                        result.5,
+                        DatabaseHandler::mode_label(fast_mode, structure_only),
+                        EntryMetadata { permissions: result.2.as_deref(), modified: result.3.as_deref(), size: None },
                         &result.1,
                     )
                 }
                 DatabaseFormat::Hashdeep => {
-                    // Get file size
-                    let file_size = fs::metadata(&result.1)
-                        .map(|m| m.len())
-                        .unwrap_or(0);
                     DatabaseHandler::write_hashdeep_entry(
                         &mut writer,
-                        file_size,
+                        result.4,
                         &[result.0.clone()],
                         &result.1,
                     )
                 }
+                DatabaseFormat::Tsv => {
+                    DatabaseHandler::write_tsv_entry(
+                        &mut writer,
+                        &result.0,
+                        result.5,
+                        result.4,
+                        &result.1,
+                    )
+                }
+                DatabaseFormat::Coreutils => {
+                    DatabaseHandler::write_coreutils_entry(
+                        &mut writer,
+                        &result.0,
+                        self.binary_marker,
+                        &result.1,
+                    )
+                }
+                DatabaseFormat::JsonDb => {
+                    DatabaseHandler::write_json_db_entry(
+                        &mut writer,
+                        entries_written == 0,
+                        &result.0,
+                        result.5,
+                        DatabaseHandler::mode_label(fast_mode, structure_only),
+                        result.4,
+                        &result.1,
+                    )
+                }
             };
             
-            if let Err(e) = write_result {
-                eprintln!("Warning: Failed to write entry: {}", e);
+            match write_result {
+                Ok(()) => entries_written += 1,
+                Err(e) => eprintln!("Warning: Failed to write entry: {}", e),
             }
         }
         
+        // Close the JSON array
+        if self.format == DatabaseFormat::JsonDb {
+            DatabaseHandler::write_json_db_end(&mut writer, entries_written).map_err(|e| {
+                HashUtilityError::from_io_error(e, "writing database", Some(output.to_path_buf()))
+            })?;
+        }
+        
         // Flush the writer to ensure all data is written
         writer.flush().map_err(|e| {
             HashUtilityError::from_io_error(e, "flushing output file", Some(output.to_path_buf()))
@@ -498,42 +1485,47 @@ impl ScanEngine {
         // Extract final statistics
         let final_processed = *files_processed.lock().unwrap();
         let final_failed = *files_failed.lock().unwrap();
+        let final_permission_denied = *files_permission_denied.lock().unwrap();
+        let final_empty = *empty_files.lock().unwrap();
         let final_skipped = *files_skipped.lock().unwrap();
         let final_bytes = *total_bytes.lock().unwrap();
         
-        // Display summary
-        println!("\nScan complete!");
-        println!("Files processed: {}", final_processed);
-        println!("Files failed: {}", final_failed);
-        println!("Files skipped: {}", final_skipped);
-        println!("Total bytes: {} ({:.2} MB)", final_bytes, final_bytes as f64 / 1_048_576.0);
-        println!("Duration: {:.2}s", duration.as_secs_f64());
-        
-        // Calculate and display throughput
-        if duration.as_secs_f64() > 0.0 {
-            let throughput_mbps = (final_bytes as f64 / 1_048_576.0) / duration.as_secs_f64();
-            println!("Throughput: {:.2} MB/s", throughput_mbps);
-        }
-        
-        println!("Output written to: {}", output.display());
-        
-        Ok(ScanStats {
+        let (timed_out, interrupted) = self.stop_reason();
+        let stats = ScanStats {
             files_processed: final_processed,
             files_failed: final_failed + final_skipped,
+            files_permission_denied: final_permission_denied,
+            empty_files: final_empty,
             total_bytes: final_bytes,
             duration,
-        })
+            timed_out,
+            interrupted,
+            slowest_files: slowest_files.into_sorted(),
+            duplicates_removed: 0,
+            duplicate_conflicts: Vec::new(),
+        };
+        
+        // Display summary
+        Self::print_summary(&stats, final_skipped, output);
+        
+        Ok((stats, symlinks))
     }
     
     /// Walk directory using jwalk and send file paths to channel as they're discovered
     /// This is the producer in the producer-consumer pattern
+    /// 
+    /// Returns the symlinks that passed the filters when `filters.collect_symlinks` is set.
     fn walk_directory_streaming(
         root: &Path,
         sender: Sender<PathBuf>,
-        use_ignore: bool,
-        exclude_file: Option<&Path>,
+        filters: WalkFilters,
         total_files_discovered: Arc<Mutex<usize>>,
-    ) -> Result<(), ScanError> {
+        total_bytes_discovered: Option<Arc<AtomicU64>>,
+        stop: Arc<AtomicBool>,
+    ) -> Result<Vec<PathBuf>, ScanError> {
+        let WalkFilters { use_ignore, exclude_file, since, skip_dirs, exclude_patterns, deadline, collect_symlinks } = filters;
+        let mut symlinks = Vec::new();
+        
         // Load .hashignore patterns if enabled
         let ignore_handler = if use_ignore {
             match IgnoreHandler::new(root) {
@@ -547,8 +1539,8 @@ impl ScanEngine {
             None
         };
         
-        // Canonicalize exclude path once before the loop to avoid redundant calls
-        let canonical_exclude = exclude_file.and_then(|p| p.canonicalize().ok());
+        // Resolve the output's directory once before the loop to avoid redundant calls
+        let exclusion = exclude_file.as_deref().and_then(OutputExclusion::new);
         
         // Use jwalk for parallel directory traversal
         // Use RayonNewPool to parallelize directory walking in a separate thread pool
@@ -558,24 +1550,32 @@ impl ScanEngine {
             .parallelism(jwalk::Parallelism::RayonNewPool(0)) // 0 = use default thread count
             .skip_hidden(false)  // Don't skip hidden files
             .follow_links(false) // Don't follow symlinks to avoid loops
+            .process_read_dir(move |_, _, _, children| {
+                // Prune --skip-dir directories before jwalk reads them
+                children.retain(|child| match child {
+                    Ok(entry) => !(entry.file_type().is_dir() && path_utils::is_skipped_dir(&entry.path(), &skip_dirs)),
+                    Err(_) => true,
+                });
+            })
         {
+            // Stop discovering new files once the deadline has passed or the scan was cancelled
+            if Self::deadline_reached(deadline, &stop) {
+                break;
+            }
+            
             match entry_result {
                 Ok(entry) => {
                     let path = entry.path();
                     
-                    // Only process regular files
-                    if !entry.file_type().is_file() {
+                    // Only process regular files, and symlinks when they are recorded
+                    let is_symlink = collect_symlinks && entry.file_type().is_symlink();
+                    if !entry.file_type().is_file() && !is_symlink {
                         continue;
                     }
                     
-                    // Check if this is the excluded file
-                    if let Some(ref exclude_canonical) = canonical_exclude {
-                        // Compare canonical paths (only canonicalize current path once)
-                        if let Ok(canonical_path) = path.canonicalize() {
-                            if &canonical_path == exclude_canonical {
-                                continue;
-                            }
-                        }
+                    // Never hash the output database or its temporary and compressed copies
+                    if exclusion.as_ref().is_some_and(|exclusion| exclusion.matches(&path)) {
+                        continue;
                     }
                     
                     // Check if this path should be ignored
@@ -587,6 +1587,33 @@ impl ScanEngine {
                         }
                     }
                     
+                    // Check the --exclude-from patterns
+                    if let Ok(rel_path) = path.strip_prefix(root) {
+                        if exclude_patterns.matches(rel_path) {
+                            continue;
+                        }
+                    }
+                    
+                    // Links are hashed by target path once the files are done
+                    if is_symlink {
+                        symlinks.push(path);
+                        continue;
+                    }
+                    
+                    // Skip files not modified since the cutoff, and sum sizes for a byte-based
+                    // progress bar (only stat when one of them needs it)
+                    if since.is_some() || total_bytes_discovered.is_some() {
+                        match fs::metadata(&path) {
+                            Ok(metadata) if !modified_since(&metadata, since) => continue,
+                            Ok(metadata) => {
+                                if let Some(bytes) = &total_bytes_discovered {
+                                    bytes.fetch_add(metadata.len(), Ordering::Relaxed);
+                                }
+                            }
+                            _ => {}
+                        }
+                    }
+                    
                     // Send file path to channel
                     // If channel is full, this will block (backpressure)
                     if let Err(_) = sender.send(path) {
@@ -606,7 +1633,38 @@ impl ScanEngine {
         }
         
         // Channel will be closed when sender is dropped
-        Ok(())
+        Ok(symlinks)
+    }
+    
+    /// Hash the contents of every file under `root` into one value (`hash --combined`)
+    /// 
+    /// Files are taken in order of their relative paths, and each path (with `/` separators)
+    /// is fed to the hasher ahead of the file's bytes. Unlike a Merkle tree, the result only
+    /// reflects the tree's structure through those names and their order: empty directories
+    /// and file metadata don't affect it. `.hashignore`, `--skip-dir` and excludes apply as in a scan.
+    pub fn combined_hash(&self, root: &Path, algorithm: &str) -> Result<HashResult, ScanError> {
+        let mut files = Vec::new();
+        for path in self.collect_files(root)? {
+            let relative = path.strip_prefix(root).unwrap_or(&path);
+            let name = path_utils::format_database_path(relative, path_utils::PathStyle::Bare);
+            files.push((name.to_string_lossy().into_owned(), path));
+        }
+        files.sort();
+        
+        Ok(HashResult {
+            algorithm: algorithm.to_string(),
+            hash: self.computer.compute_combined_hash(&files, algorithm)?,
+            file_path: root.to_path_buf(),
+        })
+    }
+    
+    /// Count the files a scan of `root` would hash, and their total size, without reading them
+    /// 
+    /// `.hashignore`, `--skip-dir`, excludes and `--since` apply as in a scan, and
+    /// `output` is left out the same way, so the count matches what `scan_directory` hashes.
+    pub fn count_files(&self, root: &Path, output: &Path) -> Result<(usize, u64), ScanError> {
+        let collected = self.collect_files_with_exclusion(root, Some(output))?;
+        Ok((collected.files.len(), collected.total_bytes))
     }
     
     /// Recursively collect all regular files in a directory tree
@@ -617,19 +1675,19 @@ impl ScanEngine {
     /// # Returns
     /// Vector of all file paths found
     fn collect_files(&self, root: &Path) -> Result<Vec<PathBuf>, ScanError> {
-        self.collect_files_with_exclusion(root, None)
+        self.collect_files_with_exclusion(root, None).map(|collected| collected.files)
     }
     
-    /// Recursively collect all regular files in a directory tree, excluding a specific file
+    /// Recursively collect all regular files in a directory tree, excluding an output database
     /// 
     /// # Arguments
     /// * `root` - Root directory to traverse
-    /// * `exclude_file` - Optional file path to exclude from collection
+    /// * `exclude_file` - Optional output database to exclude, along with its `.xz` and `.tmp*` variants
     /// 
     /// # Returns
-    /// Vector of all file paths found
-    fn collect_files_with_exclusion(&self, root: &Path, exclude_file: Option<&Path>) -> Result<Vec<PathBuf>, ScanError> {
-        let mut files = Vec::new();
+    /// All file paths found and the sum of their sizes, plus symlinks with `--hash-symlink-targets`
+    fn collect_files_with_exclusion(&self, root: &Path, exclude_file: Option<&Path>) -> Result<CollectedFiles, ScanError> {
+        let mut collected = CollectedFiles::default();
         
         // Load .hashignore patterns if enabled
         let ignore_handler = if self.use_ignore {
@@ -644,8 +1702,11 @@ impl ScanEngine {
             None
         };
         
-        self.collect_files_recursive(root, root, &mut files, ignore_handler.as_ref(), exclude_file)?;
-        Ok(files)
+        // Resolve the output's directory once for the whole walk
+        let exclusion = exclude_file.and_then(OutputExclusion::new);
+        
+        self.collect_files_recursive(root, root, &mut collected, ignore_handler.as_ref(), exclusion.as_ref())?;
+        Ok(collected)
     }
     
     /// Helper function for recursive file collection
@@ -653,22 +1714,9 @@ impl ScanEngine {
         &self,
         root: &Path,
         dir: &Path,
-        files: &mut Vec<PathBuf>,
+        collected: &mut CollectedFiles,
         ignore_handler: Option<&IgnoreHandler>,
-        exclude_file: Option<&Path>,
-    ) -> Result<(), ScanError> {
-        self.collect_files_recursive_with_cache(root, dir, files, ignore_handler, exclude_file, &mut None)
-    }
-    
-    /// Helper function for recursive file collection with cached exclude path
-    fn collect_files_recursive_with_cache(
-        &self,
-        root: &Path,
-        dir: &Path,
-        files: &mut Vec<PathBuf>,
-        ignore_handler: Option<&IgnoreHandler>,
-        exclude_file: Option<&Path>,
-        canonical_exclude_cache: &mut Option<PathBuf>,
+        exclusion: Option<&OutputExclusion>,
     ) -> Result<(), ScanError> {
         // Check if path exists and is accessible
         if !dir.exists() {
@@ -677,11 +1725,6 @@ impl ScanEngine {
             });
         }
         
-        // Canonicalize exclude path once on first call
-        if canonical_exclude_cache.is_none() && exclude_file.is_some() {
-            *canonical_exclude_cache = exclude_file.and_then(|p| p.canonicalize().ok());
-        }
-        
         // Read directory entries
         let entries = match fs::read_dir(dir) {
             Ok(entries) => entries,
@@ -717,14 +1760,9 @@ impl ScanEngine {
             
             let is_dir = metadata.is_dir();
             
-            // Check if this is the excluded file using cached canonical path
-            if let Some(ref exclude_canonical) = canonical_exclude_cache {
-                if let Ok(canonical_path) = path.canonicalize() {
-                    if &canonical_path == exclude_canonical {
-                        // Skip the excluded file
-                        continue;
-                    }
-                }
+            // Never hash the output database or its temporary and compressed copies
+            if exclusion.is_some_and(|exclusion| exclusion.matches(&path)) {
+                continue;
             }
             
             // Check if this path should be ignored
@@ -739,22 +1777,53 @@ impl ScanEngine {
             }
             
             if metadata.is_file() {
-                // Add regular files to the list
-                files.push(path);
+                // Skip files matching the --exclude-from patterns
+                if let Ok(rel_path) = path.strip_prefix(root) {
+                    if self.exclude_patterns.matches(rel_path) {
+                        continue;
+                    }
+                }
+                
+                // Add regular files to the list, unless they are older than the cutoff
+                if modified_since(&metadata, self.since) {
+                    collected.total_bytes += metadata.len();
+                    collected.files.push(path);
+                }
             } else if is_dir {
+                // Prune directories named by --skip-dir without reading them
+                if path_utils::is_skipped_dir(&path, &self.skip_dirs) {
+                    continue;
+                }
+                
                 // Recursively process subdirectories with cached exclude path
-                if let Err(e) = self.collect_files_recursive_with_cache(root, &path, files, ignore_handler, exclude_file, canonical_exclude_cache) {
+                if let Err(e) = self.collect_files_recursive(root, &path, collected, ignore_handler, exclusion) {
                     // Log error but continue with other directories (Requirement 2.4)
                     eprintln!("Warning: Error processing directory {}: {}", path.display(), e);
                 }
+            } else if self.hash_symlink_targets && metadata.file_type().is_symlink() {
+                // Keep links for --hash-symlink-targets, subject to the --exclude-from patterns
+                if !path.strip_prefix(root).is_ok_and(|rel_path| self.exclude_patterns.matches(rel_path)) {
+                    collected.symlinks.push(path);
+                }
             }
-            // Skip symbolic links and other special files
+            // Skip other special files
         }
         
         Ok(())
     }
 }
 
+/// Check whether a file was modified after the optional cutoff time
+/// 
+/// Files are included when there is no cutoff or their mtime can't be read,
+/// so a filesystem without mtimes never silently produces an empty scan.
+fn modified_since(metadata: &fs::Metadata, since: Option<SystemTime>) -> bool {
+    match (since, metadata.modified()) {
+        (Some(cutoff), Ok(modified)) => modified > cutoff,
+        _ => true,
+    }
+}
+
 impl Default for ScanEngine {
     fn default() -> Self {
         Self::new()
@@ -921,6 +1990,66 @@ mod tests {
         fs::remove_dir_all(test_dir).unwrap();
     }
     
+    #[test]
+    fn test_progress_by_bytes_total_matches_file_sizes() {
+        let test_dir = "test_progress_by_bytes";
+        fs::create_dir_all(format!("{}/sub", test_dir)).unwrap();
+        fs::write(format!("{}/big.bin", test_dir), vec![0u8; 10_000]).unwrap();
+        fs::write(format!("{}/small.txt", test_dir), b"tiny").unwrap();
+        fs::write(format!("{}/sub/empty.txt", test_dir), b"").unwrap();
+        fs::write(format!("{}/sub/mid.txt", test_dir), vec![1u8; 512]).unwrap();
+        let expected = 10_000 + 4 + 512;
+        
+        // Sequential collection sums sizes as it goes
+        let engine = ScanEngine::new().with_progress_by(ProgressBy::Bytes);
+        let collected = engine.collect_files_with_exclusion(Path::new(test_dir), None).unwrap();
+        assert_eq!(collected.files.len(), 4);
+        assert_eq!(collected.total_bytes, expected);
+        
+        // The parallel walker sums them during discovery
+        let (sender, receiver) = bounded(100);
+        let discovered = Arc::new(Mutex::new(0));
+        let discovered_bytes = Arc::new(AtomicU64::new(0));
+        let filters = WalkFilters {
+            use_ignore: true,
+            exclude_file: None,
+            since: None,
+            skip_dirs: Vec::new(),
+            exclude_patterns: ExcludePatterns::default(),
+            deadline: None,
+            collect_symlinks: false,
+        };
+        ScanEngine::walk_directory_streaming(
+            Path::new(test_dir),
+            sender,
+            filters,
+            Arc::clone(&discovered),
+            Some(Arc::clone(&discovered_bytes)),
+            Arc::new(AtomicBool::new(false)),
+        )
+        .unwrap();
+        assert_eq!(receiver.iter().count(), 4);
+        assert_eq!(*discovered.lock().unwrap(), 4);
+        assert_eq!(discovered_bytes.load(Ordering::Relaxed), expected);
+        
+        assert_eq!(ProgressBy::Bytes.step(512), 512);
+        assert_eq!(ProgressBy::Files.step(512), 1);
+        
+        // Scanning with a byte-based bar still hashes every file
+        let output = format!("{}.db", test_dir);
+        for parallel in [false, true] {
+            let stats = ScanEngine::with_parallel(parallel)
+                .with_progress_by(ProgressBy::Bytes)
+                .scan_directory(Path::new(test_dir), "sha256", Path::new(&output))
+                .unwrap();
+            assert_eq!(stats.files_processed, 4);
+            assert_eq!(stats.total_bytes, expected);
+        }
+        
+        fs::remove_file(&output).unwrap();
+        fs::remove_dir_all(test_dir).unwrap();
+    }
+    
     #[test]
     fn test_scan_parallel_mode() {
         // Create a temporary directory with multiple files
@@ -1026,4 +2155,803 @@ mod tests {
         fs::remove_file(output_seq).unwrap();
         fs::remove_file(output_par).unwrap();
     }
+    
+    #[cfg(unix)]
+    #[test]
+    fn test_scan_hdd_mode_with_sequential_hint() {
+        // HDD mode (sequential) issues the sequential-access hint; results must be unaffected
+        let test_dir = "test_scan_sequential_hint";
+        fs::create_dir_all(test_dir).unwrap();
+        
+        let small = format!("{}/small.txt", test_dir);
+        let large = format!("{}/large.bin", test_dir);
+        fs::write(&small, b"hello world").unwrap();
+        let data: Vec<u8> = (0..2_000_000u32).map(|i| (i % 199) as u8).collect();
+        fs::write(&large, &data).unwrap();
+        
+        let engine = ScanEngine::with_parallel(false);
+        let output = format!("{}/hashes.txt", test_dir);
+        let stats = engine.scan_directory(
+            Path::new(test_dir),
+            "sha256",
+            Path::new(&output),
+        ).unwrap();
+        
+        assert_eq!(stats.files_processed, 2);
+        assert_eq!(stats.files_failed, 0);
+        
+        // Compare against hashes computed without the hint
+        let computer = HashComputer::new();
+        let expected_small = computer.compute_hash(Path::new(&small), "sha256").unwrap();
+        let expected_large = computer.compute_hash(Path::new(&large), "sha256").unwrap();
+        
+        let content = fs::read_to_string(&output).unwrap();
+        assert!(content.contains(&expected_small.hash));
+        assert!(content.contains(&expected_large.hash));
+        
+        // Cleanup
+        fs::remove_dir_all(test_dir).unwrap();
+    }
+    
+    #[test]
+    fn test_scan_since_filters_old_files() {
+        let test_dir = "test_scan_since";
+        fs::create_dir_all(format!("{}/sub", test_dir)).unwrap();
+        
+        let old_file = format!("{}/old.txt", test_dir);
+        let new_file = format!("{}/sub/new.txt", test_dir);
+        fs::write(&old_file, b"old content").unwrap();
+        fs::write(&new_file, b"new content").unwrap();
+        
+        // Backdate the old file by two days
+        let two_days_ago = SystemTime::now() - Duration::from_secs(2 * 24 * 3600);
+        File::options().write(true).open(&old_file).unwrap().set_modified(two_days_ago).unwrap();
+        
+        let cutoff = SystemTime::now() - Duration::from_secs(24 * 3600);
+        
+        // Both sequential and parallel scans must apply the filter
+        for parallel in [false, true] {
+            let engine = ScanEngine::with_parallel(parallel).with_since(Some(cutoff));
+            let output = format!("test_scan_since_{}.txt", parallel);
+            let stats = engine.scan_directory(
+                Path::new(test_dir),
+                "sha256",
+                Path::new(&output),
+            ).unwrap();
+            
+            assert_eq!(stats.files_processed, 1, "parallel = {}", parallel);
+            let content = fs::read_to_string(&output).unwrap();
+            assert!(content.contains("new.txt"));
+            assert!(!content.contains("old.txt"));
+            
+            fs::remove_file(&output).unwrap();
+        }
+        
+        // Without a cutoff both files are scanned
+        let engine = ScanEngine::new();
+        let output = "test_scan_since_all.txt";
+        let stats = engine.scan_directory(Path::new(test_dir), "sha256", Path::new(output)).unwrap();
+        assert_eq!(stats.files_processed, 2);
+        fs::remove_file(output).unwrap();
+        
+        // Cleanup
+        fs::remove_dir_all(test_dir).unwrap();
+    }
+    
+    #[test]
+    fn test_scan_with_timestamp_column() {
+        let test_dir = "test_scan_with_timestamp";
+        fs::create_dir_all(test_dir).unwrap();
+        
+        let file = format!("{}/audit.txt", test_dir);
+        fs::write(&file, b"audit me").unwrap();
+        
+        // Pin the mtime so the expected column is known
+        let mtime = SystemTime::UNIX_EPOCH + Duration::from_secs(1705312800);
+        File::options().write(true).open(&file).unwrap().set_modified(mtime).unwrap();
+        
+        for parallel in [false, true] {
+            let engine = ScanEngine::with_parallel(parallel).with_timestamp(true);
+            let output = format!("test_scan_with_timestamp_{}.txt", parallel);
+            engine.scan_directory(Path::new(test_dir), "sha256", Path::new(&output)).unwrap();
+            
+            let content = fs::read_to_string(&output).unwrap();
+            let line = content.lines().next().unwrap();
+            let columns: Vec<&str> = line.split("  ").collect();
+            assert_eq!(columns.len(), 5, "parallel = {}: {}", parallel, line);
+            assert_eq!(columns[3], "mtime:2024-01-15T10:00:00Z");
+            
+            // The column is well-formed RFC3339
+            let timestamp = columns[3].strip_prefix("mtime:").unwrap();
+            assert!(chrono::DateTime::parse_from_rfc3339(timestamp).is_ok());
+            
+            // And it round-trips through the database reader
+            let db = DatabaseHandler::read_database(Path::new(&output)).unwrap();
+            assert_eq!(db[Path::new("audit.txt")].modified.as_deref(), Some(timestamp));
+            
+            fs::remove_file(&output).unwrap();
+        }
+        
+        // Cleanup
+        fs::remove_dir_all(test_dir).unwrap();
+    }
+    
+    #[test]
+    fn test_parallel_walk_matches_sequential_walk() {
+        let test_dir = "test_scan_walk_parity";
+        for dir in ["a/b/c", "a/d", "e/f/g/h", "logs"] {
+            fs::create_dir_all(format!("{}/{}", test_dir, dir)).unwrap();
+        }
+        for file in ["root.txt", "a/1.txt", "a/b/2.txt", "a/b/c/3.txt", "a/d/4.txt", "e/f/g/h/5.txt", "logs/app.log", "a/d/debug.log"] {
+            fs::write(format!("{}/{}", test_dir, file), file.as_bytes()).unwrap();
+        }
+        // Both walks must apply ignore rules the same way
+        fs::write(format!("{}/.hashignore", test_dir), "*.log\n").unwrap();
+        
+        let mut file_sets = Vec::new();
+        for parallel in [false, true] {
+            let output = format!("test_scan_walk_parity_{}.txt", parallel);
+            ScanEngine::with_parallel(parallel)
+                .scan_directory(Path::new(test_dir), "sha256", Path::new(&output))
+                .unwrap();
+            
+            let db = DatabaseHandler::read_database(Path::new(&output)).unwrap();
+            let mut paths: Vec<PathBuf> = db.into_keys().collect();
+            paths.sort();
+            file_sets.push(paths);
+            
+            fs::remove_file(&output).unwrap();
+        }
+        
+        assert_eq!(file_sets[0], file_sets[1]);
+        assert!(file_sets[0].contains(&path_utils::parse_database_path("e/f/g/h/5.txt")));
+        assert!(file_sets[0].iter().all(|p| p.extension().is_none_or(|ext| ext != "log")));
+        
+        // Cleanup
+        fs::remove_dir_all(test_dir).unwrap();
+    }
+    
+    #[test]
+    fn test_scan_path_style() {
+        let test_dir = "test_scan_path_style";
+        fs::create_dir_all(format!("{}/dir/sub", test_dir)).unwrap();
+        fs::write(format!("{}/top.txt", test_dir), b"top").unwrap();
+        fs::write(format!("{}/dir/sub/deep.txt", test_dir), b"deep").unwrap();
+        
+        for parallel in [false, true] {
+            for (style, prefix) in [(path_utils::PathStyle::Dot, "./"), (path_utils::PathStyle::Bare, "")] {
+                let output = format!("test_scan_path_style_{}.txt", parallel);
+                ScanEngine::with_parallel(parallel)
+                    .with_path_style(style)
+                    .scan_directory(Path::new(test_dir), "sha256", Path::new(&output))
+                    .unwrap();
+                
+                // Every entry uses the chosen style, with forward slashes
+                let content = fs::read_to_string(&output).unwrap();
+                let mut paths: Vec<&str> = content.lines().map(|line| line.rsplit("  ").next().unwrap()).collect();
+                paths.sort();
+                assert_eq!(paths, vec![format!("{}dir/sub/deep.txt", prefix), format!("{}top.txt", prefix)]);
+                
+                // The written database still parses
+                let db = DatabaseHandler::read_database(Path::new(&output)).unwrap();
+                assert!(db.contains_key(&path_utils::parse_database_path(&format!("{}top.txt", prefix))));
+                
+                fs::remove_file(&output).unwrap();
+            }
+        }
+        
+        // Cleanup
+        fs::remove_dir_all(test_dir).unwrap();
+    }
+    
+    #[test]
+    fn test_scan_skip_dir() {
+        let test_dir = "test_scan_skip_dir";
+        fs::create_dir_all(format!("{}/node_modules/pkg", test_dir)).unwrap();
+        fs::create_dir_all(format!("{}/src/node_modules", test_dir)).unwrap();
+        fs::create_dir_all(format!("{}/node_modules_backup", test_dir)).unwrap();
+        fs::write(format!("{}/index.js", test_dir), b"main").unwrap();
+        fs::write(format!("{}/node_modules/pkg/lib.js", test_dir), b"dep").unwrap();
+        fs::write(format!("{}/src/node_modules/nested.js", test_dir), b"nested dep").unwrap();
+        fs::write(format!("{}/node_modules_backup/keep.js", test_dir), b"not an exact match").unwrap();
+        
+        for parallel in [false, true] {
+            let engine = ScanEngine::with_parallel(parallel).with_skip_dirs(vec!["node_modules".to_string()]);
+            let output = format!("test_scan_skip_dir_{}.txt", parallel);
+            let stats = engine.scan_directory(Path::new(test_dir), "sha256", Path::new(&output)).unwrap();
+            
+            // node_modules is pruned at any depth; similar names are kept
+            let db = DatabaseHandler::read_database(Path::new(&output)).unwrap();
+            assert_eq!(stats.files_processed, 2, "parallel = {}", parallel);
+            assert!(db.contains_key(Path::new("index.js")));
+            assert!(db.contains_key(&path_utils::parse_database_path("node_modules_backup/keep.js")));
+            assert!(db.keys().all(|p| !p.components().any(|c| c.as_os_str() == "node_modules")));
+            
+            fs::remove_file(&output).unwrap();
+        }
+        
+        // Cleanup
+        fs::remove_dir_all(test_dir).unwrap();
+    }
+    
+    #[test]
+    fn test_scan_exclude_from() {
+        let test_dir = "test_scan_exclude_from";
+        fs::create_dir_all(format!("{}/sub", test_dir)).unwrap();
+        fs::write(format!("{}/keep.txt", test_dir), b"keep").unwrap();
+        fs::write(format!("{}/scratch.tmp", test_dir), b"temp").unwrap();
+        fs::write(format!("{}/sub/other.tmp", test_dir), b"temp").unwrap();
+        fs::write(format!("{}/sub/data.bin", test_dir), b"data").unwrap();
+        
+        let exclude_file = "test_scan_exclude_from.list";
+        fs::write(exclude_file, "# editor leftovers\n\n*.tmp\n").unwrap();
+        let patterns = ExcludePatterns::from_file(Path::new(exclude_file), false).unwrap();
+        
+        for parallel in [false, true] {
+            let engine = ScanEngine::with_parallel(parallel).with_exclude_patterns(patterns.clone());
+            let output = format!("test_scan_exclude_from_{}.txt", parallel);
+            let stats = engine.scan_directory(Path::new(test_dir), "sha256", Path::new(&output)).unwrap();
+            
+            // Temp files are left out at every depth
+            let db = DatabaseHandler::read_database(Path::new(&output)).unwrap();
+            assert_eq!(stats.files_processed, 2, "parallel = {}", parallel);
+            assert!(db.contains_key(Path::new("keep.txt")));
+            assert!(db.contains_key(&path_utils::parse_database_path("sub/data.bin")));
+            assert!(db.keys().all(|p| p.extension().is_none_or(|ext| ext != "tmp")));
+            
+            fs::remove_file(&output).unwrap();
+        }
+        
+        // Cleanup
+        fs::remove_file(exclude_file).unwrap();
+        fs::remove_dir_all(test_dir).unwrap();
+    }
+    
+    #[test]
+    fn test_scan_skips_database_inside_tree() {
+        let test_dir = "test_scan_database_inside";
+        fs::create_dir_all(format!("{}/sub", test_dir)).unwrap();
+        fs::write(format!("{}/data.txt", test_dir), b"data").unwrap();
+        fs::write(format!("{}/sub/hashes.db", test_dir), b"same name, other directory").unwrap();
+        
+        // Leftovers from earlier runs next to the database
+        fs::write(format!("{}/hashes.db.tmp1", test_dir), b"partial").unwrap();
+        fs::write(format!("{}/hashes.db.xz", test_dir), b"compressed").unwrap();
+        
+        let output = format!("{}/hashes.db", test_dir);
+        for parallel in [false, true] {
+            let stats = ScanEngine::with_parallel(parallel).scan_directory(Path::new(test_dir), "sha256", Path::new(&output)).unwrap();
+            
+            // Only the real files are hashed, including a namesake elsewhere in the tree
+            let db = DatabaseHandler::read_database(Path::new(&output)).unwrap();
+            let mut paths: Vec<_> = db.keys().cloned().collect();
+            paths.sort();
+            assert_eq!(paths, vec![PathBuf::from("data.txt"), path_utils::parse_database_path("sub/hashes.db")], "parallel = {}", parallel);
+            assert_eq!(stats.files_processed, 2);
+        }
+        
+        // Cleanup
+        fs::remove_dir_all(test_dir).unwrap();
+    }
+    
+    #[test]
+    fn test_scan_directory_to_entries_matches_database() {
+        let test_dir = "test_scan_to_entries";
+        fs::create_dir_all(format!("{}/sub", test_dir)).unwrap();
+        fs::write(format!("{}/a.txt", test_dir), b"alpha").unwrap();
+        fs::write(format!("{}/sub/b.txt", test_dir), b"beta").unwrap();
+        fs::write(format!("{}/sub/empty.txt", test_dir), b"").unwrap();
+        
+        for parallel in [false, true] {
+            let engine = ScanEngine::with_parallel(parallel).with_store_permissions(true).with_timestamp(true);
+            let output = format!("test_scan_to_entries_{}.txt", parallel);
+            engine.scan_directory(Path::new(test_dir), "sha256", Path::new(&output)).unwrap();
+            let db = DatabaseHandler::read_database(Path::new(&output)).unwrap();
+            
+            // Same paths and entries as the written database, in path order
+            let entries = engine.scan_directory_to_entries(Path::new(test_dir), "sha256", &HashMap::new()).unwrap();
+            assert_eq!(entries.len(), db.len(), "parallel = {}", parallel);
+            assert!(entries.windows(2).all(|pair| pair[0].0 < pair[1].0));
+            for (path, entry) in &entries {
+                let written = &db[path];
+                assert_eq!(entry.hash, written.hash);
+                assert_eq!(entry.algorithm, written.algorithm);
+                assert_eq!(entry.mode(), written.mode());
+                assert_eq!(entry.permissions, written.permissions);
+                assert_eq!(entry.modified, written.modified);
+            }
+            
+            fs::remove_file(&output).unwrap();
+        }
+        
+        // Cleanup
+        fs::remove_dir_all(test_dir).unwrap();
+    }
+    
+    #[test]
+    fn test_progress_template() {
+        let test_dir = "test_scan_progress_template";
+        fs::create_dir_all(test_dir).unwrap();
+        fs::write(format!("{}/file.txt", test_dir), b"content").unwrap();
+        
+        // A valid template is kept; one that doesn't parse falls back to the default
+        let custom = checked_progress_template(Some("{wide_bar} {pos}/{len} {msg}".to_string()));
+        assert_eq!(custom.as_deref(), Some("{wide_bar} {pos}/{len} {msg}"));
+        let invalid = checked_progress_template(Some("{bar:wide}".to_string()));
+        assert_eq!(invalid, None);
+        assert_eq!(checked_progress_template(None), None);
+        
+        // Scans complete either way
+        for (template, parallel) in [(custom.clone(), false), (custom, true), (invalid.clone(), false), (invalid, true)] {
+            let output = format!("test_scan_progress_template_{}.txt", parallel);
+            let engine = ScanEngine::with_parallel(parallel).with_progress_template(template);
+            let stats = engine.scan_directory(Path::new(test_dir), "sha256", Path::new(&output)).unwrap();
+            assert_eq!(stats.files_processed, 1);
+            fs::remove_file(&output).unwrap();
+        }
+        
+        // Cleanup
+        fs::remove_dir_all(test_dir).unwrap();
+    }
+    
+    #[test]
+    fn test_combined_hash_includes_paths() {
+        let test_dir = "test_combined_hash";
+        for (name, first, second) in [("a", "one.txt", "sub/two.txt"), ("b", "one.txt", "sub/two.txt"), ("c", "uno.txt", "sub/dos.txt")] {
+            fs::create_dir_all(format!("{}/{}/sub", test_dir, name)).unwrap();
+            fs::write(format!("{}/{}/{}", test_dir, name, first), b"first file").unwrap();
+            fs::write(format!("{}/{}/{}", test_dir, name, second), b"second file").unwrap();
+        }
+        
+        let engine = ScanEngine::new();
+        let combined = |name: &str| engine.combined_hash(&Path::new(test_dir).join(name), "sha256").unwrap().hash;
+        
+        // Same names and contents give the same hash; renaming files changes it
+        assert_eq!(combined("a"), combined("b"));
+        assert_ne!(combined("a"), combined("c"));
+        
+        // Any change to a file's bytes changes it too
+        fs::write(format!("{}/b/sub/two.txt", test_dir), b"second file!").unwrap();
+        assert_ne!(combined("a"), combined("b"));
+        
+        // Cleanup
+        fs::remove_dir_all(test_dir).unwrap();
+    }
+    
+    #[cfg(unix)]
+    #[test]
+    fn test_scan_counts_permission_denied_separately() {
+        use std::os::unix::fs::PermissionsExt;
+        
+        // Root can read any file, so the scenario can't be reproduced
+        if unsafe { libc::geteuid() } == 0 {
+            return;
+        }
+        
+        let test_dir = "test_scan_permission_denied";
+        fs::create_dir_all(test_dir).unwrap();
+        
+        let locked = format!("{}/locked.txt", test_dir);
+        fs::write(format!("{}/readable.txt", test_dir), b"readable").unwrap();
+        fs::write(&locked, b"locked").unwrap();
+        fs::set_permissions(&locked, fs::Permissions::from_mode(0o000)).unwrap();
+        
+        for parallel in [false, true] {
+            let engine = ScanEngine::with_parallel(parallel);
+            let output = format!("test_scan_permission_denied_{}.txt", parallel);
+            let stats = engine.scan_directory(Path::new(test_dir), "sha256", Path::new(&output)).unwrap();
+            
+            assert_eq!(stats.files_processed, 1, "parallel = {}", parallel);
+            assert_eq!(stats.files_permission_denied, 1, "parallel = {}", parallel);
+            assert_eq!(stats.files_failed, 0, "parallel = {}", parallel);
+            
+            fs::remove_file(&output).unwrap();
+        }
+        
+        // Cleanup
+        fs::set_permissions(&locked, fs::Permissions::from_mode(0o644)).unwrap();
+        fs::remove_dir_all(test_dir).unwrap();
+    }
+    
+    #[test]
+    fn test_scan_counts_empty_files() {
+        let test_dir = "test_scan_empty_files";
+        fs::create_dir_all(format!("{}/sub", test_dir)).unwrap();
+        
+        fs::write(format!("{}/empty1.txt", test_dir), b"").unwrap();
+        fs::write(format!("{}/empty2.txt", test_dir), b"").unwrap();
+        fs::write(format!("{}/sub/empty3.txt", test_dir), b"").unwrap();
+        fs::write(format!("{}/content.txt", test_dir), b"not empty").unwrap();
+        
+        for parallel in [false, true] {
+            let engine = ScanEngine::with_parallel(parallel);
+            let output = format!("test_scan_empty_files_{}.txt", parallel);
+            let stats = engine.scan_directory(Path::new(test_dir), "sha256", Path::new(&output)).unwrap();
+            
+            // Empty files are still hashed and written
+            assert_eq!(stats.files_processed, 4, "parallel = {}", parallel);
+            assert_eq!(stats.empty_files, 3, "parallel = {}", parallel);
+            
+            fs::remove_file(&output).unwrap();
+        }
+        
+        // Cleanup
+        fs::remove_dir_all(test_dir).unwrap();
+    }
+    
+    #[test]
+    fn test_scan_json_db_round_trip() {
+        let test_dir = "test_scan_json_db";
+        fs::create_dir_all(format!("{}/sub", test_dir)).unwrap();
+        fs::write(format!("{}/a.txt", test_dir), b"hello").unwrap();
+        fs::write(format!("{}/sub/b \"quoted\".txt", test_dir), b"world!").unwrap();
+        fs::write(format!("{}/empty.txt", test_dir), b"").unwrap();
+        
+        let standard = "test_scan_json_db_standard.txt";
+        ScanEngine::new().scan_directory(Path::new(test_dir), "sha256", Path::new(standard)).unwrap();
+        
+        for parallel in [false, true] {
+            let output = format!("test_scan_json_db_{}.json", parallel);
+            let stats = ScanEngine::with_parallel(parallel)
+                .with_format(DatabaseFormat::JsonDb)
+                .scan_directory(Path::new(test_dir), "sha256", Path::new(&output))
+                .unwrap();
+            assert_eq!(stats.files_processed, 3);
+            
+            // The whole file is one JSON array, recognized as such when read back
+            let content = fs::read_to_string(&output).unwrap();
+            let entries: Vec<crate::database::JsonDbEntry> = serde_json::from_str(&content).unwrap();
+            assert_eq!(entries.len(), 3);
+            assert!(entries.iter().any(|entry| entry.size == 6 && entry.mode == "normal"));
+            assert_eq!(DatabaseHandler::detect_format(Path::new(&output)).unwrap(), DatabaseFormat::JsonDb);
+            let from_json = DatabaseHandler::read_database(Path::new(&output)).unwrap();
+            let from_text = DatabaseHandler::read_database(Path::new(standard)).unwrap();
+            assert_eq!(from_json.len(), from_text.len());
+            for (path, entry) in &from_text {
+                assert_eq!(from_json[path].hash, entry.hash, "{}", path.display());
+                assert_eq!(from_json[path].algorithm, entry.algorithm);
+            }
+            
+            // Comparing against the text database finds nothing changed
+            let report = crate::compare::CompareEngine::new()
+                .with_progress(false)
+                .compare(Path::new(standard), Path::new(&output))
+                .unwrap();
+            assert_eq!(report.unchanged_files, 3);
+            assert!(report.changed_files.is_empty() && report.removed_files.is_empty() && report.added_files.is_empty());
+            
+            fs::remove_file(&output).unwrap();
+        }
+        
+        // Cleanup
+        fs::remove_file(standard).unwrap();
+        fs::remove_dir_all(test_dir).unwrap();
+    }
+    
+    #[test]
+    fn test_scan_roots_with_own_parallelism() {
+        assert_eq!(ScanRoot::parse("/mnt/ssd:parallel").unwrap(), ScanRoot { path: PathBuf::from("/mnt/ssd"), parallel: true });
+        assert_eq!(ScanRoot::parse("/mnt/hdd:sequential").unwrap(), ScanRoot { path: PathBuf::from("/mnt/hdd"), parallel: false });
+        assert_eq!(ScanRoot::parse("C:\\data:HDD").unwrap(), ScanRoot { path: PathBuf::from("C:\\data"), parallel: false });
+        assert!(ScanRoot::parse("/mnt/ssd").is_err());
+        assert!(ScanRoot::parse("/mnt/ssd:fast").is_err());
+        assert!(ScanRoot::parse(":parallel").is_err());
+        
+        // Each root gets an engine with its own mode, and both produce the same entries
+        let ssd_dir = "test_scan_root_ssd";
+        let hdd_dir = "test_scan_root_hdd";
+        for dir in [ssd_dir, hdd_dir] {
+            fs::create_dir_all(format!("{}/sub", dir)).unwrap();
+            fs::write(format!("{}/a.txt", dir), b"hello").unwrap();
+            fs::write(format!("{}/sub/b.txt", dir), b"world").unwrap();
+        }
+        
+        let roots = [
+            ScanRoot::parse(&format!("{}:parallel", ssd_dir)).unwrap(),
+            ScanRoot::parse(&format!("{}:sequential", hdd_dir)).unwrap(),
+        ];
+        let mut outputs = Vec::new();
+        for root in &roots {
+            let engine = ScanEngine::with_parallel(root.parallel);
+            assert_eq!(engine.is_parallel(), root.parallel);
+            
+            let output = format!("{}.txt", root.path.display());
+            let stats = engine.scan_directory(&root.path, "sha256", Path::new(&output)).unwrap();
+            assert_eq!(stats.files_processed, 2);
+            
+            let mut lines: Vec<String> = fs::read_to_string(&output).unwrap().lines().map(String::from).collect();
+            lines.sort();
+            outputs.push(lines);
+            fs::remove_file(&output).unwrap();
+        }
+        assert_eq!(outputs[0], outputs[1]);
+        
+        fs::remove_dir_all(ssd_dir).unwrap();
+        fs::remove_dir_all(hdd_dir).unwrap();
+    }
+    
+    #[test]
+    fn test_scan_append_accumulates_directories() {
+        let first_dir = "test_scan_append_first";
+        let second_dir = "test_scan_append_second";
+        fs::create_dir_all(first_dir).unwrap();
+        fs::create_dir_all(second_dir).unwrap();
+        fs::write(format!("{}/first.txt", first_dir), b"hello").unwrap();
+        fs::write(format!("{}/second.txt", second_dir), b"world").unwrap();
+        
+        for (format, parallel) in [(DatabaseFormat::Standard, false), (DatabaseFormat::Hashdeep, true)] {
+            let output = "test_scan_append.db";
+            for dir in [first_dir, second_dir] {
+                ScanEngine::with_parallel(parallel)
+                    .with_format(format)
+                    .with_append(true)
+                    .scan_directory(Path::new(dir), "sha256", Path::new(output))
+                    .unwrap();
+            }
+            
+            let database = DatabaseHandler::read_database(Path::new(output)).unwrap();
+            assert_eq!(database.len(), 2);
+            assert!(database.contains_key(Path::new("first.txt")));
+            assert!(database.contains_key(Path::new("second.txt")));
+            
+            // Only the scan that created the file writes a header
+            let content = fs::read_to_string(output).unwrap();
+            assert_eq!(content.matches("%%%% HASHDEEP").count(), usize::from(format == DatabaseFormat::Hashdeep));
+            
+            // Without --append the second scan replaces the first
+            ScanEngine::with_parallel(parallel)
+                .with_format(format)
+                .scan_directory(Path::new(second_dir), "sha256", Path::new(output))
+                .unwrap();
+            let database = DatabaseHandler::read_database(Path::new(output)).unwrap();
+            assert_eq!(database.len(), 1);
+            
+            fs::remove_file(output).unwrap();
+        }
+        
+        fs::remove_dir_all(first_dir).unwrap();
+        fs::remove_dir_all(second_dir).unwrap();
+    }
+    
+    #[test]
+    fn test_scan_dedupe_entries_on_append() {
+        let test_dir = "test_scan_dedupe";
+        fs::create_dir_all(test_dir).unwrap();
+        fs::write(format!("{}/file.txt", test_dir), b"hello").unwrap();
+        fs::write(format!("{}/same.txt", test_dir), b"world").unwrap();
+        let hello = "2cf24dba5fb0a30e26e83b2ac5b9e29e1b161e5c1fa7425e73043362938b9824";
+        let world = "486ea46224d1bb4fb680f34f7c9ad96a8f24ec88be73ea8e5a6c65260e9cb8a7";
+        
+        // An earlier scan recorded file.txt with different content, and same.txt unchanged
+        let output = "test_scan_dedupe.db";
+        let stale = format!("{}  sha256  normal  file.txt\n{}  sha256  normal  same.txt\n", "0".repeat(64), world);
+        
+        for parallel in [false, true] {
+            fs::write(output, &stale).unwrap();
+            let stats = ScanEngine::with_parallel(parallel)
+                .with_append(true)
+                .with_dedupe_entries(true)
+                .scan_directory(Path::new(test_dir), "sha256", Path::new(output))
+                .unwrap();
+            
+            assert_eq!(stats.duplicates_removed, 2);
+            assert_eq!(stats.duplicate_conflicts.len(), 1);
+            assert_eq!(stats.duplicate_conflicts[0].path, PathBuf::from("file.txt"));
+            assert_eq!(stats.duplicate_conflicts[0].kept_hash, hello);
+            
+            let content = fs::read_to_string(output).unwrap();
+            assert_eq!(content.lines().count(), 2);
+            assert_eq!(content.matches("file.txt").count(), 1);
+            assert!(content.contains(hello));
+        }
+        
+        fs::remove_file(output).unwrap();
+        fs::remove_dir_all(test_dir).unwrap();
+    }
+    
+    #[test]
+    fn test_scan_timeout_keeps_partial_valid_database() {
+        let test_dir = "test_scan_timeout";
+        fs::create_dir_all(test_dir).unwrap();
+        for i in 0..20 {
+            fs::write(format!("{}/file{:02}.txt", test_dir, i), format!("content {}", i)).unwrap();
+        }
+        
+        // A slow hasher (50ms per file) cannot finish 20 files within 200ms
+        let mut engine = ScanEngine::new()
+            .with_deadline(Some(Instant::now() + Duration::from_millis(200)));
+        engine.file_delay = Some(Duration::from_millis(50));
+        
+        let output = "test_scan_timeout.txt";
+        let stats = engine.scan_directory(Path::new(test_dir), "sha256", Path::new(output)).unwrap();
+        assert!(stats.timed_out);
+        assert!(stats.files_processed > 0);
+        assert!(stats.files_processed < 20);
+        
+        // Every line that was written is a complete, readable entry
+        let db = DatabaseHandler::read_database(Path::new(output)).unwrap();
+        assert_eq!(db.len(), stats.files_processed);
+        
+        fs::remove_file(output).unwrap();
+        fs::remove_dir_all(test_dir).unwrap();
+    }
+    
+    #[test]
+    fn test_scan_cancel_flag_keeps_partial_valid_database() {
+        let test_dir = "test_scan_cancel";
+        fs::create_dir_all(test_dir).unwrap();
+        for i in 0..20 {
+            fs::write(format!("{}/file{:02}.txt", test_dir, i), format!("content {}", i)).unwrap();
+        }
+        
+        // Simulate Ctrl-C arriving part way through a slow scan
+        let cancel = Arc::new(AtomicBool::new(false));
+        let mut engine = ScanEngine::new().with_cancel_flag(Arc::clone(&cancel));
+        engine.file_delay = Some(Duration::from_millis(50));
+        let canceller = thread::spawn(move || {
+            thread::sleep(Duration::from_millis(200));
+            cancel.store(true, Ordering::Relaxed);
+        });
+        
+        let output = "test_scan_cancel.txt";
+        let stats = engine.scan_directory(Path::new(test_dir), "sha256", Path::new(output)).unwrap();
+        canceller.join().unwrap();
+        assert!(stats.interrupted);
+        assert!(!stats.timed_out);
+        assert!(stats.files_processed > 0);
+        assert!(stats.files_processed < 20);
+        
+        let db = DatabaseHandler::read_database(Path::new(output)).unwrap();
+        assert_eq!(db.len(), stats.files_processed);
+        
+        fs::remove_file(output).unwrap();
+        fs::remove_dir_all(test_dir).unwrap();
+    }    
+    #[test]
+    fn test_scan_profile_splits_read_and_hash_time() {
+        let test_dir = "test_scan_profile";
+        fs::create_dir_all(test_dir).unwrap();
+        fs::write(format!("{}/large.bin", test_dir), vec![0xA5u8; 8 * 1024 * 1024]).unwrap();
+        fs::write(format!("{}/small.txt", test_dir), "small").unwrap();
+        
+        let profile = Arc::new(HashProfile::new());
+        let engine = ScanEngine::new().with_profile(Some(Arc::clone(&profile)));
+        
+        let output = "test_scan_profile.txt";
+        let stats = engine.scan_directory(Path::new(test_dir), "sha256", Path::new(output)).unwrap();
+        assert_eq!(stats.files_processed, 2);
+        
+        // Both halves were measured, every byte went through a timed read,
+        // and on a sequential scan they account for most of the wall time
+        assert!(profile.read_time() > Duration::ZERO);
+        assert!(profile.hash_time() > Duration::ZERO);
+        assert_eq!(profile.bytes(), 8 * 1024 * 1024 + 5);
+        let profiled = profile.read_time() + profile.hash_time();
+        assert!(profiled <= stats.duration);
+        assert!(profiled >= stats.duration / 2);
+        
+        fs::remove_file(output).unwrap();
+        fs::remove_dir_all(test_dir).unwrap();
+    }
+    
+    #[test]
+    fn test_scan_with_algorithm_map() {
+        assert_eq!(
+            AlgorithmMap::parse(".MP4=xxh3").unwrap(),
+            AlgorithmMap { extension: "mp4".to_string(), algorithm: "xxh3".to_string() }
+        );
+        assert!(AlgorithmMap::parse("mp4").is_err());
+        assert!(AlgorithmMap::parse("=xxh3").is_err());
+        assert!(AlgorithmMap::parse("mp4=").is_err());
+        assert!(AlgorithmMap::parse("mp4=nope").is_err());
+        
+        let test_dir = "test_scan_algorithm_map";
+        fs::create_dir_all(format!("{}/media", test_dir)).unwrap();
+        fs::write(format!("{}/media/clip.mp4", test_dir), b"video").unwrap();
+        fs::write(format!("{}/media/CLIP2.MP4", test_dir), b"more video").unwrap();
+        fs::write(format!("{}/notes.txt", test_dir), b"text").unwrap();
+        fs::write(format!("{}/README", test_dir), b"no extension").unwrap();
+        
+        let map = vec![AlgorithmMap::parse("mp4=xxh3").unwrap()];
+        for parallel in [false, true] {
+            let output = format!("test_scan_algorithm_map_{}.txt", parallel);
+            let engine = ScanEngine::with_parallel(parallel).with_algorithm_map(map.clone());
+            let stats = engine.scan_directory(Path::new(test_dir), "sha256", Path::new(&output)).unwrap();
+            assert_eq!(stats.files_processed, 4);
+            
+            let entries = DatabaseHandler::read_database(Path::new(&output)).unwrap();
+            for (path, entry) in &entries {
+                let is_mp4 = path.extension().is_some_and(|ext| ext.eq_ignore_ascii_case("mp4"));
+                let expected = if is_mp4 { "xxh3" } else { "sha256" };
+                assert_eq!(entry.algorithm, expected, "{}", path.display());
+                
+                let file = Path::new(test_dir).join(path);
+                assert_eq!(entry.hash, HashComputer::new().compute_hash(&file, expected).unwrap().hash);
+            }
+            
+            fs::remove_file(&output).unwrap();
+        }
+        
+        fs::remove_dir_all(test_dir).unwrap();
+    }
+
+    
+    #[test]
+    fn test_slowest_files_keeps_top_n_descending() {
+        let slowest = SlowestFiles::new(3);
+        for (name, millis) in [("a", 5), ("b", 50), ("c", 1), ("d", 20), ("e", 30), ("f", 2)] {
+            slowest.record(Path::new(name), Duration::from_millis(millis));
+        }
+        let files = slowest.into_sorted();
+        let names: Vec<_> = files.iter().map(|file| file.path.to_str().unwrap()).collect();
+        assert_eq!(names, ["b", "e", "d"]);
+        assert_eq!(files[0].duration, Duration::from_millis(50));
+        
+        // Tracking is off by default
+        let none = SlowestFiles::new(0);
+        none.record(Path::new("a"), Duration::from_secs(1));
+        assert!(none.into_sorted().is_empty());
+    }
+    
+    #[test]
+    fn test_scan_reports_slow_files() {
+        let test_dir = "test_scan_report_slow";
+        fs::create_dir_all(test_dir).unwrap();
+        for i in 0..4 {
+            fs::write(format!("{}/file{}.txt", test_dir, i), vec![b'x'; 1024 * (i + 1)]).unwrap();
+        }
+        
+        for parallel in [false, true] {
+            let output = format!("test_scan_report_slow_{}.txt", parallel);
+            let mut engine = ScanEngine::with_parallel(parallel).with_report_slow(2);
+            engine.file_delay = Some(Duration::from_millis(5));
+            let stats = engine.scan_directory(Path::new(test_dir), "sha256", Path::new(&output)).unwrap();
+            
+            assert_eq!(stats.files_processed, 4);
+            assert_eq!(stats.slowest_files.len(), 2);
+            assert!(stats.slowest_files[0].duration >= stats.slowest_files[1].duration);
+            assert!(stats.slowest_files.iter().all(|file| file.duration >= Duration::from_millis(5)));
+            
+            fs::remove_file(&output).unwrap();
+        }
+        
+        // Without the option nothing is tracked
+        let output = "test_scan_report_slow_off.txt";
+        let stats = ScanEngine::new().scan_directory(Path::new(test_dir), "sha256", Path::new(output)).unwrap();
+        assert!(stats.slowest_files.is_empty());
+        fs::remove_file(output).unwrap();
+        
+        fs::remove_dir_all(test_dir).unwrap();
+    }
+    
+    #[cfg(windows)]
+    #[test]
+    fn test_scan_path_longer_than_max_path() {
+        let test_dir = "test_scan_long_path";
+        let mut deep = PathBuf::from(test_dir);
+        for i in 0..6 {
+            deep.push(format!("{}_{}", "d".repeat(50), i));
+        }
+        fs::create_dir_all(path_utils::extended_length_path(&deep)).unwrap();
+        fs::write(path_utils::extended_length_path(&deep.join("file.txt")), b"deep content").unwrap();
+        assert!(std::env::current_dir().unwrap().join(&deep).to_string_lossy().len() > 260);
+        
+        for parallel in [false, true] {
+            let output = format!("test_scan_long_path_{}.txt", parallel);
+            let stats = ScanEngine::with_parallel(parallel)
+                .scan_directory(Path::new(test_dir), "sha256", Path::new(&output))
+                .unwrap();
+            assert_eq!(stats.files_processed, 1);
+            assert_eq!(stats.files_failed, 0);
+            
+            let content = fs::read_to_string(&output).unwrap();
+            assert!(content.contains("file.txt"));
+            assert!(!content.contains(r"\\?\"));
+            
+            fs::remove_file(&output).unwrap();
+        }
+        
+        fs::remove_dir_all(path_utils::extended_length_path(Path::new(test_dir))).unwrap();
+    }
+
 }
@@ -0,0 +1,186 @@
+// Split engine module
+// Partitions a hash database into shards for verifying on several machines
+
+use std::fs::File;
+use std::io::{BufWriter, Write};
+use std::path::{Path, PathBuf};
+use crate::database::{DatabaseFormat, DatabaseHandler};
+use crate::error::HashUtilityError;
+use xxhash_rust::xxh3::xxh3_64;
+
+/// Result of splitting a database
+#[derive(Debug, Clone, serde::Serialize)]
+pub struct SplitStats {
+    pub total_entries: usize,
+    /// Shard files in shard order
+    pub shard_paths: Vec<PathBuf>,
+    /// Number of entries written to each shard
+    pub shard_entries: Vec<usize>,
+}
+
+/// Engine for splitting hash databases into shards
+pub struct SplitEngine;
+
+impl SplitEngine {
+    /// Create a new SplitEngine
+    pub fn new() -> Self {
+        Self
+    }
+
+    /// Shard (0-based) that a database path belongs to
+    ///
+    /// Uses XXH3 of the path with `/` separators, so the same file lands in the
+    /// same shard on every run and platform.
+    pub fn shard_for(path: &Path, shards: usize) -> usize {
+        let key = path.to_string_lossy().replace('\\', "/");
+        (xxh3_64(key.as_bytes()) % shards as u64) as usize
+    }
+
+    /// Path of shard `index` (0-based): `<prefix>.<index + 1>`
+    pub fn shard_path(prefix: &Path, index: usize) -> PathBuf {
+        let mut path = prefix.as_os_str().to_os_string();
+        path.push(format!(".{}", index + 1));
+        PathBuf::from(path)
+    }
+
+    /// Write the entries of a database into `shards` shard files
+    ///
+    /// # Arguments
+    /// * `database` - Database to split (standard format, optionally .xz compressed)
+    /// * `shards` - Number of shards to write (every shard is created, even if empty)
+    /// * `prefix` - Shards are written to `<prefix>.1` through `<prefix>.<shards>`
+    pub fn split(
+        &self,
+        database: &Path,
+        shards: usize,
+        prefix: &Path,
+    ) -> Result<SplitStats, HashUtilityError> {
+        if shards == 0 {
+            return Err(HashUtilityError::InvalidArguments {
+                message: "Number of shards must be at least 1".to_string(),
+            });
+        }
+
+        if !database.exists() {
+            return Err(HashUtilityError::DatabaseNotFound {
+                path: database.to_path_buf(),
+            });
+        }
+
+        // Hashdeep and TSV entries carry file sizes that DatabaseEntry does not keep,
+        // so rewriting them would silently lose data
+        if DatabaseHandler::detect_format(database)? != DatabaseFormat::Standard {
+            return Err(HashUtilityError::InvalidArguments {
+                message: "split only supports standard format databases".to_string(),
+            });
+        }
+
+        let entries = DatabaseHandler::read_database(database)?;
+        let total_entries = entries.len();
+
+        // Sort for stable, diff-friendly output
+        let mut entries: Vec<_> = entries.into_iter().collect();
+        entries.sort_by(|a, b| a.0.cmp(&b.0));
+
+        let mut partitions = vec![Vec::new(); shards];
+        for (path, entry) in entries {
+            partitions[Self::shard_for(&path, shards)].push((path, entry));
+        }
+
+        let mut shard_paths = Vec::with_capacity(shards);
+        let mut shard_entries = Vec::with_capacity(shards);
+        for (index, partition) in partitions.iter().enumerate() {
+            let shard_path = Self::shard_path(prefix, index);
+            let write_error = |e| HashUtilityError::from_io_error(e, "writing shard", Some(shard_path.clone()));
+
+            let mut writer = BufWriter::new(File::create(&shard_path).map_err(write_error)?);
+            for (path, entry) in partition {
+                DatabaseHandler::write_entry_with_metadata(
+                    &mut writer,
+                    &entry.hash,
+                    &entry.algorithm,
+                    entry.mode(),
+                    entry.metadata(),
+                    path,
+                )
+                .map_err(write_error)?;
+            }
+            writer.flush().map_err(write_error)?;
+
+            shard_entries.push(partition.len());
+            shard_paths.push(shard_path);
+        }
+
+        Ok(SplitStats {
+            total_entries,
+            shard_paths,
+            shard_entries,
+        })
+    }
+}
+
+impl Default for SplitEngine {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::collections::HashMap;
+    use std::fs;
+
+    #[test]
+    fn test_split_places_each_entry_in_one_shard() {
+        let db_path = "test_split_shards.db";
+        let mut content = String::new();
+        for i in 0..30 {
+            content.push_str(&format!("hash{:02}  sha256  normal  dir{}/file{:02}.txt\n", i, i % 4, i));
+        }
+        content.push_str("hashxx  sha256  fast2  mtime:2024-01-15T10:00:00Z  extra.bin\n");
+        fs::write(db_path, content).unwrap();
+
+        let engine = SplitEngine::new();
+        let stats = engine.split(Path::new(db_path), 3, Path::new(db_path)).unwrap();
+        assert_eq!(stats.total_entries, 31);
+        assert_eq!(stats.shard_paths, vec![
+            PathBuf::from("test_split_shards.db.1"),
+            PathBuf::from("test_split_shards.db.2"),
+            PathBuf::from("test_split_shards.db.3"),
+        ]);
+        assert_eq!(stats.shard_entries.iter().sum::<usize>(), 31);
+
+        // Every original entry appears in exactly one shard, in the shard its path hashes to
+        let original = DatabaseHandler::read_database(Path::new(db_path)).unwrap();
+        let mut seen: HashMap<PathBuf, usize> = HashMap::new();
+        for (index, shard_path) in stats.shard_paths.iter().enumerate() {
+            let shard = DatabaseHandler::read_database(shard_path).unwrap();
+            assert_eq!(shard.len(), stats.shard_entries[index]);
+            for (path, entry) in shard {
+                assert_eq!(SplitEngine::shard_for(&path, 3), index);
+                assert_eq!(entry.hash, original[&path].hash);
+                assert_eq!(entry.modified, original[&path].modified);
+                *seen.entry(path).or_default() += 1;
+            }
+        }
+        assert_eq!(seen.len(), original.len());
+        assert!(seen.values().all(|&count| count == 1));
+
+        // Splitting again gives the same partition
+        let again = engine.split(Path::new(db_path), 3, Path::new(db_path)).unwrap();
+        assert_eq!(again.shard_entries, stats.shard_entries);
+
+        // Cleanup
+        for shard_path in &stats.shard_paths {
+            fs::remove_file(shard_path).unwrap();
+        }
+        fs::remove_file(db_path).unwrap();
+    }
+
+    #[test]
+    fn test_split_rejects_zero_shards() {
+        let result = SplitEngine::new().split(Path::new("unused.db"), 0, Path::new("unused.db"));
+        assert!(matches!(result, Err(HashUtilityError::InvalidArguments { .. })));
+    }
+}
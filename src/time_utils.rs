@@ -0,0 +1,114 @@
+// Time parsing utilities
+// Shared helpers for turning command-line durations and timestamps into points in time
+
+use crate::error::HashUtilityError;
+use std::time::{Duration, SystemTime};
+
+/// Parse a duration such as "90s", "15m", "24h", "7d" or "2w"
+///
+/// A bare number is interpreted as seconds.
+pub fn parse_duration(value: &str) -> Result<Duration, HashUtilityError> {
+    let trimmed = value.trim();
+    let invalid = || HashUtilityError::InvalidArguments {
+        message: format!(
+            "Invalid duration '{}': expected a number with optional unit s, m, h, d or w (e.g., 24h)",
+            value
+        ),
+    };
+
+    // Split into the numeric part and the unit suffix
+    let split_at = trimmed
+        .find(|c: char| !c.is_ascii_digit())
+        .unwrap_or(trimmed.len());
+    let (digits, unit) = trimmed.split_at(split_at);
+
+    let amount: u64 = digits.parse().map_err(|_| invalid())?;
+    let seconds_per_unit = match unit {
+        "" | "s" => 1,
+        "m" => 60,
+        "h" => 60 * 60,
+        "d" => 24 * 60 * 60,
+        "w" => 7 * 24 * 60 * 60,
+        _ => return Err(invalid()),
+    };
+
+    amount
+        .checked_mul(seconds_per_unit)
+        .map(Duration::from_secs)
+        .ok_or_else(invalid)
+}
+
+/// Parse a cutoff time given either as a duration before now (e.g., "24h")
+/// or as an RFC3339 timestamp (e.g., "2024-01-15T10:00:00Z")
+pub fn parse_since(value: &str) -> Result<SystemTime, HashUtilityError> {
+    // Timestamps are tried first since they can never be valid durations
+    if let Ok(timestamp) = chrono::DateTime::parse_from_rfc3339(value.trim()) {
+        return Ok(SystemTime::from(timestamp));
+    }
+
+    let duration = parse_duration(value).map_err(|_| HashUtilityError::InvalidArguments {
+        message: format!(
+            "Invalid time '{}': expected a duration (e.g., 24h, 7d) or an RFC3339 timestamp (e.g., 2024-01-15T10:00:00Z)",
+            value
+        ),
+    })?;
+
+    SystemTime::now()
+        .checked_sub(duration)
+        .ok_or_else(|| HashUtilityError::InvalidArguments {
+            message: format!("Duration '{}' is too large", value),
+        })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_duration_units() {
+        assert_eq!(parse_duration("30").unwrap(), Duration::from_secs(30));
+        assert_eq!(parse_duration("30s").unwrap(), Duration::from_secs(30));
+        assert_eq!(parse_duration("15m").unwrap(), Duration::from_secs(15 * 60));
+        assert_eq!(parse_duration("24h").unwrap(), Duration::from_secs(24 * 3600));
+        assert_eq!(parse_duration("7d").unwrap(), Duration::from_secs(7 * 86400));
+        assert_eq!(parse_duration("2w").unwrap(), Duration::from_secs(14 * 86400));
+    }
+
+    #[test]
+    fn test_parse_duration_invalid() {
+        assert!(parse_duration("").is_err());
+        assert!(parse_duration("h").is_err());
+        assert!(parse_duration("10x").is_err());
+        assert!(parse_duration("-5m").is_err());
+        assert!(parse_duration("1.5h").is_err());
+    }
+
+    #[test]
+    fn test_parse_since_duration() {
+        let before = SystemTime::now();
+        let cutoff = parse_since("1h").unwrap();
+        let after = SystemTime::now();
+
+        // The cutoff is one hour before the moment it was parsed
+        assert!(cutoff >= before - Duration::from_secs(3600));
+        assert!(cutoff <= after - Duration::from_secs(3600));
+    }
+
+    #[test]
+    fn test_parse_since_rfc3339() {
+        let cutoff = parse_since("2024-01-15T10:00:00Z").unwrap();
+        let expected = SystemTime::UNIX_EPOCH + Duration::from_secs(1705312800);
+        assert_eq!(cutoff, expected);
+
+        // Offsets are honoured
+        let with_offset = parse_since("2024-01-15T12:00:00+02:00").unwrap();
+        assert_eq!(with_offset, expected);
+    }
+
+    #[test]
+    fn test_parse_since_invalid() {
+        let error = parse_since("yesterday").unwrap_err();
+        assert!(format!("{}", error).contains("RFC3339"));
+        assert!(parse_since("2024-13-45").is_err());
+    }
+}
@@ -0,0 +1,311 @@
+// URL input module
+// Fetches http:// and https:// URLs so their response body can be streamed through the hashers
+
+use std::io::{self, Read};
+use std::time::Duration;
+use crate::error::HashUtilityError;
+use crate::hash::HashComputer;
+
+/// Give up on a server that doesn't accept the connection or stops sending data for this long
+const TIMEOUT: Duration = Duration::from_secs(30);
+
+/// Maximum number of redirects followed before giving up
+const MAX_REDIRECTS: u32 = 5;
+
+/// Checksum files larger than this are not read
+const MAX_CHECKSUM_FILE_SIZE: u64 = 1024 * 1024;
+
+/// Check whether a command-line argument is an http:// or https:// URL
+pub fn is_url(arg: &str) -> bool {
+    let lower = arg.to_ascii_lowercase();
+    lower.starts_with("http://") || lower.starts_with("https://")
+}
+
+/// Response body that fails instead of ending early when the connection drops
+/// before all of the announced Content-Length bytes have arrived
+struct CheckedBody<R> {
+    url: String,
+    inner: R,
+    expected: Option<u64>,
+    received: u64,
+}
+
+impl<R: Read> Read for CheckedBody<R> {
+    fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+        // The transport may notice the shortfall first; report it the same way
+        let read = match self.inner.read(buf) {
+            Ok(read) => read,
+            Err(e) if e.kind() == io::ErrorKind::UnexpectedEof && self.expected.is_some() => 0,
+            Err(e) => return Err(e),
+        };
+        self.received += read as u64;
+        
+        match self.expected {
+            Some(expected) if read == 0 && !buf.is_empty() && self.received < expected => {
+                Err(io::Error::new(
+                    io::ErrorKind::UnexpectedEof,
+                    format!("download of {} ended after {} of {} bytes", self.url, self.received, expected),
+                ))
+            }
+            _ => Ok(read),
+        }
+    }
+}
+
+/// Open an http:// or https:// URL and return a reader over the response body
+///
+/// Redirects are followed. The body is streamed and never buffered in full, so
+/// arbitrarily large downloads can be hashed. Responses are requested without
+/// compression so the hashed bytes are exactly the published file, and reading
+/// fails if fewer bytes arrive than the server announced.
+pub fn open_url(url: &str) -> Result<Box<dyn Read>, HashUtilityError> {
+    let fetch_error = |e| HashUtilityError::from_io_error(e, &format!("fetching {}", url), None);
+    
+    let agent = ureq::AgentBuilder::new()
+        .timeout_connect(TIMEOUT)
+        .timeout_read(TIMEOUT)
+        .redirects(MAX_REDIRECTS)
+        .user_agent(&format!("hash/{}", env!("CARGO_PKG_VERSION")))
+        .build();
+    
+    let response = agent.get(url).call().map_err(|e| match e {
+        ureq::Error::Status(status, response) => fetch_error(io::Error::other(format!(
+            "server returned {} {}",
+            status,
+            response.status_text()
+        ))),
+        ureq::Error::Transport(transport) => fetch_error(io::Error::other(transport.to_string())),
+    })?;
+    
+    let expected = response
+        .header("Content-Length")
+        .and_then(|length| length.trim().parse::<u64>().ok());
+    Ok(Box::new(CheckedBody {
+        url: url.to_string(),
+        inner: response.into_reader(),
+        expected,
+        received: 0,
+    }))
+}
+
+/// Outcome of checking a downloaded file against its expected hash
+#[derive(Debug, Clone, serde::Serialize)]
+pub struct UrlVerification {
+    pub url: String,
+    pub algorithm: String,
+    pub expected: String,
+    pub actual: String,
+}
+
+impl UrlVerification {
+    /// Whether the download matched the expected hash
+    pub fn passed(&self) -> bool {
+        self.expected.eq_ignore_ascii_case(&self.actual)
+    }
+}
+
+/// Last path segment of a URL, used to find its line in a checksum file
+fn file_name(url: &str) -> &str {
+    let path = url.split(['?', '#']).next().unwrap_or(url);
+    path.rsplit('/').next().unwrap_or(path)
+}
+
+/// Find the hash for `file_name` in checksum file contents
+///
+/// Accepts `sha256sum` style lines (`<hash>  <name>` or `<hash> *<name>`) and
+/// files holding a single bare hash.
+fn parse_checksum_file(contents: &str, file_name: &str) -> Option<String> {
+    let lines: Vec<&str> = contents
+        .lines()
+        .map(str::trim)
+        .filter(|line| !line.is_empty() && !line.starts_with('#'))
+        .collect();
+
+    let named = lines.iter().find_map(|line| {
+        let (hash, name) = line.split_once(char::is_whitespace)?;
+        let name = name.trim_start();
+        let name = name.strip_prefix('*').unwrap_or(name);
+        (name == file_name || name.ends_with(&format!("/{}", file_name))).then(|| hash.to_string())
+    });
+
+    // A lone entry applies to the download whatever name it was written with
+    named.or_else(|| match lines.as_slice() {
+        [line] => line.split_whitespace().next().map(str::to_string),
+        _ => None,
+    })
+}
+
+/// Fetch the expected hash for `url` from its sibling checksum file (`<url>.<algorithm>`)
+pub fn fetch_expected_hash(url: &str, algorithm: &str) -> Result<String, HashUtilityError> {
+    let checksum_url = format!("{}.{}", url, algorithm);
+    let mut contents = String::new();
+    open_url(&checksum_url)?
+        .take(MAX_CHECKSUM_FILE_SIZE)
+        .read_to_string(&mut contents)
+        .map_err(|e| HashUtilityError::from_io_error(e, &format!("reading {}", checksum_url), None))?;
+
+    parse_checksum_file(&contents, file_name(url)).ok_or_else(|| HashUtilityError::VerificationFailed {
+        reason: format!("no hash for '{}' found in {}", file_name(url), checksum_url),
+    })
+}
+
+/// Download `url`, hash it, and compare against `expected` or the sibling checksum file
+pub fn verify_url(
+    computer: &HashComputer,
+    url: &str,
+    algorithm: &str,
+    expected: Option<&str>,
+) -> Result<UrlVerification, HashUtilityError> {
+    // Fetch the checksum first so a missing one fails before the download
+    let expected = match expected {
+        Some(hash) => hash.trim().to_string(),
+        None => fetch_expected_hash(url, algorithm)?,
+    };
+
+    // A download cut short is an error, never a hash of the partial body that could be taken for a mismatch
+    let actual = computer.compute_hash_reader(open_url(url)?, algorithm)?;
+
+    Ok(UrlVerification {
+        url: url.to_string(),
+        algorithm: algorithm.to_string(),
+        expected,
+        actual,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::io::{BufRead, BufReader, Write};
+    use std::net::TcpListener;
+    use std::thread;
+
+    /// Serve one canned response per entry on a local port, returning the base URL
+    fn serve(responses: Vec<String>) -> String {
+        let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+        let address = listener.local_addr().unwrap();
+        thread::spawn(move || {
+            for response in responses {
+                let (mut stream, _) = listener.accept().unwrap();
+                // Read the request headers before answering
+                let mut reader = BufReader::new(stream.try_clone().unwrap());
+                let mut line = String::new();
+                while reader.read_line(&mut line).unwrap() > 0 && line != "\r\n" {
+                    line.clear();
+                }
+                stream.write_all(response.as_bytes()).unwrap();
+            }
+        });
+        format!("http://{}", address)
+    }
+
+    fn read_body(url: &str) -> Result<String, HashUtilityError> {
+        let mut body = String::new();
+        open_url(url)?.read_to_string(&mut body).unwrap();
+        Ok(body)
+    }
+
+    #[test]
+    fn test_is_url() {
+        assert!(is_url("http://example.com/file.iso"));
+        assert!(is_url("HTTPS://example.com/file.iso"));
+        assert!(!is_url("file.txt"));
+        assert!(!is_url("ftp://example.com/file"));
+        assert!(!is_url("http_logs/*.txt"));
+    }
+
+    #[test]
+    fn test_open_url_streams_body() {
+        let url = serve(vec![
+            "HTTP/1.1 200 OK\r\nContent-Length: 11\r\n\r\nhello world".to_string(),
+        ]);
+
+        let body = open_url(&format!("{}/hello.txt", url)).unwrap();
+        let results = crate::hash::HashComputer::new()
+            .compute_multiple_hashes_reader(body, &["sha256".to_string()], std::path::Path::new(&url))
+            .unwrap();
+
+        assert_eq!(results[0].hash, "b94d27b9934d3e08a52e52d7da7dabfac484efe37a5380ee9088f7ace2efcde9");
+        assert_eq!(results[0].file_path, std::path::PathBuf::from(&url));
+    }
+
+    #[test]
+    fn test_open_url_rejects_truncated_body() {
+        // The connection closes 9 bytes short of the announced length
+        let url = serve(vec![
+            "HTTP/1.1 200 OK\r\nContent-Length: 20\r\n\r\nhello world".to_string(),
+        ]);
+
+        let body = open_url(&format!("{}/hello.txt", url)).unwrap();
+        assert!(HashComputer::new().compute_hash_reader(body, "sha256").is_err());
+    }
+
+    #[test]
+    fn test_open_url_follows_redirect() {
+        let url = serve(vec![
+            "HTTP/1.1 302 Found\r\nLocation: /moved.txt\r\nContent-Length: 0\r\n\r\n".to_string(),
+            "HTTP/1.1 200 OK\r\n\r\nmoved".to_string(),
+        ]);
+
+        assert_eq!(read_body(&format!("{}/old.txt", url)).unwrap(), "moved");
+    }
+
+    #[test]
+    fn test_parse_checksum_file() {
+        let hash = "b94d27b9934d3e08a52e52d7da7dabfac484efe37a5380ee9088f7ace2efcde9";
+        assert_eq!(parse_checksum_file(hash, "x.iso"), Some(hash.to_string()));
+        assert_eq!(
+            parse_checksum_file(&format!("aaa  other.iso\n{} *x.iso\n", hash), "x.iso"),
+            Some(hash.to_string())
+        );
+        assert_eq!(parse_checksum_file("aaa  a.iso\nbbb  b.iso\n", "x.iso"), None);
+        assert_eq!(file_name("http://host/dir/x.iso?mirror=1"), "x.iso");
+    }
+
+    #[test]
+    fn test_verify_url_with_sibling_checksum() {
+        let hash = "b94d27b9934d3e08a52e52d7da7dabfac484efe37a5380ee9088f7ace2efcde9";
+        let file = "HTTP/1.1 200 OK\r\n\r\nhello world".to_string();
+        let computer = HashComputer::new();
+
+        // Matching checksum file
+        let url = serve(vec![format!("HTTP/1.1 200 OK\r\n\r\n{}  hello.txt\n", hash), file.clone()]);
+        let result = verify_url(&computer, &format!("{}/hello.txt", url), "sha256", None).unwrap();
+        assert!(result.passed());
+        assert_eq!(result.actual, hash);
+
+        // Checksum file for different content
+        let url = serve(vec![format!("HTTP/1.1 200 OK\r\n\r\n{}  hello.txt\n", "0".repeat(64)), file.clone()]);
+        let result = verify_url(&computer, &format!("{}/hello.txt", url), "sha256", None).unwrap();
+        assert!(!result.passed());
+
+        // Expected hash given inline, so only the file is fetched
+        let url = serve(vec![file]);
+        let result = verify_url(&computer, &format!("{}/hello.txt", url), "sha256", Some(&hash.to_uppercase())).unwrap();
+        assert!(result.passed());
+    }
+
+    #[test]
+    fn test_verify_url_short_body_is_an_error() {
+        let hash = "b94d27b9934d3e08a52e52d7da7dabfac484efe37a5380ee9088f7ace2efcde9";
+        let short = "HTTP/1.1 200 OK\r\nContent-Length: 20\r\n\r\nhello world".to_string();
+        let computer = HashComputer::new();
+
+        // Neither a pass nor a mismatch: the error names the shortfall
+        let url = serve(vec![format!("HTTP/1.1 200 OK\r\n\r\n{}  hello.txt\n", hash), short.clone()]);
+        let error = verify_url(&computer, &format!("{}/hello.txt", url), "sha256", None).unwrap_err();
+        assert!(error.to_string().contains("ended after 11 of 20 bytes"), "{}", error);
+
+        let url = serve(vec![short]);
+        assert!(verify_url(&computer, &format!("{}/hello.txt", url), "sha256", Some(hash)).is_err());
+    }
+
+    #[test]
+    fn test_open_url_error_status() {
+        let url = serve(vec![
+            "HTTP/1.1 404 Not Found\r\nContent-Length: 0\r\n\r\n".to_string(),
+        ]);
+
+        assert!(read_body(&format!("{}/missing.txt", url)).is_err());
+    }
+}
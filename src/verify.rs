@@ -7,9 +7,11 @@ use std::path::{Path, PathBuf};
 use std::sync::{Arc, Mutex};
 
 use crate::database::{DatabaseHandler, DatabaseEntry};
-use crate::hash::HashComputer;
-use crate::path_utils;
+use crate::hash::{HashComputer, HashRegistry, SYMLINK_ALGORITHM};
+use crate::path_utils::{self, UnicodeNormalization};
 use crate::error::HashUtilityError;
+use crate::ignore_handler::IgnoreHandler;
+use crate::wildcard::ExcludePatterns;
 use indicatif::{ProgressBar, ProgressStyle};
 use rayon::prelude::*;
 
@@ -24,6 +26,42 @@ pub struct Mismatch {
     pub actual: String,
 }
 
+/// Represents a change in file permissions between the database and the filesystem
+#[derive(Debug, Clone, serde::Serialize)]
+pub struct PermissionChange {
+    pub path: PathBuf,
+    pub expected: String,
+    pub actual: String,
+}
+
+/// Difference between a file's recorded and current modification time
+#[derive(Debug, Clone, serde::Serialize)]
+pub struct TimestampDrift {
+    pub path: PathBuf,
+    pub recorded: String,
+    pub current: String,
+    /// Current minus recorded time, in seconds
+    pub drift_seconds: i64,
+}
+
+/// A file whose size differs from the one recorded in the database (`--compare-sizes`)
+#[derive(Debug, Clone, serde::Serialize)]
+pub struct SizeMismatch {
+    pub path: PathBuf,
+    pub expected: u64,
+    pub actual: u64,
+}
+
+/// A database entry whose hash can't have come from its algorithm (e.g. a truncated line)
+#[derive(Debug, Clone, serde::Serialize)]
+pub struct CorruptEntry {
+    pub path: PathBuf,
+    pub algorithm: String,
+    pub hash: String,
+    /// Hex digits the algorithm produces
+    pub expected_length: usize,
+}
+
 /// Report of verification results
 #[derive(Debug, serde::Serialize)]
 pub struct VerifyReport {
@@ -31,15 +69,54 @@ pub struct VerifyReport {
     pub mismatches: Vec<Mismatch>,
     pub missing_files: Vec<PathBuf>,
     pub new_files: Vec<PathBuf>,
+    /// Permission changes, only for entries recorded with --store-permissions
+    pub permission_changes: Vec<PermissionChange>,
+    /// Modification time drift, only for entries recorded with --with-timestamp
+    pub timestamp_drifts: Vec<TimestampDrift>,
+    /// Entries whose hash length doesn't fit their algorithm; these files are not checked
+    pub corrupt_entries: Vec<CorruptEntry>,
+    /// With --two-pass: files that mismatched once but read differently the second time
+    pub transient_mismatches: Vec<PathBuf>,
+    /// With --verify-sample: how many entries were checked and what that says about the rest
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub sample: Option<SampleSummary>,
+    /// With --compare-sizes: files whose size changed (contents are not hashed)
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub size_mismatches: Vec<SizeMismatch>,
+    /// Only sizes were compared, so matches mean "same size", not "same content"
+    #[serde(skip)]
+    pub sizes_only: bool,
+    /// Only files present in both the database and the filesystem were considered
+    #[serde(skip)]
+    pub intersection_only: bool,
 }
 
 impl VerifyReport {
+    /// Restrict the report to files present in both the database and the filesystem
+    /// 
+    /// Missing and new files are dropped, so only content (and permission)
+    /// changes of overlapping files count as issues.
+    pub fn into_intersection(mut self) -> Self {
+        self.missing_files.clear();
+        self.new_files.clear();
+        self.intersection_only = true;
+        self
+    }
+    
+    /// Whether any file changed, disappeared, or appeared
+    pub fn has_issues(&self) -> bool {
+        !self.mismatches.is_empty() 
+            || !self.size_mismatches.is_empty()
+            || !self.missing_files.is_empty() 
+            || !self.new_files.is_empty()
+            || !self.permission_changes.is_empty()
+            || !self.corrupt_entries.is_empty()
+    }
+    
     /// Display a detailed report of verification results
     pub fn display(&self) {
         // Determine overall status
-        let has_issues = !self.mismatches.is_empty() 
-            || !self.missing_files.is_empty() 
-            || !self.new_files.is_empty();
+        let has_issues = self.has_issues();
         
         // Display clear status banner
         println!("\n================================================================");
@@ -51,16 +128,60 @@ impl VerifyReport {
         println!("================================================================\n");
         
         // Display summary counts
-        println!("Verification Summary:");
-        println!("  Matches:        {}", self.matches);
-        println!("  Mismatches:     {}", self.mismatches.len());
-        println!("  Missing files:  {}", self.missing_files.len());
-        println!("  New files:      {}", self.new_files.len());
+        if self.sizes_only {
+            println!("Verification Summary (sizes only, contents not hashed):");
+            println!("  Same size:      {}", self.matches);
+            println!("  Size changed:   {}", self.size_mismatches.len());
+        } else {
+            println!("Verification Summary:");
+            println!("  Matches:        {}", self.matches);
+            println!("  Mismatches:     {}", self.mismatches.len());
+        }
+        if !self.intersection_only {
+            println!("  Missing files:  {}", self.missing_files.len());
+            println!("  New files:      {}", self.new_files.len());
+        }
+        if !self.permission_changes.is_empty() {
+            println!("  Permissions:    {}", self.permission_changes.len());
+        }
+        if !self.timestamp_drifts.is_empty() {
+            println!("  Timestamp drift: {}", self.timestamp_drifts.len());
+        }
+        if !self.corrupt_entries.is_empty() {
+            println!("  Corrupt entries: {}", self.corrupt_entries.len());
+        }
+        if !self.transient_mismatches.is_empty() {
+            println!("  Transient read errors: {}", self.transient_mismatches.len());
+        }
+        if let Some(sample) = &self.sample {
+            println!("  Sampled:        {} of {} entries ({}%, seed {})", sample.sampled, sample.total_entries, sample.percent, sample.seed);
+            println!("  Estimated damaged entries: {:.1} (at most {:.2}% with 95% confidence)",
+                sample.estimated_failures,
+                sample.max_failure_rate * 100.0
+            );
+        }
+        
+        // Timestamp drift is informational: touching a file doesn't change its content
+        self.display_timestamp_drifts();
+        
+        // Unstable reads point at the storage rather than at changed files
+        if !self.transient_mismatches.is_empty() {
+            println!("\n--- Transient Read Errors ({}) ---", self.transient_mismatches.len());
+            println!("(mismatched once, then read differently; check the storage device)");
+            for path in &self.transient_mismatches {
+                println!("  ! {}", path.display());
+            }
+            println!("----------------------------------------------------------------");
+        }
         
         // If everything is good, show success message and return
         if !has_issues {
-            println!("\nAll files match the database. No changes detected.");
-            let total_checked = self.matches + self.mismatches.len();
+            if self.sizes_only {
+                println!("\nAll file sizes match the database. No changes detected.");
+            } else {
+                println!("\nAll files match the database. No changes detected.");
+            }
+            let total_checked = self.matches + self.mismatches.len() + self.transient_mismatches.len();
             println!("Total files verified: {}", total_checked);
             return;
         }
@@ -77,6 +198,40 @@ impl VerifyReport {
             println!("----------------------------------------------------------------");
         }
         
+        if !self.size_mismatches.is_empty() {
+            println!("\n--- Files with Changed Sizes ({}) ---", self.size_mismatches.len());
+            for mismatch in &self.size_mismatches {
+                println!();
+                println!("  File: {}", mismatch.path.display());
+                println!("    Expected: {} bytes", mismatch.expected);
+                println!("    Actual:   {} bytes", mismatch.actual);
+            }
+            println!("----------------------------------------------------------------");
+        }
+        
+        if !self.corrupt_entries.is_empty() {
+            println!("\n--- Corrupt Database Entries ({}) ---", self.corrupt_entries.len());
+            println!("(hash length doesn't match the algorithm; the database line is damaged)");
+            for corrupt in &self.corrupt_entries {
+                println!();
+                println!("  File: {}", corrupt.path.display());
+                println!("    Hash:     {} ({})", corrupt.hash, corrupt.algorithm);
+                println!("    Expected: {} hex digits, found {}", corrupt.expected_length, corrupt.hash.len());
+            }
+            println!("----------------------------------------------------------------");
+        }
+        
+        if !self.permission_changes.is_empty() {
+            println!("\n--- Files with Changed Permissions ({}) ---", self.permission_changes.len());
+            for change in &self.permission_changes {
+                println!();
+                println!("  File: {}", change.path.display());
+                println!("    Expected: {}", change.expected);
+                println!("    Actual:   {}", change.actual);
+            }
+            println!("----------------------------------------------------------------");
+        }
+        
         if !self.missing_files.is_empty() {
             println!("\n--- Deleted Files ({}) ---", self.missing_files.len());
             println!("(in database but not in filesystem)");
@@ -97,20 +252,193 @@ impl VerifyReport {
         
         // Final summary
         println!("\n================================================================");
-        let total_checked = self.matches + self.mismatches.len();
+        let total_checked = self.matches + self.mismatches.len() + self.size_mismatches.len() + self.transient_mismatches.len();
         let total_in_db = total_checked + self.missing_files.len();
         let total_in_fs = total_checked + self.new_files.len();
         println!("Total files checked:      {}", total_checked);
-        println!("Total files in database:  {}", total_in_db);
-        println!("Total files in filesystem: {}", total_in_fs);
+        if !self.intersection_only {
+            println!("Total files in database:  {}", total_in_db);
+            println!("Total files in filesystem: {}", total_in_fs);
+        }
         println!("================================================================");
     }
+    
+    /// Display files whose modification time differs from the recorded one
+    fn display_timestamp_drifts(&self) {
+        if self.timestamp_drifts.is_empty() {
+            return;
+        }
+        
+        println!("\n--- Files with Timestamp Drift ({}) ---", self.timestamp_drifts.len());
+        for drift in &self.timestamp_drifts {
+            println!();
+            println!("  File: {}", drift.path.display());
+            println!("    Recorded: {}", drift.recorded);
+            println!("    Current:  {}", drift.current);
+            println!("    Drift:    {:+}s", drift.drift_seconds);
+        }
+        println!("----------------------------------------------------------------");
+    }
+}
+
+/// Prefix rewrite applied to database paths before they are looked up on disk (`--path-map OLD=NEW`)
+/// 
+/// Lets a database recorded under one layout (e.g. `/data`) verify a tree restored elsewhere (e.g. `/mnt/restore`).
+#[derive(Debug, Clone, PartialEq)]
+pub struct PathMap {
+    pub from: PathBuf,
+    pub to: PathBuf,
+}
+
+impl PathMap {
+    /// Parse an `OLD=NEW` spec
+    pub fn parse(spec: &str) -> Result<Self, HashUtilityError> {
+        let invalid = |reason: &str| HashUtilityError::InvalidArguments {
+            message: format!("Invalid path map '{}': {} (expected OLD=NEW)", spec, reason),
+        };
+        
+        let (from, to) = spec.split_once('=').ok_or_else(|| invalid("missing '='"))?;
+        if from.is_empty() {
+            return Err(invalid("missing old prefix"));
+        }
+        
+        Ok(Self {
+            from: path_utils::parse_database_path(from),
+            to: path_utils::parse_database_path(to),
+        })
+    }
+    
+    /// The rewritten path, if `path` lies under this map's old prefix (compared by whole components)
+    pub fn apply(&self, path: &Path) -> Option<PathBuf> {
+        path.strip_prefix(&self.from).ok().map(|rest| self.to.join(rest))
+    }
+}
+
+/// Random subset of database entries to verify (`--verify-sample`)
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct VerifySample {
+    pub percent: f64,
+    pub seed: u64,
+}
+
+impl VerifySample {
+    /// Validate the percentage of entries to check (more than 0, at most 100)
+    pub fn new(percent: f64, seed: u64) -> Result<Self, HashUtilityError> {
+        if !(percent > 0.0 && percent <= 100.0) {
+            return Err(HashUtilityError::InvalidArguments {
+                message: format!("Invalid sample percentage '{}': expected a number greater than 0 and at most 100", percent),
+            });
+        }
+        Ok(Self { percent, seed })
+    }
+    
+    /// Split entries into the sampled ones and the paths left out
+    /// 
+    /// Entries are ordered by path before shuffling, so the same seed and
+    /// database always select the same subset. At least one entry is kept.
+    fn select(self, database: HashMap<PathBuf, DatabaseEntry>) -> (HashMap<PathBuf, DatabaseEntry>, Vec<PathBuf>) {
+        let mut entries: Vec<(PathBuf, DatabaseEntry)> = database.into_iter().collect();
+        entries.sort_by(|a, b| a.0.cmp(&b.0));
+        
+        let count = ((entries.len() as f64 * self.percent / 100.0).ceil() as usize).max(1).min(entries.len());
+        
+        // Partial Fisher-Yates shuffle: the first `count` slots end up a uniform random sample
+        let mut state = self.seed;
+        for i in 0..count {
+            let j = i + (splitmix64(&mut state) % (entries.len() - i) as u64) as usize;
+            entries.swap(i, j);
+        }
+        
+        let skipped = entries.split_off(count).into_iter().map(|(path, _)| path).collect();
+        (entries.into_iter().collect(), skipped)
+    }
+}
+
+/// SplitMix64 step: a small, fast generator that is plenty for picking a sample
+fn splitmix64(state: &mut u64) -> u64 {
+    *state = state.wrapping_add(0x9E37_79B9_7F4A_7C15);
+    let mut z = *state;
+    z = (z ^ (z >> 30)).wrapping_mul(0xBF58_476D_1CE4_E5B9);
+    z = (z ^ (z >> 27)).wrapping_mul(0x94D0_49BB_1331_11EB);
+    z ^ (z >> 31)
+}
+
+/// What a sampled verification says about the whole database
+#[derive(Debug, Clone, serde::Serialize)]
+pub struct SampleSummary {
+    pub percent: f64,
+    pub seed: u64,
+    /// Entries actually checked
+    pub sampled: usize,
+    /// Entries in the database
+    pub total_entries: usize,
+    /// Sampled entries that changed or are missing
+    pub failures: usize,
+    /// Failures scaled up to the whole database
+    pub estimated_failures: f64,
+    /// Upper bound on the fraction of damaged entries at 95% confidence (Wilson score interval)
+    pub max_failure_rate: f64,
+}
+
+impl SampleSummary {
+    fn new(sample: VerifySample, sampled: usize, total_entries: usize, failures: usize) -> Self {
+        let (estimated_failures, max_failure_rate) = if sampled == 0 {
+            (0.0, 0.0)
+        } else if sampled == total_entries {
+            // Everything was checked, so there is nothing to extrapolate
+            (failures as f64, failures as f64 / sampled as f64)
+        } else {
+            let n = sampled as f64;
+            let rate = failures as f64 / n;
+            let z2 = 1.96_f64 * 1.96;
+            let center = rate + z2 / (2.0 * n);
+            let margin = 1.96 * (rate * (1.0 - rate) / n + z2 / (4.0 * n * n)).sqrt();
+            (rate * total_entries as f64, ((center + margin) / (1.0 + z2 / n)).min(1.0))
+        };
+        
+        Self {
+            percent: sample.percent,
+            seed: sample.seed,
+            sampled,
+            total_entries,
+            failures,
+            estimated_failures,
+            max_failure_rate,
+        }
+    }
+}
+
+/// Outcome of re-hashing a single database entry
+enum HashCheck {
+    Match,
+    /// Differs from the database (with --two-pass, the same way on both reads)
+    Mismatch(String),
+    /// Mismatched, but a second read produced a different hash
+    Transient,
 }
 
 /// Engine for verifying file integrity against a hash database
 pub struct VerifyEngine {
     computer: HashComputer,
     parallel: bool,
+    include_xattrs: bool,
+    show_progress: bool,
+    path_maps: Vec<PathMap>,
+    two_pass: bool,
+    unicode_normalization: Option<UnicodeNormalization>,
+    sample: Option<VerifySample>,
+    compare_sizes: bool,
+    /// Algorithm the database is expected to use (`verify -a`), and whether other algorithms are an error
+    expected_algorithm: Option<String>,
+    strict_algorithm: bool,
+    /// Parse hand-edited databases leniently: trailing comments, extra whitespace, mixed separators
+    lenient: bool,
+    /// Scan filters that also decide which unrecorded symlinks count as new (`--skip-dir`, `--exclude-from`)
+    skip_dirs: Vec<String>,
+    exclude_patterns: ExcludePatterns,
+    /// Files whose next read returns corrupted bytes, so tests can simulate flaky storage
+    #[cfg(test)]
+    flaky_reads: Arc<Mutex<HashSet<PathBuf>>>,
 }
 
 impl VerifyEngine {
@@ -119,14 +447,206 @@ impl VerifyEngine {
         Self {
             computer: HashComputer::new(),
             parallel: true,
+            include_xattrs: false,
+            show_progress: true,
+            path_maps: Vec::new(),
+            two_pass: false,
+            unicode_normalization: None,
+            sample: None,
+            compare_sizes: false,
+            expected_algorithm: None,
+            strict_algorithm: false,
+            lenient: false,
+            skip_dirs: Vec::new(),
+            exclude_patterns: ExcludePatterns::default(),
+            #[cfg(test)]
+            flaky_reads: Arc::new(Mutex::new(HashSet::new())),
         }
     }
     
     /// Create a new VerifyEngine with parallel processing control
     pub fn with_parallel(parallel: bool) -> Self {
         Self {
-            computer: HashComputer::new(),
+            // Sequential (HDD) mode hints sequential access to the kernel
+            computer: HashComputer::new().with_sequential_hint(!parallel),
             parallel,
+            include_xattrs: false,
+            show_progress: true,
+            path_maps: Vec::new(),
+            two_pass: false,
+            unicode_normalization: None,
+            sample: None,
+            compare_sizes: false,
+            expected_algorithm: None,
+            strict_algorithm: false,
+            lenient: false,
+            skip_dirs: Vec::new(),
+            exclude_patterns: ExcludePatterns::default(),
+            #[cfg(test)]
+            flaky_reads: Arc::new(Mutex::new(HashSet::new())),
+        }
+    }
+    
+    /// Include extended attributes when recomputing file hashes
+    /// 
+    /// Must match the setting used when the database was created.
+    pub fn with_xattrs(mut self, include_xattrs: bool) -> Self {
+        self.include_xattrs = include_xattrs;
+        self.computer = self.computer.with_xattrs(include_xattrs);
+        self
+    }
+    
+    /// Show a progress bar while recomputing hashes (default)
+    /// 
+    /// The bar is drawn on stderr and stays hidden when stderr is not a TTY.
+    pub fn with_progress(mut self, show_progress: bool) -> Self {
+        self.show_progress = show_progress;
+        self
+    }
+    
+    /// Rewrite database path prefixes before resolving them; the first matching map wins
+    pub fn with_path_maps(mut self, path_maps: Vec<PathMap>) -> Self {
+        self.path_maps = path_maps;
+        self
+    }
+    
+    /// Re-read and re-hash mismatching files before reporting them (`--two-pass`)
+    /// 
+    /// A file only counts as changed if both reads agree; otherwise it is
+    /// reported as a transient read error.
+    pub fn with_two_pass(mut self, two_pass: bool) -> Self {
+        self.two_pass = two_pass;
+        self
+    }
+    
+    /// Match database paths to files whose names differ only in Unicode normalization (NFC vs NFD)
+    pub fn with_unicode_normalization(mut self, form: Option<UnicodeNormalization>) -> Self {
+        self.unicode_normalization = form;
+        self
+    }
+    
+    /// Only verify a seeded random subset of the database entries (`--verify-sample`)
+    /// 
+    /// Files of entries left out of the sample are not reported as new.
+    pub fn with_sample(mut self, sample: Option<VerifySample>) -> Self {
+        self.sample = sample;
+        self
+    }
+    
+    /// Compare only file sizes and existence against the database, without hashing (`--compare-sizes`)
+    /// 
+    /// Needs a database with a size column (tsv or hashdeep format).
+    pub fn with_compare_sizes(mut self, compare_sizes: bool) -> Self {
+        self.compare_sizes = compare_sizes;
+        self
+    }
+    
+    /// Parse database lines leniently (`--lenient`), for hand-edited files
+    /// 
+    /// Trailing `#` comments and extra whitespace are ignored in standard and coreutils databases.
+    pub fn with_lenient(mut self, lenient: bool) -> Self {
+        self.lenient = lenient;
+        self
+    }
+    
+    /// Leave symlinks in directories with these names out of the tree (`--skip-dir`)
+    /// 
+    /// Pass the scan's own `--skip-dir` names so links it never recorded aren't reported as new.
+    pub fn with_skip_dirs(mut self, skip_dirs: Vec<String>) -> Self {
+        self.skip_dirs = skip_dirs;
+        self
+    }
+    
+    /// Leave symlinks matching any of these patterns out of the tree (`--exclude-from`)
+    pub fn with_exclude_patterns(mut self, exclude_patterns: ExcludePatterns) -> Self {
+        self.exclude_patterns = exclude_patterns;
+        self
+    }
+    
+    /// Check that the database was made with `algorithm` (`verify -a`)
+    /// 
+    /// Files are always rehashed with the algorithm their entry records, so entries made
+    /// with another algorithm are reported with a warning, or as an error before anything
+    /// is hashed when `strict` is set (`--strict`).
+    pub fn with_expected_algorithm(mut self, algorithm: Option<String>, strict: bool) -> Self {
+        self.expected_algorithm = algorithm;
+        self.strict_algorithm = strict;
+        self
+    }
+    
+    /// Compare the entries' algorithms with the expected one, warning or failing on a difference
+    fn check_algorithm(&self, database: &HashMap<PathBuf, DatabaseEntry>) -> Result<(), VerifyError> {
+        let Some(expected) = &self.expected_algorithm else {
+            return Ok(());
+        };
+        let expected = HashRegistry::canonical_name(expected);
+        
+        // Symlink entries hash the link target, whatever the files were hashed with
+        let mut others: Vec<String> = database
+            .values()
+            .filter(|entry| entry.algorithm != SYMLINK_ALGORITHM)
+            .map(|entry| HashRegistry::canonical_name(&entry.algorithm))
+            .filter(|algorithm| !algorithm.eq_ignore_ascii_case(&expected))
+            .collect::<HashSet<_>>()
+            .into_iter()
+            .collect();
+        if others.is_empty() {
+            return Ok(());
+        }
+        others.sort();
+        
+        let message = format!(
+            "Database entries were hashed with {}, not {}; checking them as {} would report every file as changed",
+            others.join(", "),
+            expected,
+            expected
+        );
+        if self.strict_algorithm {
+            return Err(HashUtilityError::InvalidArguments { message });
+        }
+        eprintln!("Warning: {} (each entry is checked with its own algorithm; use --strict to fail instead)", message);
+        Ok(())
+    }
+    
+    /// Hash a file the way its database entry was computed
+    fn hash_entry(&self, computer: &HashComputer, path: &Path, entry: &DatabaseEntry) -> Result<String, VerifyError> {
+        #[cfg(test)]
+        if self.flaky_reads.lock().unwrap().remove(path) {
+            let mut data = fs::read(path)?;
+            if let Some(first) = data.first_mut() {
+                *first ^= 0xff;
+            }
+            return computer.compute_hash_reader(&data[..], &entry.algorithm);
+        }
+        
+        entry.rehash(computer, path).map(|result| result.hash)
+    }
+    
+    /// Compare a file against its database entry, reading it a second time on mismatch with --two-pass
+    fn check_entry(&self, computer: &HashComputer, path: &Path, entry: &DatabaseEntry) -> Result<HashCheck, VerifyError> {
+        let first = self.hash_entry(computer, path, entry)?;
+        if first == entry.hash {
+            return Ok(HashCheck::Match);
+        }
+        if !self.two_pass {
+            return Ok(HashCheck::Mismatch(first));
+        }
+        
+        // Only a difference that reads back the same way is a real change
+        let second = self.hash_entry(computer, path, entry)?;
+        if second == first {
+            Ok(HashCheck::Mismatch(first))
+        } else {
+            Ok(HashCheck::Transient)
+        }
+    }
+    
+    /// Create a progress bar for `len` files, hidden if progress is disabled
+    fn progress_bar(&self, len: u64) -> ProgressBar {
+        if self.show_progress {
+            ProgressBar::new(len)
+        } else {
+            ProgressBar::hidden()
         }
     }
     
@@ -157,26 +677,312 @@ impl VerifyEngine {
             });
         }
         
+        // Size-only check reads the size column instead of the hashes
+        if self.compare_sizes {
+            let sizes = DatabaseHandler::read_sizes(database_path)?;
+            return self.verify_sizes(&sizes, directory, database_path.canonicalize().ok());
+        }
+        
         // Load the hash database
-        let database = DatabaseHandler::read_database(database_path)?;
+        let database = DatabaseHandler::read_database_with(database_path, self.lenient)?;
         
         // Get canonical path of database file to exclude it from scan
-        let database_canonical = database_path.canonicalize().ok();
+        self.verify_loaded(&database, directory, database_path.canonicalize().ok().as_slice())
+    }
+    
+    /// Verify directory contents against several databases merged into one (`verify --merge-databases`)
+    /// 
+    /// Shards written by `split`, or any databases covering parts of one tree, are
+    /// unioned first, so a file is only reported missing or new if no database lists it.
+    /// None of the database files are counted as new files.
+    pub fn verify_merged(
+        &self,
+        database_paths: &[PathBuf],
+        directory: &Path,
+    ) -> Result<VerifyReport, VerifyError> {
+        for database_path in database_paths {
+            if !database_path.exists() {
+                return Err(HashUtilityError::DatabaseNotFound {
+                    path: database_path.to_path_buf(),
+                });
+            }
+        }
+        
+        if !directory.exists() || !directory.is_dir() {
+            return Err(HashUtilityError::DirectoryNotFound {
+                path: directory.to_path_buf(),
+            });
+        }
+        
+        let database = DatabaseHandler::read_merged(database_paths, self.lenient)?;
+        let database_files: Vec<PathBuf> = database_paths.iter().filter_map(|path| path.canonicalize().ok()).collect();
+        self.verify_loaded(&database, directory, &database_files)
+    }
+    
+    /// Re-read every file of a just-written database and confirm its hash (`scan --verify-after`)
+    /// 
+    /// Only files present in both the database and the directory are compared:
+    /// files the scan skipped (ignored, filtered by --since, or past a timeout)
+    /// and the database itself don't count as issues.
+    pub fn read_back(
+        &self,
+        database_path: &Path,
+        directory: &Path,
+    ) -> Result<VerifyReport, VerifyError> {
+        Ok(self.verify(database_path, directory)?.into_intersection())
+    }
+    
+    /// Verify directory contents against already loaded database entries
+    pub fn verify_entries(
+        &self,
+        database: &HashMap<PathBuf, DatabaseEntry>,
+        directory: &Path,
+    ) -> Result<VerifyReport, VerifyError> {
+        // Verify directory exists
+        if !directory.exists() || !directory.is_dir() {
+            return Err(HashUtilityError::DirectoryNotFound {
+                path: directory.to_path_buf(),
+            });
+        }
+        
+        // Loaded entries carry hashes only
+        if self.compare_sizes {
+            return Err(HashUtilityError::InvalidArguments {
+                message: "--compare-sizes needs a database file with a size column".to_string(),
+            });
+        }
+        
+        self.verify_loaded(database, directory, &[])
+    }
+    
+    /// Verify a single file against its database entry (`verify --file`)
+    /// 
+    /// `file` is a path as given on the command line (absolute or relative to the
+    /// current directory); database paths are resolved against `directory` as usual.
+    /// It is an error if the database has no entry for the file.
+    pub fn verify_file(
+        &self,
+        database_path: &Path,
+        directory: &Path,
+        file: &Path,
+    ) -> Result<VerifyReport, VerifyError> {
+        if !database_path.exists() {
+            return Err(HashUtilityError::DatabaseNotFound {
+                path: database_path.to_path_buf(),
+            });
+        }
         
-        // Collect all files in the directory (as canonical paths), excluding the database file
+        let database = DatabaseHandler::read_database_with(database_path, self.lenient)?;
+        self.verify_file_entries(&database, directory, file)
+    }
+    
+    /// Verify a single file against already loaded database entries
+    pub fn verify_file_entries(
+        &self,
+        database: &HashMap<PathBuf, DatabaseEntry>,
+        directory: &Path,
+        file: &Path,
+    ) -> Result<VerifyReport, VerifyError> {
+        self.check_algorithm(database)?;
+        
+        // Missing paths can't be canonicalized, so compare them as cleaned absolute paths
+        let current_dir = std::env::current_dir()?;
+        let absolute = |path: &Path| path_utils::clean_path(&current_dir.join(path));
+        let target = path_utils::try_canonicalize(&absolute(file))?;
+        
+        let (path, entry) = self
+            .resolve_database_paths_optimized(database, directory)?
+            .into_iter()
+            .find(|(path, _)| absolute(path) == target)
+            .ok_or_else(|| HashUtilityError::InvalidArguments {
+                message: format!("'{}' is not in the database", file.display()),
+            })?;
+        
+        let mut current_files = HashSet::new();
+        if path.is_file() {
+            current_files.insert(path.clone());
+        }
+        self.verify_sequential(HashMap::from([(path, entry)]), current_files)
+    }
+    
+    /// Shared verification once the database is loaded, skipping the database files themselves if they are in the tree
+    fn verify_loaded(
+        &self,
+        database: &HashMap<PathBuf, DatabaseEntry>,
+        directory: &Path,
+        database_files: &[PathBuf],
+    ) -> Result<VerifyReport, VerifyError> {
+        self.check_algorithm(database)?;
+        
+        // Collect all files in the directory (as canonical paths), excluding the database files
         let mut current_files = self.collect_files_optimized(directory)?;
-        if let Some(db_path) = &database_canonical {
+        for db_path in database_files {
             current_files.remove(db_path);
         }
         
         // Convert database paths to canonical for comparison (optimized with caching)
-        let database_canonical = self.resolve_database_paths_optimized(&database, directory)?;
+        let mut database_canonical = if database.values().any(|entry| entry.algorithm == SYMLINK_ALGORITHM) {
+            // Symlink entries (`scan --hash-symlink-targets`) name the link itself, not its target,
+            // and links in the tree that aren't recorded show up as new
+            let (links, files): (HashMap<_, _>, HashMap<_, _>) = database
+                .iter()
+                .map(|(path, entry)| (path.clone(), entry.clone()))
+                .partition(|(_, entry)| entry.algorithm == SYMLINK_ALGORITHM);
+            current_files.extend(self.collect_symlinks(directory));
+            let mut resolved = self.resolve_database_paths_optimized(&files, directory)?;
+            resolved.extend(self.resolve_link_paths(links, directory));
+            resolved
+        } else {
+            self.resolve_database_paths_optimized(database, directory)?
+        };
+        if let Some(form) = self.unicode_normalization {
+            database_canonical = Self::match_normalized_paths(database_canonical, &current_files, directory, form);
+        }
+        
+        // Set aside damaged entries: hashing their files could only ever report a false mismatch.
+        // Their files are still accounted for, so they don't show up as new either.
+        let corrupt_entries = Self::take_corrupt_entries(&mut database_canonical);
+        for corrupt in &corrupt_entries {
+            current_files.remove(&corrupt.path);
+        }
         
-        if self.parallel {
-            self.verify_parallel(database_canonical, current_files)
+        // Check only the sampled entries; files of the others are accounted for, not new
+        let total_entries = database_canonical.len();
+        if let Some(sample) = self.sample {
+            let (sampled, skipped) = sample.select(database_canonical);
+            for path in &skipped {
+                current_files.remove(path);
+            }
+            database_canonical = sampled;
+        }
+        let sampled = database_canonical.len();
+        
+        let mut report = if self.parallel {
+            self.verify_parallel(database_canonical, current_files)?
         } else {
-            self.verify_sequential(database_canonical, current_files)
+            self.verify_sequential(database_canonical, current_files)?
+        };
+        report.corrupt_entries = corrupt_entries;
+        if let Some(sample) = self.sample {
+            let failures = report.mismatches.len() + report.missing_files.len();
+            report.sample = Some(SampleSummary::new(sample, sampled, total_entries, failures));
         }
+        Ok(report)
+    }
+    
+    /// Compare recorded sizes against file metadata; no file is opened or hashed
+    fn verify_sizes(
+        &self,
+        sizes: &HashMap<PathBuf, u64>,
+        directory: &Path,
+        database_canonical: Option<PathBuf>,
+    ) -> Result<VerifyReport, VerifyError> {
+        let mut current_files = self.collect_files_optimized(directory)?;
+        if let Some(db_path) = &database_canonical {
+            current_files.remove(db_path);
+        }
+        
+        let mut sizes_canonical = self.resolve_database_paths_optimized(sizes, directory)?;
+        if let Some(form) = self.unicode_normalization {
+            sizes_canonical = Self::match_normalized_paths(sizes_canonical, &current_files, directory, form);
+        }
+        
+        let mut matches = 0;
+        let mut size_mismatches = Vec::new();
+        let mut missing_files = Vec::new();
+        for (path, expected) in sizes_canonical {
+            if !current_files.remove(&path) {
+                missing_files.push(path);
+                continue;
+            }
+            match fs::metadata(&path) {
+                Ok(metadata) if metadata.len() == expected => matches += 1,
+                Ok(metadata) => size_mismatches.push(SizeMismatch { path, expected, actual: metadata.len() }),
+                Err(e) => {
+                    eprintln!("Warning: Failed to read metadata of {}: {}", path.display(), e);
+                    missing_files.push(path);
+                }
+            }
+        }
+        
+        let mut new_files: Vec<PathBuf> = current_files.into_iter().collect();
+        size_mismatches.sort_by(|a, b| a.path.cmp(&b.path));
+        missing_files.sort();
+        new_files.sort();
+        
+        Ok(VerifyReport {
+            matches,
+            mismatches: Vec::new(),
+            missing_files,
+            new_files,
+            permission_changes: Vec::new(),
+            timestamp_drifts: Vec::new(),
+            corrupt_entries: Vec::new(),
+            transient_mismatches: Vec::new(),
+            sample: None,
+            size_mismatches,
+            sizes_only: true,
+            intersection_only: false,
+        })
+    }
+    
+    /// Re-key database entries not found on disk to the file whose normalized path matches
+    /// 
+    /// A database written on macOS stores NFD names that don't exist byte for byte
+    /// on a Linux copy of the tree (and vice versa), so they would otherwise be
+    /// reported as missing while the real files show up as new.
+    fn match_normalized_paths<T>(
+        database: HashMap<PathBuf, T>,
+        current_files: &HashSet<PathBuf>,
+        directory: &Path,
+        form: UnicodeNormalization,
+    ) -> HashMap<PathBuf, T> {
+        let live: HashMap<PathBuf, &PathBuf> = current_files
+            .iter()
+            .map(|path| (form.normalize_path(path), path))
+            .collect();
+        // Unresolved paths are relative to the directory as given, live paths are canonical
+        let canonical_directory = directory.canonicalize().unwrap_or_else(|_| directory.to_path_buf());
+        
+        database
+            .into_iter()
+            .map(|(path, entry)| {
+                if current_files.contains(&path) {
+                    return (path, entry);
+                }
+                let absolute = match path.strip_prefix(directory) {
+                    Ok(relative) => canonical_directory.join(relative),
+                    Err(_) => path.clone(),
+                };
+                match live.get(&form.normalize_path(&path_utils::clean_path(&absolute))) {
+                    Some(actual) => ((*actual).clone(), entry),
+                    None => (path, entry),
+                }
+            })
+            .collect()
+    }
+    
+    /// Remove and return entries whose hash length is inconsistent with their algorithm
+    /// 
+    /// Entries with an unknown algorithm are left in place; hashing them reports the error.
+    fn take_corrupt_entries(database: &mut HashMap<PathBuf, DatabaseEntry>) -> Vec<CorruptEntry> {
+        let mut corrupt_entries = Vec::new();
+        database.retain(|path, entry| {
+            match HashRegistry::expected_hex_len(&entry.algorithm) {
+                Some(expected_length) if entry.hash.len() != expected_length => {
+                    corrupt_entries.push(CorruptEntry {
+                        path: path.clone(),
+                        algorithm: entry.algorithm.clone(),
+                        hash: entry.hash.clone(),
+                        expected_length,
+                    });
+                    false
+                }
+                _ => true,
+            }
+        });
+        corrupt_entries.sort_by(|a, b| a.path.cmp(&b.path));
+        corrupt_entries
     }
     
     /// Sequential verification implementation
@@ -189,10 +995,13 @@ impl VerifyEngine {
         let mut matches = 0;
         let mut mismatches = Vec::new();
         let mut missing_files = Vec::new();
+        let mut permission_changes = Vec::new();
+        let mut timestamp_drifts = Vec::new();
+        let mut transient_mismatches = Vec::new();
         let mut checked_files = HashSet::new();
         
         // Create progress bar
-        let pb = ProgressBar::new(database_canonical.len() as u64);
+        let pb = self.progress_bar(database_canonical.len() as u64);
         pb.set_style(
             ProgressStyle::default_bar()
                 .template("[{elapsed_precise}] [{bar:40.cyan/blue}] {pos}/{len} files ({percent}%) | {msg}")
@@ -211,25 +1020,25 @@ impl VerifyEngine {
             pb.set_message(format!("Verifying: {}", file_name));
             
             if current_files.contains(db_path) {
-                // File exists, compute current hash using the mode specified in the database
-                let hash_result = if entry.fast_mode {
-                    self.computer.compute_hash_fast(db_path, &entry.algorithm)
-                } else {
-                    self.computer.compute_hash(db_path, &entry.algorithm)
-                };
+                // Permissions are checked independently of content
+                if let Some(change) = check_permissions(db_path, entry) {
+                    permission_changes.push(change);
+                }
+                if let Some(drift) = check_timestamp(db_path, entry) {
+                    timestamp_drifts.push(drift);
+                }
                 
-                match hash_result {
-                    Ok(result) => {
-                        if result.hash == entry.hash {
-                            matches += 1;
-                        } else {
-                            mismatches.push(Mismatch {
-                                path: db_path.clone(),
-                                expected: entry.hash.clone(),
-                                actual: result.hash,
-                            });
-                        }
+                // File exists, compute current hash using the mode specified in the database
+                match self.check_entry(&self.computer, db_path, entry) {
+                    Ok(HashCheck::Match) => matches += 1,
+                    Ok(HashCheck::Mismatch(actual)) => {
+                        mismatches.push(Mismatch {
+                            path: db_path.clone(),
+                            expected: entry.hash.clone(),
+                            actual,
+                        });
                     }
+                    Ok(HashCheck::Transient) => transient_mismatches.push(db_path.clone()),
                     Err(e) => {
                         eprintln!("Warning: Failed to hash {}: {}", db_path.display(), e);
                     }
@@ -257,6 +1066,14 @@ impl VerifyEngine {
             mismatches,
             missing_files,
             new_files,
+            permission_changes,
+            timestamp_drifts,
+            corrupt_entries: Vec::new(),
+            transient_mismatches,
+            sample: None,
+            size_mismatches: Vec::new(),
+            sizes_only: false,
+            intersection_only: false,
         })
     }
     
@@ -270,9 +1087,12 @@ impl VerifyEngine {
         let matches = Arc::new(Mutex::new(0usize));
         let mismatches = Arc::new(Mutex::new(Vec::new()));
         let missing_files = Arc::new(Mutex::new(Vec::new()));
+        let permission_changes = Arc::new(Mutex::new(Vec::new()));
+        let timestamp_drifts = Arc::new(Mutex::new(Vec::new()));
+        let transient_mismatches = Arc::new(Mutex::new(Vec::new()));
         
         // Create progress bar
-        let pb = ProgressBar::new(database_canonical.len() as u64);
+        let pb = self.progress_bar(database_canonical.len() as u64);
         pb.set_style(
             ProgressStyle::default_bar()
                 .template("[{elapsed_precise}] [{bar:40.cyan/blue}] {pos}/{len} files ({percent}%) | {msg}")
@@ -284,6 +1104,9 @@ impl VerifyEngine {
         let matches_clone = Arc::clone(&matches);
         let mismatches_clone = Arc::clone(&mismatches);
         let missing_files_clone = Arc::clone(&missing_files);
+        let permission_changes_clone = Arc::clone(&permission_changes);
+        let timestamp_drifts_clone = Arc::clone(&timestamp_drifts);
+        let transient_mismatches_clone = Arc::clone(&transient_mismatches);
         let pb_clone = pb.clone();
         
         // Collect database entries into a vector for parallel iteration
@@ -298,27 +1121,31 @@ impl VerifyEngine {
             pb_clone.set_message(format!("{} OK, {} changed, {} missing", match_count, mismatch_count, missing_count));
             
             if current_files.contains(*db_path) {
-                // File exists, compute current hash using the mode specified in the database
-                let computer = HashComputer::new();
-                let hash_result = if entry.fast_mode {
-                    computer.compute_hash_fast(db_path, &entry.algorithm)
-                } else {
-                    computer.compute_hash(db_path, &entry.algorithm)
-                };
+                // Permissions are checked independently of content
+                if let Some(change) = check_permissions(db_path, entry) {
+                    permission_changes_clone.lock().unwrap().push(change);
+                }
+                if let Some(drift) = check_timestamp(db_path, entry) {
+                    timestamp_drifts_clone.lock().unwrap().push(drift);
+                }
                 
-                match hash_result {
-                    Ok(result) => {
-                        if result.hash == entry.hash {
-                            let mut count = matches_clone.lock().unwrap();
-                            *count += 1;
-                        } else {
-                            let mut list = mismatches_clone.lock().unwrap();
-                            list.push(Mismatch {
-                                path: (*db_path).clone(),
-                                expected: entry.hash.clone(),
-                                actual: result.hash,
-                            });
-                        }
+                // File exists, compute current hash using the mode specified in the database
+                let computer = HashComputer::new().with_xattrs(self.include_xattrs);
+                match self.check_entry(&computer, db_path, entry) {
+                    Ok(HashCheck::Match) => {
+                        let mut count = matches_clone.lock().unwrap();
+                        *count += 1;
+                    }
+                    Ok(HashCheck::Mismatch(actual)) => {
+                        let mut list = mismatches_clone.lock().unwrap();
+                        list.push(Mismatch {
+                            path: (*db_path).clone(),
+                            expected: entry.hash.clone(),
+                            actual,
+                        });
+                    }
+                    Ok(HashCheck::Transient) => {
+                        transient_mismatches_clone.lock().unwrap().push((*db_path).clone());
                     }
                     Err(e) => {
                         eprintln!("Warning: Failed to hash {}: {}", db_path.display(), e);
@@ -351,12 +1178,23 @@ impl VerifyEngine {
         let final_matches = *matches.lock().unwrap();
         let final_mismatches = mismatches.lock().unwrap().clone();
         let final_missing = missing_files.lock().unwrap().clone();
+        let final_permission_changes = permission_changes.lock().unwrap().clone();
+        let final_timestamp_drifts = timestamp_drifts.lock().unwrap().clone();
+        let final_transient = transient_mismatches.lock().unwrap().clone();
         
         Ok(VerifyReport {
             matches: final_matches,
             mismatches: final_mismatches,
             missing_files: final_missing,
             new_files,
+            permission_changes: final_permission_changes,
+            timestamp_drifts: final_timestamp_drifts,
+            corrupt_entries: Vec::new(),
+            transient_mismatches: final_transient,
+            sample: None,
+            size_mismatches: Vec::new(),
+            sizes_only: false,
+            intersection_only: false,
         })
     }
     
@@ -396,23 +1234,74 @@ impl VerifyEngine {
         Ok(files)
     }
     
+    /// Collect the symlinks in a directory as canonical paths to the links themselves
+    /// 
+    /// Links pass the same .hashignore, --skip-dir and --exclude-from filters a scan
+    /// applies, so only links a scan would have recorded can show up as new.
+    fn collect_symlinks(&self, directory: &Path) -> Vec<PathBuf> {
+        let ignore_handler = IgnoreHandler::new(directory).ok();
+        let skip_dirs = self.skip_dirs.clone();
+        
+        jwalk::WalkDir::new(directory)
+            .skip_hidden(false)
+            .follow_links(false)
+            .process_read_dir(move |_, _, _, children| {
+                children.retain(|child| match child {
+                    Ok(entry) => !(entry.file_type().is_dir() && path_utils::is_skipped_dir(&entry.path(), &skip_dirs)),
+                    Err(_) => true,
+                });
+            })
+            .into_iter()
+            // Walk errors were already reported while collecting files
+            .filter_map(Result::ok)
+            .filter(|entry| entry.file_type().is_symlink())
+            .map(|entry| entry.path())
+            .filter(|path| match path.strip_prefix(directory) {
+                Ok(rel_path) => {
+                    !ignore_handler.as_ref().is_some_and(|handler| handler.should_ignore(rel_path, false))
+                        && !self.exclude_patterns.matches(rel_path)
+                }
+                Err(_) => true,
+            })
+            .filter_map(|path| path_utils::canonicalize_link(&path).ok())
+            .collect()
+    }
+    
+    /// Resolve the paths of symlink entries without following the links
+    fn resolve_link_paths(
+        &self,
+        links: HashMap<PathBuf, DatabaseEntry>,
+        base_directory: &Path,
+    ) -> HashMap<PathBuf, DatabaseEntry> {
+        links
+            .into_iter()
+            .map(|(path, entry)| {
+                let mapped = self.path_maps.iter().find_map(|map| map.apply(&path));
+                let absolute_path = path_utils::resolve_path(mapped.as_deref().unwrap_or(&path), base_directory);
+                let resolved = path_utils::canonicalize_link(&absolute_path).unwrap_or(absolute_path);
+                (resolved, entry)
+            })
+            .collect()
+    }
+    
     /// Legacy method for backward compatibility
     fn collect_files(&self, directory: &Path) -> Result<HashSet<PathBuf>, VerifyError> {
         self.collect_files_optimized(directory)
     }
     
     /// Optimized path resolution with caching to reduce canonicalization overhead
-    fn resolve_database_paths_optimized(
+    fn resolve_database_paths_optimized<T: Clone>(
         &self,
-        database: &HashMap<PathBuf, DatabaseEntry>,
+        database: &HashMap<PathBuf, T>,
         base_directory: &Path,
-    ) -> Result<HashMap<PathBuf, DatabaseEntry>, VerifyError> {
+    ) -> Result<HashMap<PathBuf, T>, VerifyError> {
         let mut resolved = HashMap::new();
         let mut canonical_cache: HashMap<PathBuf, PathBuf> = HashMap::new();
         
         for (path, entry) in database {
-            // Use path_utils to resolve the path properly
-            let absolute_path = path_utils::resolve_path(path, base_directory);
+            // Apply --path-map rewrites, then use path_utils to resolve the path properly
+            let mapped = self.path_maps.iter().find_map(|map| map.apply(path));
+            let absolute_path = path_utils::resolve_path(mapped.as_deref().unwrap_or(path), base_directory);
             
             // Check cache first to avoid redundant canonicalization
             let final_path = if let Some(cached) = canonical_cache.get(&absolute_path) {
@@ -443,6 +1332,50 @@ impl VerifyEngine {
     }
 }
 
+/// Compare a file's current permissions against those recorded in the database
+/// 
+/// Returns None when no permissions were recorded, they still match, or the
+/// file's metadata cannot be read.
+fn check_permissions(path: &Path, entry: &DatabaseEntry) -> Option<PermissionChange> {
+    let expected = entry.permissions.as_ref()?;
+    let metadata = fs::metadata(path).ok()?;
+    let actual = DatabaseHandler::format_permissions(&metadata);
+    
+    if &actual == expected {
+        None
+    } else {
+        Some(PermissionChange {
+            path: path.to_path_buf(),
+            expected: expected.clone(),
+            actual,
+        })
+    }
+}
+
+/// Compare a file's current modification time against the one recorded in the database
+/// 
+/// Returns None when no timestamp was recorded, it still matches, or either
+/// timestamp cannot be read.
+fn check_timestamp(path: &Path, entry: &DatabaseEntry) -> Option<TimestampDrift> {
+    let recorded = entry.modified.as_ref()?;
+    let metadata = fs::metadata(path).ok()?;
+    let current = DatabaseHandler::format_modified(&metadata)?;
+    
+    if &current == recorded {
+        return None;
+    }
+    
+    let recorded_time = chrono::DateTime::parse_from_rfc3339(recorded).ok()?;
+    let current_time = chrono::DateTime::parse_from_rfc3339(&current).ok()?;
+    
+    Some(TimestampDrift {
+        path: path.to_path_buf(),
+        recorded: recorded.clone(),
+        current,
+        drift_seconds: (current_time - recorded_time).num_seconds(),
+    })
+}
+
 impl Default for VerifyEngine {
     fn default() -> Self {
         Self::new()
@@ -493,6 +1426,56 @@ mod tests {
         fs::remove_dir_all(test_dir).unwrap();
     }
 
+    #[test]
+    fn test_verify_entries_from_piped_database() {
+        let test_dir = "test_verify_piped";
+        fs::create_dir_all(test_dir).unwrap();
+        create_test_file(&PathBuf::from(format!("{}/file1.txt", test_dir)), b"hello");
+        create_test_file(&PathBuf::from(format!("{}/file2.txt", test_dir)), b"changed");
+        
+        // Database content arrives on a stream, as with `verify -b -`
+        let piped = "2cf24dba5fb0a30e26e83b2ac5b9e29e1b161e5c1fa7425e73043362938b9824  sha256  normal  file1.txt\n\
+                     486ea46224d1bb4fb680f34f7c9ad96a8f24ec88be73ea8e5a6c65260e9cb8a7  sha256  normal  file2.txt\n";
+        let database = DatabaseHandler::read_database_from_with(piped.as_bytes(), Path::new("<stdin>"), false).unwrap();
+        let report = VerifyEngine::new().verify_entries(&database, Path::new(test_dir)).unwrap();
+        
+        assert_eq!(report.matches, 1);
+        assert_eq!(report.mismatches.len(), 1);
+        assert!(report.mismatches[0].path.ends_with("file2.txt"));
+        assert_eq!(report.missing_files.len(), 0);
+        assert_eq!(report.new_files.len(), 0);
+        
+        // Cleanup
+        fs::remove_dir_all(test_dir).unwrap();
+    }
+
+    #[test]
+    fn test_verify_uses_database_algorithm() {
+        let test_dir = "test_verify_db_algorithm";
+        fs::create_dir_all(test_dir).unwrap();
+        create_test_file(&PathBuf::from(format!("{}/file1.txt", test_dir)), b"hello");
+        create_test_file(&PathBuf::from(format!("{}/file2.txt", test_dir)), b"world");
+        
+        // Database scanned with sha512 rather than the blake3 default
+        let computer = HashComputer::new();
+        let db_path = format!("{}/database.txt", test_dir);
+        let mut db_file = fs::File::create(&db_path).unwrap();
+        for name in ["file1.txt", "file2.txt"] {
+            let result = computer.compute_hash(&Path::new(test_dir).join(name), "sha512").unwrap();
+            writeln!(db_file, "{}  sha512  normal  {}", result.hash, name).unwrap();
+        }
+        
+        // No algorithm is given; each entry's algorithm is used for recomputation
+        let engine = VerifyEngine::new();
+        let report = engine.verify(Path::new(&db_path), Path::new(test_dir)).unwrap();
+        
+        assert_eq!(report.matches, 2);
+        assert!(report.mismatches.is_empty());
+        
+        // Cleanup
+        fs::remove_dir_all(test_dir).unwrap();
+    }
+
     #[test]
     fn test_verify_with_mismatch() {
         // Create test directory
@@ -579,6 +1562,43 @@ mod tests {
         fs::remove_dir_all(test_dir).unwrap();
     }
 
+    #[test]
+    fn test_verify_intersection_only() {
+        let test_dir = "test_verify_intersection";
+        fs::create_dir_all(test_dir).unwrap();
+        
+        // kept.txt is in both, extra.txt only on disk, gone.txt only in the database
+        create_test_file(&PathBuf::from(format!("{}/kept.txt", test_dir)), b"hello");
+        create_test_file(&PathBuf::from(format!("{}/extra.txt", test_dir)), b"extra");
+        
+        let db_path = "test_verify_intersection.txt";
+        let mut db_file = fs::File::create(db_path).unwrap();
+        writeln!(db_file, "2cf24dba5fb0a30e26e83b2ac5b9e29e1b161e5c1fa7425e73043362938b9824  sha256  normal  kept.txt").unwrap();
+        writeln!(db_file, "1111111111111111111111111111111111111111111111111111111111111111  sha256  normal  gone.txt").unwrap();
+        drop(db_file);
+        
+        let engine = VerifyEngine::new();
+        let report = engine.verify(Path::new(db_path), Path::new(test_dir)).unwrap();
+        assert!(report.has_issues());
+        
+        // Extra and missing files don't count under intersection-only
+        let report = report.into_intersection();
+        assert_eq!(report.matches, 1);
+        assert!(report.missing_files.is_empty());
+        assert!(report.new_files.is_empty());
+        assert!(!report.has_issues());
+        
+        // A content change in the overlap still does
+        create_test_file(&PathBuf::from(format!("{}/kept.txt", test_dir)), b"tampered");
+        let report = engine.verify(Path::new(db_path), Path::new(test_dir)).unwrap().into_intersection();
+        assert_eq!(report.mismatches.len(), 1);
+        assert!(report.has_issues());
+        
+        // Cleanup
+        fs::remove_file(db_path).unwrap();
+        fs::remove_dir_all(test_dir).unwrap();
+    }
+
     #[test]
     fn test_verify_mixed_results() {
         // Create test directory
@@ -672,4 +1692,623 @@ mod tests {
         // Cleanup
         fs::remove_dir_all(test_dir).unwrap();
     }
+
+    #[test]
+    fn test_read_back_after_scan() {
+        use crate::scan::ScanEngine;
+        
+        let test_dir = "test_verify_read_back";
+        let db_path = "test_verify_read_back.db";
+        create_test_file(&PathBuf::from(format!("{}/file1.txt", test_dir)), b"hello");
+        create_test_file(&PathBuf::from(format!("{}/sub/file2.txt", test_dir)), b"world");
+        
+        let scanner = ScanEngine::with_parallel(false);
+        scanner.scan_directory(Path::new(test_dir), "sha256", Path::new(db_path)).unwrap();
+        
+        // Stable files read back with the hashes just written
+        for parallel in [false, true] {
+            let report = VerifyEngine::with_parallel(parallel)
+                .read_back(Path::new(db_path), Path::new(test_dir))
+                .unwrap();
+            assert!(!report.has_issues());
+            assert_eq!(report.matches, 2);
+        }
+        
+        // A file that reads back differently is reported; files added since the scan are not
+        create_test_file(&PathBuf::from(format!("{}/file1.txt", test_dir)), b"HELLO");
+        create_test_file(&PathBuf::from(format!("{}/file3.txt", test_dir)), b"new");
+        let report = VerifyEngine::new().read_back(Path::new(db_path), Path::new(test_dir)).unwrap();
+        assert_eq!(report.mismatches.len(), 1);
+        assert!(report.mismatches[0].path.ends_with("file1.txt"));
+        assert!(report.new_files.is_empty());
+        
+        // Cleanup
+        fs::remove_file(db_path).unwrap();
+        fs::remove_dir_all(test_dir).unwrap();
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn test_verify_reports_permission_change() {
+        use crate::scan::ScanEngine;
+        use std::os::unix::fs::PermissionsExt;
+        
+        let test_dir = "test_verify_permissions";
+        let db_path = "test_verify_permissions.db";
+        fs::create_dir_all(test_dir).unwrap();
+        
+        create_test_file(&PathBuf::from(format!("{}/file1.txt", test_dir)), b"hello");
+        create_test_file(&PathBuf::from(format!("{}/file2.txt", test_dir)), b"world");
+        fs::set_permissions(format!("{}/file1.txt", test_dir), fs::Permissions::from_mode(0o644)).unwrap();
+        
+        // Scan with permissions recorded
+        let scanner = ScanEngine::with_parallel(false).with_store_permissions(true);
+        scanner.scan_directory(Path::new(test_dir), "sha256", Path::new(db_path)).unwrap();
+        
+        let contents = fs::read_to_string(db_path).unwrap();
+        assert!(contents.contains("perm:0644  file1.txt"));
+        
+        // Change mode only; content stays the same
+        fs::set_permissions(format!("{}/file1.txt", test_dir), fs::Permissions::from_mode(0o600)).unwrap();
+        
+        for parallel in [false, true] {
+            let engine = VerifyEngine::with_parallel(parallel);
+            let report = engine.verify(Path::new(db_path), Path::new(test_dir)).unwrap();
+            
+            assert_eq!(report.matches, 2);
+            assert!(report.mismatches.is_empty());
+            assert!(report.missing_files.is_empty());
+            assert_eq!(report.permission_changes.len(), 1);
+            
+            let change = &report.permission_changes[0];
+            assert!(change.path.ends_with("file1.txt"));
+            assert_eq!(change.expected, "0644");
+            assert_eq!(change.actual, "0600");
+        }
+        
+        // Cleanup
+        fs::remove_file(db_path).unwrap();
+        fs::remove_dir_all(test_dir).unwrap();
+    }
+
+    #[test]
+    fn test_verify_structure_only() {
+        use crate::scan::ScanEngine;
+        use std::time::{Duration, SystemTime};
+        
+        let test_dir = "test_verify_structure_only";
+        let db_path = "test_verify_structure_only.db";
+        fs::create_dir_all(test_dir).unwrap();
+        
+        let file = PathBuf::from(format!("{}/file1.txt", test_dir));
+        let recorded = SystemTime::UNIX_EPOCH + Duration::from_secs(1705312800);
+        create_test_file(&file, b"hello");
+        fs::File::options().write(true).open(&file).unwrap().set_modified(recorded).unwrap();
+        create_test_file(&PathBuf::from(format!("{}/file2.txt", test_dir)), b"world");
+        
+        let scanner = ScanEngine::with_parallel(false).with_structure_only(true);
+        scanner.scan_directory(Path::new(test_dir), "sha256", Path::new(db_path)).unwrap();
+        assert!(fs::read_to_string(db_path).unwrap().contains("  sha256  structure  file1.txt"));
+        
+        // Same size and mtime, different content: not detected, by design
+        create_test_file(&file, b"HELLO");
+        fs::File::options().write(true).open(&file).unwrap().set_modified(recorded).unwrap();
+        
+        for parallel in [false, true] {
+            let report = VerifyEngine::with_parallel(parallel).verify(Path::new(db_path), Path::new(test_dir)).unwrap();
+            assert_eq!(report.matches, 2);
+            assert!(report.mismatches.is_empty());
+        }
+        
+        // A size change is detected even with the mtime restored
+        create_test_file(&file, b"hello, world");
+        fs::File::options().write(true).open(&file).unwrap().set_modified(recorded).unwrap();
+        
+        for parallel in [false, true] {
+            let report = VerifyEngine::with_parallel(parallel).verify(Path::new(db_path), Path::new(test_dir)).unwrap();
+            assert_eq!(report.matches, 1);
+            assert_eq!(report.mismatches.len(), 1);
+            assert!(report.mismatches[0].path.ends_with("file1.txt"));
+        }
+        
+        // Cleanup
+        fs::remove_file(db_path).unwrap();
+        fs::remove_dir_all(test_dir).unwrap();
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn test_verify_detects_repointed_symlink() {
+        use crate::scan::ScanEngine;
+        use std::os::unix::fs::symlink;
+        
+        let test_dir = "test_verify_repointed_symlink";
+        let db_path = "test_verify_repointed_symlink.db";
+        fs::create_dir_all(test_dir).unwrap();
+        create_test_file(&PathBuf::from(format!("{}/a.txt", test_dir)), b"same");
+        create_test_file(&PathBuf::from(format!("{}/b.txt", test_dir)), b"same");
+        let link = PathBuf::from(format!("{}/current", test_dir));
+        symlink("a.txt", &link).unwrap();
+        
+        for parallel in [false, true] {
+            ScanEngine::with_parallel(parallel)
+                .with_hash_symlink_targets(true)
+                .scan_directory(Path::new(test_dir), "sha256", Path::new(db_path))
+                .unwrap();
+            assert!(fs::read_to_string(db_path).unwrap().contains("  symlink  normal  current"));
+            
+            let report = VerifyEngine::with_parallel(parallel).verify(Path::new(db_path), Path::new(test_dir)).unwrap();
+            assert_eq!(report.matches, 3);
+            assert!(report.new_files.is_empty());
+            
+            // Both targets have the same content, so only the target string tells them apart
+            fs::remove_file(&link).unwrap();
+            symlink("b.txt", &link).unwrap();
+            let report = VerifyEngine::with_parallel(parallel).verify(Path::new(db_path), Path::new(test_dir)).unwrap();
+            assert_eq!(report.matches, 2);
+            assert_eq!(report.mismatches.len(), 1);
+            assert!(report.mismatches[0].path.ends_with("current"));
+            
+            fs::remove_file(&link).unwrap();
+            symlink("a.txt", &link).unwrap();
+        }
+        
+        fs::remove_file(db_path).unwrap();
+        fs::remove_dir_all(test_dir).unwrap();
+    }
+    
+    #[cfg(unix)]
+    #[test]
+    fn test_symlinks_follow_scan_filters() {
+        use crate::scan::ScanEngine;
+        use std::os::unix::fs::symlink;
+        
+        let test_dir = "test_verify_symlink_filters";
+        let db_path = "test_verify_symlink_filters.db";
+        fs::create_dir_all(format!("{}/node_modules", test_dir)).unwrap();
+        create_test_file(&PathBuf::from(format!("{}/a.txt", test_dir)), b"target");
+        create_test_file(&PathBuf::from(format!("{}/.hashignore", test_dir)), b"*.ignored\n");
+        symlink("a.txt", format!("{}/current", test_dir)).unwrap();
+        symlink("../a.txt", format!("{}/node_modules/dep", test_dir)).unwrap();
+        symlink("a.txt", format!("{}/old.ignored", test_dir)).unwrap();
+        symlink("a.txt", format!("{}/scratch.tmp", test_dir)).unwrap();
+        let skip_dirs = vec!["node_modules".to_string()];
+        let patterns = ExcludePatterns::parse("*.tmp", false).unwrap();
+        
+        for parallel in [false, true] {
+            ScanEngine::with_parallel(parallel)
+                .with_hash_symlink_targets(true)
+                .with_skip_dirs(skip_dirs.clone())
+                .with_exclude_patterns(patterns.clone())
+                .scan_directory(Path::new(test_dir), "sha256", Path::new(db_path))
+                .unwrap();
+            
+            // Only the link that passes every filter is recorded
+            let database = DatabaseHandler::read_database(Path::new(db_path)).unwrap();
+            let links: Vec<&PathBuf> = database.iter().filter(|(_, entry)| entry.algorithm == SYMLINK_ALGORITHM).map(|(path, _)| path).collect();
+            assert_eq!(links, vec![&PathBuf::from("current")], "parallel = {}", parallel);
+            
+            // With the same filters, the links the scan left out aren't new
+            let report = VerifyEngine::with_parallel(parallel)
+                .with_skip_dirs(skip_dirs.clone())
+                .with_exclude_patterns(patterns.clone())
+                .verify(Path::new(db_path), Path::new(test_dir))
+                .unwrap();
+            assert_eq!(report.matches, 2);
+            assert!(report.new_files.iter().all(|path| path.ends_with(".hashignore")), "{:?}", report.new_files);
+            
+            // .hashignore always applies, but the per-scan filters have to be given again
+            let report = VerifyEngine::with_parallel(parallel).verify(Path::new(db_path), Path::new(test_dir)).unwrap();
+            assert!(report.new_files.iter().any(|path| path.ends_with("node_modules/dep")));
+            assert!(report.new_files.iter().any(|path| path.ends_with("scratch.tmp")));
+            assert!(!report.new_files.iter().any(|path| path.ends_with("old.ignored")));
+        }
+        
+        fs::remove_file(db_path).unwrap();
+        fs::remove_dir_all(test_dir).unwrap();
+    }
+    
+    #[test]
+    fn test_verify_reports_timestamp_drift() {
+        use crate::scan::ScanEngine;
+        use std::time::{Duration, SystemTime};
+        
+        let test_dir = "test_verify_timestamp_drift";
+        let db_path = "test_verify_timestamp_drift.db";
+        fs::create_dir_all(test_dir).unwrap();
+        
+        let file = PathBuf::from(format!("{}/file1.txt", test_dir));
+        create_test_file(&file, b"hello");
+        create_test_file(&PathBuf::from(format!("{}/file2.txt", test_dir)), b"world");
+        
+        let recorded = SystemTime::UNIX_EPOCH + Duration::from_secs(1705312800);
+        fs::File::options().write(true).open(&file).unwrap().set_modified(recorded).unwrap();
+        
+        let scanner = ScanEngine::with_parallel(false).with_timestamp(true);
+        scanner.scan_directory(Path::new(test_dir), "sha256", Path::new(db_path)).unwrap();
+        
+        // Touch one file an hour later without changing its content
+        let touched = recorded + Duration::from_secs(3600);
+        fs::File::options().write(true).open(&file).unwrap().set_modified(touched).unwrap();
+        
+        for parallel in [false, true] {
+            let engine = VerifyEngine::with_parallel(parallel);
+            let report = engine.verify(Path::new(db_path), Path::new(test_dir)).unwrap();
+            
+            assert_eq!(report.matches, 2);
+            assert!(report.mismatches.is_empty());
+            assert_eq!(report.timestamp_drifts.len(), 1);
+            
+            let drift = &report.timestamp_drifts[0];
+            assert!(drift.path.ends_with("file1.txt"));
+            assert_eq!(drift.recorded, "2024-01-15T10:00:00Z");
+            assert_eq!(drift.current, "2024-01-15T11:00:00Z");
+            assert_eq!(drift.drift_seconds, 3600);
+        }
+        
+        // Cleanup
+        fs::remove_file(db_path).unwrap();
+        fs::remove_dir_all(test_dir).unwrap();
+    }
+    
+    #[test]
+    fn test_verify_normalize_unicode() {
+        let test_dir = "test_verify_unicode";
+        // Composed (NFC) name on disk, as on Linux
+        create_test_file(&PathBuf::from(format!("{}/caf\u{e9}/menu.txt", test_dir)), b"hello");
+        
+        // Decomposed (NFD) name in the database, as written on macOS
+        let db_path = "test_verify_unicode.db";
+        fs::write(db_path, "2cf24dba5fb0a30e26e83b2ac5b9e29e1b161e5c1fa7425e73043362938b9824  sha256  normal  cafe\u{301}/menu.txt\n").unwrap();
+        
+        // Byte-for-byte the file is missing and the one on disk is new (normalization-insensitive filesystems match anyway)
+        #[cfg(target_os = "linux")]
+        {
+            let report = VerifyEngine::new().verify(Path::new(db_path), Path::new(test_dir)).unwrap();
+            assert_eq!(report.matches, 0);
+            assert_eq!(report.missing_files.len(), 1);
+            assert_eq!(report.new_files.len(), 1);
+        }
+        
+        for parallel in [false, true] {
+            let engine = VerifyEngine::with_parallel(parallel)
+                .with_unicode_normalization(Some(UnicodeNormalization::Nfc));
+            let report = engine.verify(Path::new(db_path), Path::new(test_dir)).unwrap();
+            
+            assert_eq!(report.matches, 1);
+            assert!(report.missing_files.is_empty());
+            assert!(report.new_files.is_empty());
+        }
+        
+        // Cleanup
+        fs::remove_file(db_path).unwrap();
+        fs::remove_dir_all(test_dir).unwrap();
+    }
+    
+    #[test]
+    fn test_verify_with_path_map() {
+        let test_dir = "test_verify_path_map";
+        create_test_file(&PathBuf::from(format!("{}/new/file1.txt", test_dir)), b"hello");
+        create_test_file(&PathBuf::from(format!("{}/new/sub/file2.txt", test_dir)), b"world");
+        
+        // Paths were recorded under old/, but the tree now lives under new/
+        let db_path = "test_verify_path_map.db";
+        fs::write(db_path, "2cf24dba5fb0a30e26e83b2ac5b9e29e1b161e5c1fa7425e73043362938b9824  sha256  normal  old/file1.txt\n\
+                            486ea46224d1bb4fb680f34f7c9ad96a8f24ec88be73ea8e5a6c65260e9cb8a7  sha256  normal  old/sub/file2.txt\n").unwrap();
+        
+        // Without a map every file is missing and every file on disk is new
+        let report = VerifyEngine::new().verify(Path::new(db_path), Path::new(test_dir)).unwrap();
+        assert_eq!(report.matches, 0);
+        assert_eq!(report.missing_files.len(), 2);
+        
+        for parallel in [false, true] {
+            let engine = VerifyEngine::with_parallel(parallel)
+                .with_path_maps(vec![PathMap::parse("old=new").unwrap()]);
+            let report = engine.verify(Path::new(db_path), Path::new(test_dir)).unwrap();
+            
+            assert_eq!(report.matches, 2);
+            assert!(report.mismatches.is_empty());
+            assert!(report.missing_files.is_empty());
+            assert!(report.new_files.is_empty());
+        }
+        
+        // Cleanup
+        fs::remove_file(db_path).unwrap();
+        fs::remove_dir_all(test_dir).unwrap();
+    }
+    
+    #[test]
+    fn test_path_map_parse_and_apply() {
+        let map = PathMap::parse("/data=/mnt/restore").unwrap();
+        assert_eq!(map.apply(Path::new("/data/a/b.txt")), Some(PathBuf::from("/mnt/restore/a/b.txt")));
+        // Prefixes match whole components only
+        assert_eq!(map.apply(Path::new("/database/b.txt")), None);
+        
+        assert!(PathMap::parse("no-separator").is_err());
+        assert!(PathMap::parse("=new").is_err());
+    }
+
+    
+    #[test]
+    fn test_verify_reports_truncated_hash_as_corrupt() {
+        let test_dir = "test_verify_corrupt_entry";
+        create_test_file(&PathBuf::from(format!("{}/file1.txt", test_dir)), b"hello");
+        create_test_file(&PathBuf::from(format!("{}/file2.txt", test_dir)), b"world");
+        
+        // file2's hash was cut short; file1's is intact
+        let db_path = "test_verify_corrupt_entry.db";
+        fs::write(db_path, "2cf24dba5fb0a30e26e83b2ac5b9e29e1b161e5c1fa7425e73043362938b9824  sha256  normal  file1.txt\n\
+                            486ea46224d1bb4f  sha256  normal  file2.txt\n").unwrap();
+        
+        for parallel in [false, true] {
+            let report = VerifyEngine::with_parallel(parallel)
+                .verify(Path::new(db_path), Path::new(test_dir))
+                .unwrap();
+            
+            assert_eq!(report.matches, 1);
+            assert!(report.mismatches.is_empty());
+            assert!(report.new_files.is_empty());
+            assert_eq!(report.corrupt_entries.len(), 1);
+            let corrupt = &report.corrupt_entries[0];
+            assert!(corrupt.path.ends_with("file2.txt"));
+            assert_eq!(corrupt.hash, "486ea46224d1bb4f");
+            assert_eq!(corrupt.expected_length, 64);
+            assert!(report.has_issues());
+        }
+        
+        // Cleanup
+        fs::remove_file(db_path).unwrap();
+        fs::remove_dir_all(test_dir).unwrap();
+    }
+
+    
+    #[test]
+    fn test_two_pass_reclassifies_transient_mismatch() {
+        let test_dir = "test_verify_two_pass";
+        create_test_file(&PathBuf::from(format!("{}/flaky.txt", test_dir)), b"hello");
+        create_test_file(&PathBuf::from(format!("{}/changed.txt", test_dir)), b"world!");
+        
+        // changed.txt really differs from the recorded hash of "world"
+        let db_path = "test_verify_two_pass.db";
+        fs::write(db_path, "2cf24dba5fb0a30e26e83b2ac5b9e29e1b161e5c1fa7425e73043362938b9824  sha256  normal  flaky.txt\n\
+                            486ea46224d1bb4fb680f34f7c9ad96a8f24ec88be73ea8e5a6c65260e9cb8a7  sha256  normal  changed.txt\n").unwrap();
+        let flaky = PathBuf::from(format!("{}/flaky.txt", test_dir)).canonicalize().unwrap();
+        
+        for parallel in [false, true] {
+            // A single pass trusts the bad first read
+            let engine = VerifyEngine::with_parallel(parallel);
+            engine.flaky_reads.lock().unwrap().insert(flaky.clone());
+            let report = engine.verify(Path::new(db_path), Path::new(test_dir)).unwrap();
+            assert_eq!(report.mismatches.len(), 2);
+            assert!(report.transient_mismatches.is_empty());
+            
+            // Two passes re-read the flaky file and only confirm the real change
+            let engine = VerifyEngine::with_parallel(parallel).with_two_pass(true);
+            engine.flaky_reads.lock().unwrap().insert(flaky.clone());
+            let report = engine.verify(Path::new(db_path), Path::new(test_dir)).unwrap();
+            assert_eq!(report.mismatches.len(), 1);
+            assert!(report.mismatches[0].path.ends_with("changed.txt"));
+            assert_eq!(report.transient_mismatches, vec![flaky.clone()]);
+            assert_eq!(report.matches, 0);
+        }
+        
+        // Cleanup
+        fs::remove_file(db_path).unwrap();
+        fs::remove_dir_all(test_dir).unwrap();
+    }
+    
+    #[test]
+    fn test_verify_sample_is_deterministic() {
+        let database: HashMap<PathBuf, DatabaseEntry> = (0..100)
+            .map(|i| {
+                let path = PathBuf::from(format!("file{:03}.txt", i));
+                let entry = DatabaseEntry {
+                    hash: "00".repeat(32),
+                    algorithm: "sha256".to_string(),
+                    fast_mode: false,
+                    permissions: None,
+                    modified: None,
+                    size: None,
+                    structure_only: false,
+                    fast_v2: false,
+                };
+                (path, entry)
+            })
+            .collect();
+        let sorted_keys = |entries: &HashMap<PathBuf, DatabaseEntry>| {
+            let mut keys: Vec<PathBuf> = entries.keys().cloned().collect();
+            keys.sort();
+            keys
+        };
+        
+        // The same seed picks the same 10% every time
+        let sample = VerifySample::new(10.0, 42).unwrap();
+        let (first, skipped) = sample.select(database.clone());
+        let (second, _) = sample.select(database.clone());
+        assert_eq!(first.len(), 10);
+        assert_eq!(skipped.len(), 90);
+        assert_eq!(sorted_keys(&first), sorted_keys(&second));
+        
+        // A different seed picks a different subset
+        let (other, _) = VerifySample::new(10.0, 7).unwrap().select(database.clone());
+        assert_ne!(sorted_keys(&first), sorted_keys(&other));
+        
+        // Tiny percentages still check one entry; 100% checks all of them
+        assert_eq!(VerifySample::new(0.1, 1).unwrap().select(database.clone()).0.len(), 1);
+        assert_eq!(VerifySample::new(100.0, 1).unwrap().select(database).0.len(), 100);
+        
+        assert!(VerifySample::new(0.0, 1).is_err());
+        assert!(VerifySample::new(150.0, 1).is_err());
+    }
+    
+    #[test]
+    fn test_verify_sample_checks_subset() {
+        let test_dir = "test_verify_sample";
+        let mut database = String::new();
+        for i in 0..20 {
+            create_test_file(&PathBuf::from(format!("{}/file{:02}.txt", test_dir, i)), b"hello");
+            database.push_str(&format!("2cf24dba5fb0a30e26e83b2ac5b9e29e1b161e5c1fa7425e73043362938b9824  sha256  normal  file{:02}.txt\n", i));
+        }
+        let db_path = "test_verify_sample.db";
+        fs::write(db_path, database).unwrap();
+        
+        for parallel in [false, true] {
+            let engine = VerifyEngine::with_parallel(parallel)
+                .with_sample(Some(VerifySample::new(25.0, 3).unwrap()));
+            let report = engine.verify(Path::new(db_path), Path::new(test_dir)).unwrap();
+            
+            // Unsampled files are neither checked nor reported as new
+            assert_eq!(report.matches, 5);
+            assert!(report.new_files.is_empty());
+            assert!(!report.has_issues());
+            
+            let sample = report.sample.unwrap();
+            assert_eq!(sample.sampled, 5);
+            assert_eq!(sample.total_entries, 20);
+            assert_eq!(sample.failures, 0);
+            assert_eq!(sample.estimated_failures, 0.0);
+            assert!(sample.max_failure_rate > 0.0 && sample.max_failure_rate < 1.0);
+        }
+        
+        // Cleanup
+        fs::remove_file(db_path).unwrap();
+        fs::remove_dir_all(test_dir).unwrap();
+    }
+    
+    #[test]
+    fn test_verify_single_file() {
+        let test_dir = "test_verify_single_file";
+        create_test_file(&PathBuf::from(format!("{}/critical.txt", test_dir)), b"hello");
+        create_test_file(&PathBuf::from(format!("{}/other.txt", test_dir)), b"changed since the scan");
+        create_test_file(&PathBuf::from(format!("{}/unlisted.txt", test_dir)), b"new");
+        
+        let hello = "2cf24dba5fb0a30e26e83b2ac5b9e29e1b161e5c1fa7425e73043362938b9824";
+        let db_path = "test_verify_single_file.db";
+        fs::write(db_path, format!(
+            "{hello}  sha256  normal  critical.txt\n{hello}  sha256  normal  other.txt\n{hello}  sha256  normal  gone.txt\n"
+        )).unwrap();
+        
+        // Only the requested file is checked; the rest of the tree isn't reported
+        let engine = VerifyEngine::new().with_progress(false);
+        let critical = PathBuf::from(format!("{}/critical.txt", test_dir));
+        let report = engine.verify_file(Path::new(db_path), Path::new(test_dir), &critical).unwrap();
+        assert_eq!(report.matches, 1);
+        assert!(!report.has_issues());
+        
+        let report = engine
+            .verify_file(Path::new(db_path), Path::new(test_dir), &PathBuf::from(format!("{}/other.txt", test_dir)))
+            .unwrap();
+        assert_eq!(report.mismatches.len(), 1);
+        assert!(report.new_files.is_empty() && report.missing_files.is_empty());
+        
+        // Listed but deleted files are reported missing
+        let report = engine
+            .verify_file(Path::new(db_path), Path::new(test_dir), &PathBuf::from(format!("{}/gone.txt", test_dir)))
+            .unwrap();
+        assert_eq!(report.missing_files.len(), 1);
+        
+        // Files without an entry are an error
+        let unlisted = PathBuf::from(format!("{}/unlisted.txt", test_dir));
+        assert!(engine.verify_file(Path::new(db_path), Path::new(test_dir), &unlisted).is_err());
+        
+        // Cleanup
+        fs::remove_file(db_path).unwrap();
+        fs::remove_dir_all(test_dir).unwrap();
+    }
+    
+    #[test]
+    fn test_verify_merged_shards() {
+        let test_dir = "test_verify_merged_shards";
+        create_test_file(&PathBuf::from(format!("{}/a.txt", test_dir)), b"hello");
+        create_test_file(&PathBuf::from(format!("{}/sub/b.txt", test_dir)), b"hello");
+        
+        // Two shards with disjoint paths, the second stored inside the tree
+        let hello = "2cf24dba5fb0a30e26e83b2ac5b9e29e1b161e5c1fa7425e73043362938b9824";
+        let shard1 = PathBuf::from("test_verify_merged_shards.db.1");
+        let shard2 = PathBuf::from(format!("{}/shard.db.2", test_dir));
+        fs::write(&shard1, format!("{hello}  sha256  normal  a.txt\n")).unwrap();
+        fs::write(&shard2, format!("{hello}  sha256  normal  sub/b.txt\n")).unwrap();
+        
+        // On its own, the first shard reports the other shard's file (and the shard itself) as new
+        let engine = VerifyEngine::new().with_progress(false);
+        let report = engine.verify(&shard1, Path::new(test_dir)).unwrap();
+        assert_eq!(report.new_files.len(), 2);
+        
+        // Merged, they are one database: everything matches and neither shard counts as new
+        let report = engine.verify_merged(&[shard1.clone(), shard2.clone()], Path::new(test_dir)).unwrap();
+        assert_eq!(report.matches, 2);
+        assert!(!report.has_issues());
+        assert!(report.new_files.is_empty() && report.missing_files.is_empty());
+        
+        // Cleanup
+        fs::remove_file(&shard1).unwrap();
+        fs::remove_dir_all(test_dir).unwrap();
+    }
+    
+    #[test]
+    fn test_verify_strict_algorithm_mismatch() {
+        let test_dir = "test_verify_strict_algorithm";
+        create_test_file(&PathBuf::from(format!("{}/file.txt", test_dir)), b"hello");
+        
+        let db_path = "test_verify_strict_algorithm.db";
+        fs::write(db_path, "5d41402abc4b2a76b9719d911017c592  md5  normal  file.txt\n").unwrap();
+        
+        // With --strict the mismatch is an error naming both algorithms
+        let engine = VerifyEngine::new().with_progress(false).with_expected_algorithm(Some("sha256".to_string()), true);
+        let error = engine.verify(Path::new(db_path), Path::new(test_dir)).unwrap_err().to_string();
+        assert!(error.contains("md5") && error.contains("sha256"), "{}", error);
+        
+        // Without it the entries are still checked with their own algorithm
+        let engine = VerifyEngine::new().with_progress(false).with_expected_algorithm(Some("sha256".to_string()), false);
+        let report = engine.verify(Path::new(db_path), Path::new(test_dir)).unwrap();
+        assert_eq!(report.matches, 1);
+        
+        // A matching algorithm passes --strict
+        let engine = VerifyEngine::new().with_progress(false).with_expected_algorithm(Some("md5".to_string()), true);
+        assert!(engine.verify(Path::new(db_path), Path::new(test_dir)).is_ok());
+        
+        // Cleanup
+        fs::remove_file(db_path).unwrap();
+        fs::remove_dir_all(test_dir).unwrap();
+    }
+    
+    #[test]
+    fn test_compare_sizes_flags_size_change_without_hashing() {
+        let test_dir = "test_verify_compare_sizes";
+        create_test_file(&PathBuf::from(format!("{}/same.txt", test_dir)), b"hello");
+        create_test_file(&PathBuf::from(format!("{}/grown.txt", test_dir)), b"hello, world");
+        create_test_file(&PathBuf::from(format!("{}/new.txt", test_dir)), b"new");
+        
+        // The recorded hashes are bogus, so any hashing would report mismatches
+        let bogus = "0".repeat(64);
+        let db_path = "test_verify_compare_sizes.tsv";
+        fs::write(db_path, format!(
+            "{bogus}\tsha256\t5\tsame.txt\n{bogus}\tsha256\t5\tgrown.txt\n{bogus}\tsha256\t7\tgone.txt\n"
+        )).unwrap();
+        
+        let engine = VerifyEngine::new().with_compare_sizes(true);
+        let report = engine.verify(Path::new(db_path), Path::new(test_dir)).unwrap();
+        
+        assert!(report.sizes_only);
+        assert_eq!(report.matches, 1);
+        assert!(report.mismatches.is_empty());
+        assert_eq!(report.size_mismatches.len(), 1);
+        assert!(report.size_mismatches[0].path.ends_with("grown.txt"));
+        assert_eq!((report.size_mismatches[0].expected, report.size_mismatches[0].actual), (5, 12));
+        assert_eq!(report.missing_files.len(), 1);
+        assert!(report.missing_files[0].ends_with("gone.txt"));
+        assert_eq!(report.new_files.len(), 1);
+        assert!(report.new_files[0].ends_with("new.txt"));
+        assert!(report.has_issues());
+        
+        // Formats without a size column can't be size-checked
+        let standard_db = "test_verify_compare_sizes.txt";
+        fs::write(standard_db, format!("{bogus}  sha256  normal  same.txt\n")).unwrap();
+        assert!(engine.verify(Path::new(standard_db), Path::new(test_dir)).is_err());
+        
+        // Cleanup
+        fs::remove_file(db_path).unwrap();
+        fs::remove_file(standard_db).unwrap();
+        fs::remove_dir_all(test_dir).unwrap();
+    }
 }
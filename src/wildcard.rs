@@ -1,7 +1,7 @@
 // Wildcard pattern expansion module
 // Handles cross-platform wildcard pattern matching using glob
 
-use std::path::PathBuf;
+use std::path::{Path, PathBuf};
 use crate::error::HashUtilityError;
 
 /// Expand a wildcard pattern into a list of matching file paths
@@ -11,26 +11,64 @@ use crate::error::HashUtilityError;
 /// - `file?.bin` - matches file1.bin, fileA.bin, etc.
 /// - `[abc]*.jpg` - matches files starting with a, b, or c
 /// - `data/*/hashes` - matches hashes file in any subdirectory of data
+/// - `img.{jpg,png}` - matches img.jpg and img.png (brace groups may be nested)
+/// - `data/**/logs` - matches logs at any depth below data, including data/logs
+/// 
+/// `**` follows standard globstar semantics: it must be a whole path component
+/// and matches zero or more directories. `*` never crosses a path separator.
 /// 
 /// # Arguments
 /// * `pattern` - The wildcard pattern to expand
+/// * `ignore_case` - Match wildcard patterns case-insensitively (e.g., `*.TXT` matches `a.txt`)
 /// 
 /// # Returns
 /// A vector of matching file paths, sorted alphabetically
 /// 
 /// # Errors
 /// Returns an error if the pattern is invalid or no matches are found
-pub fn expand_pattern(pattern: &str) -> Result<Vec<PathBuf>, HashUtilityError> {
+pub fn expand_pattern(pattern: &str, ignore_case: bool) -> Result<Vec<PathBuf>, HashUtilityError> {
+    // Expand brace groups first; each alternative is then matched on its own
+    let patterns = expand_braces(pattern);
+    
     // Check if the pattern contains wildcard characters
-    if !contains_wildcard(pattern) {
+    if patterns.len() == 1 && !contains_wildcard(pattern) {
         // Not a wildcard pattern, return as-is
         return Ok(vec![PathBuf::from(pattern)]);
     }
     
-    // Use glob to expand the pattern
     let mut matches = Vec::new();
     
-    match glob::glob(pattern) {
+    for alternative in &patterns {
+        if contains_wildcard(alternative) {
+            glob_into(alternative, ignore_case, &mut matches)?;
+        } else if Path::new(alternative).exists() {
+            // Literal alternatives from brace expansion only count if they exist
+            matches.push(PathBuf::from(alternative));
+        }
+    }
+    
+    // Check if any matches were found
+    if matches.is_empty() {
+        return Err(HashUtilityError::InvalidArguments {
+            message: format!("No files match pattern '{}'", pattern),
+        });
+    }
+    
+    // Sort matches for consistent ordering; overlapping alternatives may repeat paths
+    matches.sort();
+    matches.dedup();
+    
+    Ok(matches)
+}
+
+/// Use glob to expand a single pattern, appending the results to `matches`
+fn glob_into(pattern: &str, ignore_case: bool, matches: &mut Vec<PathBuf>) -> Result<(), HashUtilityError> {
+    let options = glob::MatchOptions {
+        case_sensitive: !ignore_case,
+        ..glob::MatchOptions::new()
+    };
+    
+    match glob::glob_with(pattern, options) {
         Ok(paths) => {
             for entry in paths {
                 match entry {
@@ -50,17 +88,135 @@ pub fn expand_pattern(pattern: &str) -> Result<Vec<PathBuf>, HashUtilityError> {
         }
     }
     
-    // Check if any matches were found
-    if matches.is_empty() {
-        return Err(HashUtilityError::InvalidArguments {
-            message: format!("No files match pattern '{}'", pattern),
-        });
+    Ok(())
+}
+
+/// Glob patterns that exclude files from a scan, read from a file (`scan --exclude-from`)
+/// 
+/// Patterns without a `/` match a file's name at any depth (`*.tmp`); patterns
+/// with one match its path relative to the scanned directory (`build/**/*.o`).
+/// Brace groups are expanded as in [`expand_pattern`].
+#[derive(Debug, Clone, Default)]
+pub struct ExcludePatterns {
+    name_patterns: Vec<glob::Pattern>,
+    path_patterns: Vec<glob::Pattern>,
+    case_sensitive: bool,
+}
+
+impl ExcludePatterns {
+    /// Read patterns from a file, one per line; blank lines and lines starting with `#` are skipped
+    pub fn from_file(path: &Path, ignore_case: bool) -> Result<Self, HashUtilityError> {
+        let content = std::fs::read_to_string(path)
+            .map_err(|e| HashUtilityError::from_io_error(e, "reading", Some(path.to_path_buf())))?;
+        Self::parse(&content, ignore_case)
     }
     
-    // Sort matches for consistent ordering
-    matches.sort();
+    /// Parse patterns from the contents of an exclude file
+    pub fn parse(content: &str, ignore_case: bool) -> Result<Self, HashUtilityError> {
+        let mut patterns = Self {
+            case_sensitive: !ignore_case,
+            ..Self::default()
+        };
+        
+        for line in content.lines().map(str::trim) {
+            if line.is_empty() || line.starts_with('#') {
+                continue;
+            }
+            for alternative in expand_braces(line) {
+                let pattern = glob::Pattern::new(&alternative).map_err(|e| HashUtilityError::InvalidArguments {
+                    message: format!("Invalid exclude pattern '{}': {}", line, e),
+                })?;
+                if alternative.contains('/') {
+                    patterns.path_patterns.push(pattern);
+                } else {
+                    patterns.name_patterns.push(pattern);
+                }
+            }
+        }
+        
+        Ok(patterns)
+    }
     
-    Ok(matches)
+    /// Whether a file, given by its path relative to the scanned directory, is excluded
+    pub fn matches(&self, relative: &Path) -> bool {
+        let options = glob::MatchOptions {
+            case_sensitive: self.case_sensitive,
+            require_literal_separator: true,
+            require_literal_leading_dot: false,
+        };
+        
+        let name_matches = relative.file_name().is_some_and(|name| {
+            let name = name.to_string_lossy();
+            self.name_patterns.iter().any(|pattern| pattern.matches_with(&name, options))
+        });
+        
+        // Compare with forward slashes so patterns work the same on every platform
+        name_matches || (!self.path_patterns.is_empty() && {
+            let relative = relative.to_string_lossy().replace('\\', "/");
+            self.path_patterns.iter().any(|pattern| pattern.matches_with(&relative, options))
+        })
+    }
+}
+
+/// Whether patterns should match case-insensitively when `--ignore-case` isn't given
+/// 
+/// True on Windows and macOS, whose default filesystems are case-insensitive.
+pub fn case_insensitive_by_default() -> bool {
+    cfg!(any(windows, target_os = "macos"))
+}
+
+/// Expand brace groups like `{a,b,c}` into one pattern per alternative
+/// 
+/// Follows shell conventions: groups may be nested (`a{b,c{d,e}}`), empty
+/// alternatives are kept (`file{,.bak}`), and groups without a comma (`{}`,
+/// `{a}`) or without a closing brace are left as literal text.
+fn expand_braces(pattern: &str) -> Vec<String> {
+    for (open, c) in pattern.char_indices() {
+        if c != '{' {
+            continue;
+        }
+        
+        // Find the matching close brace and the commas at this nesting level
+        let mut depth = 0;
+        let mut commas = Vec::new();
+        let mut close = None;
+        for (i, c) in pattern[open..].char_indices() {
+            let i = open + i;
+            match c {
+                '{' => depth += 1,
+                '}' => {
+                    depth -= 1;
+                    if depth == 0 {
+                        close = Some(i);
+                        break;
+                    }
+                }
+                ',' if depth == 1 => commas.push(i),
+                _ => {}
+            }
+        }
+        
+        // Unbalanced or comma-less groups are literal; keep looking for a later group
+        let close = match close {
+            Some(close) if !commas.is_empty() => close,
+            _ => continue,
+        };
+        
+        let prefix = &pattern[..open];
+        let suffix = &pattern[close + 1..];
+        
+        let mut bounds = vec![open];
+        bounds.extend(&commas);
+        bounds.push(close);
+        
+        // Each alternative may itself contain groups, as may the suffix
+        return bounds
+            .windows(2)
+            .flat_map(|w| expand_braces(&format!("{}{}{}", prefix, &pattern[w[0] + 1..w[1]], suffix)))
+            .collect();
+    }
+    
+    vec![pattern.to_string()]
 }
 
 /// Check if a string contains wildcard characters
@@ -84,16 +240,39 @@ mod tests {
         assert!(!contains_wildcard("path/to/file.bin"));
     }
     
+    #[test]
+    fn test_exclude_patterns() {
+        let patterns = ExcludePatterns::parse("# scratch files\n*.tmp\n\n  build/**/*.{o,a}  \n", false).unwrap();
+        
+        // Name patterns match at any depth
+        assert!(patterns.matches(Path::new("a.tmp")));
+        assert!(patterns.matches(Path::new("deep/dir/b.tmp")));
+        assert!(!patterns.matches(Path::new("a.tmp.keep")));
+        
+        // Path patterns match from the scanned directory
+        assert!(patterns.matches(Path::new("build/x/y.o")));
+        assert!(patterns.matches(Path::new("build/lib.a")));
+        assert!(!patterns.matches(Path::new("src/build/lib.a")));
+        
+        // Comments aren't patterns
+        assert!(!patterns.matches(Path::new("# scratch files")));
+        
+        assert!(!patterns.matches(Path::new("A.TMP")));
+        assert!(ExcludePatterns::parse("*.tmp", true).unwrap().matches(Path::new("A.TMP")));
+        
+        assert!(ExcludePatterns::parse("[unclosed", false).is_err());
+    }
+    
     #[test]
     fn test_expand_pattern_no_wildcard() {
-        let result = expand_pattern("file.txt").unwrap();
+        let result = expand_pattern("file.txt", false).unwrap();
         assert_eq!(result.len(), 1);
         assert_eq!(result[0], PathBuf::from("file.txt"));
     }
     
     #[test]
     fn test_expand_pattern_no_matches() {
-        let result = expand_pattern("nonexistent*.txt");
+        let result = expand_pattern("nonexistent*.txt", false);
         assert!(result.is_err());
         
         if let Err(HashUtilityError::InvalidArguments { message }) = result {
@@ -121,7 +300,7 @@ mod tests {
         
         // Test wildcard expansion
         let pattern = temp_dir.join("test_wildcard_*.txt").to_string_lossy().to_string();
-        let result = expand_pattern(&pattern).unwrap();
+        let result = expand_pattern(&pattern, false).unwrap();
         
         assert_eq!(result.len(), 3);
         assert!(result.iter().all(|p| p.to_string_lossy().contains("test_wildcard_")));
@@ -150,7 +329,7 @@ mod tests {
         
         // Test wildcard expansion with ?
         let pattern = temp_dir.join("test_q?.bin").to_string_lossy().to_string();
-        let result = expand_pattern(&pattern).unwrap();
+        let result = expand_pattern(&pattern, false).unwrap();
         
         assert_eq!(result.len(), 3);
         
@@ -159,4 +338,112 @@ mod tests {
             let _ = fs::remove_file(file);
         }
     }
+    
+    #[test]
+    fn test_expand_braces_single_group() {
+        assert_eq!(expand_braces("img.{jpg,png}"), vec!["img.jpg", "img.png"]);
+        assert_eq!(expand_braces("file{,.bak}"), vec!["file", "file.bak"]);
+        assert_eq!(expand_braces("plain.txt"), vec!["plain.txt"]);
+    }
+    
+    #[test]
+    fn test_expand_braces_multiple_groups() {
+        assert_eq!(
+            expand_braces("{a,b}/{x,y}.txt"),
+            vec!["a/x.txt", "a/y.txt", "b/x.txt", "b/y.txt"]
+        );
+    }
+    
+    #[test]
+    fn test_expand_braces_nested_groups() {
+        assert_eq!(expand_braces("a{b,c{d,e}}f"), vec!["abf", "acdf", "acef"]);
+        // A comma-less outer group stays literal while the inner one expands
+        assert_eq!(expand_braces("{x{1,2}}"), vec!["{x1}", "{x2}"]);
+    }
+    
+    #[test]
+    fn test_expand_braces_literal_groups() {
+        assert_eq!(expand_braces("{}"), vec!["{}"]);
+        assert_eq!(expand_braces("{a}.txt"), vec!["{a}.txt"]);
+        assert_eq!(expand_braces("{a,b"), vec!["{a,b"]);
+        assert_eq!(expand_braces("a}b{c,d}"), vec!["a}bc", "a}bd"]);
+    }
+    
+    #[test]
+    fn test_expand_pattern_with_braces() {
+        let temp_dir = std::env::temp_dir();
+        let test_files = vec![
+            temp_dir.join("test_brace_img.jpg"),
+            temp_dir.join("test_brace_img.png"),
+            temp_dir.join("test_brace_img.gif"),
+        ];
+        
+        for file in &test_files {
+            fs::File::create(file).unwrap();
+        }
+        
+        // Literal alternatives, one of which doesn't exist
+        let pattern = temp_dir.join("test_brace_img.{jpg,png,bmp}").to_string_lossy().to_string();
+        let result = expand_pattern(&pattern, false).unwrap();
+        assert_eq!(result, vec![test_files[0].clone(), test_files[1].clone()]);
+        
+        // Braces combined with wildcards; overlapping matches are reported once
+        let pattern = temp_dir.join("test_brace_*.{jpg,j*}").to_string_lossy().to_string();
+        let result = expand_pattern(&pattern, false).unwrap();
+        assert_eq!(result, vec![test_files[0].clone()]);
+        
+        for file in &test_files {
+            let _ = fs::remove_file(file);
+        }
+    }
+    
+    #[test]
+    fn test_expand_pattern_globstar() {
+        let root = std::env::temp_dir().join("test_wildcard_globstar");
+        let _ = fs::remove_dir_all(&root);
+        fs::create_dir_all(root.join("a/x/y")).unwrap();
+        fs::create_dir_all(root.join("c")).unwrap();
+        
+        let shallow = root.join("a/b.txt");
+        let deep = root.join("a/x/y/b.txt");
+        let outside = root.join("c/b.txt");
+        let other = root.join("a/x/other.txt");
+        for file in [&shallow, &deep, &outside, &other] {
+            fs::File::create(file).unwrap();
+        }
+        
+        // `**` matches zero or more directories
+        let pattern = root.join("a/**/b.txt").to_string_lossy().to_string();
+        let result = expand_pattern(&pattern, false).unwrap();
+        assert_eq!(result, vec![shallow.clone(), deep.clone()]);
+        
+        // A single `*` does not cross directory levels
+        let pattern = root.join("a/*/b.txt").to_string_lossy().to_string();
+        assert!(expand_pattern(&pattern, false).is_err());
+        
+        // `**` must be a whole path component
+        let pattern = root.join("a/**b.txt").to_string_lossy().to_string();
+        assert!(matches!(expand_pattern(&pattern, false), Err(HashUtilityError::InvalidArguments { .. })));
+        
+        fs::remove_dir_all(&root).unwrap();
+    }
+    
+    #[test]
+    fn test_expand_pattern_ignore_case() {
+        let temp_dir = std::env::temp_dir();
+        let file = temp_dir.join("test_case_match_a.txt");
+        fs::File::create(&file).unwrap();
+        
+        let pattern = temp_dir.join("test_case_match_*.TXT").to_string_lossy().to_string();
+        
+        // Matches regardless of case with the flag
+        let result = expand_pattern(&pattern, true).unwrap();
+        assert_eq!(result, vec![file.clone()]);
+        
+        // Case-sensitive without it
+        #[cfg(target_os = "linux")]
+        assert!(expand_pattern(&pattern, false).is_err());
+        
+        let _ = fs::remove_file(&file);
+    }
 }
@@ -0,0 +1,81 @@
+// Tests for coreutils format databases (`hash scan --format coreutils`)
+// Databases are checked by the crate's own --check and, where installed, by sha256sum -c
+
+use std::fs;
+use std::path::Path;
+use std::process::Command;
+
+fn hash_command() -> Command {
+    Command::new(env!("CARGO_BIN_EXE_hash"))
+}
+
+/// Scan `dir` into `database` in coreutils format and return the database contents
+fn scan_coreutils(dir: &str, database: &str, extra_args: &[&str]) -> String {
+    let output = hash_command()
+        .args(["scan", "-d", dir, "-b", database, "-a", "sha256", "--format", "coreutils", "--hdd"])
+        .args(extra_args)
+        .output()
+        .expect("Failed to execute scan command");
+    assert!(output.status.success(), "scan failed: {}", String::from_utf8_lossy(&output.stderr));
+    fs::read_to_string(database).unwrap()
+}
+
+#[test]
+fn test_coreutils_format_round_trip() {
+    let test_dir = "test_coreutils_format";
+    fs::create_dir_all(format!("{}/sub", test_dir)).unwrap();
+    fs::write(format!("{}/hello.txt", test_dir), b"hello world").unwrap();
+    fs::write(format!("{}/sub/data.bin", test_dir), b"\x00\x01\x02").unwrap();
+    
+    let text_db = "test_coreutils_format_text.sha256";
+    let binary_db = "test_coreutils_format_binary.sha256";
+    
+    // Text marker by default, binary marker on request; no algorithm or mode columns
+    let text = scan_coreutils(test_dir, text_db, &[]);
+    assert!(text.contains("b94d27b9934d3e08a52e52d7da7dabfac484efe37a5380ee9088f7ace2efcde9  hello.txt\n"));
+    let binary = scan_coreutils(test_dir, binary_db, &["--binary"]);
+    assert!(binary.contains("b94d27b9934d3e08a52e52d7da7dabfac484efe37a5380ee9088f7ace2efcde9 *hello.txt\n"));
+    
+    // Paths are relative to the scanned directory, so checks run from there
+    for database in [text_db, binary_db] {
+        let database = Path::new(database).canonicalize().unwrap();
+        
+        let output = hash_command()
+            .args(["--check", database.to_str().unwrap(), "-a", "sha256"])
+            .current_dir(test_dir)
+            .output()
+            .expect("Failed to execute check command");
+        assert!(output.status.success(), "--check failed: {}", String::from_utf8_lossy(&output.stdout));
+        assert!(String::from_utf8_lossy(&output.stdout).contains("hello.txt: OK"));
+        
+        // The system tool accepts the same file, where it's installed
+        if let Ok(output) = Command::new("sha256sum").arg("-c").arg(&database).current_dir(test_dir).output() {
+            assert!(output.status.success(), "sha256sum -c failed: {}", String::from_utf8_lossy(&output.stdout));
+        }
+    }
+    
+    fs::remove_file(text_db).unwrap();
+    fs::remove_file(binary_db).unwrap();
+    fs::remove_dir_all(test_dir).unwrap();
+}
+
+#[test]
+fn test_fast_rejected_without_mode_column() {
+    let test_dir = "test_coreutils_format_fast";
+    fs::create_dir_all(test_dir).unwrap();
+    fs::write(format!("{}/hello.txt", test_dir), b"hello world").unwrap();
+    
+    // Neither coreutils nor tsv lines can mark an entry as sampled
+    for format in ["coreutils", "tsv"] {
+        let database = format!("test_coreutils_format_fast.{}", format);
+        let output = hash_command()
+            .args(["scan", "-d", test_dir, "-b", &database, "--format", format, "--fast", "--hdd"])
+            .output()
+            .expect("Failed to execute scan command");
+        assert!(!output.status.success());
+        assert!(String::from_utf8_lossy(&output.stderr).contains(&format!("--fast is not supported with the {} format", format)));
+        assert!(!Path::new(&database).exists());
+    }
+    
+    fs::remove_dir_all(test_dir).unwrap();
+}
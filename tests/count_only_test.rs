@@ -0,0 +1,42 @@
+// Tests for --count-only on scan and dedup
+// Counts a tree with ignored files and checks nothing is hashed or written
+
+use std::fs;
+use std::path::Path;
+use std::process::Command;
+
+fn run_hash(args: &[&str]) -> serde_json::Value {
+    let output = Command::new(env!("CARGO_BIN_EXE_hash"))
+        .args(args)
+        .output()
+        .expect("Failed to execute hash command");
+    assert!(output.status.success(), "hash failed: {}", String::from_utf8_lossy(&output.stderr));
+    // Loading .hashignore prints a note ahead of the JSON
+    let stdout = String::from_utf8(output.stdout).unwrap();
+    serde_json::from_str(&stdout[stdout.find('{').unwrap()..]).unwrap()
+}
+
+#[test]
+fn test_count_only_counts_non_ignored_files() {
+    let test_dir = "test_count_only";
+    let database = "test_count_only.db";
+    fs::create_dir_all(format!("{}/sub", test_dir)).unwrap();
+    fs::create_dir_all(format!("{}/skipped", test_dir)).unwrap();
+    fs::write(format!("{}/a.txt", test_dir), b"hello").unwrap();
+    fs::write(format!("{}/sub/b.txt", test_dir), b"world!").unwrap();
+    fs::write(format!("{}/build.log", test_dir), b"ignored by .hashignore").unwrap();
+    fs::write(format!("{}/skipped/c.txt", test_dir), b"pruned by --skip-dir").unwrap();
+    fs::write(format!("{}/.hashignore", test_dir), b"*.log\n").unwrap();
+
+    // Only a.txt and sub/b.txt remain (.hashignore itself isn't hashed either)
+    let scan = run_hash(&["scan", "-d", test_dir, "-b", database, "--skip-dir", "skipped", "--count-only", "--json"]);
+    assert_eq!(scan["files"], 2);
+    assert_eq!(scan["total_bytes"], 11);
+    assert!(!Path::new(database).exists());
+
+    let dedup = run_hash(&["dedup", "-d", test_dir, "--skip-dir", "skipped", "--count-only", "--json"]);
+    assert_eq!(dedup["files"], 2);
+    assert_eq!(dedup["total_bytes"], 11);
+
+    fs::remove_dir_all(test_dir).unwrap();
+}
@@ -0,0 +1,69 @@
+// Tests for the equal subcommand
+// Compares files and text and checks the exit status reports whether they match
+
+use std::fs;
+use std::process::{Command, Output};
+
+fn run_equal(args: &[&str]) -> Output {
+    Command::new(env!("CARGO_BIN_EXE_hash"))
+        .arg("equal")
+        .args(args)
+        .output()
+        .expect("Failed to execute hash command")
+}
+
+#[test]
+fn test_equal_files() {
+    let a = "test_equal_files_a.txt";
+    let b = "test_equal_files_b.txt";
+    fs::write(a, b"same content").unwrap();
+    fs::write(b, b"same content").unwrap();
+
+    let output = run_equal(&[a, b, "-a", "sha256"]);
+    assert_eq!(output.status.code(), Some(0));
+    assert!(String::from_utf8_lossy(&output.stdout).starts_with("Identical"));
+
+    // A file named twice is trivially equal
+    let output = run_equal(&[a, a]);
+    assert_eq!(output.status.code(), Some(0));
+    assert!(String::from_utf8_lossy(&output.stdout).contains("same file"));
+
+    fs::remove_file(a).unwrap();
+    fs::remove_file(b).unwrap();
+}
+
+#[test]
+fn test_unequal_files() {
+    let a = "test_unequal_files_a.txt";
+    let b = "test_unequal_files_b.txt";
+    fs::write(a, b"one").unwrap();
+    fs::write(b, b"two").unwrap();
+
+    // Both hashes are listed so the difference can be seen
+    let output = run_equal(&[a, b]);
+    assert_eq!(output.status.code(), Some(1));
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    assert!(stdout.starts_with("Different"));
+    assert!(stdout.contains(a) && stdout.contains(b));
+
+    fs::remove_file(a).unwrap();
+    fs::remove_file(b).unwrap();
+}
+
+#[test]
+fn test_equal_file_and_text() {
+    let file = "test_equal_file_and_text.txt";
+    fs::write(file, b"hello world").unwrap();
+
+    assert_eq!(run_equal(&[file, "--text", "hello world", "-a", "md5"]).status.code(), Some(0));
+    assert_eq!(run_equal(&[file, "--text", "hello world!"]).status.code(), Some(1));
+
+    // Two text inputs need no file at all; three inputs are rejected
+    assert_eq!(run_equal(&["-t", "abc", "-t", "abc"]).status.code(), Some(0));
+    assert_eq!(run_equal(&[file, file, "--text", "x"]).status.code(), Some(2));
+
+    // An unreadable input is an error, not a difference
+    assert_eq!(run_equal(&[file, "test_equal_missing_input.txt"]).status.code(), Some(2));
+
+    fs::remove_file(file).unwrap();
+}
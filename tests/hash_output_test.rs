@@ -0,0 +1,51 @@
+// Tests for the plain-text output of the hash command
+// Runs the built binary and compares its stdout byte-for-byte
+
+use std::fs;
+use std::process::Command;
+
+/// sha256 of "hello world"
+const HELLO_SHA256: &str = "b94d27b9934d3e08a52e52d7da7dabfac484efe37a5380ee9088f7ace2efcde9";
+
+fn run_hash(args: &[&str]) -> Vec<u8> {
+    let output = Command::new(env!("CARGO_BIN_EXE_hash"))
+        .args(args)
+        .output()
+        .expect("Failed to execute hash command");
+    assert!(output.status.success(), "hash failed: {}", String::from_utf8_lossy(&output.stderr));
+    output.stdout
+}
+
+#[test]
+fn test_trailing_newline_toggle() {
+    let test_file = "test_hash_output_newline.txt";
+    fs::write(test_file, b"hello world").unwrap();
+    
+    // Default output is the same bytes sha256sum prints
+    let default_output = run_hash(&[test_file, "-a", "sha256"]);
+    assert_eq!(default_output, format!("{}  {}\n", HELLO_SHA256, test_file).into_bytes());
+    
+    // The flag drops exactly the final newline
+    let trimmed_output = run_hash(&[test_file, "-a", "sha256", "--no-trailing-newline"]);
+    assert_eq!(trimmed_output.len() + 1, default_output.len());
+    assert_eq!(trimmed_output, default_output[..default_output.len() - 1]);
+    
+    fs::remove_file(test_file).unwrap();
+}
+
+#[test]
+fn test_hash_only() {
+    let test_file = "test_hash_output_hash_only.txt";
+    fs::write(test_file, b"hello world").unwrap();
+    
+    // Just the hex digest, without the path
+    let output = run_hash(&[test_file, "-a", "sha256", "--hash-only"]);
+    assert_eq!(output, format!("{}\n", HELLO_SHA256).into_bytes());
+    
+    // Multiple algorithms print one hash per line in the order they were given
+    let output = String::from_utf8(run_hash(&[test_file, "-a", "sha256", "-a", "md5", "--hash-only"])).unwrap();
+    let lines: Vec<&str> = output.lines().collect();
+    assert_eq!(lines, [HELLO_SHA256, "5eb63bbbe01eeed093cb22bb8f5acdc3"]);
+    
+    fs::remove_file(test_file).unwrap();
+}
@@ -0,0 +1,49 @@
+// Tests for scan --paths-relative-to-cwd
+// Scans an absolute directory from a parent working directory and checks the stored paths
+
+use std::fs;
+use std::path::Path;
+use std::process::Command;
+
+#[test]
+fn test_paths_relative_to_cwd() {
+    let test_dir = "test_paths_relative_to_cwd";
+    fs::create_dir_all(format!("{}/data/sub", test_dir)).unwrap();
+    fs::write(format!("{}/data/a.txt", test_dir), b"alpha").unwrap();
+    fs::write(format!("{}/data/sub/b.txt", test_dir), b"beta").unwrap();
+    
+    let cwd = Path::new(test_dir).canonicalize().unwrap();
+    let data = cwd.join("data");
+    let database = cwd.join("hashes.db");
+    
+    let scan = |extra: &[&str]| {
+        let output = Command::new(env!("CARGO_BIN_EXE_hash"))
+            .current_dir(&cwd)
+            .arg("scan")
+            .arg("-d").arg(&data)
+            .arg("-b").arg(&database)
+            .args(extra)
+            .output()
+            .expect("Failed to execute hash command");
+        assert!(output.status.success(), "scan failed: {}", String::from_utf8_lossy(&output.stderr));
+        let mut paths: Vec<String> = fs::read_to_string(&database)
+            .unwrap()
+            .lines()
+            .map(|line| line.splitn(4, "  ").nth(3).unwrap().to_string())
+            .collect();
+        paths.sort();
+        paths
+    };
+    
+    // Paths include the scanned directory's name, as seen from the working directory
+    let a = Path::new("data").join("a.txt");
+    let b = Path::new("data").join("sub").join("b.txt");
+    assert_eq!(scan(&["--paths-relative-to-cwd"]), [a.to_str().unwrap(), b.to_str().unwrap()]);
+    assert_eq!(scan(&["--paths-relative-to-cwd", "--hdd"]), [a.to_str().unwrap(), b.to_str().unwrap()]);
+    
+    // By default they are relative to the scanned directory
+    let b = Path::new("sub").join("b.txt");
+    assert_eq!(scan(&[]), ["a.txt", b.to_str().unwrap()]);
+    
+    fs::remove_dir_all(test_dir).unwrap();
+}
@@ -0,0 +1,45 @@
+// Tests for scan --warn-duplicates
+// Runs a scan over files with shared content and checks the summary lists them
+
+use std::fs;
+use std::path::Path;
+use std::process::Command;
+
+fn run_scan(args: &[&str]) -> String {
+    let output = Command::new(env!("CARGO_BIN_EXE_hash"))
+        .arg("scan")
+        .args(args)
+        .output()
+        .expect("Failed to execute hash command");
+    assert!(output.status.success(), "scan failed: {}", String::from_utf8_lossy(&output.stderr));
+    String::from_utf8(output.stdout).unwrap()
+}
+
+#[test]
+fn test_warn_duplicates_in_summary() {
+    let test_dir = "test_scan_warn_duplicates";
+    let database = "test_scan_warn_duplicates.db";
+    fs::create_dir_all(format!("{}/sub", test_dir)).unwrap();
+    fs::write(format!("{}/original.txt", test_dir), b"same content").unwrap();
+    fs::write(format!("{}/sub/copy.txt", test_dir), b"same content").unwrap();
+    fs::write(format!("{}/unique.txt", test_dir), b"something else").unwrap();
+
+    // Both copies are listed under one group; the unique file isn't mentioned
+    let stdout = run_scan(&["-d", test_dir, "-b", database, "-a", "sha256", "--warn-duplicates"]);
+    assert!(stdout.contains("Warning: 2 files have identical content in 1 groups"), "{}", stdout);
+    let listed: Vec<&str> = stdout
+        .lines()
+        .skip_while(|line| !line.starts_with("Warning:"))
+        .skip(2)
+        .map(str::trim)
+        .collect();
+    let copy = Path::new("sub").join("copy.txt");
+    assert_eq!(listed, ["original.txt", copy.to_str().unwrap()]);
+
+    // Without the flag there is no warning
+    let stdout = run_scan(&["-d", test_dir, "-b", database, "-a", "sha256"]);
+    assert!(!stdout.contains("identical content"));
+
+    fs::remove_file(database).unwrap();
+    fs::remove_dir_all(test_dir).unwrap();
+}
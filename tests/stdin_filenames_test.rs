@@ -0,0 +1,57 @@
+// Tests for --stdin-filenames, the streaming hashing worker mode
+// Feeds file names one at a time and checks each result arrives before stdin is closed
+
+use std::fs;
+use std::io::{BufRead, BufReader, Write};
+use std::process::{Command, Stdio};
+use std::sync::mpsc;
+use std::thread;
+use std::time::Duration;
+
+/// sha256 of "hello world"
+const HELLO_SHA256: &str = "b94d27b9934d3e08a52e52d7da7dabfac484efe37a5380ee9088f7ace2efcde9";
+
+/// sha256 of "goodbye"
+const GOODBYE_SHA256: &str = "82e35a63ceba37e9646434c5dd412ea577147f1e4a41ccde1614253187e3dbf9";
+
+#[test]
+fn test_stdin_filenames_streams_results() {
+    let first = "test_stdin_filenames_first.txt";
+    let second = "test_stdin_filenames_second.txt";
+    fs::write(first, b"hello world").unwrap();
+    fs::write(second, b"goodbye").unwrap();
+
+    let mut child = Command::new(env!("CARGO_BIN_EXE_hash"))
+        .args(["--stdin-filenames", "-a", "sha256"])
+        .stdin(Stdio::piped())
+        .stdout(Stdio::piped())
+        .spawn()
+        .expect("Failed to execute hash command");
+    let mut stdin = child.stdin.take().unwrap();
+
+    // Forward output lines as they arrive, so a missing flush shows up as a timeout
+    let stdout = child.stdout.take().unwrap();
+    let (sender, receiver) = mpsc::channel();
+    thread::spawn(move || {
+        for line in BufReader::new(stdout).lines() {
+            if sender.send(line.unwrap()).is_err() {
+                break;
+            }
+        }
+    });
+
+    // Each result is printed while stdin is still open
+    for (name, expected) in [(first, HELLO_SHA256), (second, GOODBYE_SHA256)] {
+        writeln!(stdin, "{}", name).unwrap();
+        stdin.flush().unwrap();
+        let line = receiver.recv_timeout(Duration::from_secs(10)).expect("result was not flushed");
+        assert_eq!(line, format!("{}  {}", expected, name));
+    }
+
+    drop(stdin);
+    assert!(child.wait().unwrap().success());
+    assert!(receiver.recv().is_err());
+
+    fs::remove_file(first).unwrap();
+    fs::remove_file(second).unwrap();
+}